@@ -0,0 +1,18 @@
+-- Add task_dependencies table for blocks/blocked-by relations between tasks
+-- relationship_type mirrors the remote issue_relationship_type enum so
+-- relations can round-trip with shared issues.
+CREATE TABLE task_dependencies (
+    id                BLOB PRIMARY KEY,
+    task_id           BLOB NOT NULL,
+    related_task_id   BLOB NOT NULL,
+    relationship_type TEXT NOT NULL DEFAULT 'blocking'
+                         CHECK (relationship_type IN ('blocking','related','has_duplicate')),
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (related_task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    CHECK (task_id != related_task_id),
+    UNIQUE (task_id, related_task_id, relationship_type)
+);
+
+CREATE INDEX idx_task_dependencies_task_id ON task_dependencies(task_id);
+CREATE INDEX idx_task_dependencies_related_task_id ON task_dependencies(related_task_id);
@@ -0,0 +1,22 @@
+-- Race mode: one task run on several executors at once, one workspace per executor.
+-- Picking a winner archives the other workspaces of the race.
+CREATE TABLE workspace_races (
+    id                  BLOB PRIMARY KEY,
+    task_id             BLOB NOT NULL,
+    winner_workspace_id BLOB,
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (winner_workspace_id) REFERENCES workspaces(id) ON DELETE SET NULL
+);
+
+CREATE TABLE workspace_race_entries (
+    race_id      BLOB NOT NULL,
+    workspace_id BLOB NOT NULL UNIQUE,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (race_id, workspace_id),
+    FOREIGN KEY (race_id) REFERENCES workspace_races(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_workspace_races_task_id ON workspace_races(task_id);
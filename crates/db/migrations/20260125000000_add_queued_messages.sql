@@ -0,0 +1,13 @@
+-- Persist queued follow-up messages so they survive restarts and several
+-- can be queued per session. Messages drain in ascending position order.
+CREATE TABLE queued_messages (
+    id              BLOB PRIMARY KEY,
+    session_id      BLOB NOT NULL,
+    position        INTEGER NOT NULL,
+    data            TEXT NOT NULL, -- JSON DraftFollowUpData
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_queued_messages_session_position ON queued_messages(session_id, position);
@@ -0,0 +1,23 @@
+-- Recurring tasks: on every cron tick the scheduler creates a task from the
+-- template below and starts a workspace for it.
+CREATE TABLE task_schedules (
+    id                   BLOB PRIMARY KEY,
+    project_id           BLOB NOT NULL,
+    name                 TEXT NOT NULL,
+    cron_expression      TEXT NOT NULL,
+    executor_profile_id  TEXT NOT NULL, -- JSON ExecutorProfileId
+    repos                TEXT NOT NULL, -- JSON array of ScheduleRepo
+    task_title           TEXT NOT NULL,
+    task_description     TEXT,
+    enabled              INTEGER NOT NULL DEFAULT 1,
+    next_run_at          TEXT,
+    last_run_at          TEXT,
+    last_task_id         BLOB,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (last_task_id) REFERENCES tasks(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_task_schedules_project_id ON task_schedules(project_id);
+CREATE INDEX idx_task_schedules_next_run_at ON task_schedules(enabled, next_run_at);
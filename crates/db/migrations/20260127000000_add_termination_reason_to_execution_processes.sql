@@ -0,0 +1,3 @@
+-- Why an execution process was stopped by its timeouts or resource limits
+ALTER TABLE execution_processes ADD COLUMN termination_reason TEXT
+    CHECK (termination_reason IN ('timeout','idle_timeout','memory_limit'));
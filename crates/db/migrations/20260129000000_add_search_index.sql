@@ -0,0 +1,69 @@
+-- Full-text index over task titles/descriptions, coding agent turns and
+-- normalized execution log entries.
+-- Tasks and turns are kept in sync by the triggers below; log entries are
+-- written by the server once an execution has finished.
+CREATE VIRTUAL TABLE search_index USING fts5(
+    title,
+    body,
+    kind                 UNINDEXED, -- 'task' | 'turn' | 'log'
+    task_id              UNINDEXED,
+    execution_process_id UNINDEXED,
+    created_at           UNINDEXED,
+    tokenize = 'porter unicode61'
+);
+
+INSERT INTO search_index (title, body, kind, task_id, execution_process_id, created_at)
+SELECT title, COALESCE(description, ''), 'task', id, NULL, created_at
+FROM tasks;
+
+INSERT INTO search_index (title, body, kind, task_id, execution_process_id, created_at)
+SELECT '',
+       TRIM(COALESCE(cat.prompt, '') || char(10) || COALESCE(cat.summary, '')),
+       'turn', w.task_id, cat.execution_process_id, cat.created_at
+FROM coding_agent_turns cat
+JOIN execution_processes ep ON ep.id = cat.execution_process_id
+JOIN sessions s ON s.id = ep.session_id
+JOIN workspaces w ON w.id = s.workspace_id;
+
+CREATE TRIGGER search_index_tasks_insert AFTER INSERT ON tasks
+BEGIN
+    INSERT INTO search_index (title, body, kind, task_id, execution_process_id, created_at)
+    VALUES (new.title, COALESCE(new.description, ''), 'task', new.id, NULL, new.created_at);
+END;
+
+CREATE TRIGGER search_index_tasks_update AFTER UPDATE OF title, description ON tasks
+BEGIN
+    UPDATE search_index
+       SET title = new.title, body = COALESCE(new.description, '')
+     WHERE kind = 'task' AND task_id = new.id;
+END;
+
+-- Also drops the turns and log entries of the task's attempts
+CREATE TRIGGER search_index_tasks_delete AFTER DELETE ON tasks
+BEGIN
+    DELETE FROM search_index WHERE task_id = old.id;
+END;
+
+CREATE TRIGGER search_index_turns_insert AFTER INSERT ON coding_agent_turns
+BEGIN
+    INSERT INTO search_index (title, body, kind, task_id, execution_process_id, created_at)
+    SELECT '',
+           TRIM(COALESCE(new.prompt, '') || char(10) || COALESCE(new.summary, '')),
+           'turn', w.task_id, new.execution_process_id, new.created_at
+    FROM execution_processes ep
+    JOIN sessions s ON s.id = ep.session_id
+    JOIN workspaces w ON w.id = s.workspace_id
+    WHERE ep.id = new.execution_process_id;
+END;
+
+CREATE TRIGGER search_index_turns_update AFTER UPDATE OF prompt, summary ON coding_agent_turns
+BEGIN
+    UPDATE search_index
+       SET body = TRIM(COALESCE(new.prompt, '') || char(10) || COALESCE(new.summary, ''))
+     WHERE kind = 'turn' AND execution_process_id = new.execution_process_id;
+END;
+
+CREATE TRIGGER search_index_execution_processes_delete AFTER DELETE ON execution_processes
+BEGIN
+    DELETE FROM search_index WHERE execution_process_id = old.id;
+END;
@@ -0,0 +1,18 @@
+-- Token usage of a coding agent turn, one row per model used in the turn
+CREATE TABLE coding_agent_turn_usages (
+    id                   BLOB PRIMARY KEY,
+    coding_agent_turn_id BLOB NOT NULL,
+    executor             TEXT NOT NULL,
+    variant              TEXT,
+    model                TEXT NOT NULL,
+    input_tokens         INTEGER NOT NULL DEFAULT 0,
+    output_tokens        INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens    INTEGER NOT NULL DEFAULT 0,
+    cache_write_tokens   INTEGER NOT NULL DEFAULT 0,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (coding_agent_turn_id) REFERENCES coding_agent_turns(id) ON DELETE CASCADE,
+    UNIQUE (coding_agent_turn_id, model)
+);
+
+CREATE INDEX idx_coding_agent_turn_usages_created_at ON coding_agent_turn_usages(created_at);
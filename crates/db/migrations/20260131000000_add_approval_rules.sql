@@ -0,0 +1,19 @@
+-- Rules that answer tool approval requests without asking the user. Rules
+-- without a project apply to every project.
+CREATE TABLE approval_rules (
+    id           BLOB PRIMARY KEY,
+    project_id   BLOB,
+    name         TEXT NOT NULL,
+    tool_name    TEXT,
+    action_type  TEXT
+                 CHECK (action_type IN ('file_read','file_edit','command_run','search','web_fetch','tool')),
+    pattern      TEXT,
+    decision     TEXT NOT NULL
+                 CHECK (decision IN ('allow','deny','ask')),
+    enabled      INTEGER NOT NULL DEFAULT 1,
+    created_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at   TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_approval_rules_project_id ON approval_rules(project_id);
@@ -0,0 +1,23 @@
+-- Tool approval requests and how they were answered, so that pending requests
+-- and past decisions outlive the server process.
+CREATE TABLE approval_requests (
+    id                   TEXT PRIMARY KEY,
+    execution_process_id BLOB NOT NULL,
+    tool_name            TEXT NOT NULL,
+    tool_input           TEXT NOT NULL, -- JSON
+    tool_call_id         TEXT NOT NULL,
+    status               TEXT NOT NULL DEFAULT 'pending'
+                         CHECK (status IN ('pending','approved','denied','timed_out')),
+    denial_reason        TEXT,
+    resolved_by          TEXT
+                         CHECK (resolved_by IN ('user','rule','timeout','cancelled')),
+    approval_rule_id     BLOB,
+    created_at           TEXT NOT NULL,
+    timeout_at           TEXT NOT NULL,
+    responded_at         TEXT,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (approval_rule_id) REFERENCES approval_rules(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_approval_requests_execution_process_id ON approval_requests(execution_process_id);
+CREATE INDEX idx_approval_requests_status ON approval_requests(status);
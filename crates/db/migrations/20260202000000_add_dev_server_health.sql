@@ -0,0 +1,18 @@
+-- Readiness probe and crash handling for dev servers
+ALTER TABLE repos ADD COLUMN dev_server_health_check TEXT;
+ALTER TABLE repos ADD COLUMN dev_server_auto_restart INTEGER NOT NULL DEFAULT 0;
+
+-- Health of each dev server process, as observed by the container service
+CREATE TABLE dev_server_statuses (
+    execution_process_id BLOB PRIMARY KEY,
+    repo_id              BLOB NOT NULL,
+    state                TEXT NOT NULL DEFAULT 'starting'
+                         CHECK (state IN ('starting','ready','unhealthy','crashed','stopped')),
+    port                 INTEGER,
+    restart_count        INTEGER NOT NULL DEFAULT 0,
+    last_error           TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
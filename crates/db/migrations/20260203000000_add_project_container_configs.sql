@@ -0,0 +1,14 @@
+-- Projects whose executions run in a Docker/Podman container instead of on
+-- the host
+CREATE TABLE project_container_configs (
+    project_id      BLOB PRIMARY KEY,
+    enabled         INTEGER NOT NULL DEFAULT 1,
+    runtime         TEXT NOT NULL DEFAULT 'docker'
+                    CHECK (runtime IN ('docker','podman')),
+    image           TEXT NOT NULL,
+    network_enabled INTEGER NOT NULL DEFAULT 1,
+    env_passthrough TEXT NOT NULL DEFAULT '[]', -- JSON array of variable names
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
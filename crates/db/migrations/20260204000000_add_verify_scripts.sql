@@ -0,0 +1,98 @@
+-- Verification scripts (tests, lint, typecheck) that run after the coding agent
+ALTER TABLE repos ADD COLUMN verify_script TEXT;
+ALTER TABLE repos ADD COLUMN verify_max_retries INTEGER NOT NULL DEFAULT 0;
+
+-- Result of the latest verification of each workspace. `retries` counts the
+-- automatic follow-ups sent to the agent since verification last passed.
+CREATE TABLE workspace_verifications (
+    workspace_id         BLOB PRIMARY KEY,
+    execution_process_id BLOB,
+    status               TEXT NOT NULL DEFAULT 'running'
+                         CHECK (status IN ('running','passed','failed','fixing')),
+    retries              INTEGER NOT NULL DEFAULT 0,
+    output               TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+-- Allow the 'verifyscript' run reason.
+-- SQLite cannot alter CHECK constraints, so the table is rebuilt.
+
+-- Rebuild needs FK disabled to avoid cascade deletes during DROP TABLE
+-- sqlx workaround: end auto-transaction to allow PRAGMA to take effect
+-- https://github.com/launchbadge/sqlx/issues/2085#issuecomment-1499859906
+COMMIT;
+
+PRAGMA foreign_keys = OFF;
+
+BEGIN TRANSACTION;
+
+-- search_index_turns_insert joins execution_processes and would block the
+-- RENAME below while the table is missing; it is recreated afterwards.
+DROP TRIGGER search_index_turns_insert;
+
+CREATE TABLE execution_processes_new (
+    id                 BLOB PRIMARY KEY,
+    session_id         BLOB NOT NULL,
+    run_reason         TEXT NOT NULL DEFAULT 'setupscript'
+                          CHECK (run_reason IN ('setupscript','codingagent','devserver','cleanupscript','verifyscript')),
+    executor_action    TEXT NOT NULL DEFAULT '{}',
+    status             TEXT NOT NULL DEFAULT 'running'
+                          CHECK (status IN ('queued','running','completed','failed','killed')),
+    exit_code          INTEGER,
+    termination_reason TEXT
+                          CHECK (termination_reason IN ('timeout','idle_timeout','memory_limit')),
+    dropped            INTEGER NOT NULL DEFAULT 0,
+    started_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    completed_at       TEXT,
+    created_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at         TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+);
+
+INSERT INTO execution_processes_new (id, session_id, run_reason, executor_action, status, exit_code, termination_reason, dropped, started_at, completed_at, created_at, updated_at)
+SELECT id, session_id, run_reason, executor_action, status, exit_code, termination_reason, dropped, started_at, completed_at, created_at, updated_at
+FROM execution_processes;
+
+DROP TABLE execution_processes;
+ALTER TABLE execution_processes_new RENAME TO execution_processes;
+
+CREATE INDEX idx_execution_processes_session_id ON execution_processes(session_id);
+CREATE INDEX idx_execution_processes_status ON execution_processes(status);
+CREATE INDEX idx_execution_processes_run_reason ON execution_processes(run_reason);
+
+CREATE INDEX idx_execution_processes_session_status_run_reason
+ON execution_processes (session_id, status, run_reason);
+
+CREATE INDEX idx_execution_processes_session_run_reason_created
+ON execution_processes (session_id, run_reason, created_at DESC);
+
+-- Dropped together with the old table
+CREATE TRIGGER search_index_execution_processes_delete AFTER DELETE ON execution_processes
+BEGIN
+    DELETE FROM search_index WHERE execution_process_id = old.id;
+END;
+
+CREATE TRIGGER search_index_turns_insert AFTER INSERT ON coding_agent_turns
+BEGIN
+    INSERT INTO search_index (title, body, kind, task_id, execution_process_id, created_at)
+    SELECT '',
+           TRIM(COALESCE(new.prompt, '') || char(10) || COALESCE(new.summary, '')),
+           'turn', w.task_id, new.execution_process_id, new.created_at
+    FROM execution_processes ep
+    JOIN sessions s ON s.id = ep.session_id
+    JOIN workspaces w ON w.id = s.workspace_id
+    WHERE ep.id = new.execution_process_id;
+END;
+
+-- Verify foreign key constraints before committing
+PRAGMA foreign_key_check;
+
+COMMIT;
+
+PRAGMA foreign_keys = ON;
+
+-- sqlx workaround: start empty transaction for sqlx to close gracefully
+BEGIN TRANSACTION;
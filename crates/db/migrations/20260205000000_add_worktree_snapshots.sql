@@ -0,0 +1,3 @@
+-- Commit recording the full worktree, untracked files included, after each
+-- coding agent turn. Kept alive by a hidden ref in the repo.
+ALTER TABLE execution_process_repo_states ADD COLUMN after_snapshot_commit TEXT;
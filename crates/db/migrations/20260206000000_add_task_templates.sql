@@ -0,0 +1,32 @@
+-- Task templates: a title and description with `{{variable}}` placeholders,
+-- a preselected executor, default repos and an ordered chain of agent steps.
+-- Templates without a project are available in every project.
+CREATE TABLE task_templates (
+    id                  BLOB PRIMARY KEY,
+    project_id          BLOB,
+    name                TEXT NOT NULL,
+    title               TEXT NOT NULL,
+    description         TEXT,
+    variables           TEXT NOT NULL DEFAULT '[]',
+    executor_profile_id TEXT,
+    repos               TEXT NOT NULL DEFAULT '[]',
+    steps               TEXT NOT NULL DEFAULT '[]',
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_task_templates_project_id ON task_templates(project_id);
+
+-- What a task took from its template, rendered with the task's variables, so
+-- later edits of the template don't change existing tasks
+CREATE TABLE task_template_instances (
+    task_id             BLOB PRIMARY KEY,
+    template_id         BLOB,
+    executor_profile_id TEXT,
+    repos               TEXT NOT NULL DEFAULT '[]',
+    steps               TEXT NOT NULL DEFAULT '[]',
+    created_at          TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (task_id) REFERENCES tasks(id) ON DELETE CASCADE,
+    FOREIGN KEY (template_id) REFERENCES task_templates(id) ON DELETE SET NULL
+);
@@ -0,0 +1,19 @@
+-- Stacked workspaces: a workspace whose repos branch off another workspace's
+-- branch instead of their target branches. `base_commit` is the commit of the
+-- base branch the workspace was last based on, so restacking only replays the
+-- workspace's own commits.
+CREATE TABLE workspace_stack_repos (
+    workspace_id      BLOB NOT NULL,
+    repo_id           BLOB NOT NULL,
+    base_workspace_id BLOB NOT NULL,
+    base_commit       TEXT NOT NULL,
+    created_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (workspace_id, repo_id),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (base_workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_workspace_stack_repos_base_workspace_id
+    ON workspace_stack_repos(base_workspace_id);
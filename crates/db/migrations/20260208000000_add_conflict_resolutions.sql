@@ -0,0 +1,22 @@
+-- Conflicts of a paused rebase or merge handed to the coding agent, one row per
+-- worktree. A rebase can stop again on a later commit, so `rounds` counts the
+-- follow-ups sent for the same resolution.
+CREATE TABLE conflict_resolutions (
+    workspace_id         BLOB NOT NULL,
+    repo_id              BLOB NOT NULL,
+    execution_process_id BLOB,
+    status               TEXT NOT NULL DEFAULT 'resolving'
+                         CHECK (status IN ('resolving','resolved','failed')),
+    conflicted_files     TEXT NOT NULL DEFAULT '[]',
+    rounds               INTEGER NOT NULL DEFAULT 1,
+    error                TEXT,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (workspace_id, repo_id),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE,
+    FOREIGN KEY (execution_process_id) REFERENCES execution_processes(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_conflict_resolutions_execution_process_id
+ON conflict_resolutions(execution_process_id);
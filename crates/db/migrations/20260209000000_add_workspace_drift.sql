@@ -0,0 +1,19 @@
+-- Rebase workspaces onto their target branch when it moves and the rebase
+-- would apply cleanly
+ALTER TABLE projects ADD COLUMN auto_rebase_on_drift INTEGER NOT NULL DEFAULT 0;
+
+-- How far each repo of a workspace has drifted from its target branch, as of
+-- the latest check of the drift monitor
+CREATE TABLE workspace_drift (
+    workspace_id      BLOB NOT NULL,
+    repo_id           BLOB NOT NULL,
+    target_branch     TEXT NOT NULL,
+    target_commit     TEXT NOT NULL,
+    commits_ahead     INTEGER NOT NULL DEFAULT 0,
+    commits_behind    INTEGER NOT NULL DEFAULT 0,
+    conflicted_files  TEXT NOT NULL DEFAULT '[]', -- JSON array of paths
+    checked_at        TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    PRIMARY KEY (workspace_id, repo_id),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
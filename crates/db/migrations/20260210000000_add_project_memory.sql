@@ -0,0 +1,23 @@
+-- Repo file, such as AGENTS.md, the accepted project memory is mirrored into
+ALTER TABLE projects ADD COLUMN memory_file TEXT;
+
+-- Knowledge about a project prepended to the prompt of every new coding agent
+-- session. Candidates are proposed from the summary of completed tasks and
+-- only used once accepted.
+CREATE TABLE project_memory_entries (
+    id                   BLOB PRIMARY KEY,
+    project_id           BLOB NOT NULL,
+    content              TEXT NOT NULL,
+    status               TEXT NOT NULL DEFAULT 'accepted'
+                         CHECK (status IN ('candidate','accepted','discarded')),
+    workspace_id         BLOB,
+    coding_agent_turn_id BLOB UNIQUE,
+    created_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at           TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE SET NULL,
+    FOREIGN KEY (coding_agent_turn_id) REFERENCES coding_agent_turns(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_project_memory_entries_project_id
+ON project_memory_entries (project_id, status);
@@ -86,6 +86,20 @@ impl DBService {
         Ok(DBService { pool })
     }
 
+    /// Fresh in-memory database with every migration applied.
+    pub async fn new_in_memory() -> Result<DBService, Error> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")?;
+        // Every connection to `sqlite::memory:` opens its own database, so keep a single one alive
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+            .connect_with(options)
+            .await?;
+        run_migrations(&pool).await?;
+        Ok(DBService { pool })
+    }
+
     pub async fn new_with_after_connect<F>(after_connect: F) -> Result<DBService, Error>
     where
         F: for<'a> Fn(
@@ -0,0 +1,204 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use utils::approvals::{ApprovalRequest, ApprovalStatus};
+use uuid::Uuid;
+
+/// How a tool approval request was answered. `cancelled` requests were given
+/// up by the agent or left pending when the server restarted.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_resolution", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalResolution {
+    User,
+    Rule,
+    Timeout,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct ApprovalRecordRow {
+    id: String,
+    execution_process_id: Uuid,
+    tool_name: String,
+    tool_input: Json<Value>,
+    tool_call_id: String,
+    status: String,
+    denial_reason: Option<String>,
+    resolved_by: Option<ApprovalResolution>,
+    approval_rule_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    timeout_at: DateTime<Utc>,
+    responded_at: Option<DateTime<Utc>>,
+}
+
+/// A tool approval request as stored in the database, with its outcome
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ApprovalRecord {
+    pub id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+    pub tool_input: Value,
+    pub tool_call_id: String,
+    pub status: ApprovalStatus,
+    /// Unset while the request is pending
+    pub resolved_by: Option<ApprovalResolution>,
+    /// Rule that answered the request, when it was answered by a rule
+    pub approval_rule_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub timeout_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+}
+
+impl From<ApprovalRecordRow> for ApprovalRecord {
+    fn from(row: ApprovalRecordRow) -> Self {
+        let status = match row.status.as_str() {
+            "approved" => ApprovalStatus::Approved,
+            "denied" => ApprovalStatus::Denied {
+                reason: row.denial_reason,
+            },
+            "timed_out" => ApprovalStatus::TimedOut,
+            _ => ApprovalStatus::Pending,
+        };
+        Self {
+            id: row.id,
+            execution_process_id: row.execution_process_id,
+            tool_name: row.tool_name,
+            tool_input: row.tool_input.0,
+            tool_call_id: row.tool_call_id,
+            status,
+            resolved_by: row.resolved_by,
+            approval_rule_id: row.approval_rule_id,
+            created_at: row.created_at,
+            timeout_at: row.timeout_at,
+            responded_at: row.responded_at,
+        }
+    }
+}
+
+/// Column values of a status: its name and the denial reason
+fn status_columns(status: &ApprovalStatus) -> (&'static str, Option<&str>) {
+    match status {
+        ApprovalStatus::Pending => ("pending", None),
+        ApprovalStatus::Approved => ("approved", None),
+        ApprovalStatus::Denied { reason } => ("denied", reason.as_deref()),
+        ApprovalStatus::TimedOut => ("timed_out", None),
+    }
+}
+
+impl ApprovalRecord {
+    pub async fn create(pool: &SqlitePool, request: &ApprovalRequest) -> Result<(), sqlx::Error> {
+        let tool_input = Json(&request.tool_input);
+        sqlx::query!(
+            r#"INSERT INTO approval_requests
+                   (id, execution_process_id, tool_name, tool_input, tool_call_id, created_at,
+                    timeout_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            request.id,
+            request.execution_process_id,
+            request.tool_name,
+            tool_input,
+            request.tool_call_id,
+            request.created_at,
+            request.timeout_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record the outcome of a pending request. Returns false if the request
+    /// is unknown or was already answered.
+    pub async fn resolve(
+        pool: &SqlitePool,
+        id: &str,
+        status: &ApprovalStatus,
+        resolved_by: ApprovalResolution,
+        approval_rule_id: Option<Uuid>,
+    ) -> Result<bool, sqlx::Error> {
+        let (status, denial_reason) = status_columns(status);
+        let result = sqlx::query!(
+            r#"UPDATE approval_requests
+               SET status = $2, denial_reason = $3, resolved_by = $4, approval_rule_id = $5,
+                   responded_at = datetime('now', 'subsec')
+               WHERE id = $1 AND status = 'pending'"#,
+            id,
+            status,
+            denial_reason,
+            resolved_by,
+            approval_rule_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Deny every request that is still pending. Used at startup: agents run
+    /// as child processes of the server, so none is left waiting for them.
+    pub async fn cancel_all_pending(pool: &SqlitePool, reason: &str) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE approval_requests
+               SET status = 'denied', denial_reason = $1, resolved_by = 'cancelled',
+                   responded_at = datetime('now', 'subsec')
+               WHERE status = 'pending'"#,
+            reason
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: &str) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            ApprovalRecordRow,
+            r#"SELECT id as "id!",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      tool_name,
+                      tool_input as "tool_input!: Json<Value>",
+                      tool_call_id,
+                      status,
+                      denial_reason,
+                      resolved_by as "resolved_by: ApprovalResolution",
+                      approval_rule_id as "approval_rule_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      timeout_at as "timeout_at!: DateTime<Utc>",
+                      responded_at as "responded_at: DateTime<Utc>"
+               FROM approval_requests
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            ApprovalRecordRow,
+            r#"SELECT id as "id!",
+                      execution_process_id as "execution_process_id!: Uuid",
+                      tool_name,
+                      tool_input as "tool_input!: Json<Value>",
+                      tool_call_id,
+                      status,
+                      denial_reason,
+                      resolved_by as "resolved_by: ApprovalResolution",
+                      approval_rule_id as "approval_rule_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      timeout_at as "timeout_at!: DateTime<Utc>",
+                      responded_at as "responded_at: DateTime<Utc>"
+               FROM approval_requests
+               WHERE execution_process_id = $1
+               ORDER BY created_at ASC"#,
+            execution_process_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+}
@@ -0,0 +1,266 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::rust::double_option;
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What an approval rule does with the tool calls it matches. `ask` shows the
+/// request to the user even when an allow rule matches too.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_rule_decision", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRuleDecision {
+    Allow,
+    Deny,
+    Ask,
+}
+
+/// Kind of tool action an approval rule applies to
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "approval_rule_action_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalRuleActionType {
+    FileRead,
+    FileEdit,
+    CommandRun,
+    Search,
+    WebFetch,
+    Tool,
+}
+
+/// Rule that answers tool approval requests without asking the user
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ApprovalRule {
+    pub id: Uuid,
+    /// Unset for global rules, which apply to every project
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    /// Tool name to match exactly, e.g. `Bash`
+    pub tool_name: Option<String>,
+    pub action_type: Option<ApprovalRuleActionType>,
+    /// Glob on the path for file actions, otherwise a regex on the command,
+    /// search query, URL or tool arguments
+    pub pattern: Option<String>,
+    pub decision: ApprovalRuleDecision,
+    pub enabled: bool,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateApprovalRule {
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub tool_name: Option<String>,
+    pub action_type: Option<ApprovalRuleActionType>,
+    pub pattern: Option<String>,
+    pub decision: ApprovalRuleDecision,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateApprovalRule {
+    pub name: Option<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub tool_name: Option<Option<String>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "ApprovalRuleActionType | null")]
+    pub action_type: Option<Option<ApprovalRuleActionType>>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub pattern: Option<Option<String>>,
+    pub decision: Option<ApprovalRuleDecision>,
+    pub enabled: Option<bool>,
+}
+
+impl ApprovalRule {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      tool_name,
+                      action_type as "action_type: ApprovalRuleActionType",
+                      pattern,
+                      decision as "decision!: ApprovalRuleDecision",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM approval_rules
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      tool_name,
+                      action_type as "action_type: ApprovalRuleActionType",
+                      pattern,
+                      decision as "decision!: ApprovalRuleDecision",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM approval_rules
+               WHERE project_id = $1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Enabled rules of the project together with the enabled global rules
+    pub async fn find_enabled_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      tool_name,
+                      action_type as "action_type: ApprovalRuleActionType",
+                      pattern,
+                      decision as "decision!: ApprovalRuleDecision",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM approval_rules
+               WHERE (project_id = $1 OR project_id IS NULL) AND enabled = 1
+               ORDER BY created_at ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      tool_name,
+                      action_type as "action_type: ApprovalRuleActionType",
+                      pattern,
+                      decision as "decision!: ApprovalRuleDecision",
+                      enabled as "enabled!: bool",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM approval_rules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateApprovalRule,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let enabled = data.enabled.unwrap_or(true);
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"INSERT INTO approval_rules
+                   (id, project_id, name, tool_name, action_type, pattern, decision, enabled)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id: Uuid",
+                         name,
+                         tool_name,
+                         action_type as "action_type: ApprovalRuleActionType",
+                         pattern,
+                         decision as "decision!: ApprovalRuleDecision",
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.tool_name,
+            data.action_type,
+            data.pattern,
+            data.decision,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        existing: &ApprovalRule,
+        data: &UpdateApprovalRule,
+    ) -> Result<Self, sqlx::Error> {
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        // None = keep the existing condition, Some(None) = drop it
+        let tool_name = data.tool_name.as_ref().unwrap_or(&existing.tool_name);
+        let action_type = data.action_type.unwrap_or(existing.action_type);
+        let pattern = data.pattern.as_ref().unwrap_or(&existing.pattern);
+        let decision = data.decision.unwrap_or(existing.decision);
+        let enabled = data.enabled.unwrap_or(existing.enabled);
+
+        sqlx::query_as!(
+            ApprovalRule,
+            r#"UPDATE approval_rules
+               SET name = $2, tool_name = $3, action_type = $4, pattern = $5, decision = $6,
+                   enabled = $7, updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id: Uuid",
+                         name,
+                         tool_name,
+                         action_type as "action_type: ApprovalRuleActionType",
+                         pattern,
+                         decision as "decision!: ApprovalRuleDecision",
+                         enabled as "enabled!: bool",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            existing.id,
+            name,
+            tool_name,
+            action_type,
+            pattern,
+            decision,
+            enabled
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM approval_rules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
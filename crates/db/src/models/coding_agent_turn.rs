@@ -72,6 +72,36 @@ impl CodingAgentTurn {
         .await
     }
 
+    /// Latest turn of the session's completed coding agents that has a summary
+    pub async fn find_latest_with_summary_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            CodingAgentTurn,
+            r#"SELECT
+                cat.id as "id!: Uuid",
+                cat.execution_process_id as "execution_process_id!: Uuid",
+                cat.agent_session_id,
+                cat.prompt,
+                cat.summary,
+                cat.seen as "seen!: bool",
+                cat.created_at as "created_at!: DateTime<Utc>",
+                cat.updated_at as "updated_at!: DateTime<Utc>"
+               FROM coding_agent_turns cat
+               JOIN execution_processes ep ON cat.execution_process_id = ep.id
+               WHERE ep.session_id = $1
+                 AND ep.run_reason = 'codingagent'
+                 AND ep.status = 'completed'
+                 AND cat.summary IS NOT NULL
+               ORDER BY ep.created_at DESC
+               LIMIT 1"#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     /// Create a new coding agent turn
     pub async fn create(
         pool: &SqlitePool,
@@ -0,0 +1,119 @@
+use chrono::{DateTime, Utc};
+use executors::{executors::BaseCodingAgent, logs::ModelTokenUsage, profile::ExecutorProfileId};
+use sqlx::{FromRow, SqlitePool};
+use uuid::Uuid;
+
+/// Token usage of coding agent turns, summed per task, executor profile, day and model
+#[derive(Debug, Clone, FromRow)]
+pub struct UsageRecord {
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub executor: BaseCodingAgent,
+    pub variant: Option<String>,
+    /// UTC date, `YYYY-MM-DD`
+    pub day: String,
+    pub model: String,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UsageFilters {
+    pub project_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+pub struct CodingAgentTurnUsage;
+
+impl CodingAgentTurnUsage {
+    /// Store the usage reported for a turn, replacing earlier reports per model
+    pub async fn record(
+        pool: &SqlitePool,
+        coding_agent_turn_id: Uuid,
+        executor_profile_id: &ExecutorProfileId,
+        model_usage: &[ModelTokenUsage],
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        let mut tx = pool.begin().await?;
+        for usage in model_usage {
+            let id = Uuid::new_v4();
+            let input_tokens = saturating_i64(usage.input_tokens);
+            let output_tokens = saturating_i64(usage.output_tokens);
+            let cache_read_tokens = saturating_i64(usage.cache_read_tokens);
+            let cache_write_tokens = saturating_i64(usage.cache_write_tokens);
+            sqlx::query!(
+                r#"INSERT INTO coding_agent_turn_usages (
+                    id, coding_agent_turn_id, executor, variant, model, input_tokens,
+                    output_tokens, cache_read_tokens, cache_write_tokens, created_at, updated_at
+                   )
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $10)
+                   ON CONFLICT (coding_agent_turn_id, model) DO UPDATE SET
+                    input_tokens = excluded.input_tokens,
+                    output_tokens = excluded.output_tokens,
+                    cache_read_tokens = excluded.cache_read_tokens,
+                    cache_write_tokens = excluded.cache_write_tokens,
+                    updated_at = excluded.updated_at"#,
+                id,
+                coding_agent_turn_id,
+                executor_profile_id.executor,
+                executor_profile_id.variant,
+                usage.model,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
+                now
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
+    pub async fn find_records(
+        pool: &SqlitePool,
+        filters: &UsageFilters,
+    ) -> Result<Vec<UsageRecord>, sqlx::Error> {
+        sqlx::query_as!(
+            UsageRecord,
+            r#"SELECT t.id                      AS "task_id!: Uuid",
+                      t.title                   AS "task_title!",
+                      p.id                      AS "project_id!: Uuid",
+                      p.name                    AS "project_name!",
+                      u.executor                AS "executor!: BaseCodingAgent",
+                      u.variant,
+                      date(u.created_at)        AS "day!: String",
+                      u.model                   AS "model!",
+                      SUM(u.input_tokens)       AS "input_tokens!: i64",
+                      SUM(u.output_tokens)      AS "output_tokens!: i64",
+                      SUM(u.cache_read_tokens)  AS "cache_read_tokens!: i64",
+                      SUM(u.cache_write_tokens) AS "cache_write_tokens!: i64"
+               FROM coding_agent_turn_usages u
+               JOIN coding_agent_turns cat ON cat.id = u.coding_agent_turn_id
+               JOIN execution_processes ep ON ep.id = cat.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               JOIN tasks t ON t.id = w.task_id
+               JOIN projects p ON p.id = t.project_id
+               WHERE ($1 IS NULL OR p.id = $1)
+                 AND ($2 IS NULL OR datetime(u.created_at) >= datetime($2))
+                 AND ($3 IS NULL OR datetime(u.created_at) <= datetime($3))
+               GROUP BY t.id, u.executor, u.variant, date(u.created_at), u.model
+               ORDER BY date(u.created_at) ASC"#,
+            filters.project_id,
+            filters.from,
+            filters.to
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
+
+fn saturating_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
@@ -0,0 +1,194 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "conflict_resolution_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolutionStatus {
+    Resolving,
+    Resolved,
+    Failed,
+}
+
+/// Conflicts of a worktree handed to the coding agent. Once the agent is done
+/// the files are checked for conflict markers and the rebase or merge is
+/// continued.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ConflictResolution {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    /// Latest follow-up asking the agent to resolve the conflicts
+    pub execution_process_id: Option<Uuid>,
+    pub status: ConflictResolutionStatus,
+    /// Files that were conflicted when the latest follow-up started
+    pub conflicted_files: Vec<String>,
+    /// Follow-ups sent so far; a rebase can stop on conflicts again on a
+    /// later commit
+    pub rounds: u32,
+    pub error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct ConflictResolutionRow {
+    workspace_id: Uuid,
+    repo_id: Uuid,
+    execution_process_id: Option<Uuid>,
+    status: ConflictResolutionStatus,
+    conflicted_files: Json<Vec<String>>,
+    rounds: u32,
+    error: Option<String>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ConflictResolutionRow> for ConflictResolution {
+    fn from(row: ConflictResolutionRow) -> Self {
+        Self {
+            workspace_id: row.workspace_id,
+            repo_id: row.repo_id,
+            execution_process_id: row.execution_process_id,
+            status: row.status,
+            conflicted_files: row.conflicted_files.0,
+            rounds: row.rounds,
+            error: row.error,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+impl ConflictResolution {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            ConflictResolutionRow,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      execution_process_id as "execution_process_id: Uuid",
+                      status as "status!: ConflictResolutionStatus",
+                      conflicted_files as "conflicted_files!: Json<Vec<String>>",
+                      rounds as "rounds!: u32",
+                      error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM conflict_resolutions
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    /// Resolution an execution was started for. The process id is attached
+    /// only once the execution was spawned, so a resolution still waiting for
+    /// it matches too.
+    pub async fn find_for_execution_process(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            ConflictResolutionRow,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      execution_process_id as "execution_process_id: Uuid",
+                      status as "status!: ConflictResolutionStatus",
+                      conflicted_files as "conflicted_files!: Json<Vec<String>>",
+                      rounds as "rounds!: u32",
+                      error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM conflict_resolutions
+               WHERE workspace_id = $1
+                 AND (execution_process_id = $2
+                      OR (execution_process_id IS NULL AND status = 'resolving'))
+               ORDER BY execution_process_id IS NULL
+               LIMIT 1"#,
+            workspace_id,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    /// Record a follow-up about to be sent to resolve the worktree's
+    /// conflicts, before its execution is spawned. Rounds only add up while
+    /// the same resolution is still going on.
+    pub async fn start(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        conflicted_files: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let conflicted_files = Json(conflicted_files);
+        sqlx::query!(
+            r#"INSERT INTO conflict_resolutions (workspace_id, repo_id, conflicted_files)
+               VALUES ($1, $2, $3)
+               ON CONFLICT(workspace_id, repo_id) DO UPDATE SET
+                   execution_process_id = NULL,
+                   conflicted_files = excluded.conflicted_files,
+                   rounds = CASE WHEN status = 'resolving' THEN rounds + 1 ELSE 1 END,
+                   status = 'resolving',
+                   error = NULL,
+                   updated_at = datetime('now', 'subsec')"#,
+            workspace_id,
+            repo_id,
+            conflicted_files
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Attach the follow-up's execution once it was spawned
+    pub async fn set_execution_process(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE conflict_resolutions
+               SET execution_process_id = $3, updated_at = datetime('now', 'subsec')
+               WHERE workspace_id = $1 AND repo_id = $2"#,
+            workspace_id,
+            repo_id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn finish(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        status: ConflictResolutionStatus,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE conflict_resolutions
+               SET status = $3, error = $4, updated_at = datetime('now', 'subsec')
+               WHERE workspace_id = $1 AND repo_id = $2"#,
+            workspace_id,
+            repo_id,
+            status,
+            error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Health of a dev server process. `unhealthy` servers are running but fail
+/// their readiness probe; `crashed` ones exited on their own.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "dev_server_state", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum DevServerState {
+    Starting,
+    Ready,
+    Unhealthy,
+    Crashed,
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DevServerStatus {
+    pub execution_process_id: Uuid,
+    pub repo_id: Uuid,
+    pub state: DevServerState,
+    /// Port the server listens on, once detected
+    pub port: Option<u16>,
+    /// How many times the server was restarted after crashing before this run
+    pub restart_count: u32,
+    pub last_error: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl DevServerStatus {
+    pub async fn create(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            DevServerStatus,
+            r#"INSERT INTO dev_server_statuses (execution_process_id, repo_id)
+               VALUES ($1, $2)
+               RETURNING execution_process_id as "execution_process_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         state as "state!: DevServerState",
+                         port as "port: u16",
+                         restart_count as "restart_count!: u32",
+                         last_error,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            execution_process_id,
+            repo_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_execution_process_id(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServerStatus,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      state as "state!: DevServerState",
+                      port as "port: u16",
+                      restart_count as "restart_count!: u32",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_server_statuses
+               WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn find_by_rowid(pool: &SqlitePool, rowid: i64) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServerStatus,
+            r#"SELECT execution_process_id as "execution_process_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      state as "state!: DevServerState",
+                      port as "port: u16",
+                      restart_count as "restart_count!: u32",
+                      last_error,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_server_statuses
+               WHERE rowid = $1"#,
+            rowid
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Statuses of the dev servers started in a workspace, newest first
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            DevServerStatus,
+            r#"SELECT ds.execution_process_id as "execution_process_id!: Uuid",
+                      ds.repo_id as "repo_id!: Uuid",
+                      ds.state as "state!: DevServerState",
+                      ds.port as "port: u16",
+                      ds.restart_count as "restart_count!: u32",
+                      ds.last_error,
+                      ds.created_at as "created_at!: DateTime<Utc>",
+                      ds.updated_at as "updated_at!: DateTime<Utc>"
+               FROM dev_server_statuses ds
+               JOIN execution_processes ep ON ds.execution_process_id = ep.id
+               JOIN sessions s ON ep.session_id = s.id
+               WHERE s.workspace_id = $1
+               ORDER BY ds.created_at DESC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn set_port(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        port: u16,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE dev_server_statuses
+               SET port = $2, updated_at = datetime('now', 'subsec')
+               WHERE execution_process_id = $1"#,
+            execution_process_id,
+            port
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn set_restart_count(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        restart_count: u32,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE dev_server_statuses
+               SET restart_count = $2, updated_at = datetime('now', 'subsec')
+               WHERE execution_process_id = $1"#,
+            execution_process_id,
+            restart_count
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Move to a new state. Servers that crashed or were stopped keep their
+    /// state, and the update is skipped when nothing changed so repeated probe
+    /// results don't flood the events stream.
+    pub async fn update_state(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        state: DevServerState,
+        last_error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE dev_server_statuses
+               SET state = $2, last_error = $3, updated_at = datetime('now', 'subsec')
+               WHERE execution_process_id = $1
+                 AND state NOT IN ('crashed', 'stopped')
+                 AND (state != $2 OR last_error IS NOT $3)"#,
+            execution_process_id,
+            state,
+            last_error
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark every dev server that is not already down as stopped, e.g. at
+    /// startup when none of their processes survived
+    pub async fn stop_all_active(pool: &SqlitePool) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE dev_server_statuses
+               SET state = 'stopped', updated_at = datetime('now', 'subsec')
+               WHERE state IN ('starting', 'ready', 'unhealthy')"#
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
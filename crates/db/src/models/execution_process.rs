@@ -43,6 +43,8 @@ pub enum ExecutionProcessError {
 #[serde(rename_all = "lowercase")]
 #[ts(use_ts_enum)]
 pub enum ExecutionProcessStatus {
+    /// Waiting for a free coding agent slot
+    Queued,
     Running,
     Completed,
     Failed,
@@ -57,6 +59,20 @@ pub enum ExecutionProcessRunReason {
     CleanupScript,
     CodingAgent,
     DevServer,
+    VerifyScript,
+}
+
+/// Why a process was stopped by its profile's execution limits
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "execution_termination_reason", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ExecutionTerminationReason {
+    /// Ran longer than the configured wall-clock timeout
+    Timeout,
+    /// Produced no output for longer than the configured idle timeout
+    IdleTimeout,
+    /// Killed for exceeding the configured memory limit
+    MemoryLimit,
 }
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
@@ -68,6 +84,8 @@ pub struct ExecutionProcess {
     pub executor_action: sqlx::types::Json<ExecutorActionField>,
     pub status: ExecutionProcessStatus,
     pub exit_code: Option<i64>,
+    /// Set when the process was stopped for exceeding a timeout or resource limit
+    pub termination_reason: Option<ExecutionTerminationReason>,
     /// dropped: true if this process is excluded from the current
     /// history view (due to restore/trimming). Hidden from logs/timeline;
     /// still listed in the Processes tab.
@@ -143,6 +161,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.termination_reason as "termination_reason: ExecutionTerminationReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -217,6 +236,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.termination_reason as "termination_reason: ExecutionTerminationReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -244,6 +264,7 @@ impl ExecutionProcess {
                       ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                       ep.status          as "status!: ExecutionProcessStatus",
                       ep.exit_code,
+                      ep.termination_reason as "termination_reason: ExecutionTerminationReason",
                       ep.dropped as "dropped!: bool",
                       ep.started_at      as "started_at!: DateTime<Utc>",
                       ep.completed_at    as "completed_at?: DateTime<Utc>",
@@ -271,6 +292,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.termination_reason as "termination_reason: ExecutionTerminationReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -282,6 +304,70 @@ impl ExecutionProcess {
         .await
     }
 
+    /// Find queued execution processes, oldest first
+    pub async fn find_queued(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcess,
+            r#"SELECT
+                    ep.id as "id!: Uuid",
+                    ep.session_id as "session_id!: Uuid",
+                    ep.run_reason as "run_reason!: ExecutionProcessRunReason",
+                    ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
+                    ep.status as "status!: ExecutionProcessStatus",
+                    ep.exit_code,
+                    ep.termination_reason as "termination_reason: ExecutionTerminationReason",
+                    ep.dropped as "dropped!: bool",
+                    ep.started_at as "started_at!: DateTime<Utc>",
+                    ep.completed_at as "completed_at?: DateTime<Utc>",
+                    ep.created_at as "created_at!: DateTime<Utc>",
+                    ep.updated_at as "updated_at!: DateTime<Utc>"
+               FROM execution_processes ep WHERE ep.status = 'queued' ORDER BY ep.created_at ASC"#,
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn has_queued(pool: &SqlitePool) -> Result<bool, sqlx::Error> {
+        let count: i64 = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM execution_processes WHERE status = 'queued'"#
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(count > 0)
+    }
+
+    /// Move a queued process to running. Returns false if it is no longer queued
+    /// (e.g. it was cancelled in the meantime).
+    pub async fn mark_started(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = 'running', started_at = $1
+               WHERE id = $2 AND status = 'queued'"#,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cancel a process that is still waiting in the run queue. Returns false if
+    /// it has already been started.
+    pub async fn cancel_queued(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let now = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = 'killed', completed_at = $1, updated_at = $1
+               WHERE id = $2 AND status = 'queued'"#,
+            now,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Find running dev servers for a specific project
     pub async fn find_running_dev_servers_by_project(
         pool: &SqlitePool,
@@ -290,7 +376,7 @@ impl ExecutionProcess {
         sqlx::query_as!(
             ExecutionProcess,
             r#"SELECT ep.id as "id!: Uuid", ep.session_id as "session_id!: Uuid", ep.run_reason as "run_reason!: ExecutionProcessRunReason", ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
-                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code,
+                      ep.status as "status!: ExecutionProcessStatus", ep.exit_code, ep.termination_reason as "termination_reason: ExecutionTerminationReason",
                       ep.dropped as "dropped!: bool", ep.started_at as "started_at!: DateTime<Utc>", ep.completed_at as "completed_at?: DateTime<Utc>", ep.created_at as "created_at!: DateTime<Utc>", ep.updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
@@ -304,7 +390,7 @@ impl ExecutionProcess {
         .await
     }
 
-    /// Check if there are running processes (excluding dev servers) for a workspace (across all sessions)
+    /// Check if there are running or queued processes (excluding dev servers) for a workspace (across all sessions)
     pub async fn has_running_non_dev_server_processes_for_workspace(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -314,7 +400,7 @@ impl ExecutionProcess {
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
                WHERE s.workspace_id = $1
-                 AND ep.status = 'running'
+                 AND ep.status IN ('running', 'queued')
                  AND ep.run_reason != 'devserver'"#,
             workspace_id
         )
@@ -323,6 +409,26 @@ impl ExecutionProcess {
         Ok(count > 0)
     }
 
+    /// Check if there are running or queued processes (excluding dev servers) in any workspace of a task
+    pub async fn has_running_non_dev_server_processes_for_task(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let count: i64 = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               JOIN workspaces w ON s.workspace_id = w.id
+               WHERE w.task_id = $1
+                 AND ep.status IN ('running', 'queued')
+                 AND ep.run_reason != 'devserver'"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(count > 0)
+    }
+
     /// Find running dev servers for a specific workspace (across all sessions)
     pub async fn find_running_dev_servers_by_workspace(
         pool: &SqlitePool,
@@ -338,6 +444,7 @@ impl ExecutionProcess {
             ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
             ep.status as "status!: ExecutionProcessStatus",
             ep.exit_code,
+            ep.termination_reason as "termination_reason: ExecutionTerminationReason",
             ep.dropped as "dropped!: bool",
             ep.started_at as "started_at!: DateTime<Utc>",
             ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -400,6 +507,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.termination_reason as "termination_reason: ExecutionTerminationReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -430,6 +538,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.termination_reason as "termination_reason: ExecutionTerminationReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -457,6 +566,7 @@ impl ExecutionProcess {
         pool: &SqlitePool,
         data: &CreateExecutionProcess,
         process_id: Uuid,
+        status: ExecutionProcessStatus,
         repo_states: &[CreateExecutionProcessRepoState],
     ) -> Result<Self, sqlx::Error> {
         let now = Utc::now();
@@ -471,7 +581,7 @@ impl ExecutionProcess {
             data.session_id,
             data.run_reason,
             executor_action_json,
-            ExecutionProcessStatus::Running,
+            status,
             None::<i64>,
             now,
             None::<DateTime<Utc>>,
@@ -528,6 +638,25 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    /// Record that the process was stopped by an execution limit
+    pub async fn set_termination_reason(
+        pool: &SqlitePool,
+        id: Uuid,
+        reason: ExecutionTerminationReason,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET termination_reason = $1
+               WHERE id = $2"#,
+            reason,
+            id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub fn executor_action(&self) -> Result<&ExecutorAction, anyhow::Error> {
         match &self.executor_action.0 {
             ExecutorActionField::ExecutorAction(action) => Ok(action),
@@ -557,6 +686,26 @@ impl ExecutionProcess {
         Ok(result.rows_affected() as i64)
     }
 
+    /// Soft-drop the processes of a session that started after the boundary process
+    pub async fn drop_after(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        boundary_process_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET dropped = TRUE
+             WHERE session_id = $1
+               AND created_at > (SELECT created_at FROM execution_processes WHERE id = $2)
+               AND dropped = FALSE"#,
+            session_id,
+            boundary_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() as i64)
+    }
+
     /// Find the previous process's after_head_commit before the given boundary process
     /// for a specific repository
     pub async fn find_prev_after_head_commit(
@@ -657,6 +806,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.termination_reason as "termination_reason: ExecutionTerminationReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -12,6 +12,9 @@ pub struct ExecutionProcessRepoState {
     pub before_head_commit: Option<String>,
     pub after_head_commit: Option<String>,
     pub merge_commit: Option<String>,
+    /// Snapshot of the worktree, untracked files included, taken when a coding
+    /// agent turn ended. Its parent is the HEAD at that time.
+    pub after_snapshot_commit: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -133,6 +136,28 @@ impl ExecutionProcessRepoState {
         Ok(())
     }
 
+    pub async fn update_after_snapshot_commit(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        repo_id: Uuid,
+        after_snapshot_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE execution_process_repo_states
+               SET after_snapshot_commit = $1, updated_at = $2
+             WHERE execution_process_id = $3
+               AND repo_id = $4"#,
+            after_snapshot_commit,
+            now,
+            execution_process_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn find_by_execution_process_id(
         pool: &SqlitePool,
         execution_process_id: Uuid,
@@ -146,6 +171,7 @@ impl ExecutionProcessRepoState {
                     before_head_commit,
                     after_head_commit,
                     merge_commit,
+                    after_snapshot_commit,
                     created_at as "created_at!: DateTime<Utc>",
                     updated_at as "updated_at!: DateTime<Utc>"
                FROM execution_process_repo_states
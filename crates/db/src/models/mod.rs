@@ -1,15 +1,32 @@
+pub mod approval_request;
+pub mod approval_rule;
 pub mod coding_agent_turn;
+pub mod coding_agent_turn_usage;
+pub mod conflict_resolution;
+pub mod dev_server_status;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
 pub mod image;
 pub mod merge;
 pub mod project;
+pub mod project_container_config;
+pub mod project_memory;
 pub mod project_repo;
+pub mod queued_message;
 pub mod repo;
 pub mod scratch;
+pub mod search;
 pub mod session;
 pub mod tag;
 pub mod task;
+pub mod task_archive;
+pub mod task_dependency;
+pub mod task_schedule;
+pub mod task_template;
 pub mod workspace;
+pub mod workspace_drift;
+pub mod workspace_race;
 pub mod workspace_repo;
+pub mod workspace_stack;
+pub mod workspace_verification;
@@ -1,5 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_with::rust::double_option;
 use sqlx::{Executor, FromRow, Sqlite, SqlitePool};
 use thiserror::Error;
 use ts_rs::TS;
@@ -23,6 +24,12 @@ pub struct Project {
     pub name: String,
     pub default_agent_working_dir: Option<String>,
     pub remote_project_id: Option<Uuid>,
+    /// Rebase workspaces onto their target branch when it moves and the rebase
+    /// would apply cleanly
+    pub auto_rebase_on_drift: bool,
+    /// Repo file, such as `AGENTS.md`, the accepted project memory is mirrored
+    /// into, relative to the root of each repo
+    pub memory_file: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -38,6 +45,16 @@ pub struct CreateProject {
 #[derive(Debug, Deserialize, TS)]
 pub struct UpdateProject {
     pub name: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub auto_rebase_on_drift: Option<bool>,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub memory_file: Option<Option<String>>,
 }
 
 #[derive(Debug, Serialize, TS)]
@@ -71,6 +88,8 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      auto_rebase_on_drift as "auto_rebase_on_drift!: bool",
+                      memory_file,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -88,6 +107,8 @@ impl Project {
             SELECT p.id as "id!: Uuid", p.name,
                    p.default_agent_working_dir,
                    p.remote_project_id as "remote_project_id: Uuid",
+                   p.auto_rebase_on_drift as "auto_rebase_on_drift!: bool",
+                   p.memory_file,
                    p.created_at as "created_at!: DateTime<Utc>", p.updated_at as "updated_at!: DateTime<Utc>"
             FROM projects p
             WHERE p.id IN (
@@ -111,6 +132,8 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      auto_rebase_on_drift as "auto_rebase_on_drift!: bool",
+                      memory_file,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -128,6 +151,8 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      auto_rebase_on_drift as "auto_rebase_on_drift!: bool",
+                      memory_file,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -148,6 +173,8 @@ impl Project {
                       name,
                       default_agent_working_dir,
                       remote_project_id as "remote_project_id: Uuid",
+                      auto_rebase_on_drift as "auto_rebase_on_drift!: bool",
+                      memory_file,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM projects
@@ -176,6 +203,8 @@ impl Project {
                           name,
                           default_agent_working_dir,
                           remote_project_id as "remote_project_id: Uuid",
+                          auto_rebase_on_drift as "auto_rebase_on_drift!: bool",
+                          memory_file,
                           created_at as "created_at!: DateTime<Utc>",
                           updated_at as "updated_at!: DateTime<Utc>""#,
             project_id,
@@ -195,20 +224,31 @@ impl Project {
             .ok_or(sqlx::Error::RowNotFound)?;
 
         let name = payload.name.clone().unwrap_or(existing.name);
+        let auto_rebase_on_drift = payload
+            .auto_rebase_on_drift
+            .unwrap_or(existing.auto_rebase_on_drift);
+        let memory_file = match &payload.memory_file {
+            None => existing.memory_file,
+            Some(v) => v.clone(),
+        };
 
         sqlx::query_as!(
             Project,
             r#"UPDATE projects
-               SET name = $2
+               SET name = $2, auto_rebase_on_drift = $3, memory_file = $4
                WHERE id = $1
                RETURNING id as "id!: Uuid",
                          name,
                          default_agent_working_dir,
                          remote_project_id as "remote_project_id: Uuid",
+                         auto_rebase_on_drift as "auto_rebase_on_drift!: bool",
+                         memory_file,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
             name,
+            auto_rebase_on_drift,
+            memory_file,
         )
         .fetch_one(pool)
         .await
@@ -0,0 +1,138 @@
+use chrono::{DateTime, Utc};
+use executors::sandbox::ContainerRuntime;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, FromRow)]
+struct ProjectContainerConfigRow {
+    project_id: Uuid,
+    enabled: bool,
+    runtime: ContainerRuntime,
+    image: String,
+    network_enabled: bool,
+    env_passthrough: Json<Vec<String>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// Runs a project's executions in a Docker/Podman container instead of on the
+/// host. Projects without one, or with it disabled, run on the host.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectContainerConfig {
+    pub project_id: Uuid,
+    pub enabled: bool,
+    pub runtime: ContainerRuntime,
+    /// Image every execution's container is started from
+    pub image: String,
+    /// Share the host network; when off the container has no network at all
+    pub network_enabled: bool,
+    /// Host environment variables to forward into the container, e.g. API keys
+    pub env_passthrough: Vec<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<ProjectContainerConfigRow> for ProjectContainerConfig {
+    fn from(row: ProjectContainerConfigRow) -> Self {
+        Self {
+            project_id: row.project_id,
+            enabled: row.enabled,
+            runtime: row.runtime,
+            image: row.image,
+            network_enabled: row.network_enabled,
+            env_passthrough: row.env_passthrough.0,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateProjectContainerConfig {
+    pub enabled: Option<bool>,
+    pub runtime: Option<ContainerRuntime>,
+    pub image: String,
+    pub network_enabled: Option<bool>,
+    pub env_passthrough: Option<Vec<String>>,
+}
+
+impl ProjectContainerConfig {
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            ProjectContainerConfigRow,
+            r#"SELECT project_id as "project_id!: Uuid",
+                      enabled as "enabled!: bool",
+                      runtime as "runtime!: ContainerRuntime",
+                      image,
+                      network_enabled as "network_enabled!: bool",
+                      env_passthrough as "env_passthrough!: Json<Vec<String>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_container_configs
+               WHERE project_id = $1"#,
+            project_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    /// Create or replace the project's container configuration
+    pub async fn upsert(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        data: &UpdateProjectContainerConfig,
+    ) -> Result<Self, sqlx::Error> {
+        let enabled = data.enabled.unwrap_or(true);
+        let runtime = data.runtime.unwrap_or(ContainerRuntime::Docker);
+        let network_enabled = data.network_enabled.unwrap_or(true);
+        let env_passthrough = Json(data.env_passthrough.clone().unwrap_or_default());
+        let row = sqlx::query_as!(
+            ProjectContainerConfigRow,
+            r#"INSERT INTO project_container_configs
+                   (project_id, enabled, runtime, image, network_enabled, env_passthrough)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               ON CONFLICT(project_id) DO UPDATE SET
+                   enabled = excluded.enabled,
+                   runtime = excluded.runtime,
+                   image = excluded.image,
+                   network_enabled = excluded.network_enabled,
+                   env_passthrough = excluded.env_passthrough,
+                   updated_at = datetime('now', 'subsec')
+               RETURNING project_id as "project_id!: Uuid",
+                         enabled as "enabled!: bool",
+                         runtime as "runtime!: ContainerRuntime",
+                         image,
+                         network_enabled as "network_enabled!: bool",
+                         env_passthrough as "env_passthrough!: Json<Vec<String>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            project_id,
+            enabled,
+            runtime,
+            data.image,
+            network_enabled,
+            env_passthrough
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.into())
+    }
+
+    pub async fn delete(pool: &SqlitePool, project_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM project_container_configs WHERE project_id = $1",
+            project_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,202 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(
+    Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, EnumString, Display,
+)]
+#[sqlx(type_name = "project_memory_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ProjectMemoryStatus {
+    /// Proposed from the summary of a completed task, waiting to be accepted
+    /// or discarded
+    Candidate,
+    Accepted,
+    Discarded,
+}
+
+/// Knowledge about a project, such as a convention or a pitfall, prepended to
+/// the prompt of every new coding agent session once accepted
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectMemoryEntry {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub content: String,
+    pub status: ProjectMemoryStatus,
+    /// Workspace whose task summary the entry was proposed from
+    pub workspace_id: Option<Uuid>,
+    pub coding_agent_turn_id: Option<Uuid>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateProjectMemoryEntry {
+    pub project_id: Uuid,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct UpdateProjectMemoryEntry {
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct AcceptProjectMemoryEntry {
+    /// Edited content to accept instead of the proposed one
+    #[serde(default)]
+    #[ts(optional)]
+    pub content: Option<String>,
+}
+
+impl ProjectMemoryEntry {
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMemoryEntry,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      content,
+                      status as "status!: ProjectMemoryStatus",
+                      workspace_id as "workspace_id: Uuid",
+                      coding_agent_turn_id as "coding_agent_turn_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_memory_entries
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Entries of the project, oldest first, optionally only those with the
+    /// given status
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        status: Option<ProjectMemoryStatus>,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMemoryEntry,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      content,
+                      status as "status!: ProjectMemoryStatus",
+                      workspace_id as "workspace_id: Uuid",
+                      coding_agent_turn_id as "coding_agent_turn_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM project_memory_entries
+               WHERE project_id = $1 AND ($2 IS NULL OR status = $2)
+               ORDER BY created_at ASC"#,
+            project_id,
+            status
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Add an accepted entry written by hand
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateProjectMemoryEntry,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMemoryEntry,
+            r#"INSERT INTO project_memory_entries (id, project_id, content, status)
+               VALUES ($1, $2, $3, 'accepted')
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         content,
+                         status as "status!: ProjectMemoryStatus",
+                         workspace_id as "workspace_id: Uuid",
+                         coding_agent_turn_id as "coding_agent_turn_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.content
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Propose a candidate from a coding agent turn. Each turn is proposed at
+    /// most once, so a discarded candidate does not come back; `None` when
+    /// the turn was already proposed.
+    pub async fn propose(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        workspace_id: Uuid,
+        coding_agent_turn_id: Uuid,
+        content: &str,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            ProjectMemoryEntry,
+            r#"INSERT INTO project_memory_entries
+                   (id, project_id, content, status, workspace_id, coding_agent_turn_id)
+               VALUES ($1, $2, $3, 'candidate', $4, $5)
+               ON CONFLICT(coding_agent_turn_id) DO NOTHING
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         content,
+                         status as "status!: ProjectMemoryStatus",
+                         workspace_id as "workspace_id: Uuid",
+                         coding_agent_turn_id as "coding_agent_turn_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            project_id,
+            content,
+            workspace_id,
+            coding_agent_turn_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Change the status, and the content when given
+    pub async fn update(
+        pool: &SqlitePool,
+        id: Uuid,
+        status: ProjectMemoryStatus,
+        content: Option<&str>,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            ProjectMemoryEntry,
+            r#"UPDATE project_memory_entries
+               SET status = $2,
+                   content = COALESCE($3, content),
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         content,
+                         status as "status!: ProjectMemoryStatus",
+                         workspace_id as "workspace_id: Uuid",
+                         coding_agent_turn_id as "coding_agent_turn_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            status,
+            content
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM project_memory_entries WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -83,6 +83,10 @@ impl ProjectRepo {
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
+                      r.dev_server_health_check,
+                      r.dev_server_auto_restart as "dev_server_auto_restart!: bool",
+                      r.verify_script,
+                      r.verify_max_retries as "verify_max_retries!: u32",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
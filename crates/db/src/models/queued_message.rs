@@ -0,0 +1,232 @@
+use std::collections::HashSet;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::scratch::DraftFollowUpData;
+
+#[derive(Debug, Error)]
+pub enum QueuedMessageError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Queued message not found")]
+    NotFound,
+    #[error("New order must list every queued message of the session exactly once")]
+    InvalidOrder,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct QueuedMessageRow {
+    id: Uuid,
+    session_id: Uuid,
+    data: Json<DraftFollowUpData>,
+    created_at: DateTime<Utc>,
+}
+
+/// Represents a queued follow-up message for a session
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct QueuedMessage {
+    pub id: Uuid,
+    /// The session this message is queued for
+    pub session_id: Uuid,
+    /// The follow-up data (message + variant)
+    pub data: DraftFollowUpData,
+    /// Timestamp when the message was queued
+    pub queued_at: DateTime<Utc>,
+}
+
+impl From<QueuedMessageRow> for QueuedMessage {
+    fn from(row: QueuedMessageRow) -> Self {
+        Self {
+            id: row.id,
+            session_id: row.session_id,
+            data: row.data.0,
+            queued_at: row.created_at,
+        }
+    }
+}
+
+impl QueuedMessage {
+    /// Queued messages of a session in the order they will be sent
+    pub async fn find_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            QueuedMessageRow,
+            r#"SELECT id as "id!: Uuid",
+                      session_id as "session_id!: Uuid",
+                      data as "data!: Json<DraftFollowUpData>",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM queued_messages
+               WHERE session_id = $1
+               ORDER BY position ASC"#,
+            session_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    pub async fn has_queued(pool: &SqlitePool, session_id: Uuid) -> Result<bool, sqlx::Error> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM queued_messages WHERE session_id = $1"#,
+            session_id
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(count > 0)
+    }
+
+    /// Append a message to the end of the session's queue
+    pub async fn enqueue(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        let data = Json(data);
+        let row = sqlx::query_as!(
+            QueuedMessageRow,
+            r#"INSERT INTO queued_messages (id, session_id, position, data)
+               VALUES (
+                   $1,
+                   $2,
+                   (SELECT COALESCE(MAX(position), -1) + 1 FROM queued_messages WHERE session_id = $2),
+                   $3
+               )
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         data as "data!: Json<DraftFollowUpData>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            session_id,
+            data
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// Replace the contents of a queued message, keeping its position
+    pub async fn update_data(
+        pool: &SqlitePool,
+        id: Uuid,
+        session_id: Uuid,
+        data: &DraftFollowUpData,
+    ) -> Result<Self, QueuedMessageError> {
+        let data = Json(data);
+        let row = sqlx::query_as!(
+            QueuedMessageRow,
+            r#"UPDATE queued_messages
+               SET data = $3, updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND session_id = $2
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         data as "data!: Json<DraftFollowUpData>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            session_id,
+            data
+        )
+        .fetch_optional(pool)
+        .await?
+        .ok_or(QueuedMessageError::NotFound)?;
+
+        Ok(row.into())
+    }
+
+    /// Rewrite queue positions to follow `ordered_ids`, which must be a
+    /// permutation of the session's queued message ids.
+    pub async fn reorder(
+        pool: &SqlitePool,
+        session_id: Uuid,
+        ordered_ids: &[Uuid],
+    ) -> Result<Vec<Self>, QueuedMessageError> {
+        let mut tx = pool.begin().await?;
+
+        let current: HashSet<Uuid> = sqlx::query_scalar!(
+            r#"SELECT id as "id!: Uuid" FROM queued_messages WHERE session_id = $1"#,
+            session_id
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+        let requested: HashSet<Uuid> = ordered_ids.iter().copied().collect();
+        if requested.len() != ordered_ids.len() || requested != current {
+            return Err(QueuedMessageError::InvalidOrder);
+        }
+
+        for (position, id) in ordered_ids.iter().enumerate() {
+            let position = position as i64;
+            sqlx::query!(
+                "UPDATE queued_messages SET position = $1 WHERE id = $2",
+                position,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(Self::find_by_session_id(pool, session_id).await?)
+    }
+
+    /// Remove and return the message at the head of the session's queue
+    pub async fn take_next(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            QueuedMessageRow,
+            r#"DELETE FROM queued_messages
+               WHERE id = (
+                   SELECT id FROM queued_messages
+                   WHERE session_id = $1
+                   ORDER BY position ASC
+                   LIMIT 1
+               )
+               RETURNING id as "id!: Uuid",
+                         session_id as "session_id!: Uuid",
+                         data as "data!: Json<DraftFollowUpData>",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            session_id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(Self::from))
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid, session_id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_messages WHERE id = $1 AND session_id = $2",
+            id,
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete_by_session_id(
+        pool: &SqlitePool,
+        session_id: Uuid,
+    ) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!(
+            "DELETE FROM queued_messages WHERE session_id = $1",
+            session_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -28,6 +28,16 @@ pub struct Repo {
     pub parallel_setup_script: bool,
     pub dev_server_script: Option<String>,
     pub default_target_branch: Option<String>,
+    /// Path (e.g. `/health`) or full URL probed over HTTP to tell when the dev
+    /// server is ready. Without it the server counts as ready once it listens.
+    pub dev_server_health_check: Option<String>,
+    /// Restart the dev server with backoff when it crashes
+    pub dev_server_auto_restart: bool,
+    /// Script (tests, lint, typecheck) run after the coding agent; a task only
+    /// counts as done once it passes
+    pub verify_script: Option<String>,
+    /// Automatic follow-ups asking the agent to fix a failed verification
+    pub verify_max_retries: u32,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -92,6 +102,34 @@ pub struct UpdateRepo {
     )]
     #[ts(optional, type = "string | null")]
     pub default_target_branch: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub dev_server_health_check: Option<Option<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub dev_server_auto_restart: Option<bool>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub verify_script: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub verify_max_retries: Option<Option<u32>>,
 }
 
 impl Repo {
@@ -110,6 +148,10 @@ impl Repo {
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
+                      dev_server_health_check,
+                      dev_server_auto_restart as "dev_server_auto_restart!: bool",
+                      verify_script,
+                      verify_max_retries as "verify_max_retries!: u32",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -149,6 +191,10 @@ impl Repo {
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
+                      dev_server_health_check,
+                      dev_server_auto_restart as "dev_server_auto_restart!: bool",
+                      verify_script,
+                      verify_max_retries as "verify_max_retries!: u32",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -205,6 +251,10 @@ impl Repo {
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
                          default_target_branch,
+                         dev_server_health_check,
+                         dev_server_auto_restart as "dev_server_auto_restart!: bool",
+                         verify_script,
+                         verify_max_retries as "verify_max_retries!: u32",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -240,6 +290,10 @@ impl Repo {
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
+                      dev_server_health_check,
+                      dev_server_auto_restart as "dev_server_auto_restart!: bool",
+                      verify_script,
+                      verify_max_retries as "verify_max_retries!: u32",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -289,6 +343,21 @@ impl Repo {
             None => existing.default_target_branch,
             Some(v) => v.clone(),
         };
+        let dev_server_health_check = match &payload.dev_server_health_check {
+            None => existing.dev_server_health_check,
+            Some(v) => v.clone(),
+        };
+        let dev_server_auto_restart = payload
+            .dev_server_auto_restart
+            .unwrap_or(existing.dev_server_auto_restart);
+        let verify_script = match &payload.verify_script {
+            None => existing.verify_script,
+            Some(v) => v.clone(),
+        };
+        let verify_max_retries = match &payload.verify_max_retries {
+            None => existing.verify_max_retries,
+            Some(v) => v.unwrap_or(0),
+        };
 
         sqlx::query_as!(
             Repo,
@@ -300,8 +369,12 @@ impl Repo {
                    parallel_setup_script = $5,
                    dev_server_script = $6,
                    default_target_branch = $7,
+                   dev_server_health_check = $8,
+                   dev_server_auto_restart = $9,
+                   verify_script = $10,
+                   verify_max_retries = $11,
                    updated_at = datetime('now', 'subsec')
-               WHERE id = $8
+               WHERE id = $12
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -312,6 +385,10 @@ impl Repo {
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
                          default_target_branch,
+                         dev_server_health_check,
+                         dev_server_auto_restart as "dev_server_auto_restart!: bool",
+                         verify_script,
+                         verify_max_retries as "verify_max_retries!: u32",
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             display_name,
@@ -321,6 +398,10 @@ impl Repo {
             parallel_setup_script,
             dev_server_script,
             default_target_branch,
+            dev_server_health_check,
+            dev_server_auto_restart,
+            verify_script,
+            verify_max_retries,
             id
         )
         .fetch_one(pool)
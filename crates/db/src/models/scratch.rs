@@ -22,6 +22,11 @@ pub enum ScratchError {
 pub struct DraftFollowUpData {
     pub message: String,
     pub executor_profile_id: ExecutorProfileId,
+    /// Start a code review with `message` as extra instructions instead of
+    /// sending it as a follow-up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub review: Option<bool>,
 }
 
 /// Data for preview settings scratch (URL override and screen size)
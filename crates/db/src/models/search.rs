@@ -0,0 +1,166 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::Display;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// What a search hit was found in: a task's title or description, the prompt or
+/// summary of a coding agent turn, or an entry of an execution's conversation log
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS, Display)]
+#[sqlx(type_name = "search_result_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum SearchResultKind {
+    Task,
+    Turn,
+    Log,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct SearchResult {
+    pub kind: SearchResultKind,
+    pub task_id: Uuid,
+    pub task_title: String,
+    pub project_id: Uuid,
+    /// Set for turn and log hits
+    pub workspace_id: Option<Uuid>,
+    /// Set for turn and log hits
+    pub execution_process_id: Option<Uuid>,
+    /// Matching excerpt, with matched terms wrapped in `<mark>` tags
+    pub snippet: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Finished coding agent execution whose stored logs were never indexed
+#[derive(Debug, Clone, FromRow)]
+pub struct UnindexedLogExecution {
+    pub execution_process_id: Uuid,
+    pub task_id: Uuid,
+    pub started_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilters {
+    pub project_id: Option<Uuid>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Turn free text into an FTS5 query matching all of its words, so characters
+/// like `-` or `:` are not parsed as query syntax. The last word also matches
+/// as a prefix.
+pub fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    let (last, rest) = terms.split_last()?;
+    let mut query = rest.join(" ");
+    if !query.is_empty() {
+        query.push(' ');
+    }
+    query.push_str(last);
+    query.push('*');
+    Some(query)
+}
+
+pub struct SearchIndex;
+
+impl SearchIndex {
+    /// Search tasks, turns and execution logs, best matches first
+    pub async fn search(
+        pool: &SqlitePool,
+        query: &str,
+        filters: &SearchFilters,
+        limit: i64,
+    ) -> Result<Vec<SearchResult>, sqlx::Error> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        sqlx::query_as!(
+            SearchResult,
+            r#"SELECT search_index.kind                 AS "kind!: SearchResultKind",
+                      t.id                              AS "task_id!: Uuid",
+                      t.title                           AS "task_title!",
+                      t.project_id                      AS "project_id!: Uuid",
+                      s.workspace_id                    AS "workspace_id?: Uuid",
+                      search_index.execution_process_id AS "execution_process_id?: Uuid",
+                      snippet(search_index, -1, '<mark>', '</mark>', '…', 16) AS "snippet!: String",
+                      search_index.created_at           AS "created_at!: DateTime<Utc>"
+               FROM search_index
+               JOIN tasks t ON t.id = search_index.task_id
+               LEFT JOIN execution_processes ep ON ep.id = search_index.execution_process_id
+               LEFT JOIN sessions s ON s.id = ep.session_id
+               WHERE search_index MATCH $1
+                 AND ($2 IS NULL OR t.project_id = $2)
+                 AND ($3 IS NULL OR datetime(search_index.created_at) >= datetime($3))
+                 AND ($4 IS NULL OR datetime(search_index.created_at) <= datetime($4))
+               ORDER BY bm25(search_index)
+               LIMIT $5"#,
+            fts_query,
+            filters.project_id,
+            filters.from,
+            filters.to,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Replace the indexed log entries of an execution process
+    pub async fn replace_log_entries(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        task_id: Uuid,
+        created_at: DateTime<Utc>,
+        entries: &[String],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        sqlx::query!(
+            r#"DELETE FROM search_index
+               WHERE kind = 'log' AND execution_process_id = $1"#,
+            execution_process_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        for entry in entries {
+            sqlx::query!(
+                r#"INSERT INTO search_index (title, body, kind, task_id, execution_process_id, created_at)
+                   VALUES ('', $1, 'log', $2, $3, $4)"#,
+                entry,
+                task_id,
+                execution_process_id,
+                created_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+    /// Finished coding agent executions that have stored logs but no indexed
+    /// log entries, e.g. those that ran before the index existed
+    pub async fn find_unindexed_log_executions(
+        pool: &SqlitePool,
+    ) -> Result<Vec<UnindexedLogExecution>, sqlx::Error> {
+        sqlx::query_as!(
+            UnindexedLogExecution,
+            r#"SELECT ep.id         AS "execution_process_id!: Uuid",
+                      w.task_id     AS "task_id!: Uuid",
+                      ep.started_at AS "started_at!: DateTime<Utc>"
+               FROM execution_processes ep
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE ep.run_reason = 'codingagent'
+                 AND ep.status NOT IN ('running', 'queued')
+                 AND EXISTS (SELECT 1 FROM execution_process_logs l WHERE l.execution_id = ep.id)
+                 AND ep.id NOT IN (
+                     SELECT execution_process_id FROM search_index
+                     WHERE kind = 'log' AND execution_process_id IS NOT NULL
+                 )
+               ORDER BY ep.started_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+}
@@ -5,7 +5,7 @@ use strum_macros::{Display, EnumString};
 use ts_rs::TS;
 use uuid::Uuid;
 
-use super::{project::Project, workspace::Workspace};
+use super::{project::Project, task_template::TemplateInstance, workspace::Workspace};
 
 #[derive(
     Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS, EnumString, Display, Default,
@@ -42,6 +42,10 @@ pub struct TaskWithAttemptStatus {
     pub has_in_progress_attempt: bool,
     pub last_attempt_failed: bool,
     pub executor: String,
+    /// 1-based position in the coding agent run queue, if an attempt is waiting for a slot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional, type = "number")]
+    pub queue_position: Option<i64>,
 }
 
 impl std::ops::Deref for TaskWithAttemptStatus {
@@ -72,6 +76,11 @@ pub struct CreateTask {
     pub status: Option<TaskStatus>,
     pub parent_workspace_id: Option<Uuid>,
     pub image_ids: Option<Vec<Uuid>>,
+    /// Template to create the task from. Its rendered title and description
+    /// are used when `title` is empty and `description` is unset.
+    #[serde(default)]
+    #[ts(optional)]
+    pub template: Option<TemplateInstance>,
 }
 
 impl CreateTask {
@@ -87,6 +96,7 @@ impl CreateTask {
             status: Some(TaskStatus::Todo),
             parent_workspace_id: None,
             image_ids: None,
+            template: None,
         }
     }
 }
@@ -134,7 +144,7 @@ impl Task {
       JOIN sessions s ON s.workspace_id = w.id
       JOIN execution_processes ep ON ep.session_id = s.id
      WHERE w.task_id       = t.id
-       AND ep.status        = 'running'
+       AND ep.status       IN ('running', 'queued')
        AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
      LIMIT 1
   ) THEN 1 ELSE 0 END            AS "has_in_progress_attempt!: i64",
@@ -157,7 +167,20 @@ impl Task {
       WHERE w.task_id = t.id
      ORDER BY s.created_at DESC
       LIMIT 1
-    )                               AS "executor!: String"
+    )                               AS "executor!: String",
+
+  ( SELECT COUNT(*)
+      FROM execution_processes q
+     WHERE q.status = 'queued'
+       AND q.created_at <= (
+         SELECT MIN(ep.created_at)
+           FROM workspaces w
+           JOIN sessions s ON s.workspace_id = w.id
+           JOIN execution_processes ep ON ep.session_id = s.id
+          WHERE w.task_id   = t.id
+            AND ep.status   = 'queued'
+       )
+  )                               AS "queue_position!: i64"
 
 FROM tasks t
 WHERE t.project_id = $1
@@ -183,6 +206,7 @@ ORDER BY t.updated_at DESC"#,
                 has_in_progress_attempt: rec.has_in_progress_attempt != 0,
                 last_attempt_failed: rec.last_attempt_failed != 0,
                 executor: rec.executor,
+                queue_position: (rec.queue_position > 0).then_some(rec.queue_position),
             })
             .collect();
 
@@ -273,19 +297,28 @@ ORDER BY t.updated_at DESC"#,
         .await
     }
 
+    /// Set the status of a task, returning the status it had before
     pub async fn update_status(
         pool: &SqlitePool,
         id: Uuid,
         status: TaskStatus,
-    ) -> Result<(), sqlx::Error> {
+    ) -> Result<Option<TaskStatus>, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+        let previous = sqlx::query_scalar!(
+            r#"SELECT status as "status!: TaskStatus" FROM tasks WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
         sqlx::query!(
             "UPDATE tasks SET status = $2, updated_at = CURRENT_TIMESTAMP WHERE id = $1",
             id,
             status
         )
-        .execute(pool)
+        .execute(&mut *tx)
         .await?;
-        Ok(())
+        tx.commit().await?;
+        Ok(previous)
     }
 
     /// Update the parent_workspace_id field for a task
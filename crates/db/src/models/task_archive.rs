@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+use super::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    image::Image,
+    session::Session,
+    task::Task,
+    workspace::{Workspace, WorkspaceError},
+    workspace_repo::WorkspaceRepo,
+};
+
+/// Repository of a workspace, referenced by name since repository ids differ
+/// between machines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedWorkspaceRepo {
+    pub workspace_id: Uuid,
+    pub repo_name: String,
+    pub target_branch: String,
+}
+
+/// Database records of a task and of all attempts made at it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskRecords {
+    pub task: Task,
+    pub images: Vec<Image>,
+    pub workspaces: Vec<Workspace>,
+    pub workspace_repos: Vec<ArchivedWorkspaceRepo>,
+    pub sessions: Vec<Session>,
+    pub execution_processes: Vec<ExecutionProcess>,
+    pub coding_agent_turns: Vec<CodingAgentTurn>,
+}
+
+impl TaskRecords {
+    pub async fn load(pool: &SqlitePool, task_id: Uuid) -> Result<Option<Self>, WorkspaceError> {
+        let Some(task) = Task::find_by_id(pool, task_id).await? else {
+            return Ok(None);
+        };
+        let images = Image::find_by_task_id(pool, task_id).await?;
+        let mut workspaces = Workspace::fetch_all(pool, Some(task_id)).await?;
+        // Oldest first, the order attempts were made in
+        workspaces.reverse();
+
+        let mut workspace_repos = Vec::new();
+        let mut sessions = Vec::new();
+        let mut execution_processes = Vec::new();
+        let mut coding_agent_turns = Vec::new();
+        for workspace in &workspaces {
+            for repo in
+                WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id)
+                    .await?
+            {
+                workspace_repos.push(ArchivedWorkspaceRepo {
+                    workspace_id: workspace.id,
+                    repo_name: repo.repo.name,
+                    target_branch: repo.target_branch,
+                });
+            }
+            for session in Session::find_by_workspace_id(pool, workspace.id).await? {
+                for process in ExecutionProcess::find_by_session_id(pool, session.id, true).await? {
+                    if let Some(turn) =
+                        CodingAgentTurn::find_by_execution_process_id(pool, process.id).await?
+                    {
+                        coding_agent_turns.push(turn);
+                    }
+                    execution_processes.push(process);
+                }
+                sessions.push(session);
+            }
+        }
+
+        Ok(Some(Self {
+            task,
+            images,
+            workspaces,
+            workspace_repos,
+            sessions,
+            execution_processes,
+            coding_agent_turns,
+        }))
+    }
+
+    /// Insert a copy of the records with new ids as a task of `project_id`.
+    ///
+    /// `repo_ids` maps repository names to repositories of the target project;
+    /// workspace repositories without a match are left out. `logs` holds the
+    /// JSONL logs of the execution processes, keyed by their archived ids.
+    /// Workspaces are imported without a container and processes that were
+    /// still running are marked as killed.
+    pub async fn insert_copy(
+        &self,
+        pool: &SqlitePool,
+        project_id: Uuid,
+        repo_ids: &HashMap<String, Uuid>,
+        image_ids: &[Uuid],
+        logs: &HashMap<Uuid, String>,
+    ) -> Result<Task, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let task = &self.task;
+        let task_id = Uuid::new_v4();
+        sqlx::query!(
+            r#"INSERT INTO tasks (id, project_id, title, description, status, created_at, updated_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            task_id,
+            project_id,
+            task.title,
+            task.description,
+            task.status,
+            task.created_at,
+            task.updated_at
+        )
+        .execute(&mut *tx)
+        .await?;
+        for image_id in image_ids {
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO task_images (id, task_id, image_id) VALUES ($1, $2, $3)"#,
+                id,
+                task_id,
+                image_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let mut workspace_ids = HashMap::new();
+        for workspace in &self.workspaces {
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO workspaces (
+                    id, task_id, container_ref, branch, agent_working_dir, setup_completed_at,
+                    created_at, updated_at, archived, pinned, name
+                   )
+                   VALUES ($1, $2, NULL, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+                id,
+                task_id,
+                workspace.branch,
+                workspace.agent_working_dir,
+                workspace.setup_completed_at,
+                workspace.created_at,
+                workspace.updated_at,
+                workspace.archived,
+                workspace.pinned,
+                workspace.name
+            )
+            .execute(&mut *tx)
+            .await?;
+            workspace_ids.insert(workspace.id, id);
+        }
+        for workspace_repo in &self.workspace_repos {
+            let (Some(workspace_id), Some(repo_id)) = (
+                workspace_ids.get(&workspace_repo.workspace_id),
+                repo_ids.get(&workspace_repo.repo_name),
+            ) else {
+                continue;
+            };
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO workspace_repos (id, workspace_id, repo_id, target_branch)
+                   VALUES ($1, $2, $3, $4)"#,
+                id,
+                workspace_id,
+                repo_id,
+                workspace_repo.target_branch
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let mut session_ids = HashMap::new();
+        for session in &self.sessions {
+            let Some(workspace_id) = workspace_ids.get(&session.workspace_id) else {
+                continue;
+            };
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO sessions (id, workspace_id, executor, created_at, updated_at)
+                   VALUES ($1, $2, $3, $4, $5)"#,
+                id,
+                workspace_id,
+                session.executor,
+                session.created_at,
+                session.updated_at
+            )
+            .execute(&mut *tx)
+            .await?;
+            session_ids.insert(session.id, id);
+        }
+
+        let mut execution_process_ids = HashMap::new();
+        for process in &self.execution_processes {
+            let Some(session_id) = session_ids.get(&process.session_id) else {
+                continue;
+            };
+            let id = Uuid::new_v4();
+            let status = match &process.status {
+                ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running => {
+                    ExecutionProcessStatus::Killed
+                }
+                status => status.clone(),
+            };
+            let completed_at = process.completed_at.unwrap_or(process.updated_at);
+            sqlx::query!(
+                r#"INSERT INTO execution_processes (
+                    id, session_id, run_reason, executor_action, status, exit_code,
+                    termination_reason, dropped, started_at, completed_at, created_at, updated_at
+                   )
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)"#,
+                id,
+                session_id,
+                process.run_reason,
+                process.executor_action,
+                status,
+                process.exit_code,
+                process.termination_reason,
+                process.dropped,
+                process.started_at,
+                completed_at,
+                process.created_at,
+                process.updated_at
+            )
+            .execute(&mut *tx)
+            .await?;
+            if let Some(jsonl) = logs.get(&process.id) {
+                let byte_size = jsonl.len() as i64;
+                sqlx::query!(
+                    r#"INSERT INTO execution_process_logs (execution_id, logs, byte_size, inserted_at)
+                       VALUES ($1, $2, $3, $4)"#,
+                    id,
+                    jsonl,
+                    byte_size,
+                    completed_at
+                )
+                .execute(&mut *tx)
+                .await?;
+            }
+            execution_process_ids.insert(process.id, id);
+        }
+
+        for turn in &self.coding_agent_turns {
+            let Some(execution_process_id) = execution_process_ids.get(&turn.execution_process_id)
+            else {
+                continue;
+            };
+            let id = Uuid::new_v4();
+            sqlx::query!(
+                r#"INSERT INTO coding_agent_turns (
+                    id, execution_process_id, agent_session_id, prompt, summary, seen,
+                    created_at, updated_at
+                   )
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
+                id,
+                execution_process_id,
+                turn.agent_session_id,
+                turn.prompt,
+                turn.summary,
+                turn.seen,
+                turn.created_at,
+                turn.updated_at
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Task::find_by_id(pool, task_id)
+            .await?
+            .ok_or(sqlx::Error::RowNotFound)
+    }
+}
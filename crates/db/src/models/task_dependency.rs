@@ -0,0 +1,246 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use strum_macros::{Display, EnumString};
+use thiserror::Error;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use super::task::{Task, TaskStatus};
+
+#[derive(Debug, Error)]
+pub enum TaskDependencyError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error("Task dependency not found")]
+    NotFound,
+    #[error("A task cannot depend on itself")]
+    SelfReference,
+    #[error("Dependent tasks must belong to the same project")]
+    CrossProject,
+    #[error("Task dependency already exists")]
+    AlreadyExists,
+    #[error("Adding this dependency would create a cycle")]
+    Cycle,
+}
+
+/// Kind of relation between two tasks.
+/// Mirrors the remote `IssueRelationshipType` (same variants and wire format),
+/// so relations round-trip unchanged when tasks are synced with remote issues.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Type,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    TS,
+    EnumString,
+    Display,
+    Default,
+)]
+#[sqlx(type_name = "task_dependency_type", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum TaskDependencyType {
+    /// `task_id` blocks `related_task_id`
+    #[default]
+    Blocking,
+    Related,
+    HasDuplicate,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct TaskDependency {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub related_task_id: Uuid,
+    pub relationship_type: TaskDependencyType,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct CreateTaskDependency {
+    pub related_task_id: Uuid,
+    #[serde(default)]
+    pub relationship_type: TaskDependencyType,
+}
+
+impl TaskDependency {
+    /// All dependencies where the task appears on either side of the relation
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDependency,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      related_task_id as "related_task_id!: Uuid",
+                      relationship_type as "relationship_type!: TaskDependencyType",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_dependencies
+               WHERE task_id = $1 OR related_task_id = $1
+               ORDER BY created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            TaskDependency,
+            r#"SELECT id as "id!: Uuid",
+                      task_id as "task_id!: Uuid",
+                      related_task_id as "related_task_id!: Uuid",
+                      relationship_type as "relationship_type!: TaskDependencyType",
+                      created_at as "created_at!: DateTime<Utc>"
+               FROM task_dependencies
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Tasks that block the given task
+    pub async fn find_blockers(pool: &SqlitePool, task_id: Uuid) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies d
+               JOIN tasks t ON t.id = d.task_id
+               WHERE d.related_task_id = $1 AND d.relationship_type = 'blocking'
+               ORDER BY t.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Tasks blocked by the given task that are still in Todo, have never been
+    /// attempted, and whose blockers are now all Done.
+    pub async fn find_unblocked_dependents(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Vec<Task>, sqlx::Error> {
+        sqlx::query_as!(
+            Task,
+            r#"SELECT t.id as "id!: Uuid", t.project_id as "project_id!: Uuid", t.title, t.description, t.status as "status!: TaskStatus", t.parent_workspace_id as "parent_workspace_id: Uuid", t.created_at as "created_at!: DateTime<Utc>", t.updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_dependencies d
+               JOIN tasks t ON t.id = d.related_task_id
+               WHERE d.task_id = $1
+                 AND d.relationship_type = 'blocking'
+                 AND t.status = 'todo'
+                 AND NOT EXISTS (
+                   SELECT 1
+                     FROM task_dependencies b
+                     JOIN tasks bt ON bt.id = b.task_id
+                    WHERE b.related_task_id = t.id
+                      AND b.relationship_type = 'blocking'
+                      AND bt.status != 'done'
+                 )
+                 AND NOT EXISTS (SELECT 1 FROM workspaces w WHERE w.task_id = t.id)
+               ORDER BY t.created_at ASC"#,
+            task_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Whether `to` is reachable from `from` by following blocking edges
+    async fn blocking_path_exists(
+        pool: &SqlitePool,
+        from: Uuid,
+        to: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let found = sqlx::query_scalar!(
+            r#"WITH RECURSIVE reachable(id) AS (
+                   SELECT related_task_id FROM task_dependencies
+                    WHERE task_id = $1 AND relationship_type = 'blocking'
+                   UNION
+                   SELECT d.related_task_id
+                     FROM task_dependencies d
+                     JOIN reachable r ON d.task_id = r.id
+                    WHERE d.relationship_type = 'blocking'
+               )
+               SELECT EXISTS (SELECT 1 FROM reachable WHERE id = $2) as "found!: bool""#,
+            from,
+            to
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(found)
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        task: &Task,
+        data: &CreateTaskDependency,
+    ) -> Result<Self, TaskDependencyError> {
+        if task.id == data.related_task_id {
+            return Err(TaskDependencyError::SelfReference);
+        }
+
+        let related = Task::find_by_id(pool, data.related_task_id)
+            .await?
+            .ok_or(TaskDependencyError::NotFound)?;
+        if related.project_id != task.project_id {
+            return Err(TaskDependencyError::CrossProject);
+        }
+
+        if data.relationship_type == TaskDependencyType::Blocking
+            && Self::blocking_path_exists(pool, related.id, task.id).await?
+        {
+            return Err(TaskDependencyError::Cycle);
+        }
+
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            TaskDependency,
+            r#"INSERT INTO task_dependencies (id, task_id, related_task_id, relationship_type)
+               VALUES ($1, $2, $3, $4)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         related_task_id as "related_task_id!: Uuid",
+                         relationship_type as "relationship_type!: TaskDependencyType",
+                         created_at as "created_at!: DateTime<Utc>""#,
+            id,
+            task.id,
+            related.id,
+            data.relationship_type
+        )
+        .fetch_one(pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                TaskDependencyError::AlreadyExists
+            }
+            e => TaskDependencyError::Database(e),
+        })
+    }
+
+    /// Delete a dependency, scoped to a task on either side of the relation
+    pub async fn delete(
+        pool: &SqlitePool,
+        id: Uuid,
+        task_id: Uuid,
+    ) -> Result<(), TaskDependencyError> {
+        let result = sqlx::query!(
+            "DELETE FROM task_dependencies WHERE id = $1 AND (task_id = $2 OR related_task_id = $2)",
+            id,
+            task_id
+        )
+        .execute(pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(TaskDependencyError::NotFound);
+        }
+
+        Ok(())
+    }
+}
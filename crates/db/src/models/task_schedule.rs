@@ -0,0 +1,367 @@
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Repository a scheduled task runs against, with the branch to base its workspace on
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ScheduleRepo {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct TaskScheduleRow {
+    id: Uuid,
+    project_id: Uuid,
+    name: String,
+    cron_expression: String,
+    executor_profile_id: Json<ExecutorProfileId>,
+    repos: Json<Vec<ScheduleRepo>>,
+    task_title: String,
+    task_description: Option<String>,
+    enabled: bool,
+    next_run_at: Option<DateTime<Utc>>,
+    last_run_at: Option<DateTime<Utc>>,
+    last_task_id: Option<Uuid>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+/// A recurring task: on every tick of `cron_expression` a task is created from
+/// the template fields and a workspace is started for it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskSchedule {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    /// Five-field cron expression, evaluated in the server's local timezone
+    pub cron_expression: String,
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<ScheduleRepo>,
+    pub task_title: String,
+    pub task_description: Option<String>,
+    pub enabled: bool,
+    /// Unset while the schedule is disabled
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Task created by the most recent run
+    pub last_task_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TaskScheduleRow> for TaskSchedule {
+    fn from(row: TaskScheduleRow) -> Self {
+        Self {
+            id: row.id,
+            project_id: row.project_id,
+            name: row.name,
+            cron_expression: row.cron_expression,
+            executor_profile_id: row.executor_profile_id.0,
+            repos: row.repos.0,
+            task_title: row.task_title,
+            task_description: row.task_description,
+            enabled: row.enabled,
+            next_run_at: row.next_run_at,
+            last_run_at: row.last_run_at,
+            last_task_id: row.last_task_id,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateTaskSchedule {
+    pub project_id: Uuid,
+    pub name: String,
+    pub cron_expression: String,
+    pub executor_profile_id: ExecutorProfileId,
+    pub repos: Vec<ScheduleRepo>,
+    pub task_title: String,
+    pub task_description: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateTaskSchedule {
+    pub name: Option<String>,
+    pub cron_expression: Option<String>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub repos: Option<Vec<ScheduleRepo>>,
+    pub task_title: Option<String>,
+    pub task_description: Option<String>,
+    pub enabled: Option<bool>,
+}
+
+impl TaskSchedule {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            TaskScheduleRow,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      cron_expression,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<ScheduleRepo>>",
+                      task_title,
+                      task_description,
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               ORDER BY name ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    pub async fn find_by_project_id(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            TaskScheduleRow,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      cron_expression,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<ScheduleRepo>>",
+                      task_title,
+                      task_description,
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE project_id = $1
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            TaskScheduleRow,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      cron_expression,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<ScheduleRepo>>",
+                      task_title,
+                      task_description,
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(row.map(Self::from))
+    }
+
+    /// Enabled schedules whose next run is at or before `now`
+    pub async fn find_due(pool: &SqlitePool, now: DateTime<Utc>) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            TaskScheduleRow,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id!: Uuid",
+                      name,
+                      cron_expression,
+                      executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<ScheduleRepo>>",
+                      task_title,
+                      task_description,
+                      enabled as "enabled!: bool",
+                      next_run_at as "next_run_at: DateTime<Utc>",
+                      last_run_at as "last_run_at: DateTime<Utc>",
+                      last_task_id as "last_task_id: Uuid",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_schedules
+               WHERE enabled = 1 AND next_run_at IS NOT NULL AND next_run_at <= $1
+               ORDER BY next_run_at ASC"#,
+            now
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskSchedule,
+        id: Uuid,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let executor_profile_id = Json(&data.executor_profile_id);
+        let repos = Json(&data.repos);
+        let enabled = data.enabled.unwrap_or(true);
+        let row = sqlx::query_as!(
+            TaskScheduleRow,
+            r#"INSERT INTO task_schedules
+                   (id, project_id, name, cron_expression, executor_profile_id, repos,
+                    task_title, task_description, enabled, next_run_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         cron_expression,
+                         executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                         repos as "repos!: Json<Vec<ScheduleRepo>>",
+                         task_title,
+                         task_description,
+                         enabled as "enabled!: bool",
+                         next_run_at as "next_run_at: DateTime<Utc>",
+                         last_run_at as "last_run_at: DateTime<Utc>",
+                         last_task_id as "last_task_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.cron_expression,
+            executor_profile_id,
+            repos,
+            data.task_title,
+            data.task_description,
+            enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// Apply the set fields of `data`. `next_run_at` is recomputed by the
+    /// caller, since it depends on the merged expression and enabled flag.
+    pub async fn update(
+        pool: &SqlitePool,
+        existing: &TaskSchedule,
+        data: &UpdateTaskSchedule,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<Self, sqlx::Error> {
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let cron_expression = data
+            .cron_expression
+            .as_ref()
+            .unwrap_or(&existing.cron_expression);
+        let executor_profile_id = Json(
+            data.executor_profile_id
+                .as_ref()
+                .unwrap_or(&existing.executor_profile_id),
+        );
+        let repos = Json(data.repos.as_ref().unwrap_or(&existing.repos));
+        let task_title = data.task_title.as_ref().unwrap_or(&existing.task_title);
+        let task_description = data
+            .task_description
+            .as_ref()
+            .or(existing.task_description.as_ref());
+        let enabled = data.enabled.unwrap_or(existing.enabled);
+
+        let row = sqlx::query_as!(
+            TaskScheduleRow,
+            r#"UPDATE task_schedules
+               SET name = $2, cron_expression = $3, executor_profile_id = $4, repos = $5,
+                   task_title = $6, task_description = $7, enabled = $8, next_run_at = $9,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id!: Uuid",
+                         name,
+                         cron_expression,
+                         executor_profile_id as "executor_profile_id!: Json<ExecutorProfileId>",
+                         repos as "repos!: Json<Vec<ScheduleRepo>>",
+                         task_title,
+                         task_description,
+                         enabled as "enabled!: bool",
+                         next_run_at as "next_run_at: DateTime<Utc>",
+                         last_run_at as "last_run_at: DateTime<Utc>",
+                         last_task_id as "last_task_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            existing.id,
+            name,
+            cron_expression,
+            executor_profile_id,
+            repos,
+            task_title,
+            task_description,
+            enabled,
+            next_run_at
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(row.into())
+    }
+
+    /// Record a tick that created `task_id` and schedule the next one
+    pub async fn record_run(
+        pool: &SqlitePool,
+        id: Uuid,
+        task_id: Uuid,
+        ran_at: DateTime<Utc>,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE task_schedules
+               SET last_task_id = $2, last_run_at = $3, next_run_at = $4
+               WHERE id = $1"#,
+            id,
+            task_id,
+            ran_at,
+            next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Move to the next tick without running, e.g. when the previous run is still busy
+    pub async fn set_next_run_at(
+        pool: &SqlitePool,
+        id: Uuid,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE task_schedules SET next_run_at = $2 WHERE id = $1",
+            id,
+            next_run_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_schedules WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use executors::profile::ExecutorProfileId;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateVariableKind {
+    Text,
+    Number,
+    Boolean,
+    Url,
+    /// One of the variable's `options`
+    Select,
+}
+
+/// Value a template asks for when it is instantiated, referenced as
+/// `{{name}}` in the title, description and step prompts
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TemplateVariable {
+    pub name: String,
+    pub kind: TemplateVariableKind,
+    pub description: Option<String>,
+    pub required: bool,
+    /// Used when no value is given
+    pub default: Option<String>,
+    /// Allowed values of `select` variables
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateStepKind {
+    /// Send the step's prompt to the coding agent
+    Prompt,
+    /// Ask the coding agent for a review, with the prompt as extra instructions
+    Review,
+}
+
+/// One coding agent turn of a template's chain. The first step runs with the
+/// task's prompt, the others are queued as follow-ups of the same session.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TemplateStep {
+    pub kind: TemplateStepKind,
+    #[serde(default)]
+    pub prompt: String,
+    /// Executor variant for this step, e.g. `PLAN`; the attempt's when unset
+    pub variant: Option<String>,
+}
+
+/// Repository a template's attempts run against, with the branch to base them on
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TemplateRepo {
+    pub repo_id: Uuid,
+    pub target_branch: String,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct TaskTemplateRow {
+    id: Uuid,
+    project_id: Option<Uuid>,
+    name: String,
+    title: String,
+    description: Option<String>,
+    variables: Json<Vec<TemplateVariable>>,
+    executor_profile_id: Option<Json<ExecutorProfileId>>,
+    repos: Json<Vec<TemplateRepo>>,
+    steps: Json<Vec<TemplateStep>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskTemplate {
+    pub id: Uuid,
+    /// Unset for global templates, which are available in every project
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    /// Title of the created tasks, may contain `{{variable}}` placeholders
+    pub title: String,
+    pub description: Option<String>,
+    pub variables: Vec<TemplateVariable>,
+    /// Executor preselected for the task's attempts
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub repos: Vec<TemplateRepo>,
+    pub steps: Vec<TemplateStep>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TaskTemplateRow> for TaskTemplate {
+    fn from(row: TaskTemplateRow) -> Self {
+        Self {
+            id: row.id,
+            project_id: row.project_id,
+            name: row.name,
+            title: row.title,
+            description: row.description,
+            variables: row.variables.0,
+            executor_profile_id: row.executor_profile_id.map(|profile| profile.0),
+            repos: row.repos.0,
+            steps: row.steps.0,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CreateTaskTemplate {
+    pub project_id: Option<Uuid>,
+    pub name: String,
+    pub title: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub variables: Vec<TemplateVariable>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    #[serde(default)]
+    pub repos: Vec<TemplateRepo>,
+    #[serde(default)]
+    pub steps: Vec<TemplateStep>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct UpdateTaskTemplate {
+    pub name: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub variables: Option<Vec<TemplateVariable>>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub repos: Option<Vec<TemplateRepo>>,
+    pub steps: Option<Vec<TemplateStep>>,
+}
+
+/// Template to create a task from, with the values of its variables
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TemplateInstance {
+    pub template_id: Uuid,
+    #[serde(default)]
+    pub variables: HashMap<String, String>,
+}
+
+impl TaskTemplate {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            TaskTemplateRow,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      title,
+                      description,
+                      variables as "variables!: Json<Vec<TemplateVariable>>",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<TemplateRepo>>",
+                      steps as "steps!: Json<Vec<TemplateStep>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_templates
+               ORDER BY name ASC"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    /// Templates of the project together with the global templates
+    pub async fn find_for_project(
+        pool: &SqlitePool,
+        project_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            TaskTemplateRow,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      title,
+                      description,
+                      variables as "variables!: Json<Vec<TemplateVariable>>",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<TemplateRepo>>",
+                      steps as "steps!: Json<Vec<TemplateStep>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_templates
+               WHERE project_id = $1 OR project_id IS NULL
+               ORDER BY name ASC"#,
+            project_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            TaskTemplateRow,
+            r#"SELECT id as "id!: Uuid",
+                      project_id as "project_id: Uuid",
+                      name,
+                      title,
+                      description,
+                      variables as "variables!: Json<Vec<TemplateVariable>>",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<TemplateRepo>>",
+                      steps as "steps!: Json<Vec<TemplateStep>>",
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM task_templates
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        data: &CreateTaskTemplate,
+        id: Uuid,
+    ) -> Result<Self, sqlx::Error> {
+        let variables = Json(&data.variables);
+        let executor_profile_id = data.executor_profile_id.as_ref().map(Json);
+        let repos = Json(&data.repos);
+        let steps = Json(&data.steps);
+        let row = sqlx::query_as!(
+            TaskTemplateRow,
+            r#"INSERT INTO task_templates
+                   (id, project_id, name, title, description, variables, executor_profile_id,
+                    repos, steps)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id: Uuid",
+                         name,
+                         title,
+                         description,
+                         variables as "variables!: Json<Vec<TemplateVariable>>",
+                         executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                         repos as "repos!: Json<Vec<TemplateRepo>>",
+                         steps as "steps!: Json<Vec<TemplateStep>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            data.project_id,
+            data.name,
+            data.title,
+            data.description,
+            variables,
+            executor_profile_id,
+            repos,
+            steps
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.into())
+    }
+
+    pub async fn update(
+        pool: &SqlitePool,
+        existing: &TaskTemplate,
+        data: &UpdateTaskTemplate,
+    ) -> Result<Self, sqlx::Error> {
+        let name = data.name.as_ref().unwrap_or(&existing.name);
+        let title = data.title.as_ref().unwrap_or(&existing.title);
+        let description = data.description.as_ref().or(existing.description.as_ref());
+        let variables = Json(data.variables.as_ref().unwrap_or(&existing.variables));
+        let executor_profile_id = data
+            .executor_profile_id
+            .as_ref()
+            .or(existing.executor_profile_id.as_ref())
+            .map(Json);
+        let repos = Json(data.repos.as_ref().unwrap_or(&existing.repos));
+        let steps = Json(data.steps.as_ref().unwrap_or(&existing.steps));
+        let row = sqlx::query_as!(
+            TaskTemplateRow,
+            r#"UPDATE task_templates
+               SET name = $2, title = $3, description = $4, variables = $5,
+                   executor_profile_id = $6, repos = $7, steps = $8,
+                   updated_at = datetime('now', 'subsec')
+               WHERE id = $1
+               RETURNING id as "id!: Uuid",
+                         project_id as "project_id: Uuid",
+                         name,
+                         title,
+                         description,
+                         variables as "variables!: Json<Vec<TemplateVariable>>",
+                         executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                         repos as "repos!: Json<Vec<TemplateRepo>>",
+                         steps as "steps!: Json<Vec<TemplateStep>>",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            existing.id,
+            name,
+            title,
+            description,
+            variables,
+            executor_profile_id,
+            repos,
+            steps
+        )
+        .fetch_one(pool)
+        .await?;
+        Ok(row.into())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query!("DELETE FROM task_templates WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct TaskTemplateInstanceRow {
+    task_id: Uuid,
+    template_id: Option<Uuid>,
+    executor_profile_id: Option<Json<ExecutorProfileId>>,
+    repos: Json<Vec<TemplateRepo>>,
+    steps: Json<Vec<TemplateStep>>,
+}
+
+/// What a task took from the template it was created from. Step prompts are
+/// already rendered with the task's variables.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskTemplateInstance {
+    pub task_id: Uuid,
+    /// Unset once the template is deleted
+    pub template_id: Option<Uuid>,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    pub repos: Vec<TemplateRepo>,
+    pub steps: Vec<TemplateStep>,
+}
+
+impl From<TaskTemplateInstanceRow> for TaskTemplateInstance {
+    fn from(row: TaskTemplateInstanceRow) -> Self {
+        Self {
+            task_id: row.task_id,
+            template_id: row.template_id,
+            executor_profile_id: row.executor_profile_id.map(|profile| profile.0),
+            repos: row.repos.0,
+            steps: row.steps.0,
+        }
+    }
+}
+
+impl TaskTemplateInstance {
+    pub async fn find_by_task_id(
+        pool: &SqlitePool,
+        task_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            TaskTemplateInstanceRow,
+            r#"SELECT task_id as "task_id!: Uuid",
+                      template_id as "template_id: Uuid",
+                      executor_profile_id as "executor_profile_id: Json<ExecutorProfileId>",
+                      repos as "repos!: Json<Vec<TemplateRepo>>",
+                      steps as "steps!: Json<Vec<TemplateStep>>"
+               FROM task_template_instances
+               WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(Self::from))
+    }
+
+    pub async fn create(
+        pool: &SqlitePool,
+        instance: &TaskTemplateInstance,
+    ) -> Result<(), sqlx::Error> {
+        let executor_profile_id = instance.executor_profile_id.as_ref().map(Json);
+        let repos = Json(&instance.repos);
+        let steps = Json(&instance.steps);
+        sqlx::query!(
+            r#"INSERT INTO task_template_instances
+                   (task_id, template_id, executor_profile_id, repos, steps)
+               VALUES ($1, $2, $3, $4, $5)"#,
+            instance.task_id,
+            instance.template_id,
+            executor_profile_id,
+            repos,
+            steps
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
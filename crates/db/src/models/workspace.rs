@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, SqlitePool};
@@ -11,6 +13,7 @@ const WORKSPACE_NAME_MAX_LEN: usize = 60;
 use super::{
     project::Project,
     task::Task,
+    workspace_drift::WorkspaceDrift,
     workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
 };
 
@@ -57,6 +60,9 @@ pub struct WorkspaceWithStatus {
     pub workspace: Workspace,
     pub is_running: bool,
     pub is_errored: bool,
+    /// How far each repo is from its target branch, once checked by the drift
+    /// monitor
+    pub drift: Vec<WorkspaceDrift>,
 }
 
 impl std::ops::Deref for WorkspaceWithStatus {
@@ -596,6 +602,14 @@ impl Workspace {
         .fetch_all(pool)
         .await?;
 
+        let mut drift_by_workspace: HashMap<Uuid, Vec<WorkspaceDrift>> = HashMap::new();
+        for drift in WorkspaceDrift::find_all(pool).await? {
+            drift_by_workspace
+                .entry(drift.workspace_id)
+                .or_default()
+                .push(drift);
+        }
+
         let mut workspaces: Vec<WorkspaceWithStatus> = records
             .into_iter()
             .map(|rec| WorkspaceWithStatus {
@@ -614,6 +628,7 @@ impl Workspace {
                 },
                 is_running: rec.is_running != 0,
                 is_errored: rec.is_errored != 0,
+                drift: drift_by_workspace.remove(&rec.id).unwrap_or_default(),
             })
             // Apply archived filter if provided
             .filter(|ws| archived.is_none_or(|a| ws.workspace.archived == a))
@@ -702,6 +717,7 @@ impl Workspace {
             return Ok(None);
         };
 
+        let drift = WorkspaceDrift::find_by_workspace_id(pool, rec.id).await?;
         let mut ws = WorkspaceWithStatus {
             workspace: Workspace {
                 id: rec.id,
@@ -718,6 +734,7 @@ impl Workspace {
             },
             is_running: rec.is_running != 0,
             is_errored: rec.is_errored != 0,
+            drift,
         };
 
         if ws.workspace.name.is_none()
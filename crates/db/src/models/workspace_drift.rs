@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, types::Json};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// How far a repo of a workspace is from its target branch, as of the latest
+/// check of the drift monitor
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkspaceDrift {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub target_branch: String,
+    /// Commit the target branch was at when checked
+    pub target_commit: String,
+    pub commits_ahead: u32,
+    pub commits_behind: u32,
+    /// Files that would conflict when merging the workspace branch with the
+    /// target branch
+    pub conflicted_files: Vec<String>,
+    #[ts(type = "Date")]
+    pub checked_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow)]
+struct WorkspaceDriftRow {
+    workspace_id: Uuid,
+    repo_id: Uuid,
+    target_branch: String,
+    target_commit: String,
+    commits_ahead: u32,
+    commits_behind: u32,
+    conflicted_files: Json<Vec<String>>,
+    checked_at: DateTime<Utc>,
+}
+
+impl From<WorkspaceDriftRow> for WorkspaceDrift {
+    fn from(row: WorkspaceDriftRow) -> Self {
+        Self {
+            workspace_id: row.workspace_id,
+            repo_id: row.repo_id,
+            target_branch: row.target_branch,
+            target_commit: row.target_commit,
+            commits_ahead: row.commits_ahead,
+            commits_behind: row.commits_behind,
+            conflicted_files: row.conflicted_files.0,
+            checked_at: row.checked_at,
+        }
+    }
+}
+
+impl WorkspaceDrift {
+    pub async fn find_all(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            WorkspaceDriftRow,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch,
+                      target_commit,
+                      commits_ahead as "commits_ahead!: u32",
+                      commits_behind as "commits_behind!: u32",
+                      conflicted_files as "conflicted_files!: Json<Vec<String>>",
+                      checked_at as "checked_at!: DateTime<Utc>"
+               FROM workspace_drift"#
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        let rows = sqlx::query_as!(
+            WorkspaceDriftRow,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      target_branch,
+                      target_commit,
+                      commits_ahead as "commits_ahead!: u32",
+                      commits_behind as "commits_behind!: u32",
+                      conflicted_files as "conflicted_files!: Json<Vec<String>>",
+                      checked_at as "checked_at!: DateTime<Utc>"
+               FROM workspace_drift
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await?;
+        Ok(rows.into_iter().map(Self::from).collect())
+    }
+
+    pub async fn upsert(pool: &SqlitePool, drift: &WorkspaceDrift) -> Result<(), sqlx::Error> {
+        let conflicted_files = Json(&drift.conflicted_files);
+        sqlx::query!(
+            r#"INSERT INTO workspace_drift
+                   (workspace_id, repo_id, target_branch, target_commit, commits_ahead,
+                    commits_behind, conflicted_files, checked_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               ON CONFLICT(workspace_id, repo_id) DO UPDATE SET
+                   target_branch = excluded.target_branch,
+                   target_commit = excluded.target_commit,
+                   commits_ahead = excluded.commits_ahead,
+                   commits_behind = excluded.commits_behind,
+                   conflicted_files = excluded.conflicted_files,
+                   checked_at = excluded.checked_at"#,
+            drift.workspace_id,
+            drift.repo_id,
+            drift.target_branch,
+            drift.target_commit,
+            drift.commits_ahead,
+            drift.commits_behind,
+            conflicted_files,
+            drift.checked_at
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
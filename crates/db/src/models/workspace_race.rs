@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A group of workspaces running the same task on different executors.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceRace {
+    pub id: Uuid,
+    pub task_id: Uuid,
+    pub winner_workspace_id: Option<Uuid>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkspaceRace {
+    pub async fn create(
+        pool: &SqlitePool,
+        task_id: Uuid,
+        workspace_ids: &[Uuid],
+    ) -> Result<Self, sqlx::Error> {
+        let mut tx = pool.begin().await?;
+
+        let id = Uuid::new_v4();
+        let race = sqlx::query_as!(
+            WorkspaceRace,
+            r#"INSERT INTO workspace_races (id, task_id)
+               VALUES ($1, $2)
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         winner_workspace_id as "winner_workspace_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            id,
+            task_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for workspace_id in workspace_ids {
+            sqlx::query!(
+                "INSERT INTO workspace_race_entries (race_id, workspace_id) VALUES ($1, $2)",
+                race.id,
+                workspace_id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(race)
+    }
+
+    /// Find the race a workspace takes part in, if any
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceRace,
+            r#"SELECT r.id as "id!: Uuid",
+                      r.task_id as "task_id!: Uuid",
+                      r.winner_workspace_id as "winner_workspace_id: Uuid",
+                      r.created_at as "created_at!: DateTime<Utc>",
+                      r.updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspace_races r
+               JOIN workspace_race_entries e ON e.race_id = r.id
+               WHERE e.workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Workspace ids taking part in the race, in creation order
+    pub async fn find_workspace_ids(
+        pool: &SqlitePool,
+        race_id: Uuid,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT workspace_id as "workspace_id!: Uuid"
+               FROM workspace_race_entries
+               WHERE race_id = $1
+               ORDER BY created_at ASC, rowid ASC"#,
+            race_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record the winner of a race. Returns `None` when a winner has already
+    /// been picked, so concurrent picks cannot replace each other.
+    pub async fn set_winner(
+        pool: &SqlitePool,
+        race_id: Uuid,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceRace,
+            r#"UPDATE workspace_races
+               SET winner_workspace_id = $2, updated_at = datetime('now', 'subsec')
+               WHERE id = $1 AND winner_workspace_id IS NULL
+               RETURNING id as "id!: Uuid",
+                         task_id as "task_id!: Uuid",
+                         winner_workspace_id as "winner_workspace_id: Uuid",
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            race_id,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+}
@@ -121,6 +121,10 @@ impl WorkspaceRepo {
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
+                      r.dev_server_health_check,
+                      r.dev_server_auto_restart as "dev_server_auto_restart!: bool",
+                      r.verify_script,
+                      r.verify_max_retries as "verify_max_retries!: u32",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -148,6 +152,10 @@ impl WorkspaceRepo {
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
+                      r.dev_server_health_check,
+                      r.dev_server_auto_restart as "dev_server_auto_restart!: bool",
+                      r.verify_script,
+                      r.verify_max_retries as "verify_max_retries!: u32",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>",
                       wr.target_branch
@@ -174,6 +182,10 @@ impl WorkspaceRepo {
                     parallel_setup_script: row.parallel_setup_script,
                     dev_server_script: row.dev_server_script,
                     default_target_branch: row.default_target_branch,
+                    dev_server_health_check: row.dev_server_health_check,
+                    dev_server_auto_restart: row.dev_server_auto_restart,
+                    verify_script: row.verify_script,
+                    verify_max_retries: row.verify_max_retries,
                     created_at: row.created_at,
                     updated_at: row.updated_at,
                 },
@@ -221,6 +233,8 @@ impl WorkspaceRepo {
         Ok(())
     }
 
+    /// Retarget the workspaces of subtasks, and the workspaces stacked on this
+    /// one, from its old branch to the new one
     pub async fn update_target_branch_for_children_of_workspace(
         pool: &SqlitePool,
         parent_workspace_id: Uuid,
@@ -231,10 +245,16 @@ impl WorkspaceRepo {
             r#"UPDATE workspace_repos
                SET target_branch = $1, updated_at = datetime('now')
                WHERE target_branch = $2
-                 AND workspace_id IN (
-                     SELECT w.id FROM workspaces w
-                     JOIN tasks t ON w.task_id = t.id
-                     WHERE t.parent_workspace_id = $3
+                 AND (
+                     workspace_id IN (
+                         SELECT w.id FROM workspaces w
+                         JOIN tasks t ON w.task_id = t.id
+                         WHERE t.parent_workspace_id = $3
+                     )
+                     OR workspace_id IN (
+                         SELECT workspace_id FROM workspace_stack_repos
+                         WHERE base_workspace_id = $3
+                     )
                  )"#,
             new_branch,
             old_branch,
@@ -261,6 +281,10 @@ impl WorkspaceRepo {
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
+                      r.dev_server_health_check,
+                      r.dev_server_auto_restart as "dev_server_auto_restart!: bool",
+                      r.verify_script,
+                      r.verify_max_retries as "verify_max_retries!: u32",
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// A repo of a stacked workspace: its branch was created from the branch of
+/// `base_workspace_id` rather than from the repo's target branch.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct WorkspaceStackRepo {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub base_workspace_id: Uuid,
+    /// Commit of the base branch the workspace was last based on
+    pub base_commit: String,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkspaceStackRepo {
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        base_workspace_id: Uuid,
+        base_commit: &str,
+    ) -> Result<Self, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceStackRepo,
+            r#"INSERT INTO workspace_stack_repos (workspace_id, repo_id, base_workspace_id, base_commit)
+               VALUES ($1, $2, $3, $4)
+               RETURNING workspace_id as "workspace_id!: Uuid",
+                         repo_id as "repo_id!: Uuid",
+                         base_workspace_id as "base_workspace_id!: Uuid",
+                         base_commit,
+                         created_at as "created_at!: DateTime<Utc>",
+                         updated_at as "updated_at!: DateTime<Utc>""#,
+            workspace_id,
+            repo_id,
+            base_workspace_id,
+            base_commit
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceStackRepo,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      base_workspace_id as "base_workspace_id!: Uuid",
+                      base_commit,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspace_stack_repos
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Repos of the workspaces stacked directly on `base_workspace_id`
+    pub async fn find_by_base_workspace_id(
+        pool: &SqlitePool,
+        base_workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceStackRepo,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      repo_id as "repo_id!: Uuid",
+                      base_workspace_id as "base_workspace_id!: Uuid",
+                      base_commit,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspace_stack_repos
+               WHERE base_workspace_id = $1
+               ORDER BY created_at ASC"#,
+            base_workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Move a repo of the workspace onto a new base
+    pub async fn update_base(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        base_workspace_id: Uuid,
+        base_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE workspace_stack_repos
+               SET base_workspace_id = $3, base_commit = $4, updated_at = datetime('now', 'subsec')
+               WHERE workspace_id = $1 AND repo_id = $2"#,
+            workspace_id,
+            repo_id,
+            base_workspace_id,
+            base_commit
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Unstack a repo of the workspace, e.g. once its base was merged
+    pub async fn delete(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "DELETE FROM workspace_stack_repos WHERE workspace_id = $1 AND repo_id = $2",
+            workspace_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Outcome of a workspace's verify scripts. `fixing` means the failure was
+/// sent back to the coding agent, which is working on a fix.
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "verification_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationStatus {
+    Running,
+    Passed,
+    Failed,
+    Fixing,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct WorkspaceVerification {
+    pub workspace_id: Uuid,
+    /// Latest verify script run
+    pub execution_process_id: Option<Uuid>,
+    pub status: VerificationStatus,
+    /// Automatic fix follow-ups sent to the agent since verification last
+    /// passed or was given up on
+    pub retries: u32,
+    /// Tail of the failed script's output
+    pub output: Option<String>,
+    #[ts(type = "Date")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "Date")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorkspaceVerification {
+    pub async fn find_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            WorkspaceVerification,
+            r#"SELECT workspace_id as "workspace_id!: Uuid",
+                      execution_process_id as "execution_process_id: Uuid",
+                      status as "status!: VerificationStatus",
+                      retries as "retries!: u32",
+                      output,
+                      created_at as "created_at!: DateTime<Utc>",
+                      updated_at as "updated_at!: DateTime<Utc>"
+               FROM workspace_verifications
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Record that a verify script started. The retry count carries over only
+    /// while the agent is fixing an earlier failure.
+    pub async fn start(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        execution_process_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"INSERT INTO workspace_verifications (workspace_id, execution_process_id)
+               VALUES ($1, $2)
+               ON CONFLICT(workspace_id) DO UPDATE SET
+                   execution_process_id = excluded.execution_process_id,
+                   status = 'running',
+                   retries = CASE WHEN status = 'fixing' THEN retries ELSE 0 END,
+                   output = NULL,
+                   updated_at = datetime('now', 'subsec')"#,
+            workspace_id,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn finish(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        status: VerificationStatus,
+        output: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE workspace_verifications
+               SET status = $2, output = $3, updated_at = datetime('now', 'subsec')
+               WHERE workspace_id = $1"#,
+            workspace_id,
+            status,
+            output
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Record that the failure was sent back to the agent
+    pub async fn start_fix(pool: &SqlitePool, workspace_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE workspace_verifications
+               SET status = 'fixing', retries = retries + 1, updated_at = datetime('now', 'subsec')
+               WHERE workspace_id = $1"#,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// A fix attempt that ended without verification running again, e.g. because
+    /// the agent failed or changed nothing, leaves the verification failed
+    pub async fn abandon_fix(pool: &SqlitePool, workspace_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE workspace_verifications
+               SET status = 'failed', updated_at = datetime('now', 'subsec')
+               WHERE workspace_id = $1 AND status = 'fixing'"#,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
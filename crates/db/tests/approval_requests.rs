@@ -0,0 +1,69 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use db::models::{
+    approval_request::{ApprovalRecord, ApprovalResolution},
+    execution_process::ExecutionProcessStatus,
+};
+use utils::approvals::{ApprovalRequest, ApprovalStatus};
+use uuid::Uuid;
+
+fn request(execution_process_id: Uuid) -> ApprovalRequest {
+    ApprovalRequest {
+        id: Uuid::new_v4().to_string(),
+        tool_name: "Bash".to_string(),
+        tool_input: serde_json::json!({ "command": "ls" }),
+        tool_call_id: Uuid::new_v4().to_string(),
+        execution_process_id,
+        created_at: Utc::now(),
+        timeout_at: Utc::now() + Duration::minutes(10),
+    }
+}
+
+#[tokio::test]
+async fn pending_requests_are_denied_after_a_restart() {
+    let (db, project) = common::setup().await;
+    let pool = &db.pool;
+    let task = common::create_task(pool, &project, "Task").await;
+    let workspace = common::create_workspace(pool, &task, "vk/task").await;
+    let session = common::create_session(pool, &workspace).await;
+    let process =
+        common::create_coding_agent_process(pool, &session, ExecutionProcessStatus::Failed).await;
+
+    let pending = request(process.id);
+    let answered = request(process.id);
+    for request in [&pending, &answered] {
+        ApprovalRecord::create(pool, request).await.unwrap();
+    }
+    ApprovalRecord::resolve(
+        pool,
+        &answered.id,
+        &ApprovalStatus::Approved,
+        ApprovalResolution::User,
+        None,
+    )
+    .await
+    .unwrap();
+
+    let cancelled = ApprovalRecord::cancel_all_pending(pool, "Server restarted")
+        .await
+        .unwrap();
+    assert_eq!(cancelled, 1);
+
+    let pending = ApprovalRecord::find_by_id(pool, &pending.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(
+        pending.status,
+        ApprovalStatus::Denied { reason: Some(ref reason) } if reason == "Server restarted"
+    ));
+    assert_eq!(pending.resolved_by, Some(ApprovalResolution::Cancelled));
+
+    let answered = ApprovalRecord::find_by_id(pool, &answered.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(answered.status, ApprovalStatus::Approved));
+    assert_eq!(answered.resolved_by, Some(ApprovalResolution::User));
+}
@@ -0,0 +1,100 @@
+#![allow(dead_code)]
+
+use db::{
+    DBService,
+    models::{
+        execution_process::{
+            CreateExecutionProcess, ExecutionProcess, ExecutionProcessRunReason,
+            ExecutionProcessStatus,
+        },
+        project::{CreateProject, Project},
+        session::{CreateSession, Session},
+        task::{CreateTask, Task},
+        workspace::{CreateWorkspace, Workspace},
+    },
+};
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType, coding_agent_initial::CodingAgentInitialRequest,
+    },
+    executors::{AppendPrompt, BaseCodingAgent},
+    profile::ExecutorProfileId,
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+pub async fn setup() -> (DBService, Project) {
+    let db = DBService::new_in_memory().await.unwrap();
+    let project = Project::create(
+        &db.pool,
+        &CreateProject {
+            name: "project".to_string(),
+            repositories: vec![],
+        },
+        Uuid::new_v4(),
+    )
+    .await
+    .unwrap();
+    (db, project)
+}
+
+pub async fn create_task(pool: &SqlitePool, project: &Project, title: &str) -> Task {
+    let data = CreateTask::from_title_description(project.id, title.to_string(), None);
+    Task::create(pool, &data, Uuid::new_v4()).await.unwrap()
+}
+
+pub async fn create_workspace(pool: &SqlitePool, task: &Task, branch: &str) -> Workspace {
+    Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: branch.to_string(),
+            agent_working_dir: None,
+        },
+        Uuid::new_v4(),
+        task.id,
+    )
+    .await
+    .unwrap()
+}
+
+pub async fn create_session(pool: &SqlitePool, workspace: &Workspace) -> Session {
+    Session::create(
+        pool,
+        &CreateSession {
+            executor: Some("CLAUDE_CODE".to_string()),
+        },
+        Uuid::new_v4(),
+        workspace.id,
+    )
+    .await
+    .unwrap()
+}
+
+pub async fn create_coding_agent_process(
+    pool: &SqlitePool,
+    session: &Session,
+    status: ExecutionProcessStatus,
+) -> ExecutionProcess {
+    let action = ExecutorAction::new(
+        ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+            prompt: "prompt".to_string(),
+            executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            working_dir: None,
+            project_memory: AppendPrompt::default(),
+        }),
+        None,
+    );
+    ExecutionProcess::create(
+        pool,
+        &CreateExecutionProcess {
+            session_id: session.id,
+            executor_action: action,
+            run_reason: ExecutionProcessRunReason::CodingAgent,
+        },
+        Uuid::new_v4(),
+        status,
+        &[],
+    )
+    .await
+    .unwrap()
+}
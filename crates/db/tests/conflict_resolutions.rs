@@ -0,0 +1,69 @@
+mod common;
+
+use std::path::Path;
+
+use db::models::{
+    conflict_resolution::{ConflictResolution, ConflictResolutionStatus},
+    execution_process::ExecutionProcessStatus,
+    repo::Repo,
+};
+
+#[tokio::test]
+async fn resolution_is_found_before_its_execution_is_attached() {
+    let (db, project) = common::setup().await;
+    let pool = &db.pool;
+    let task = common::create_task(pool, &project, "Task").await;
+    let workspace = common::create_workspace(pool, &task, "vk/task").await;
+    let session = common::create_session(pool, &workspace).await;
+    let repo = Repo::find_or_create(pool, Path::new("/repos/app"), "app")
+        .await
+        .unwrap();
+    let files = vec!["src/lib.rs".to_string()];
+
+    ConflictResolution::start(pool, workspace.id, repo.id, &files)
+        .await
+        .unwrap();
+    let process =
+        common::create_coding_agent_process(pool, &session, ExecutionProcessStatus::Failed).await;
+
+    // The execution ended before its id was attached
+    let pending = ConflictResolution::find_for_execution_process(pool, workspace.id, process.id)
+        .await
+        .unwrap()
+        .expect("pending resolution");
+    assert_eq!(pending.execution_process_id, None);
+    assert_eq!(pending.status, ConflictResolutionStatus::Resolving);
+    assert_eq!(pending.conflicted_files, files);
+    assert_eq!(pending.rounds, 1);
+
+    ConflictResolution::set_execution_process(pool, workspace.id, repo.id, process.id)
+        .await
+        .unwrap();
+    let attached = ConflictResolution::find_for_execution_process(pool, workspace.id, process.id)
+        .await
+        .unwrap()
+        .expect("attached resolution");
+    assert_eq!(attached.execution_process_id, Some(process.id));
+
+    // Another execution of the workspace is not a resolution once it is attached
+    let other =
+        common::create_coding_agent_process(pool, &session, ExecutionProcessStatus::Completed)
+            .await;
+    assert!(
+        ConflictResolution::find_for_execution_process(pool, workspace.id, other.id)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    // A later round of the same resolution counts up
+    ConflictResolution::start(pool, workspace.id, repo.id, &files)
+        .await
+        .unwrap();
+    let next_round = ConflictResolution::find_for_execution_process(pool, workspace.id, other.id)
+        .await
+        .unwrap()
+        .expect("next round");
+    assert_eq!(next_round.execution_process_id, None);
+    assert_eq!(next_round.rounds, 2);
+}
@@ -0,0 +1,33 @@
+use db::DBService;
+
+#[tokio::test]
+async fn migrations_apply_to_fresh_database() {
+    let db = DBService::new_in_memory()
+        .await
+        .expect("every migration applies to a fresh database");
+
+    let triggers: Vec<String> =
+        sqlx::query_scalar("SELECT name FROM sqlite_master WHERE type = 'trigger' ORDER BY name")
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+
+    // Table rebuilds must restore the search triggers that reference the rebuilt tables
+    for trigger in [
+        "search_index_execution_processes_delete",
+        "search_index_turns_insert",
+        "search_index_turns_update",
+        "search_index_tasks_insert",
+    ] {
+        assert!(
+            triggers.iter().any(|t| t == trigger),
+            "missing trigger {trigger}"
+        );
+    }
+
+    let violations: Vec<(String,)> = sqlx::query_as("PRAGMA foreign_key_check")
+        .fetch_all(&db.pool)
+        .await
+        .unwrap();
+    assert!(violations.is_empty());
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use db::models::{
+    queued_message::{QueuedMessage, QueuedMessageError},
+    scratch::DraftFollowUpData,
+};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use uuid::Uuid;
+
+fn follow_up(message: &str) -> DraftFollowUpData {
+    DraftFollowUpData {
+        message: message.to_string(),
+        executor_profile_id: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+        review: None,
+    }
+}
+
+fn messages(queue: &[QueuedMessage]) -> Vec<&str> {
+    queue.iter().map(|m| m.data.message.as_str()).collect()
+}
+
+#[tokio::test]
+async fn queue_drains_in_order_and_follows_reorders() {
+    let (db, project) = common::setup().await;
+    let pool = &db.pool;
+    let task = common::create_task(pool, &project, "queue").await;
+    let workspace = common::create_workspace(pool, &task, "vk/queue").await;
+    let session = common::create_session(pool, &workspace).await;
+
+    let first = QueuedMessage::enqueue(pool, session.id, &follow_up("first"))
+        .await
+        .unwrap();
+    let second = QueuedMessage::enqueue(pool, session.id, &follow_up("second"))
+        .await
+        .unwrap();
+    let third = QueuedMessage::enqueue(pool, session.id, &follow_up("third"))
+        .await
+        .unwrap();
+
+    let queue = QueuedMessage::find_by_session_id(pool, session.id)
+        .await
+        .unwrap();
+    assert_eq!(messages(&queue), ["first", "second", "third"]);
+
+    let queue = QueuedMessage::reorder(pool, session.id, &[third.id, first.id, second.id])
+        .await
+        .unwrap();
+    assert_eq!(messages(&queue), ["third", "first", "second"]);
+
+    // Editing keeps the position; new messages go to the end
+    QueuedMessage::update_data(pool, first.id, session.id, &follow_up("first, edited"))
+        .await
+        .unwrap();
+    QueuedMessage::enqueue(pool, session.id, &follow_up("fourth"))
+        .await
+        .unwrap();
+
+    let mut drained = Vec::new();
+    while let Some(message) = QueuedMessage::take_next(pool, session.id).await.unwrap() {
+        drained.push(message.data.message);
+    }
+    assert_eq!(drained, ["third", "first, edited", "second", "fourth"]);
+}
+
+#[tokio::test]
+async fn reorder_requires_every_queued_message_once() {
+    let (db, project) = common::setup().await;
+    let pool = &db.pool;
+    let task = common::create_task(pool, &project, "queue").await;
+    let workspace = common::create_workspace(pool, &task, "vk/queue").await;
+    let session = common::create_session(pool, &workspace).await;
+
+    let first = QueuedMessage::enqueue(pool, session.id, &follow_up("first"))
+        .await
+        .unwrap();
+    let second = QueuedMessage::enqueue(pool, session.id, &follow_up("second"))
+        .await
+        .unwrap();
+
+    for order in [
+        vec![first.id],
+        vec![first.id, first.id],
+        vec![first.id, second.id, Uuid::new_v4()],
+    ] {
+        assert!(matches!(
+            QueuedMessage::reorder(pool, session.id, &order).await,
+            Err(QueuedMessageError::InvalidOrder)
+        ));
+    }
+
+    let queue = QueuedMessage::find_by_session_id(pool, session.id)
+        .await
+        .unwrap();
+    assert_eq!(messages(&queue), ["first", "second"]);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use db::models::{
+    execution_process::ExecutionProcessStatus,
+    execution_process_logs::ExecutionProcessLogs,
+    search::{SearchFilters, SearchIndex, SearchResultKind, fts_query},
+};
+use uuid::Uuid;
+
+#[test]
+fn fts_query_quotes_every_term() {
+    assert_eq!(fts_query("   "), None);
+    assert_eq!(fts_query("login"), Some("\"login\"*".to_string()));
+    assert_eq!(
+        fts_query("fix: login-page NOT"),
+        Some("\"fix:\" \"login-page\" \"NOT\"*".to_string())
+    );
+    assert_eq!(
+        fts_query("say \"hi\""),
+        Some("\"say\" \"\"\"hi\"\"\"*".to_string())
+    );
+}
+
+#[tokio::test]
+async fn search_matches_tasks_and_logs_with_query_syntax_in_the_input() {
+    let (db, project) = common::setup().await;
+    let pool = &db.pool;
+    let task = common::create_task(pool, &project, "Fix login-page crash").await;
+    let workspace = common::create_workspace(pool, &task, "vk/login").await;
+    let session = common::create_session(pool, &workspace).await;
+    let process =
+        common::create_coding_agent_process(pool, &session, ExecutionProcessStatus::Completed)
+            .await;
+    SearchIndex::replace_log_entries(
+        pool,
+        process.id,
+        task.id,
+        process.started_at,
+        &["The session token was: expired (AND NOT refreshed)".to_string()],
+    )
+    .await
+    .unwrap();
+
+    let filters = SearchFilters::default();
+    let hits = SearchIndex::search(pool, "login-page cra", &filters, 10)
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].kind, SearchResultKind::Task);
+    assert_eq!(hits[0].task_id, task.id);
+
+    // FTS5 operators and punctuation are matched as plain words
+    let hits = SearchIndex::search(pool, "was: expired (AND NOT", &filters, 10)
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].kind, SearchResultKind::Log);
+    assert_eq!(hits[0].workspace_id, Some(workspace.id));
+    assert_eq!(hits[0].execution_process_id, Some(process.id));
+    assert!(hits[0].snippet.contains("<mark>"));
+
+    let other_project = SearchFilters {
+        project_id: Some(Uuid::new_v4()),
+        ..Default::default()
+    };
+    assert!(
+        SearchIndex::search(pool, "login", &other_project, 10)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn finished_executions_with_unindexed_logs_are_backfilled() {
+    let (db, project) = common::setup().await;
+    let pool = &db.pool;
+    let task = common::create_task(pool, &project, "backfill").await;
+    let workspace = common::create_workspace(pool, &task, "vk/backfill").await;
+    let session = common::create_session(pool, &workspace).await;
+
+    let finished =
+        common::create_coding_agent_process(pool, &session, ExecutionProcessStatus::Completed)
+            .await;
+    let running =
+        common::create_coding_agent_process(pool, &session, ExecutionProcessStatus::Running).await;
+    // Never produced any output
+    common::create_coding_agent_process(pool, &session, ExecutionProcessStatus::Failed).await;
+    for process in [&finished, &running] {
+        ExecutionProcessLogs::append_log_line(pool, process.id, r#"{"Stdout":"hello"}"#)
+            .await
+            .unwrap();
+    }
+
+    let unindexed = SearchIndex::find_unindexed_log_executions(pool)
+        .await
+        .unwrap();
+    assert_eq!(unindexed.len(), 1);
+    assert_eq!(unindexed[0].execution_process_id, finished.id);
+    assert_eq!(unindexed[0].task_id, task.id);
+
+    SearchIndex::replace_log_entries(
+        pool,
+        finished.id,
+        task.id,
+        finished.started_at,
+        &["hello".to_string()],
+    )
+    .await
+    .unwrap();
+    assert!(
+        SearchIndex::find_unindexed_log_executions(pool)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
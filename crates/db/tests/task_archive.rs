@@ -0,0 +1,85 @@
+mod common;
+
+use std::collections::HashMap;
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    execution_process_logs::ExecutionProcessLogs,
+    task_archive::TaskRecords,
+};
+
+#[tokio::test]
+async fn records_round_trip_through_insert_copy() {
+    let (db, project) = common::setup().await;
+    let pool = &db.pool;
+    let task = common::create_task(pool, &project, "Archive me").await;
+    let first = common::create_workspace(pool, &task, "vk/first").await;
+    let second = common::create_workspace(pool, &task, "vk/second").await;
+    let session = common::create_session(pool, &second).await;
+    let completed =
+        common::create_coding_agent_process(pool, &session, ExecutionProcessStatus::Completed)
+            .await;
+    let running =
+        common::create_coding_agent_process(pool, &session, ExecutionProcessStatus::Running).await;
+
+    let records = TaskRecords::load(pool, task.id).await.unwrap().unwrap();
+    let mut workspace_ids: Vec<_> = records.workspaces.iter().map(|w| w.id).collect();
+    workspace_ids.sort();
+    let mut expected = vec![first.id, second.id];
+    expected.sort();
+    assert_eq!(workspace_ids, expected);
+
+    // Records travel as JSON inside the archive
+    let json = serde_json::to_string(&records).unwrap();
+    let records: TaskRecords = serde_json::from_str(&json).unwrap();
+    let logs = HashMap::from([(completed.id, "{\"Stdout\":\"hello\"}\n".to_string())]);
+    let copy = records
+        .insert_copy(pool, project.id, &HashMap::new(), &[], &logs)
+        .await
+        .unwrap();
+    assert_ne!(copy.id, task.id);
+    assert_eq!(copy.title, task.title);
+
+    let copied = TaskRecords::load(pool, copy.id).await.unwrap().unwrap();
+    let mut branches: Vec<_> = copied
+        .workspaces
+        .iter()
+        .map(|w| w.branch.as_str())
+        .collect();
+    branches.sort();
+    assert_eq!(branches, vec!["vk/first", "vk/second"]);
+    assert!(copied.workspaces.iter().all(|w| w.container_ref.is_none()));
+    let copied_second = copied
+        .workspaces
+        .iter()
+        .find(|w| w.branch == "vk/second")
+        .unwrap();
+    assert_eq!(copied.sessions.len(), 1);
+    assert_eq!(copied.sessions[0].workspace_id, copied_second.id);
+    assert_eq!(copied.execution_processes.len(), 2);
+
+    // Processes that never finished are not resumable in the copy
+    let copied_completed = copied
+        .execution_processes
+        .iter()
+        .find(|p| p.status == ExecutionProcessStatus::Completed)
+        .unwrap();
+    assert!(
+        copied
+            .execution_processes
+            .iter()
+            .any(|p| p.status == ExecutionProcessStatus::Killed)
+    );
+    let stored = ExecutionProcessLogs::find_by_execution_id(pool, copied_completed.id)
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].logs, logs[&completed.id]);
+
+    // The original task is untouched
+    let original = ExecutionProcess::find_by_id(pool, running.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(original.status, ExecutionProcessStatus::Running);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use db::models::{
+    task::{Task, TaskStatus},
+    task_dependency::{
+        CreateTaskDependency, TaskDependency, TaskDependencyError, TaskDependencyType,
+    },
+};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+async fn add_dependency(
+    pool: &SqlitePool,
+    task: &Task,
+    related: &Task,
+    relationship_type: TaskDependencyType,
+) -> Result<TaskDependency, TaskDependencyError> {
+    TaskDependency::create(
+        pool,
+        task,
+        &CreateTaskDependency {
+            related_task_id: related.id,
+            relationship_type,
+        },
+    )
+    .await
+}
+
+#[tokio::test]
+async fn blocking_cycles_are_rejected() {
+    let (db, project) = common::setup().await;
+    let pool = &db.pool;
+    let a = common::create_task(pool, &project, "a").await;
+    let b = common::create_task(pool, &project, "b").await;
+    let c = common::create_task(pool, &project, "c").await;
+
+    add_dependency(pool, &a, &b, TaskDependencyType::Blocking)
+        .await
+        .unwrap();
+    add_dependency(pool, &b, &c, TaskDependencyType::Blocking)
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        add_dependency(pool, &c, &a, TaskDependencyType::Blocking).await,
+        Err(TaskDependencyError::Cycle)
+    ));
+    assert!(matches!(
+        add_dependency(pool, &a, &a, TaskDependencyType::Blocking).await,
+        Err(TaskDependencyError::SelfReference)
+    ));
+    assert!(matches!(
+        add_dependency(pool, &a, &b, TaskDependencyType::Blocking).await,
+        Err(TaskDependencyError::AlreadyExists)
+    ));
+    // Only blocking edges form cycles
+    add_dependency(pool, &c, &a, TaskDependencyType::Related)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn dependents_unblock_once_every_blocker_is_done() {
+    let (db, project) = common::setup().await;
+    let pool = &db.pool;
+    let a = common::create_task(pool, &project, "a").await;
+    let b = common::create_task(pool, &project, "b").await;
+    let blocked_by_both = common::create_task(pool, &project, "blocked by both").await;
+    let already_attempted = common::create_task(pool, &project, "already attempted").await;
+    let in_progress = common::create_task(pool, &project, "in progress").await;
+    let related = common::create_task(pool, &project, "related").await;
+
+    for task in [&blocked_by_both, &already_attempted, &in_progress] {
+        add_dependency(pool, &a, task, TaskDependencyType::Blocking)
+            .await
+            .unwrap();
+    }
+    add_dependency(pool, &b, &blocked_by_both, TaskDependencyType::Blocking)
+        .await
+        .unwrap();
+    add_dependency(pool, &a, &related, TaskDependencyType::Related)
+        .await
+        .unwrap();
+    common::create_workspace(pool, &already_attempted, "vk/already-attempted").await;
+    Task::update_status(pool, in_progress.id, TaskStatus::InProgress)
+        .await
+        .unwrap();
+
+    assert!(
+        TaskDependency::find_unblocked_dependents(pool, a.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let previous = Task::update_status(pool, a.id, TaskStatus::Done)
+        .await
+        .unwrap();
+    assert_eq!(previous, Some(TaskStatus::Todo));
+    assert!(
+        TaskDependency::find_unblocked_dependents(pool, a.id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    Task::update_status(pool, b.id, TaskStatus::Done)
+        .await
+        .unwrap();
+    let unblocked: Vec<Uuid> = TaskDependency::find_unblocked_dependents(pool, a.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|task| task.id)
+        .collect();
+    assert_eq!(unblocked, vec![blocked_by_both.id]);
+}
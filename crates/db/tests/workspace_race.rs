@@ -0,0 +1,32 @@
+mod common;
+
+use db::models::workspace_race::WorkspaceRace;
+
+#[tokio::test]
+async fn only_the_first_winner_is_recorded() {
+    let (db, project) = common::setup().await;
+    let pool = &db.pool;
+    let task = common::create_task(pool, &project, "race").await;
+
+    let mut workspace_ids = Vec::new();
+    for branch in ["vk/race-a", "vk/race-b"] {
+        workspace_ids.push(common::create_workspace(pool, &task, branch).await.id);
+    }
+    let race = WorkspaceRace::create(pool, task.id, &workspace_ids)
+        .await
+        .unwrap();
+
+    let (first, second) = tokio::join!(
+        WorkspaceRace::set_winner(pool, race.id, workspace_ids[0]),
+        WorkspaceRace::set_winner(pool, race.id, workspace_ids[1]),
+    );
+    let first = first.unwrap().expect("first pick wins");
+    assert_eq!(first.winner_workspace_id, Some(workspace_ids[0]));
+    assert!(second.unwrap().is_none());
+
+    let race = WorkspaceRace::find_by_workspace_id(pool, workspace_ids[1])
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(race.winner_workspace_id, Some(workspace_ids[0]));
+}
@@ -22,6 +22,7 @@ use services::services::{
     auth::AuthContext,
     config::{Config, ConfigError},
     container::{ContainerError, ContainerService},
+    drift_monitor::DriftMonitorService,
     events::{EventError, EventService},
     file_search::FileSearchCache,
     filesystem::{FilesystemError, FilesystemService},
@@ -31,6 +32,7 @@ use services::services::{
     project::ProjectService,
     queued_message::QueuedMessageService,
     repo::RepoService,
+    scheduler::SchedulerService,
     worktree_manager::WorktreeError,
 };
 use sqlx::Error as SqlxError;
@@ -88,7 +90,7 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn analytics(&self) -> &Option<AnalyticsService>;
 
-    fn container(&self) -> &impl ContainerService;
+    fn container(&self) -> &(impl ContainerService + Clone + Send + Sync + 'static);
 
     fn git(&self) -> &GitService;
 
@@ -129,7 +131,20 @@ pub trait Deployment: Clone + Send + Sync + 'static {
                 user_id: self.user_id().to_string(),
                 analytics_service: analytics_service.clone(),
             });
-        PrMonitorService::spawn(db, analytics).await
+        PrMonitorService::spawn(db, self.container().clone(), analytics).await
+    }
+
+    async fn spawn_drift_monitor_service(&self) -> tokio::task::JoinHandle<()> {
+        DriftMonitorService::spawn(
+            self.db().clone(),
+            self.container().clone(),
+            self.events().clone(),
+        )
+        .await
+    }
+
+    async fn spawn_scheduler_service(&self) -> tokio::task::JoinHandle<()> {
+        SchedulerService::spawn(self.db().clone(), self.container().clone()).await
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
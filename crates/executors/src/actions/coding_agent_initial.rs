@@ -10,7 +10,9 @@ use crate::{
     actions::Executable,
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
-    executors::{BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor},
+    executors::{
+        AppendPrompt, BaseCodingAgent, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+    },
     profile::ExecutorProfileId,
 };
 
@@ -25,6 +27,9 @@ pub struct CodingAgentInitialRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Accepted project memory, prepended to the prompt when the agent is spawned
+    #[serde(default)]
+    pub project_memory: AppendPrompt,
 }
 
 impl CodingAgentInitialRequest {
@@ -50,12 +55,13 @@ impl Executable for CodingAgentInitialRequest {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let effective_dir = self.effective_dir(current_dir);
+        let prompt = self.project_memory.prepend_to_prompt(&self.prompt);
 
         #[cfg(feature = "qa-mode")]
         {
             tracing::info!("QA mode: using mock executor instead of real agent");
             let executor = crate::executors::qa_mock::QaMockExecutor;
-            return executor.spawn(&effective_dir, &self.prompt, env).await;
+            return executor.spawn(&effective_dir, &prompt, env).await;
         }
 
         #[cfg(not(feature = "qa-mode"))]
@@ -69,7 +75,7 @@ impl Executable for CodingAgentInitialRequest {
 
             agent.use_approvals(approvals.clone());
 
-            agent.spawn(&effective_dir, &self.prompt, env).await
+            agent.spawn(&effective_dir, &prompt, env).await
         }
     }
 }
@@ -25,6 +25,7 @@ pub enum ScriptContext {
     CleanupScript,
     DevServer,
     ToolInstallScript,
+    VerifyScript,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
@@ -64,7 +65,7 @@ impl Executable for ScriptRequest {
             .current_dir(&effective_dir);
 
         // Apply environment variables
-        env.apply_to_command(&mut command);
+        env.apply_to_command_without_stdin(&mut command);
 
         let child = command.group_spawn()?;
 
@@ -31,6 +31,10 @@ impl CommandParts {
         Self { program, args }
     }
 
+    pub fn program(&self) -> &str {
+        &self.program
+    }
+
     pub async fn into_resolved(self) -> Result<(PathBuf, Vec<String>), ExecutorError> {
         let CommandParts { program, args } = self;
         let executable = resolve_executable_path(&program)
@@ -60,6 +64,87 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+    #[schemars(
+        title = "Execution Limits",
+        description = "Timeouts and resource caps applied to the executor process"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ExecutionLimits>,
+    #[schemars(
+        title = "Approval Timeout",
+        description = "How long tool approval requests wait for a response, and what happens after"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_timeout: Option<ApprovalTimeout>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
+pub struct ExecutionLimits {
+    #[schemars(
+        title = "Timeout (minutes)",
+        description = "Stop the executor after it has run for this many minutes"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_minutes: Option<u64>,
+    #[schemars(
+        title = "Idle Timeout (minutes)",
+        description = "Stop the executor when it produces no output for this many minutes"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_timeout_minutes: Option<u64>,
+    #[schemars(
+        title = "Memory Limit (MB)",
+        description = "Maximum memory for the executor and its children (Linux only)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_mb: Option<u64>,
+    #[schemars(
+        title = "CPU Limit",
+        description = "Maximum number of CPUs the executor may use, e.g. 1.5 (Linux only)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cpus: Option<f64>,
+    #[schemars(
+        title = "Process Limit",
+        description = "Maximum number of processes the executor and its children may run (Linux only)"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_processes: Option<u64>,
+}
+
+impl ExecutionLimits {
+    pub fn is_empty(&self) -> bool {
+        self.timeout_minutes.is_none()
+            && self.idle_timeout_minutes.is_none()
+            && self.max_memory_mb.is_none()
+            && self.max_cpus.is_none()
+            && self.max_processes.is_none()
+    }
+}
+
+/// What happens to a tool approval request nobody answered in time
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalTimeoutAction {
+    #[default]
+    Deny,
+    Approve,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
+pub struct ApprovalTimeout {
+    #[schemars(
+        title = "Timeout (minutes)",
+        description = "How long a tool approval request waits for a response, 60 by default"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_minutes: Option<u64>,
+    #[schemars(
+        title = "On Timeout",
+        description = "Whether unanswered requests are denied or approved, deny by default"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_timeout: Option<ApprovalTimeoutAction>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
@@ -1,9 +1,12 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use git::GitService;
 use tokio::process::Command;
 
-use crate::command::CmdOverrides;
+use crate::{command::CmdOverrides, sandbox::ContainerSandbox};
 
 /// Repository context for executor operations
 #[derive(Debug, Clone, Default)]
@@ -80,6 +83,11 @@ pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
     pub repo_context: RepoContext,
     pub commit_reminder: bool,
+    /// Container to run the process in instead of the host
+    pub sandbox: Option<ContainerSandbox>,
+    /// `cgroup.procs` of the group enforcing the execution's resource limits.
+    /// The process joins it before exec, so its children start inside it.
+    pub cgroup_procs: Option<PathBuf>,
 }
 
 impl ExecutionEnv {
@@ -88,6 +96,8 @@ impl ExecutionEnv {
             vars: HashMap::new(),
             repo_context,
             commit_reminder,
+            sandbox: None,
+            cgroup_procs: None,
         }
     }
 
@@ -117,11 +127,28 @@ impl ExecutionEnv {
         }
     }
 
-    /// Apply all environment variables to a Command
+    /// Apply all environment variables to a Command. With a sandbox the
+    /// command is then rewritten to run in its container, otherwise it joins
+    /// the execution's cgroup.
     pub fn apply_to_command(&self, command: &mut Command) {
+        self.apply(command, true);
+    }
+
+    /// Like `apply_to_command`, for commands that read nothing from stdin,
+    /// such as scripts. A sandboxed command then runs with stdin closed.
+    pub fn apply_to_command_without_stdin(&self, command: &mut Command) {
+        self.apply(command, false);
+    }
+
+    fn apply(&self, command: &mut Command, interactive: bool) {
         for (key, value) in &self.vars {
             command.env(key, value);
         }
+        if let Some(sandbox) = &self.sandbox {
+            *command = sandbox.wrap(command, interactive);
+        } else if let Some(procs) = &self.cgroup_procs {
+            join_cgroup_before_exec(command, procs);
+        }
     }
 
     pub fn contains_key(&self, key: &str) -> bool {
@@ -133,6 +160,28 @@ impl ExecutionEnv {
     }
 }
 
+/// Move the child into the cgroup between fork and exec. The file is opened
+/// up front so the child only issues a `write`, which is async-signal-safe.
+#[cfg(unix)]
+fn join_cgroup_before_exec(command: &mut Command, procs: &Path) {
+    use std::{fs::OpenOptions, io::Write};
+
+    let file = match OpenOptions::new().write(true).open(procs) {
+        Ok(file) => file,
+        Err(e) => {
+            tracing::warn!("Failed to open cgroup {}: {}", procs.display(), e);
+            return;
+        }
+    };
+    // SAFETY: the hook only writes to an already open file descriptor
+    unsafe {
+        command.pre_exec(move || (&file).write_all(b"0"));
+    }
+}
+
+#[cfg(not(unix))]
+fn join_cgroup_before_exec(_command: &mut Command, _procs: &Path) {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
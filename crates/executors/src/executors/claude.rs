@@ -37,8 +37,8 @@ use crate::{
         codex::client::LogWriter, utils::reorder_slash_commands,
     },
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolStatus,
+        ActionType, FileChange, ModelTokenUsage, NormalizedEntry, NormalizedEntryError,
+        NormalizedEntryType, TodoItem, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
             EntryIndexProvider,
@@ -1305,7 +1305,7 @@ impl ClaudeLogProcessor {
                         let total_tokens = input_tokens + output_tokens;
                         self.context_tokens_used = total_tokens as u32;
 
-                        patches.push(self.add_token_usage_entry(entry_index_provider));
+                        patches.push(self.add_token_usage_entry(entry_index_provider, Vec::new()));
                     }
                 }
                 ClaudeStreamEvent::MessageStop => {
@@ -1318,19 +1318,28 @@ impl ClaudeLogProcessor {
             ClaudeJson::Result {
                 is_error,
                 model_usage,
+                usage,
                 subtype,
                 result,
                 ..
             } => {
                 // get the real model context window and correct the context usage entry
-                if let Some(context_window) = model_usage.as_ref().and_then(|model_usage| {
+                let context_window = model_usage.as_ref().and_then(|model_usage| {
                     self.main_model_name
                         .as_ref()
                         .and_then(|name| model_usage.get(name))
                         .and_then(|usage| usage.context_window)
-                }) {
+                });
+                if let Some(context_window) = context_window {
                     self.main_model_context_window = context_window;
-                    patches.push(self.add_token_usage_entry(entry_index_provider));
+                }
+                let turn_usage = Self::turn_usage(
+                    model_usage.as_ref(),
+                    usage.as_ref(),
+                    self.main_model_name.as_deref(),
+                );
+                if context_window.is_some() || !turn_usage.is_empty() {
+                    patches.push(self.add_token_usage_entry(entry_index_provider, turn_usage));
                 }
 
                 if matches!(self.strategy, HistoryStrategy::AmpResume) && is_error.unwrap_or(false)
@@ -1516,12 +1525,14 @@ impl ClaudeLogProcessor {
     fn add_token_usage_entry(
         &mut self,
         entry_index_provider: &EntryIndexProvider,
+        model_usage: Vec<ModelTokenUsage>,
     ) -> json_patch::Patch {
         let entry = NormalizedEntry {
             timestamp: None,
             entry_type: NormalizedEntryType::TokenUsageInfo(crate::logs::TokenUsageInfo {
                 total_tokens: self.context_tokens_used,
                 model_context_window: self.main_model_context_window,
+                model_usage,
             }),
             content: format!(
                 "Tokens used: {} / Context window: {}",
@@ -1532,6 +1543,39 @@ impl ClaudeLogProcessor {
         let idx = entry_index_provider.next();
         ConversationPatch::add_normalized_entry(idx, entry)
     }
+
+    /// Billable usage of a finished turn, split by model when the CLI reports it
+    fn turn_usage(
+        model_usage: Option<&HashMap<String, ClaudeModelUsage>>,
+        usage: Option<&ClaudeUsage>,
+        main_model_name: Option<&str>,
+    ) -> Vec<ModelTokenUsage> {
+        let mut turn_usage: Vec<ModelTokenUsage> = model_usage
+            .into_iter()
+            .flatten()
+            .filter(|(_, usage)| usage.input_tokens.is_some() || usage.output_tokens.is_some())
+            .map(|(model, usage)| ModelTokenUsage {
+                model: model.clone(),
+                input_tokens: usage.input_tokens.unwrap_or(0),
+                output_tokens: usage.output_tokens.unwrap_or(0),
+                cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                cache_write_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+            })
+            .collect();
+        if turn_usage.is_empty()
+            && let (Some(usage), Some(model)) = (usage, main_model_name)
+        {
+            turn_usage.push(ModelTokenUsage {
+                model: model.to_string(),
+                input_tokens: usage.input_tokens.unwrap_or(0),
+                output_tokens: usage.output_tokens.unwrap_or(0),
+                cache_read_tokens: usage.cache_read_input_tokens.unwrap_or(0),
+                cache_write_tokens: usage.cache_creation_input_tokens.unwrap_or(0),
+            });
+        }
+        turn_usage.sort_by(|a, b| a.model.cmp(&b.model));
+        turn_usage
+    }
 }
 
 fn add_system_message(
@@ -1928,6 +1972,14 @@ pub struct ClaudeUsage {
 pub struct ClaudeModelUsage {
     #[serde(default)]
     pub context_window: Option<u32>,
+    #[serde(default)]
+    pub input_tokens: Option<u64>,
+    #[serde(default)]
+    pub output_tokens: Option<u64>,
+    #[serde(default)]
+    pub cache_read_input_tokens: Option<u64>,
+    #[serde(default)]
+    pub cache_creation_input_tokens: Option<u64>,
 }
 
 /// Structured tool data for Claude tools based on real samples
@@ -2339,6 +2391,8 @@ mod tests {
                 base_command_override: None,
                 additional_params: None,
                 env: None,
+                limits: None,
+                approval_timeout: None,
             },
             approvals_service: None,
             disable_api_key: None,
@@ -2670,4 +2724,37 @@ mod tests {
         let parsed: ClaudeJson = serde_json::from_str(control_request_json).unwrap();
         assert!(matches!(parsed, ClaudeJson::ControlRequest { .. }));
     }
+
+    #[test]
+    fn test_result_reports_turn_usage_per_model() {
+        let result_json = r#"{"type":"result","subtype":"success","is_error":false,"modelUsage":{"claude-sonnet-4":{"inputTokens":12,"outputTokens":340,"cacheReadInputTokens":5000,"cacheCreationInputTokens":800,"contextWindow":200000},"claude-haiku-4":{"inputTokens":30,"outputTokens":4}}}"#;
+        let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
+
+        let entries = normalize(&parsed, "");
+        let NormalizedEntryType::TokenUsageInfo(info) = &entries[0].entry_type else {
+            panic!(
+                "expected a token usage entry, got {:?}",
+                entries[0].entry_type
+            );
+        };
+        assert_eq!(
+            info.model_usage,
+            vec![
+                ModelTokenUsage {
+                    model: "claude-haiku-4".to_string(),
+                    input_tokens: 30,
+                    output_tokens: 4,
+                    cache_read_tokens: 0,
+                    cache_write_tokens: 0,
+                },
+                ModelTokenUsage {
+                    model: "claude-sonnet-4".to_string(),
+                    input_tokens: 12,
+                    output_tokens: 340,
+                    cache_read_tokens: 5000,
+                    cache_write_tokens: 800,
+                },
+            ]
+        );
+    }
 }
@@ -34,9 +34,9 @@ use crate::{
     approvals::ToolCallMetadata,
     executors::codex::session::SessionHandler,
     logs::{
-        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-        NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType,
-        ToolStatus,
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, ModelTokenUsage,
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult,
+        ToolResultValueType, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
             ConversationPatch, EntryIndexProvider,
@@ -215,6 +215,12 @@ struct LogState {
     mcp_tools: HashMap<String, McpToolState>,
     patches: HashMap<String, PatchState>,
     web_searches: HashMap<String, WebSearchState>,
+    /// Model of the session, which token counts are attributed to
+    model: Option<String>,
+    /// Usage of each request of this turn
+    request_usage: Vec<ModelTokenUsage>,
+    /// Session total at the last token count, to skip repeated reports
+    last_total_tokens: Option<u64>,
 }
 
 enum StreamingTextKind {
@@ -232,6 +238,9 @@ impl LogState {
             mcp_tools: HashMap::new(),
             patches: HashMap::new(),
             web_searches: HashMap::new(),
+            model: None,
+            request_usage: Vec::new(),
+            last_total_tokens: None,
         }
     }
 
@@ -378,7 +387,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             }
 
             if let Ok(response) = serde_json::from_str::<JSONRPCResponse>(&line) {
-                handle_jsonrpc_response(response, &msg_store, &entry_index);
+                handle_jsonrpc_response(response, &mut state, &msg_store, &entry_index);
                 continue;
             }
 
@@ -387,6 +396,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                     server_notification
                 {
                     msg_store.push_session_id(session_configured.session_id.to_string());
+                    state.model = Some(session_configured.model.clone());
                     handle_model_params(
                         session_configured.model,
                         session_configured.reasoning_effort,
@@ -425,6 +435,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
             match event {
                 EventMsg::SessionConfigured(payload) => {
                     msg_store.push_session_id(payload.session_id.to_string());
+                    state.model = Some(payload.model.clone());
                     handle_model_params(
                         payload.model,
                         payload.reasoning_effort,
@@ -999,6 +1010,22 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                 }
                 EventMsg::TokenCount(payload) => {
                     if let Some(info) = payload.info {
+                        // Token counts are re-sent with rate limit updates; only a
+                        // changed session total means a new request was made
+                        let total_tokens = token_count(info.total_token_usage.total_tokens);
+                        if state.last_total_tokens != Some(total_tokens) {
+                            state.last_total_tokens = Some(total_tokens);
+                            let usage = &info.last_token_usage;
+                            let cached_input = token_count(usage.cached_input_tokens);
+                            state.request_usage.push(ModelTokenUsage {
+                                model: state.model.clone().unwrap_or_else(|| "unknown".to_string()),
+                                input_tokens: token_count(usage.input_tokens)
+                                    .saturating_sub(cached_input),
+                                output_tokens: token_count(usage.output_tokens),
+                                cache_read_tokens: cached_input,
+                                cache_write_tokens: 0,
+                            });
+                        }
                         add_normalized_entry(
                             &msg_store,
                             &entry_index,
@@ -1011,6 +1038,9 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                                             .model_context_window
                                             .unwrap_or_default()
                                             as u32,
+                                        model_usage: ModelTokenUsage::merge_by_model(
+                                            state.request_usage.iter().cloned(),
+                                        ),
                                     },
                                 ),
                                 content: format!(
@@ -1079,6 +1109,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
 
 fn handle_jsonrpc_response(
     response: JSONRPCResponse,
+    state: &mut LogState,
     msg_store: &Arc<MsgStore>,
     entry_index: &EntryIndexProvider,
 ) {
@@ -1086,6 +1117,7 @@ fn handle_jsonrpc_response(
     else {
         return;
     };
+    state.model = Some(response.model.clone());
 
     match SessionHandler::extract_session_id_from_rollout_path(response.rollout_path) {
         Ok(session_id) => msg_store.push_session_id(session_id),
@@ -1100,6 +1132,11 @@ fn handle_jsonrpc_response(
     );
 }
 
+/// Codex reports token counts as signed integers
+fn token_count(value: impl TryInto<u64>) -> u64 {
+    value.try_into().unwrap_or(0)
+}
+
 fn handle_model_params(
     model: String,
     reasoning_effort: Option<ReasoningEffort>,
@@ -0,0 +1,659 @@
+use std::{collections::HashMap, path::Path, process::Stdio, sync::Arc};
+
+use async_trait::async_trait;
+use command_group::AsyncCommandGroup;
+use derivative::Derivative;
+use futures::StreamExt;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{fs, io::AsyncWriteExt, process::Command};
+use ts_rs::TS;
+use uuid::Uuid;
+use workspace_utils::{
+    msg_store::MsgStore, path::get_vibe_kanban_temp_dir, shell::resolve_executable_path_blocking,
+};
+
+use crate::{
+    approvals::ExecutorApprovalService,
+    command::{CmdOverrides, CommandBuildError, CommandBuilder, CommandParts, apply_overrides},
+    env::ExecutionEnv,
+    executors::{
+        AppendPrompt, AvailabilityInfo, ExecutorError, SpawnedChild, StandardCodingAgentExecutor,
+        acp::AcpAgentHarness,
+    },
+    logs::{
+        ActionType, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, ToolStatus,
+        plain_text_processor::PlainTextLogProcessor,
+        stderr_processor::normalize_stderr_logs,
+        utils::{EntryIndexProvider, patch::add_normalized_entry},
+    },
+    stdout_dup::duplicate_stdout,
+};
+
+const PROMPT_PLACEHOLDER: &str = "{prompt}";
+const PROMPT_FILE_PLACEHOLDER: &str = "{prompt_file}";
+const SESSION_ID_PLACEHOLDER: &str = "{session_id}";
+
+/// How the prompt is handed to the agent: written to its stdin, passed as the
+/// `{prompt}` argument, or written to a file passed as the `{prompt_file}`
+/// argument. Missing placeholders are appended as the last argument.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptDelivery {
+    #[default]
+    Stdin,
+    Arg,
+    File,
+}
+
+/// How the agent's output is turned into conversation entries: each line as
+/// agent text, JSON objects read with `json_lines`, or the Agent Client
+/// Protocol over stdio
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CustomLogFormat {
+    #[default]
+    PlainText,
+    JsonLines,
+    Acp,
+}
+
+/// Entry a JSON line is shown as
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonLineKind {
+    Assistant,
+    Thinking,
+    Tool,
+    System,
+    Error,
+}
+
+/// Where the fields of JSON-lines output are found. Fields are dot-separated
+/// paths, e.g. `message.content`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema, Default)]
+pub struct JsonLinesMapping {
+    #[schemars(
+        title = "Type Field",
+        description = "Field holding the event type, `type` by default"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub type_field: Option<String>,
+    #[schemars(
+        title = "Content Field",
+        description = "Field holding the text of the event, `content` by default"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_field: Option<String>,
+    #[schemars(
+        title = "Tool Name Field",
+        description = "Field holding the tool name of tool events"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_name_field: Option<String>,
+    #[schemars(
+        title = "Session ID Field",
+        description = "Field holding the session ID used to resume the agent"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id_field: Option<String>,
+    #[schemars(
+        title = "Event Types",
+        description = "How each event type is shown; events of other types are hidden. All events are shown as agent text when unset"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_types: Option<HashMap<String, JsonLineKind>>,
+}
+
+impl JsonLinesMapping {
+    /// Conversation entry for one line of output, if it should be shown
+    fn entry(&self, line: &str) -> Option<NormalizedEntry> {
+        let Ok(value) = serde_json::from_str::<Value>(line) else {
+            return Some(NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::SystemMessage,
+                content: line.to_string(),
+                metadata: None,
+            });
+        };
+
+        let kind = match &self.event_types {
+            Some(event_types) => {
+                let event_type = field(&value, self.type_field.as_deref().unwrap_or("type"))
+                    .and_then(Value::as_str)?;
+                *event_types.get(event_type)?
+            }
+            None => JsonLineKind::Assistant,
+        };
+        let content = match field(&value, self.content_field.as_deref().unwrap_or("content")) {
+            Some(Value::String(text)) => text.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+
+        let entry_type = match kind {
+            JsonLineKind::Assistant => NormalizedEntryType::AssistantMessage,
+            JsonLineKind::Thinking => NormalizedEntryType::Thinking,
+            JsonLineKind::System => NormalizedEntryType::SystemMessage,
+            JsonLineKind::Error => NormalizedEntryType::ErrorMessage {
+                error_type: NormalizedEntryError::Other,
+            },
+            JsonLineKind::Tool => {
+                let tool_name = self
+                    .tool_name_field
+                    .as_deref()
+                    .and_then(|path| field(&value, path))
+                    .and_then(Value::as_str)
+                    .unwrap_or("tool")
+                    .to_string();
+                NormalizedEntryType::ToolUse {
+                    tool_name: tool_name.clone(),
+                    action_type: ActionType::Tool {
+                        tool_name,
+                        arguments: None,
+                        result: None,
+                    },
+                    status: ToolStatus::Success,
+                }
+            }
+        };
+        if content.is_empty() && !matches!(entry_type, NormalizedEntryType::ToolUse { .. }) {
+            return None;
+        }
+
+        Some(NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content,
+            metadata: Some(value),
+        })
+    }
+
+    fn session_id(&self, line: &str) -> Option<String> {
+        let path = self.session_id_field.as_deref()?;
+        let value = serde_json::from_str::<Value>(line).ok()?;
+        match field(&value, path)? {
+            Value::String(id) => Some(id.clone()),
+            Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        }
+    }
+}
+
+fn field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+/// Replace the placeholders of `args`. The prompt, or the prompt file, is
+/// appended as the last argument when its placeholder is missing.
+fn render_args(
+    args: Vec<String>,
+    delivery: PromptDelivery,
+    prompt: &str,
+    prompt_file: Option<&Path>,
+    session_id: Option<&str>,
+) -> Vec<String> {
+    let prompt_file = prompt_file
+        .map(|path| path.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let delivered = match delivery {
+        PromptDelivery::Stdin => None,
+        PromptDelivery::Arg => Some((PROMPT_PLACEHOLDER, prompt)),
+        PromptDelivery::File => Some((PROMPT_FILE_PLACEHOLDER, prompt_file.as_str())),
+    };
+    let append =
+        delivered.filter(|(placeholder, _)| !args.iter().any(|arg| arg.contains(placeholder)));
+
+    let mut rendered: Vec<String> = args
+        .into_iter()
+        .map(|arg| {
+            let mut arg = arg.replace(SESSION_ID_PLACEHOLDER, session_id.unwrap_or_default());
+            if let Some((placeholder, value)) = delivered {
+                arg = arg.replace(placeholder, value);
+            }
+            arg
+        })
+        .collect();
+    if let Some((_, value)) = append {
+        rendered.push(value.to_string());
+    }
+    rendered
+}
+
+/// Any CLI agent, configured through its profile instead of code
+#[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
+#[derivative(Debug, PartialEq)]
+pub struct Custom {
+    #[serde(default)]
+    pub append_prompt: AppendPrompt,
+    #[schemars(
+        title = "Command",
+        description = "Command that starts the agent, e.g. `aider --yes-always --message {prompt}`. `{prompt}` and `{prompt_file}` are replaced according to the prompt delivery"
+    )]
+    pub command: String,
+    #[schemars(
+        title = "Follow-up Command",
+        description = "Command that resumes a session, with `{session_id}` replaced by the ID captured from the first run. Follow-ups are not supported when unset, except over ACP"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_up_command: Option<String>,
+    #[schemars(
+        title = "Prompt Delivery",
+        description = "How the prompt is passed to the agent: stdin, arg or file"
+    )]
+    #[serde(default)]
+    pub prompt_delivery: PromptDelivery,
+    #[schemars(
+        title = "Session ID Pattern",
+        description = "Regex matched against each output line to capture the session ID; its first group is used when it has one"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id_regex: Option<String>,
+    #[schemars(
+        title = "Log Format",
+        description = "How the agent's output is displayed: plain_text, json_lines or acp"
+    )]
+    #[serde(default)]
+    pub log_format: CustomLogFormat,
+    #[schemars(
+        title = "JSON Lines Mapping",
+        description = "Where to find the fields of json_lines output"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json_lines: Option<JsonLinesMapping>,
+    #[serde(flatten)]
+    pub cmd: CmdOverrides,
+    #[serde(skip)]
+    #[ts(skip)]
+    #[derivative(Debug = "ignore", PartialEq = "ignore")]
+    pub approvals: Option<Arc<dyn ExecutorApprovalService>>,
+}
+
+impl Custom {
+    fn build_command_builder(&self, template: &str) -> Result<CommandBuilder, CommandBuildError> {
+        apply_overrides(CommandBuilder::new(template), &self.cmd)
+    }
+
+    fn session_id_regex(&self) -> Option<Regex> {
+        let pattern = self.session_id_regex.as_deref()?;
+        Regex::new(pattern)
+            .inspect_err(|e| tracing::warn!("Invalid session ID pattern '{pattern}': {e}"))
+            .ok()
+    }
+
+    async fn write_prompt_file(prompt: &str) -> Result<std::path::PathBuf, ExecutorError> {
+        let prompt_dir = get_vibe_kanban_temp_dir().join("custom_prompts");
+        fs::create_dir_all(&prompt_dir)
+            .await
+            .map_err(ExecutorError::Io)?;
+        let prompt_file = prompt_dir.join(format!("{}.md", Uuid::new_v4()));
+        fs::write(&prompt_file, prompt)
+            .await
+            .map_err(ExecutorError::Io)?;
+        Ok(prompt_file)
+    }
+
+    /// Environment of the agent process. The prompt file is written outside
+    /// the workspace, so a sandboxed agent gets it mounted as well.
+    fn command_env(&self, env: &ExecutionEnv, prompt_file: Option<&Path>) -> ExecutionEnv {
+        let mut env = env.clone().with_profile(&self.cmd);
+        if let (Some(sandbox), Some(prompt_file)) = (env.sandbox.as_mut(), prompt_file) {
+            sandbox.mounts.push(prompt_file.to_path_buf());
+        }
+        env
+    }
+
+    async fn spawn_cli(
+        &self,
+        current_dir: &Path,
+        command_parts: CommandParts,
+        prompt: &str,
+        session_id: Option<&str>,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let (program_path, args) = command_parts.into_resolved().await?;
+        let prompt = self.append_prompt.combine_prompt(prompt);
+        let prompt_file = match self.prompt_delivery {
+            PromptDelivery::File => Some(Self::write_prompt_file(&prompt).await?),
+            PromptDelivery::Stdin | PromptDelivery::Arg => None,
+        };
+        let args = render_args(
+            args,
+            self.prompt_delivery,
+            &prompt,
+            prompt_file.as_deref(),
+            session_id,
+        );
+
+        let stdin = match self.prompt_delivery {
+            PromptDelivery::Stdin => Stdio::piped(),
+            PromptDelivery::Arg | PromptDelivery::File => Stdio::null(),
+        };
+        let mut command = Command::new(program_path);
+        command
+            .kill_on_drop(true)
+            .stdin(stdin)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .current_dir(current_dir)
+            .args(&args);
+
+        self.command_env(env, prompt_file.as_deref())
+            .apply_to_command(&mut command);
+
+        let mut child = match command.group_spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                if let Some(prompt_file) = &prompt_file {
+                    let _ = fs::remove_file(prompt_file).await;
+                }
+                return Err(e.into());
+            }
+        };
+
+        // The prompt file is needed until the agent exits, which closes its stdout
+        if let Some(prompt_file) = prompt_file {
+            let mut stdout = duplicate_stdout(&mut child)?;
+            tokio::spawn(async move {
+                while stdout.next().await.is_some() {}
+                if let Err(e) = fs::remove_file(&prompt_file).await {
+                    tracing::warn!(
+                        "Failed to delete prompt file {}: {}",
+                        prompt_file.display(),
+                        e
+                    );
+                }
+            });
+        }
+
+        if let Some(mut stdin) = child.inner().stdin.take() {
+            stdin.write_all(prompt.as_bytes()).await?;
+            stdin.shutdown().await?;
+        }
+
+        Ok(child.into())
+    }
+}
+
+#[async_trait]
+impl StandardCodingAgentExecutor for Custom {
+    fn use_approvals(&mut self, approvals: Arc<dyn ExecutorApprovalService>) {
+        self.approvals = Some(approvals);
+    }
+
+    async fn spawn(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        let command_parts = self.build_command_builder(&self.command)?.build_initial()?;
+        if self.log_format == CustomLogFormat::Acp {
+            return AcpAgentHarness::with_session_namespace("custom_sessions")
+                .spawn_with_command(
+                    current_dir,
+                    self.append_prompt.combine_prompt(prompt),
+                    command_parts,
+                    env,
+                    &self.cmd,
+                    self.approvals.clone(),
+                )
+                .await;
+        }
+        self.spawn_cli(current_dir, command_parts, prompt, None, env)
+            .await
+    }
+
+    async fn spawn_follow_up(
+        &self,
+        current_dir: &Path,
+        prompt: &str,
+        session_id: &str,
+        env: &ExecutionEnv,
+    ) -> Result<SpawnedChild, ExecutorError> {
+        if self.log_format == CustomLogFormat::Acp {
+            let command_parts = self
+                .build_command_builder(&self.command)?
+                .build_follow_up(&[])?;
+            return AcpAgentHarness::with_session_namespace("custom_sessions")
+                .spawn_follow_up_with_command(
+                    current_dir,
+                    self.append_prompt.combine_prompt(prompt),
+                    session_id,
+                    command_parts,
+                    env,
+                    &self.cmd,
+                    self.approvals.clone(),
+                )
+                .await;
+        }
+        let Some(follow_up_command) = &self.follow_up_command else {
+            return Err(ExecutorError::FollowUpNotSupported(
+                "no follow-up command is configured for this custom agent".to_string(),
+            ));
+        };
+        let command_parts = self
+            .build_command_builder(follow_up_command)?
+            .build_follow_up(&[])?;
+        self.spawn_cli(current_dir, command_parts, prompt, Some(session_id), env)
+            .await
+    }
+
+    fn normalize_logs(&self, msg_store: Arc<MsgStore>, worktree_path: &Path) {
+        if self.log_format == CustomLogFormat::Acp {
+            super::acp::normalize_logs(msg_store, worktree_path);
+            return;
+        }
+
+        let entry_index_counter = EntryIndexProvider::start_from(&msg_store);
+        normalize_stderr_logs(msg_store.clone(), entry_index_counter.clone());
+
+        let session_id_regex = self.session_id_regex();
+        let json_lines = (self.log_format == CustomLogFormat::JsonLines)
+            .then(|| self.json_lines.clone().unwrap_or_default());
+        tokio::spawn(async move {
+            let mut stdout_lines = msg_store.stdout_lines_stream();
+            let mut processor = plain_text_normalizer(entry_index_counter.clone());
+            let mut session_id_found = false;
+
+            while let Some(Ok(line)) = stdout_lines.next().await {
+                if !session_id_found {
+                    let session_id = json_lines
+                        .as_ref()
+                        .and_then(|mapping| mapping.session_id(&line))
+                        .or_else(|| {
+                            let caps = session_id_regex.as_ref()?.captures(&line)?;
+                            caps.get(1)
+                                .or_else(|| caps.get(0))
+                                .map(|id| id.as_str().to_string())
+                        });
+                    if let Some(session_id) = session_id {
+                        msg_store.push_session_id(session_id);
+                        session_id_found = true;
+                    }
+                }
+
+                match &json_lines {
+                    Some(mapping) => {
+                        let line = line.trim();
+                        if !line.is_empty()
+                            && let Some(entry) = mapping.entry(line)
+                        {
+                            add_normalized_entry(&msg_store, &entry_index_counter, entry);
+                        }
+                    }
+                    None => {
+                        for patch in processor.process(line + "\n") {
+                            msg_store.push_patch(patch);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    fn default_mcp_config_path(&self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    fn get_availability_info(&self) -> AvailabilityInfo {
+        let installed = CommandBuilder::new(self.command.as_str())
+            .build_initial()
+            .ok()
+            .and_then(|parts| resolve_executable_path_blocking(parts.program()))
+            .is_some();
+        if installed {
+            AvailabilityInfo::InstallationFound
+        } else {
+            AvailabilityInfo::NotFound
+        }
+    }
+}
+
+fn plain_text_normalizer(index_provider: EntryIndexProvider) -> PlainTextLogProcessor {
+    PlainTextLogProcessor::builder()
+        .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content,
+            metadata: None,
+        }))
+        .transform_lines(Box::new(|lines| {
+            lines.iter_mut().for_each(|line| {
+                *line = strip_ansi_escapes::strip_str(&line);
+            })
+        }))
+        .index_provider(index_provider)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{
+        command::ExecutionLimits,
+        env::RepoContext,
+        sandbox::{ContainerRuntime, ContainerSandbox},
+    };
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn renders_command_placeholders() {
+        let rendered = render_args(
+            args(&["--resume", "{session_id}", "--message", "{prompt}"]),
+            PromptDelivery::Arg,
+            "fix the bug",
+            None,
+            Some("abc"),
+        );
+        assert_eq!(
+            rendered,
+            args(&["--resume", "abc", "--message", "fix the bug"])
+        );
+
+        let rendered = render_args(
+            args(&["--yes"]),
+            PromptDelivery::File,
+            "fix the bug",
+            Some(Path::new("/tmp/prompt.md")),
+            None,
+        );
+        assert_eq!(rendered, args(&["--yes", "/tmp/prompt.md"]));
+
+        let rendered = render_args(args(&["--yes"]), PromptDelivery::Stdin, "fix", None, None);
+        assert_eq!(rendered, args(&["--yes"]));
+    }
+
+    #[test]
+    fn sandboxed_agents_get_the_prompt_file_mounted() {
+        let custom: Custom = serde_json::from_value(serde_json::json!({
+            "command": "aider --message-file {prompt_file}",
+            "prompt_delivery": "file",
+        }))
+        .unwrap();
+        let mut env = ExecutionEnv::new(RepoContext::default(), false);
+        env.sandbox = Some(ContainerSandbox {
+            runtime: ContainerRuntime::Docker,
+            image: "node:22".to_string(),
+            name: "vk-exec-1".to_string(),
+            mounts: vec![PathBuf::from("/work/ws")],
+            home: None,
+            network: false,
+            ports: vec![],
+            env_passthrough: vec![],
+            user: None,
+            limits: ExecutionLimits::default(),
+        });
+        let prompt_file = Path::new("/tmp/vibe-kanban/custom_prompts/1.md");
+
+        let mut command = Command::new("aider");
+        command.current_dir("/work/ws").args(render_args(
+            args(&["--message-file", "{prompt_file}"]),
+            PromptDelivery::File,
+            "fix the bug",
+            Some(prompt_file),
+            None,
+        ));
+        custom
+            .command_env(&env, Some(prompt_file))
+            .apply_to_command(&mut command);
+
+        let run: Vec<_> = command
+            .as_std()
+            .get_args()
+            .map(|arg| arg.to_str().unwrap())
+            .collect();
+        let volume = format!("{0}:{0}", prompt_file.display());
+        assert!(
+            run.windows(2)
+                .any(|pair| pair == ["--volume", volume.as_str()])
+        );
+        assert_eq!(run.last(), Some(&"/tmp/vibe-kanban/custom_prompts/1.md"));
+        // The profile's own sandbox is left as it was
+        assert_eq!(env.sandbox.unwrap().mounts, vec![PathBuf::from("/work/ws")]);
+    }
+
+    #[test]
+    fn maps_json_lines_to_entries() {
+        let mapping = JsonLinesMapping {
+            content_field: Some("message.text".to_string()),
+            tool_name_field: Some("name".to_string()),
+            session_id_field: Some("session".to_string()),
+            event_types: Some(HashMap::from([
+                ("text".to_string(), JsonLineKind::Assistant),
+                ("tool_call".to_string(), JsonLineKind::Tool),
+            ])),
+            ..Default::default()
+        };
+
+        let entry = mapping
+            .entry(r#"{"type":"text","message":{"text":"Done"}}"#)
+            .unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::AssistantMessage
+        ));
+        assert_eq!(entry.content, "Done");
+
+        let entry = mapping
+            .entry(r#"{"type":"tool_call","name":"bash"}"#)
+            .unwrap();
+        assert!(matches!(
+            entry.entry_type,
+            NormalizedEntryType::ToolUse { ref tool_name, .. } if tool_name == "bash"
+        ));
+
+        assert!(mapping.entry(r#"{"type":"usage","tokens":3}"#).is_none());
+        assert_eq!(
+            mapping.session_id(r#"{"type":"init","session":"s-1"}"#),
+            Some("s-1".to_string())
+        );
+    }
+}
@@ -18,11 +18,11 @@ use crate::executors::qa_mock::QaMockExecutor;
 use crate::{
     actions::{ExecutorAction, review::RepoReviewContext},
     approvals::ExecutorApprovalService,
-    command::CommandBuildError,
+    command::{ApprovalTimeout, CmdOverrides, CommandBuildError, ExecutionLimits},
     env::ExecutionEnv,
     executors::{
         amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
-        droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
+        custom::Custom, droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
     },
     logs::utils::patch,
     mcp_config::McpConfig,
@@ -34,6 +34,7 @@ pub mod claude;
 pub mod codex;
 pub mod copilot;
 pub mod cursor;
+pub mod custom;
 pub mod droid;
 pub mod gemini;
 pub mod opencode;
@@ -117,6 +118,8 @@ pub enum CodingAgent {
     QwenCode,
     Copilot,
     Droid,
+    /// Any CLI agent, configured entirely through its profile
+    Custom,
     #[cfg(feature = "qa-mode")]
     QaMock(QaMockExecutor),
 }
@@ -191,11 +194,41 @@ impl CodingAgent {
                 vec![BaseAgentCapability::SessionFork]
             }
             Self::CursorAgent(_) => vec![BaseAgentCapability::SetupHelper],
-            Self::Copilot(_) => vec![],
+            Self::Copilot(_) | Self::Custom(_) => vec![],
             #[cfg(feature = "qa-mode")]
             Self::QaMock(_) => vec![], // QA mock doesn't need special capabilities
         }
     }
+
+    fn cmd_overrides(&self) -> Option<&CmdOverrides> {
+        match self {
+            Self::ClaudeCode(agent) => Some(&agent.cmd),
+            Self::Amp(agent) => Some(&agent.cmd),
+            Self::Gemini(agent) => Some(&agent.cmd),
+            Self::Codex(agent) => Some(&agent.cmd),
+            Self::Opencode(agent) => Some(&agent.cmd),
+            Self::CursorAgent(agent) => Some(&agent.cmd),
+            Self::QwenCode(agent) => Some(&agent.cmd),
+            Self::Copilot(agent) => Some(&agent.cmd),
+            Self::Droid(agent) => Some(&agent.cmd),
+            Self::Custom(agent) => Some(&agent.cmd),
+            #[cfg(feature = "qa-mode")]
+            Self::QaMock(_) => None,
+        }
+    }
+
+    /// Timeouts and resource caps configured for this profile, if any
+    pub fn execution_limits(&self) -> Option<&ExecutionLimits> {
+        self.cmd_overrides()?
+            .limits
+            .as_ref()
+            .filter(|limits| !limits.is_empty())
+    }
+
+    /// Tool approval timeout configured for this profile, if any
+    pub fn approval_timeout(&self) -> Option<&ApprovalTimeout> {
+        self.cmd_overrides()?.approval_timeout.as_ref()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -339,6 +372,13 @@ impl AppendPrompt {
             AppendPrompt(None) => prompt.to_string(),
         }
     }
+
+    pub fn prepend_to_prompt(&self, prompt: &str) -> String {
+        match self {
+            AppendPrompt(Some(value)) => format!("{value}{prompt}"),
+            AppendPrompt(None) => prompt.to_string(),
+        }
+    }
 }
 
 pub fn build_review_prompt(
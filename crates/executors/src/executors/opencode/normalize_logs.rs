@@ -12,9 +12,9 @@ use super::types::{
 use crate::{
     approvals::ToolCallMetadata,
     logs::{
-        ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
-        NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsageInfo, ToolResult,
-        ToolStatus,
+        ActionType, CommandExitStatus, CommandRunResult, FileChange, ModelTokenUsage,
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem, TokenUsageInfo,
+        ToolResult, ToolStatus,
         stderr_processor::normalize_stderr_logs,
         utils::{
             EntryIndexProvider,
@@ -80,6 +80,7 @@ pub fn normalize_logs(msg_store: Arc<MsgStore>, worktree_path: &Path) {
                             entry_type: NormalizedEntryType::TokenUsageInfo(TokenUsageInfo {
                                 total_tokens,
                                 model_context_window,
+                                model_usage: state.turn_usage(),
                             }),
                             content: format!(
                                 "Tokens used: {} / Context window: {}",
@@ -177,6 +178,8 @@ struct LogState {
     todo_update_entry: Option<usize>,
     todo_update_fingerprint: Option<String>,
     retry_status_fingerprint: Option<String>,
+    /// Latest token counts of each assistant message, which grow as it streams
+    message_usage: HashMap<String, ModelTokenUsage>,
 }
 
 impl LogState {
@@ -193,6 +196,7 @@ impl LogState {
             todo_update_entry: None,
             todo_update_fingerprint: None,
             retry_status_fingerprint: None,
+            message_usage: HashMap::new(),
         }
     }
 
@@ -211,6 +215,7 @@ impl LogState {
             SdkEvent::MessageUpdated(event) => {
                 let info = event.info;
                 self.maybe_emit_model_system_message(&info);
+                self.record_message_usage(&info);
                 self.message_roles.insert(info.id, info.role);
             }
             SdkEvent::MessagePartUpdated(event) => {
@@ -353,6 +358,31 @@ impl LogState {
         self.model_system_message_emitted = true;
     }
 
+    fn record_message_usage(&mut self, info: &MessageInfo) {
+        if info.role != MessageRole::Assistant {
+            return;
+        }
+        let (Some(tokens), Some(model)) = (&info.tokens, info.model_id()) else {
+            return;
+        };
+        let cache = tokens.cache.as_ref();
+        self.message_usage.insert(
+            info.id.clone(),
+            ModelTokenUsage {
+                model: model.to_string(),
+                input_tokens: tokens.input.into(),
+                output_tokens: u64::from(tokens.output) + u64::from(tokens.reasoning),
+                cache_read_tokens: cache.map_or(0, |cache| cache.read.into()),
+                cache_write_tokens: cache.map_or(0, |cache| cache.write.into()),
+            },
+        );
+    }
+
+    /// Usage of every assistant message of this turn so far, per model
+    fn turn_usage(&self) -> Vec<ModelTokenUsage> {
+        ModelTokenUsage::merge_by_model(self.message_usage.values().cloned())
+    }
+
     fn handle_part_update(
         &mut self,
         part: Part,
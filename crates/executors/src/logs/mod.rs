@@ -102,6 +102,45 @@ pub enum NormalizedEntryType {
 pub struct TokenUsageInfo {
     pub total_tokens: u32,
     pub model_context_window: u32,
+    /// Billable usage of the whole turn per model, reported once the turn has finished
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[ts(as = "Option<Vec<ModelTokenUsage>>", optional)]
+    pub model_usage: Vec<ModelTokenUsage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+pub struct ModelTokenUsage {
+    pub model: String,
+    /// Input tokens that were neither read from nor written to the prompt cache
+    #[ts(type = "number")]
+    pub input_tokens: u64,
+    #[ts(type = "number")]
+    pub output_tokens: u64,
+    #[ts(type = "number")]
+    pub cache_read_tokens: u64,
+    #[ts(type = "number")]
+    pub cache_write_tokens: u64,
+}
+
+impl ModelTokenUsage {
+    /// Sum usage reported per request or message into one entry per model,
+    /// ordered by model name
+    pub fn merge_by_model(usage: impl IntoIterator<Item = ModelTokenUsage>) -> Vec<Self> {
+        let mut merged: Vec<Self> = Vec::new();
+        for usage in usage {
+            match merged.iter_mut().find(|merged| merged.model == usage.model) {
+                Some(merged) => {
+                    merged.input_tokens += usage.input_tokens;
+                    merged.output_tokens += usage.output_tokens;
+                    merged.cache_read_tokens += usage.cache_read_tokens;
+                    merged.cache_write_tokens += usage.cache_write_tokens;
+                }
+                None => merged.push(usage),
+            }
+        }
+        merged.sort_by(|a, b| a.model.cmp(&b.model));
+        merged
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -239,3 +278,37 @@ pub enum FileChange {
         has_line_numbers: bool,
     },
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(model: &str, input_tokens: u64, output_tokens: u64) -> ModelTokenUsage {
+        ModelTokenUsage {
+            model: model.to_string(),
+            input_tokens,
+            output_tokens,
+            cache_read_tokens: 1,
+            cache_write_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn merges_usage_per_model() {
+        let merged = ModelTokenUsage::merge_by_model([
+            usage("gpt-5", 10, 5),
+            usage("claude-haiku", 3, 1),
+            usage("gpt-5", 20, 7),
+        ]);
+        assert_eq!(
+            merged,
+            [
+                usage("claude-haiku", 3, 1),
+                ModelTokenUsage {
+                    cache_read_tokens: 2,
+                    ..usage("gpt-5", 30, 12)
+                },
+            ]
+        );
+    }
+}
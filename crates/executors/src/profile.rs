@@ -401,17 +401,18 @@ impl ExecutorConfigs {
             .cloned()
     }
 
+    /// The profile's variant, falling back to its DEFAULT one. None for an
+    /// executor without any configuration, such as CUSTOM before a profile for
+    /// it is added.
     pub fn get_coding_agent_or_default(
         &self,
         executor_profile_id: &ExecutorProfileId,
-    ) -> CodingAgent {
-        self.get_coding_agent(executor_profile_id)
-            .unwrap_or_else(|| {
-                let mut default_executor_profile_id = executor_profile_id.clone();
-                default_executor_profile_id.variant = Some("DEFAULT".to_string());
-                self.get_coding_agent(&default_executor_profile_id)
-                    .expect("No default variant found")
-            })
+    ) -> Option<CodingAgent> {
+        self.get_coding_agent(executor_profile_id).or_else(|| {
+            let mut default_executor_profile_id = executor_profile_id.clone();
+            default_executor_profile_id.variant = Some("DEFAULT".to_string());
+            self.get_coding_agent(&default_executor_profile_id)
+        })
     }
     pub async fn get_recommended_executor_profile(
         &self,
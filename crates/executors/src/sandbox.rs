@@ -0,0 +1,374 @@
+//! Running executor processes inside a Docker or Podman container instead of
+//! directly on the host.
+//!
+//! Every execution gets its own container, started from the project's image
+//! with the same program, arguments and environment the executor built for the
+//! host. The workspace and the repos' git directories are bind-mounted at their
+//! host paths, so worktree links and the paths agents report stay valid, as
+//! are the agents' configuration and credentials in the user's home.
+
+use std::{
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use serde::{Deserialize, Serialize};
+use sqlx::Type;
+use tokio::process::Command;
+use ts_rs::TS;
+
+use crate::command::ExecutionLimits;
+
+/// Configuration and credentials of the coding agents, relative to the home
+/// directory. Those that exist are mounted so agents run as they do on the host.
+const AGENT_CONFIG_PATHS: &[&str] = &[
+    ".claude",
+    ".claude.json",
+    ".codex",
+    ".gemini",
+    ".qwen",
+    ".copilot",
+    ".cursor",
+    ".factory",
+    ".config/amp",
+    ".config/opencode",
+    ".local/share/opencode",
+    ".config/github-copilot",
+];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, TS, Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "TEXT", rename_all = "lowercase")]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn program(self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+}
+
+/// Container an execution's process runs in
+#[derive(Debug, Clone)]
+pub struct ContainerSandbox {
+    pub runtime: ContainerRuntime,
+    pub image: String,
+    /// Container name, used to remove the container once the execution ends
+    pub name: String,
+    /// Host directories mounted at the same path inside the container
+    pub mounts: Vec<PathBuf>,
+    /// Host home directory, set as `HOME` so agents find their mounted config
+    pub home: Option<PathBuf>,
+    /// Share the host network; without it the container has no network at all
+    pub network: bool,
+    /// Ports published on the host's loopback interface, e.g. a dev server's.
+    /// Without the host network this puts the container on the runtime's
+    /// bridge network, since ports cannot be published without one.
+    pub ports: Vec<u16>,
+    /// Host environment variables forwarded into the container as they are
+    pub env_passthrough: Vec<String>,
+    /// `uid:gid` to run as with Docker, so files written to the mounts belong
+    /// to the host user. Rootless Podman maps the host user by itself.
+    pub user: Option<String>,
+    /// Memory/CPU/process caps, enforced by the runtime on the container
+    pub limits: ExecutionLimits,
+}
+
+impl ContainerSandbox {
+    /// Existing agent configuration paths under `home`
+    pub fn agent_config_mounts(home: &Path) -> Vec<PathBuf> {
+        AGENT_CONFIG_PATHS
+            .iter()
+            .map(|path| home.join(path))
+            .filter(|path| path.exists())
+            .collect()
+    }
+
+    /// A command that runs `command` in a new container, with its arguments,
+    /// working directory and environment. Stdout and stderr are piped like
+    /// executors do; stdin only for `interactive` commands, which talk to the
+    /// process over it, and is closed otherwise.
+    pub fn wrap(&self, command: &Command, interactive: bool) -> Command {
+        let inner = command.as_std();
+        let envs: Vec<(OsString, OsString)> = inner
+            .get_envs()
+            .filter_map(|(key, value)| Some((key.to_os_string(), value?.to_os_string())))
+            .collect();
+        let env_names = envs
+            .iter()
+            .map(|(key, _)| key.clone())
+            .chain(self.env_passthrough.iter().map(OsString::from));
+
+        let mut wrapped = Command::new(self.runtime.program());
+        wrapped
+            .kill_on_drop(true)
+            .stdin(if interactive {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .args(self.run_args(
+                inner.get_program(),
+                inner.get_args(),
+                inner.get_current_dir(),
+                env_names,
+                interactive,
+            ))
+            // `--env NAME` takes the value from the runtime client's environment
+            .envs(envs);
+        if let Some(dir) = inner.get_current_dir() {
+            wrapped.current_dir(dir);
+        }
+        wrapped
+    }
+
+    fn run_args<'a>(
+        &self,
+        program: &OsStr,
+        args: impl Iterator<Item = &'a OsStr>,
+        current_dir: Option<&Path>,
+        env_names: impl Iterator<Item = OsString>,
+        interactive: bool,
+    ) -> Vec<OsString> {
+        let mut run: Vec<OsString> = vec!["run".into(), "--rm".into()];
+        if interactive {
+            run.push("--interactive".into());
+        }
+        run.extend(["--init".into(), "--name".into(), self.name.clone().into()]);
+        match self.runtime {
+            ContainerRuntime::Podman => run.push("--userns=keep-id".into()),
+            ContainerRuntime::Docker => {
+                if let Some(user) = &self.user {
+                    run.extend(["--user".into(), user.into()]);
+                }
+            }
+        }
+        if let Some(mb) = self.limits.max_memory_mb {
+            let memory = OsString::from(format!("{mb}m"));
+            // Equal to --memory, so the container cannot swap past the limit
+            run.extend(["--memory".into(), memory.clone()]);
+            run.extend(["--memory-swap".into(), memory]);
+        }
+        if let Some(cpus) = self.limits.max_cpus {
+            run.extend(["--cpus".into(), cpus.to_string().into()]);
+        }
+        if let Some(processes) = self.limits.max_processes {
+            run.extend(["--pids-limit".into(), processes.to_string().into()]);
+        }
+        let network = match (self.network, self.ports.is_empty()) {
+            (true, _) => "host",
+            (false, false) => "bridge",
+            (false, true) => "none",
+        };
+        run.extend(["--network".into(), network.into()]);
+        if !self.network {
+            for port in &self.ports {
+                run.extend([
+                    "--publish".into(),
+                    format!("127.0.0.1:{port}:{port}").into(),
+                ]);
+            }
+        }
+        for mount in &self.mounts {
+            let mut volume = mount.as_os_str().to_os_string();
+            volume.push(":");
+            volume.push(mount.as_os_str());
+            run.extend(["--volume".into(), volume]);
+        }
+        if let Some(dir) = current_dir {
+            run.extend(["--workdir".into(), dir.as_os_str().to_os_string()]);
+        }
+        if let Some(home) = &self.home {
+            let mut env = OsString::from("HOME=");
+            env.push(home.as_os_str());
+            run.extend(["--env".into(), env]);
+        }
+        for name in env_names {
+            run.extend(["--env".into(), name]);
+        }
+        run.push(self.image.clone().into());
+        run.push(self.container_program(program));
+        run.extend(args.map(OsStr::to_os_string));
+        run
+    }
+
+    /// Executors resolve their program on the host. Paths outside the mounts
+    /// don't exist in the container, so those are looked up on its `PATH`.
+    fn container_program(&self, program: &OsStr) -> OsString {
+        let path = Path::new(program);
+        if path.is_absolute() && !self.mounts.iter().any(|mount| path.starts_with(mount)) {
+            return path.file_name().unwrap_or(program).to_os_string();
+        }
+        program.to_os_string()
+    }
+
+    /// Command printing the host pid of the container's init process, once
+    /// the container is running
+    pub fn pid_command(&self) -> Command {
+        let mut command = Command::new(self.runtime.program());
+        command
+            .args(["inspect", "--format", "{{.State.Pid}}", &self.name])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null());
+        command
+    }
+
+    /// Command that force-removes the container, in case it outlived the
+    /// runtime client (e.g. when the client was killed)
+    pub fn remove_command(&self) -> Command {
+        let mut command = Command::new(self.runtime.program());
+        command
+            .args(["rm", "--force", &self.name])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sandbox() -> ContainerSandbox {
+        ContainerSandbox {
+            runtime: ContainerRuntime::Docker,
+            image: "node:22".to_string(),
+            name: "vk-exec-1".to_string(),
+            mounts: vec![PathBuf::from("/work/ws"), PathBuf::from("/repos/app/.git")],
+            home: None,
+            network: false,
+            ports: vec![],
+            env_passthrough: vec![],
+            user: None,
+            limits: ExecutionLimits::default(),
+        }
+    }
+
+    fn run_args(wrapped: &Command) -> Vec<&str> {
+        wrapped
+            .as_std()
+            .get_args()
+            .map(|a| a.to_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn wraps_the_command_in_a_container_run() {
+        let sandbox = ContainerSandbox {
+            home: Some(PathBuf::from("/home/dev")),
+            env_passthrough: vec!["ANTHROPIC_API_KEY".to_string()],
+            user: Some("1000:1000".to_string()),
+            limits: ExecutionLimits {
+                max_memory_mb: Some(2048),
+                max_cpus: Some(1.5),
+                max_processes: Some(512),
+                ..Default::default()
+            },
+            ..sandbox()
+        };
+        let mut command = Command::new("/usr/local/bin/npx");
+        command
+            .args(["-y", "@anthropic-ai/claude-code"])
+            .current_dir("/work/ws/app")
+            .env("VK_TASK_ID", "42");
+
+        let wrapped = sandbox.wrap(&command, true);
+        assert_eq!(wrapped.as_std().get_program(), "docker");
+        assert_eq!(
+            run_args(&wrapped),
+            [
+                "run",
+                "--rm",
+                "--interactive",
+                "--init",
+                "--name",
+                "vk-exec-1",
+                "--user",
+                "1000:1000",
+                "--memory",
+                "2048m",
+                "--memory-swap",
+                "2048m",
+                "--cpus",
+                "1.5",
+                "--pids-limit",
+                "512",
+                "--network",
+                "none",
+                "--volume",
+                "/work/ws:/work/ws",
+                "--volume",
+                "/repos/app/.git:/repos/app/.git",
+                "--workdir",
+                "/work/ws/app",
+                "--env",
+                "HOME=/home/dev",
+                "--env",
+                "VK_TASK_ID",
+                "--env",
+                "ANTHROPIC_API_KEY",
+                "node:22",
+                "npx",
+                "-y",
+                "@anthropic-ai/claude-code",
+            ]
+        );
+        assert!(
+            wrapped
+                .as_std()
+                .get_envs()
+                .any(|(key, value)| key == "VK_TASK_ID" && value == Some(OsStr::new("42")))
+        );
+    }
+
+    #[test]
+    fn publishes_ports_of_non_interactive_commands() {
+        let sandbox = ContainerSandbox {
+            ports: vec![3000],
+            ..sandbox()
+        };
+        let mut command = Command::new("bash");
+        command.args(["-c", "npm run dev"]);
+
+        let wrapped = sandbox.wrap(&command, false);
+        let args = run_args(&wrapped);
+        assert!(!args.contains(&"--interactive"));
+        assert!(args.windows(2).any(|w| w == ["--network", "bridge"]));
+        assert!(
+            args.windows(2)
+                .any(|w| w == ["--publish", "127.0.0.1:3000:3000"])
+        );
+
+        let sandbox = ContainerSandbox {
+            network: true,
+            ..sandbox
+        };
+        let wrapped = sandbox.wrap(&command, false);
+        let args = run_args(&wrapped);
+        assert!(args.windows(2).any(|w| w == ["--network", "host"]));
+        assert!(!args.contains(&"--publish"));
+    }
+
+    #[test]
+    fn mounts_existing_agent_config() {
+        let home = std::env::temp_dir().join(format!("vk-sandbox-home-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(home.join(".claude")).unwrap();
+        std::fs::write(home.join(".claude.json"), "{}").unwrap();
+
+        let mounts = ContainerSandbox::agent_config_mounts(&home);
+        let _ = std::fs::remove_dir_all(&home);
+        assert_eq!(
+            mounts,
+            vec![home.join(".claude"), home.join(".claude.json")]
+        );
+    }
+}
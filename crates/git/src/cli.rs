@@ -163,6 +163,22 @@ impl GitCli {
         Ok(!out.is_empty())
     }
 
+    /// Return true if the path has staged, unstaged or untracked changes.
+    pub fn path_has_changes(&self, worktree_path: &Path, path: &str) -> Result<bool, GitCliError> {
+        let out = self.git(
+            worktree_path,
+            [
+                "--no-optional-locks",
+                "--literal-pathspecs",
+                "status",
+                "--porcelain",
+                "--",
+                path,
+            ],
+        )?;
+        Ok(!out.is_empty())
+    }
+
     /// Diff status vs a base branch using a temporary index (always includes untracked).
     /// Path filter limits the reported paths.
     pub fn diff_status(
@@ -343,6 +359,29 @@ impl GitCli {
         Ok(())
     }
 
+    /// Stage and commit only the given path; other staged changes stay staged.
+    pub fn commit_path(
+        &self,
+        worktree_path: &Path,
+        path: &str,
+        message: &str,
+    ) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["--literal-pathspecs", "add", "--", path])?;
+        self.git(
+            worktree_path,
+            [
+                "--literal-pathspecs",
+                "commit",
+                "--only",
+                "-m",
+                message,
+                "--",
+                path,
+            ],
+        )?;
+        Ok(())
+    }
+
     /// Amend the latest commit message without changing the tree.
     pub fn amend_commit_message(
         &self,
@@ -661,6 +700,74 @@ impl GitCli {
             .map(|_| ())
     }
 
+    /// Commit the worktree as it is, untracked files included, on top of HEAD
+    /// and point `refname` at it. A temporary index is used, so the real index,
+    /// HEAD and the files are left untouched. Returns the snapshot's sha.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        refname: &str,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        let tmp_dir = tempfile::TempDir::new()
+            .map_err(|e| GitCliError::CommandFailed(format!("temp dir create failed: {e}")))?;
+        let tmp_index = tmp_dir.path().join("index");
+        let envs = vec![(
+            OsString::from("GIT_INDEX_FILE"),
+            tmp_index.as_os_str().to_os_string(),
+        )];
+        self.git_with_env(worktree_path, ["read-tree", "HEAD"], &envs)?;
+        self.git_with_env(
+            worktree_path,
+            Self::apply_default_excludes(["add", "-A"]),
+            &envs,
+        )?;
+        let tree = self
+            .git_with_env(worktree_path, ["write-tree"], &envs)?
+            .trim()
+            .to_string();
+        let sha = self
+            .git(
+                worktree_path,
+                ["commit-tree", &tree, "-p", "HEAD", "-m", message],
+            )?
+            .trim()
+            .to_string();
+        self.update_ref(worktree_path, refname, &sha)?;
+        Ok(sha)
+    }
+
+    /// Make the worktree match a snapshot taken by `snapshot_worktree`: HEAD
+    /// goes back to the snapshot's parent, the files to the snapshot's content,
+    /// and untracked files that are not part of it are removed. Ignored files
+    /// are left alone.
+    pub fn restore_snapshot(&self, worktree_path: &Path, sha: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["reset", "--hard", &format!("{sha}^")])?;
+        self.git(
+            worktree_path,
+            Self::apply_default_excludes(["clean", "--force", "-d"]),
+        )?;
+        self.git(worktree_path, ["read-tree", "-u", "--reset", sha])?;
+        // Back to HEAD's index, so the snapshot's changes are uncommitted again
+        self.git(worktree_path, ["reset", "--quiet"])?;
+        Ok(())
+    }
+
+    /// Delete every ref under `prefix`
+    pub fn delete_refs(&self, repo_path: &Path, prefix: &str) -> Result<(), GitCliError> {
+        let refs = self.git(repo_path, ["for-each-ref", "--format=%(refname)", prefix])?;
+        let input: String = refs
+            .lines()
+            .filter(|refname| !refname.is_empty())
+            .map(|refname| format!("delete {refname}\n"))
+            .collect();
+        if input.is_empty() {
+            return Ok(());
+        }
+        self.git_with_stdin(repo_path, ["update-ref", "--stdin"], None, input.as_bytes())?;
+        Ok(())
+    }
+
     pub fn abort_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
         if !self.is_merge_in_progress(worktree_path)? {
             return Ok(());
@@ -683,6 +790,59 @@ impl GitCli {
         self.git(worktree_path, ["revert", "--abort"]).map(|_| ())
     }
 
+    /// Continue `git <command>` (rebase, merge, cherry-pick or revert) after
+    /// its conflicts were resolved and staged, keeping the prepared commit
+    /// message instead of opening an editor
+    pub fn continue_conflict_op(
+        &self,
+        worktree_path: &Path,
+        command: &str,
+    ) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_EDITOR"), OsString::from("true"))];
+        self.git_with_env(worktree_path, [command, "--continue"], &envs)?;
+        Ok(())
+    }
+
+    /// Content of a conflicted path at an index stage: 1 is the common
+    /// ancestor, 2 our side and 3 theirs. None when that side has no such file.
+    pub fn show_conflict_stage(
+        &self,
+        worktree_path: &Path,
+        stage: u8,
+        path: &str,
+    ) -> Result<Option<String>, GitCliError> {
+        match self.git(worktree_path, ["show", &format!(":{stage}:{path}")]) {
+            Ok(content) => Ok(Some(content)),
+            Err(GitCliError::CommandFailed(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Full messages of the commits in `revision_range`, newest first, each
+    /// prefixed with its short sha
+    pub fn commit_messages(
+        &self,
+        worktree_path: &Path,
+        revision_range: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, GitCliError> {
+        let out = self.git(
+            worktree_path,
+            [
+                "log",
+                &format!("--max-count={limit}"),
+                "--format=%h %B%x00",
+                revision_range,
+            ],
+        )?;
+        Ok(out
+            .split('\0')
+            .map(str::trim)
+            .filter(|message| !message.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
     /// Revert a merge commit using `git revert -m 1`
     /// This creates a new commit that undoes the changes from the merge
     pub fn revert_merge(
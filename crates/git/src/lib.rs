@@ -2,8 +2,8 @@ use std::{collections::HashMap, path::Path};
 
 use chrono::{DateTime, Utc};
 use git2::{
-    BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, Reference, Remote,
-    Repository, Sort,
+    BranchType, Delta, DiffFindOptions, DiffOptions, Error as GitError, ErrorCode, Reference,
+    Remote, Repository, Sort,
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -61,6 +61,9 @@ pub struct GitService {}
 // their contents omitted from the diff stream to avoid UI crashes.
 const MAX_INLINE_DIFF_BYTES: usize = 2 * 1024 * 1024; // ~2MB
 
+/// Hidden refs keeping worktree snapshots reachable, so `git gc` keeps them
+pub const SNAPSHOT_REFS_PREFIX: &str = "refs/vibe-kanban/snapshots";
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -71,6 +74,52 @@ pub enum ConflictOp {
     Revert,
 }
 
+impl ConflictOp {
+    /// Git command running the operation
+    pub fn command(&self) -> &'static str {
+        match self {
+            ConflictOp::Rebase => "rebase",
+            ConflictOp::Merge => "merge",
+            ConflictOp::CherryPick => "cherry-pick",
+            ConflictOp::Revert => "revert",
+        }
+    }
+
+    /// Ref of the commit the operation is applying
+    fn incoming_ref(&self) -> &'static str {
+        match self {
+            ConflictOp::Rebase => "REBASE_HEAD",
+            ConflictOp::Merge => "MERGE_HEAD",
+            ConflictOp::CherryPick => "CHERRY_PICK_HEAD",
+            ConflictOp::Revert => "REVERT_HEAD",
+        }
+    }
+}
+
+/// The versions of a conflicted file. "Ours" is the side the changes are
+/// applied onto (HEAD), "theirs" the commit being applied; during a rebase
+/// that is the task branch's commit.
+#[derive(Debug, Clone)]
+pub struct ConflictedFile {
+    pub path: String,
+    pub base: Option<String>,
+    pub ours: Option<String>,
+    pub theirs: Option<String>,
+    /// Worktree content, with conflict markers
+    pub merged: Option<String>,
+}
+
+/// What a worktree stopped on conflicts is in the middle of
+#[derive(Debug, Clone)]
+pub struct ConflictDetails {
+    pub op: ConflictOp,
+    /// Messages of the commits on our side since the sides diverged
+    pub ours_commits: Vec<String>,
+    /// Messages of the commits being applied
+    pub theirs_commits: Vec<String>,
+    pub files: Vec<ConflictedFile>,
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct GitBranch {
     pub name: String,
@@ -86,6 +135,16 @@ pub struct GitRemote {
     pub url: String,
 }
 
+/// Branch a local branch tracks on its remote
+#[derive(Debug, Clone)]
+pub struct BranchUpstream {
+    pub remote: GitRemote,
+    /// Name of the branch on the remote
+    pub branch: String,
+    /// Remote-tracking branch, e.g. `origin/main`
+    pub tracking_branch: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct HeadInfo {
     pub branch: String,
@@ -339,6 +398,31 @@ impl GitService {
         Ok(true)
     }
 
+    /// Whether a file of a checkout has uncommitted changes, untracked included
+    pub fn file_has_changes(&self, path: &Path, file: &str) -> Result<bool, GitServiceError> {
+        GitCli::new()
+            .path_has_changes(path, file)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git status failed: {e}")))
+    }
+
+    /// Commit one file of a checkout as it is on disk, leaving the other
+    /// staged and unstaged changes alone. Returns whether it had changes.
+    pub fn commit_file(
+        &self,
+        path: &Path,
+        file: &str,
+        message: &str,
+    ) -> Result<bool, GitServiceError> {
+        if !self.file_has_changes(path, file)? {
+            return Ok(false);
+        }
+        self.ensure_cli_commit_identity(path)?;
+        GitCli::new()
+            .commit_path(path, file, message)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git commit failed: {e}")))?;
+        Ok(true)
+    }
+
     /// Get diffs between branches or worktree changes
     pub fn get_diffs(
         &self,
@@ -1058,6 +1142,43 @@ impl GitService {
         Ok(Commit::new(oid))
     }
 
+    /// Files that would conflict when merging `branch_name` with
+    /// `base_branch_name`. The merge is done in memory, nothing is checked out
+    /// or written.
+    pub fn trial_merge_conflicts(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        base_branch_name: &str,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let branch_commit = Self::find_branch(&repo, branch_name)?
+            .get()
+            .peel_to_commit()?;
+        let base_commit = Self::find_branch(&repo, base_branch_name)?
+            .get()
+            .peel_to_commit()?;
+        let mut merge_opts = git2::MergeOptions::new();
+        merge_opts.find_renames(true);
+        let index = repo.merge_commits(&base_commit, &branch_commit, Some(&merge_opts))?;
+        if !index.has_conflicts() {
+            return Ok(Vec::new());
+        }
+
+        let mut files = Vec::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+            if let Some(entry) = entry {
+                let path = String::from_utf8_lossy(&entry.path).into_owned();
+                if !files.contains(&path) {
+                    files.push(path);
+                }
+            }
+        }
+        Ok(files)
+    }
+
     pub fn get_remote_branch_status(
         &self,
         repo_path: &Path,
@@ -1157,6 +1278,22 @@ impl GitService {
         Ok(HeadInfo { branch, oid })
     }
 
+    /// Name of the checked out branch, `None` on a detached or unborn HEAD
+    pub fn get_checked_out_branch(
+        &self,
+        repo_path: &Path,
+    ) -> Result<Option<String>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        if repo.head_detached()? {
+            return Ok(None);
+        }
+        match repo.head() {
+            Ok(head) => Ok(head.shorthand().map(str::to_string)),
+            Err(e) if e.code() == ErrorCode::UnbornBranch => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     pub fn get_current_branch(&self, repo_path: &Path) -> Result<String, git2::Error> {
         // Thin wrapper for backward compatibility
         match self.get_head_info(repo_path) {
@@ -1402,6 +1539,39 @@ impl GitService {
         )
     }
 
+    /// Unified patch of the changes committed on `branch_name` since it forked
+    /// from `base_branch`
+    pub fn get_branch_patch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+        base_branch: &str,
+    ) -> Result<Vec<u8>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let base_commit = self.get_base_commit(repo_path, branch_name, base_branch)?;
+        let base_tree = repo.find_commit(base_commit.as_oid())?.tree()?;
+        let branch_tree = Self::find_branch(&repo, branch_name)?
+            .get()
+            .peel_to_commit()?
+            .tree()?;
+
+        let mut diff_opts = DiffOptions::new();
+        diff_opts.include_typechange(true);
+        let mut diff =
+            repo.diff_tree_to_tree(Some(&base_tree), Some(&branch_tree), Some(&mut diff_opts))?;
+        diff.find_similar(Some(&mut DiffFindOptions::new()))?;
+
+        let mut patch = Vec::new();
+        diff.print(git2::DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin() as u8);
+            }
+            patch.extend_from_slice(line.content());
+            true
+        })?;
+        Ok(patch)
+    }
+
     /// Compare two OIDs and return (ahead, behind) counts: how many commits
     /// `from_oid` is ahead of and behind `to_oid`.
     pub fn ahead_behind_commits_by_oid(
@@ -1502,6 +1672,53 @@ impl GitService {
         Ok(())
     }
 
+    /// Snapshot a worktree, untracked files included, as a commit on top of
+    /// HEAD referenced by `refname`. Returns the snapshot's sha.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        refname: &str,
+        message: &str,
+    ) -> Result<String, GitServiceError> {
+        self.ensure_cli_commit_identity(worktree_path)?;
+        GitCli::new()
+            .snapshot_worktree(worktree_path, refname, message)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("snapshot failed: {e}")))
+    }
+
+    /// Restore a worktree to a snapshot taken by `snapshot_worktree`. HEAD is
+    /// reset to the commit the snapshot was taken on and its changes are left
+    /// uncommitted. If `force` is false and the worktree is dirty, returns
+    /// WorktreeDirty error.
+    pub fn restore_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        snapshot_sha: &str,
+        force: bool,
+    ) -> Result<(), GitServiceError> {
+        if !force {
+            let repo = self.open_repo(worktree_path)?;
+            self.check_worktree_clean(&repo)?;
+        }
+        let cli = GitCli::new();
+        cli.restore_snapshot(worktree_path, snapshot_sha)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("restore failed: {e}")))?;
+        // Reapply sparse-checkout if configured (non-fatal)
+        let _ = cli.git(worktree_path, ["sparse-checkout", "reapply"]);
+        Ok(())
+    }
+
+    /// Delete the snapshot refs under `prefix`, letting `git gc` collect them
+    pub fn delete_snapshot_refs(
+        &self,
+        repo_path: &Path,
+        prefix: &str,
+    ) -> Result<(), GitServiceError> {
+        GitCli::new()
+            .delete_refs(repo_path, prefix)
+            .map_err(|e| GitServiceError::InvalidRepository(e.to_string()))
+    }
+
     /// Add a worktree for a branch, optionally creating the branch
     pub fn add_worktree(
         &self,
@@ -1852,6 +2069,105 @@ impl GitService {
         })
     }
 
+    /// Describe the conflicts the worktree stopped on, or None when no
+    /// operation is in progress
+    pub fn conflict_details(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Option<ConflictDetails>, GitServiceError> {
+        const MAX_COMMITS: usize = 10;
+
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(None);
+        };
+        let git = GitCli::new();
+        let incoming = op.incoming_ref();
+        let (ours_commits, theirs_commits) = match op {
+            // The commit being picked or reverted is all there is to theirs
+            ConflictOp::CherryPick | ConflictOp::Revert => (
+                git.commit_messages(worktree_path, "HEAD", 1),
+                git.commit_messages(worktree_path, incoming, 1),
+            ),
+            ConflictOp::Rebase | ConflictOp::Merge => {
+                // Plain merge base: HEAD's reflog holds the commits being
+                // rebased, which would throw `--fork-point` off
+                let merge_base = git
+                    .git(worktree_path, ["merge-base", "HEAD", incoming])
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git merge-base failed: {e}"))
+                    })?;
+                let merge_base = merge_base.trim();
+                (
+                    git.commit_messages(worktree_path, &format!("{merge_base}..HEAD"), MAX_COMMITS),
+                    git.commit_messages(
+                        worktree_path,
+                        &format!("{merge_base}..{incoming}"),
+                        MAX_COMMITS,
+                    ),
+                )
+            }
+        };
+        let to_service_error =
+            |e: GitCliError| GitServiceError::InvalidRepository(format!("git log failed: {e}"));
+
+        let mut files = Vec::new();
+        for path in self.get_conflicted_files(worktree_path)? {
+            let stage = |stage| {
+                git.show_conflict_stage(worktree_path, stage, &path)
+                    .map_err(|e| {
+                        GitServiceError::InvalidRepository(format!("git show failed: {e}"))
+                    })
+            };
+            files.push(ConflictedFile {
+                base: stage(1)?,
+                ours: stage(2)?,
+                theirs: stage(3)?,
+                merged: std::fs::read_to_string(worktree_path.join(&path)).ok(),
+                path,
+            });
+        }
+
+        Ok(Some(ConflictDetails {
+            op,
+            ours_commits: ours_commits.map_err(to_service_error)?,
+            theirs_commits: theirs_commits.map_err(to_service_error)?,
+            files,
+        }))
+    }
+
+    /// Stage the worktree and continue the operation that stopped on
+    /// conflicts. A rebase can stop again on a later commit, which is reported
+    /// as `MergeConflicts`.
+    pub fn continue_conflicts(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
+        let Some(op) = self.detect_conflict_op(worktree_path)? else {
+            return Ok(());
+        };
+        let git = GitCli::new();
+        self.ensure_cli_commit_identity(worktree_path)?;
+        git.add_all(worktree_path)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git add failed: {e}")))?;
+        match git.continue_conflict_op(worktree_path, op.command()) {
+            Ok(()) => Ok(()),
+            Err(e) => {
+                let conflicted_files = self.get_conflicted_files(worktree_path)?;
+                if conflicted_files.is_empty() {
+                    return Err(GitServiceError::InvalidRepository(format!(
+                        "git {} --continue failed: {e}",
+                        op.command()
+                    )));
+                }
+                Err(GitServiceError::MergeConflicts {
+                    message: format!(
+                        "Continuing the {} stopped on new conflicts in: {}.",
+                        op.command(),
+                        conflicted_files.join(", ")
+                    ),
+                    conflicted_files,
+                })
+            }
+        }
+    }
+
     /// Abort an in-progress rebase in this worktree (no-op if none).
     pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
         let git = GitCli::new();
@@ -1972,6 +2288,8 @@ impl GitService {
             .map_err(GitServiceError::from)
     }
 
+    /// Fast-forward a local branch from the branch of the same name on a
+    /// remote. Fails when the branch diverged or is checked out.
     pub fn fetch_branch(
         &self,
         repo_path: &Path,
@@ -1979,12 +2297,63 @@ impl GitService {
         branch_name: &str,
     ) -> Result<(), GitServiceError> {
         let git_cli = GitCli::new();
-        let refspec = format!("+refs/heads/{branch_name}:refs/heads/{branch_name}");
+        let refspec = format!("refs/heads/{branch_name}:refs/heads/{branch_name}");
         git_cli
             .fetch_with_refspec(repo_path, remote_url, &refspec)
             .map_err(GitServiceError::from)
     }
 
+    /// Update a remote-tracking branch such as `origin/main` from its remote.
+    /// Local branches are left alone.
+    pub fn fetch_remote_branch(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let branch_ref = Self::find_branch(&repo, branch_name)?.into_reference();
+        if branch_ref.is_remote() {
+            self.fetch_branch_from_remote(&repo, &branch_ref)?;
+        }
+        Ok(())
+    }
+
+    /// The upstream of a local branch; None for a remote-tracking branch or a
+    /// local one that tracks nothing
+    pub fn get_branch_upstream(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<Option<BranchUpstream>, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        let Ok(branch) = repo.find_branch(branch_name, BranchType::Local) else {
+            return Ok(None);
+        };
+        let Ok(upstream) = branch.upstream() else {
+            return Ok(None);
+        };
+        let refname = format!("refs/heads/{branch_name}");
+        let remote_name = repo.branch_upstream_remote(&refname)?;
+        let merge = repo.branch_upstream_merge(&refname)?;
+        let (Some(remote_name), Some(merge), Some(tracking_branch)) =
+            (remote_name.as_str(), merge.as_str(), upstream.name()?)
+        else {
+            return Ok(None);
+        };
+        let remote = repo.find_remote(remote_name)?;
+        let Some(url) = remote.url() else {
+            return Ok(None);
+        };
+        Ok(Some(BranchUpstream {
+            remote: GitRemote {
+                name: remote_name.to_string(),
+                url: url.to_string(),
+            },
+            branch: merge.trim_start_matches("refs/heads/").to_string(),
+            tracking_branch: tracking_branch.to_string(),
+        }))
+    }
+
     pub fn resolve_remote_for_branch(
         &self,
         repo_path: &Path,
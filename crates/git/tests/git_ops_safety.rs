@@ -4,7 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use git::{GitCli, GitCliError, GitService};
+use git::{ConflictOp, GitCli, GitCliError, GitService};
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
@@ -333,6 +333,101 @@ fn fetch_with_missing_ref_returns_error() {
     }
 }
 
+#[test]
+fn fetch_branch_only_fast_forwards_tracked_branch() {
+    let temp_dir = TempDir::new().unwrap();
+    let remote_path = temp_dir.path().join("remote.git");
+    Repository::init_bare(&remote_path).expect("init bare remote");
+    let remote_url = remote_path.to_str().expect("remote path str");
+
+    let seed_path = temp_dir.path().join("seed");
+    let service = GitService::new();
+    service
+        .initialize_repo_with_main_branch(&seed_path)
+        .expect("init seed repo");
+    let seed_repo = Repository::open(&seed_path).expect("open seed repo");
+    configure_user(&seed_repo);
+    seed_repo.remote("origin", remote_url).expect("add remote");
+    push_ref(&seed_repo, "refs/heads/main", "refs/heads/main");
+    Repository::open_bare(&remote_path)
+        .expect("open bare remote")
+        .set_head("refs/heads/main")
+        .expect("set remote HEAD");
+
+    // The consumer tracks origin/main but works on another branch
+    let consumer_path = temp_dir.path().join("consumer");
+    let consumer_repo = Repository::clone(remote_url, &consumer_path).expect("clone consumer");
+    configure_user(&consumer_repo);
+    create_branch_from_head(&consumer_repo, "work");
+    checkout_branch(&consumer_repo, "work");
+
+    let upstream = service
+        .get_branch_upstream(&consumer_path, "main")
+        .unwrap()
+        .expect("main tracks origin/main");
+    assert_eq!(upstream.remote.name, "origin");
+    assert_eq!(upstream.branch, "main");
+    assert_eq!(upstream.tracking_branch, "origin/main");
+    assert!(
+        service
+            .get_branch_upstream(&consumer_path, "work")
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        service
+            .get_branch_upstream(&consumer_path, "origin/main")
+            .unwrap()
+            .is_none()
+    );
+
+    let push_upstream = |content: &str| {
+        checkout_branch(&seed_repo, "main");
+        write_file(&seed_path, "file.txt", content);
+        commit_all(&seed_repo, content);
+        push_ref(&seed_repo, "refs/heads/main", "refs/heads/main");
+        service.get_branch_oid(&seed_path, "main").unwrap()
+    };
+
+    let pushed = push_upstream("upstream change\n");
+    service
+        .fetch_branch(&consumer_path, &upstream.remote.url, "main")
+        .expect("fast-forward main");
+    assert_eq!(
+        service.get_branch_oid(&consumer_path, "main").unwrap(),
+        pushed
+    );
+
+    // A local commit on main is never overwritten by the remote
+    let main_commit = consumer_repo
+        .find_reference("refs/heads/main")
+        .unwrap()
+        .peel_to_commit()
+        .unwrap();
+    let sig = consumer_repo.signature().unwrap();
+    let local = consumer_repo
+        .commit(
+            Some("refs/heads/main"),
+            &sig,
+            &sig,
+            "local commit",
+            &main_commit.tree().unwrap(),
+            &[&main_commit],
+        )
+        .unwrap()
+        .to_string();
+    push_upstream("another upstream change\n");
+    assert!(
+        service
+            .fetch_branch(&consumer_path, &upstream.remote.url, "main")
+            .is_err()
+    );
+    assert_eq!(
+        service.get_branch_oid(&consumer_path, "main").unwrap(),
+        local
+    );
+}
+
 #[test]
 fn push_and_fetch_roundtrip_updates_tracking_branch() {
     let temp_dir = TempDir::new().unwrap();
@@ -713,6 +808,183 @@ fn rebase_refuses_to_abort_existing_rebase() {
     // Note: We do not auto-abort; user should resolve or abort explicitly
 }
 
+#[test]
+fn rebase_conflicts_are_described_and_continued() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let svc = GitService::new();
+    let _ = svc
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+        )
+        .expect_err("rebase should stop on conflicts");
+
+    let details = svc
+        .conflict_details(&worktree_path)
+        .unwrap()
+        .expect("rebase in progress");
+    assert_eq!(details.op, ConflictOp::Rebase);
+    assert_eq!(details.files.len(), 1);
+    let file = &details.files[0];
+    assert_eq!(file.path, "conflict.txt");
+    assert_eq!(file.ours.as_deref(), Some("new-base version\n"));
+    assert_eq!(file.theirs.as_deref(), Some("feature version\n"));
+    assert!(file.merged.as_deref().unwrap().contains("<<<<<<<"));
+    assert!(
+        details
+            .theirs_commits
+            .iter()
+            .any(|message| message.contains("feature conflicting change"))
+    );
+    assert!(
+        details
+            .ours_commits
+            .iter()
+            .any(|message| message.contains("new-base change"))
+    );
+
+    write_file(&worktree_path, "conflict.txt", "resolved\n");
+    svc.continue_conflicts(&worktree_path)
+        .expect("rebase should continue");
+    assert!(svc.detect_conflict_op(&worktree_path).unwrap().is_none());
+    assert_eq!(svc.get_head_info(&worktree_path).unwrap().branch, "feature");
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("conflict.txt")).unwrap(),
+        "resolved\n"
+    );
+}
+
+// A base worktree on `base` and a worktree on `stacked`, branched from it.
+// Returns the repo, both worktrees and the commit `stacked` was created on.
+fn setup_stacked_worktrees(root: &TempDir) -> (PathBuf, PathBuf, PathBuf, String) {
+    let repo_path = root.path().join("repo");
+    let base_path = root.path().join("wt-base");
+    let stacked_path = root.path().join("wt-stacked");
+
+    let s = GitService::new();
+    s.initialize_repo_with_main_branch(&repo_path)
+        .expect("init repo");
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "common.txt", "base\n");
+    commit_all(&repo, "initial main commit");
+
+    create_branch_from_head(&repo, "base");
+    s.add_worktree(&repo_path, &base_path, "base", false)
+        .expect("create base worktree");
+    write_file(&base_path, "base.txt", "base change\n");
+    commit_all(&Repository::open(&base_path).unwrap(), "base commit");
+    let base_commit = s.get_branch_oid(&repo_path, "base").unwrap();
+
+    let base_tip = repo
+        .find_commit(git2::Oid::from_str(&base_commit).unwrap())
+        .unwrap();
+    repo.branch("stacked", &base_tip, false).unwrap();
+    s.add_worktree(&repo_path, &stacked_path, "stacked", false)
+        .expect("create stacked worktree");
+    write_file(&stacked_path, "stacked.txt", "stacked change\n");
+    commit_all(&Repository::open(&stacked_path).unwrap(), "stacked commit");
+
+    (repo_path, base_path, stacked_path, base_commit)
+}
+
+fn head_parent(worktree_path: &Path) -> String {
+    let repo = Repository::open(worktree_path).unwrap();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    assert_eq!(head.message(), Some("stacked commit"));
+    head.parent_id(0).unwrap().to_string()
+}
+
+#[test]
+fn restack_onto_rebased_base() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, base_path, stacked_path, base_commit) = setup_stacked_worktrees(&td);
+    let s = GitService::new();
+
+    // main moves on and the base is rebased onto it
+    let old_main = s.get_branch_oid(&repo_path, "main").unwrap();
+    let repo = Repository::open(&repo_path).unwrap();
+    write_file(&repo_path, "main.txt", "main change\n");
+    commit_all(&repo, "main commit");
+    s.rebase_branch(&repo_path, &base_path, "main", &old_main, "base")
+        .expect("rebase base onto main");
+    let new_base = s.get_branch_oid(&repo_path, "base").unwrap();
+    assert_ne!(new_base, base_commit);
+
+    // Only the stacked commit is replayed onto the rebased base
+    s.rebase_branch(&repo_path, &stacked_path, "base", &base_commit, "stacked")
+        .expect("restack onto the rebased base");
+    assert_eq!(head_parent(&stacked_path), new_base);
+    for (file, content) in [
+        ("main.txt", "main change\n"),
+        ("base.txt", "base change\n"),
+        ("stacked.txt", "stacked change\n"),
+    ] {
+        assert_eq!(
+            fs::read_to_string(stacked_path.join(file)).unwrap(),
+            content
+        );
+    }
+}
+
+#[test]
+fn restack_onto_merged_base_target() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, base_path, stacked_path, base_commit) = setup_stacked_worktrees(&td);
+    let s = GitService::new();
+
+    // The base is squash merged, so its commit never reaches main
+    let squash = s
+        .merge_changes(&repo_path, &base_path, "base", "main", "squash base")
+        .expect("merge base into main");
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), squash);
+
+    // Rebasing from the old base commit skips the base's own commit, which
+    // would otherwise be applied a second time on top of the squash
+    s.rebase_branch(&repo_path, &stacked_path, "main", &base_commit, "stacked")
+        .expect("restack onto the base's target");
+    assert_eq!(head_parent(&stacked_path), squash);
+    assert_eq!(
+        fs::read_to_string(stacked_path.join("base.txt")).unwrap(),
+        "base change\n"
+    );
+    assert_eq!(
+        fs::read_to_string(stacked_path.join("stacked.txt")).unwrap(),
+        "stacked change\n"
+    );
+    assert!(s.is_worktree_clean(&stacked_path).unwrap());
+}
+
+#[test]
+fn trial_merge_reports_conflicts_without_touching_worktree() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+    let svc = GitService::new();
+    let head_before = svc.get_head_info(&worktree_path).unwrap().oid;
+
+    let conflicts = svc
+        .trial_merge_conflicts(&repo_path, "feature", "new-base")
+        .unwrap();
+    assert_eq!(conflicts, vec!["conflict.txt".to_string()]);
+    assert!(
+        svc.trial_merge_conflicts(&repo_path, "feature", "old-base")
+            .unwrap()
+            .is_empty()
+    );
+
+    assert_eq!(svc.get_head_info(&worktree_path).unwrap().oid, head_before);
+    assert!(svc.detect_conflict_op(&worktree_path).unwrap().is_none());
+    assert_eq!(
+        fs::read_to_string(worktree_path.join("conflict.txt")).unwrap(),
+        "feature version\n"
+    );
+}
+
 #[test]
 fn rebase_fast_forwards_when_no_unique_commits() {
     let td = TempDir::new().unwrap();
@@ -256,6 +256,68 @@ fn commit_and_is_worktree_clean() {
     );
 }
 
+#[test]
+fn commit_file_leaves_other_changes_uncommitted() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "src/lib.rs", "a\n");
+    let _ = s.commit(&repo_path, "seed").unwrap();
+
+    write_file(&repo_path, "src/lib.rs", "b\n");
+    add_path(&repo_path, "src/lib.rs");
+    write_file(&repo_path, "notes.txt", "draft\n");
+    write_file(&repo_path, "docs/AGENTS.md", "memory\n");
+
+    assert!(s.file_has_changes(&repo_path, "docs/AGENTS.md").unwrap());
+    assert!(
+        s.commit_file(&repo_path, "docs/AGENTS.md", "memory")
+            .unwrap()
+    );
+    assert!(!s.file_has_changes(&repo_path, "docs/AGENTS.md").unwrap());
+    // Nothing left to commit
+    assert!(
+        !s.commit_file(&repo_path, "docs/AGENTS.md", "memory")
+            .unwrap()
+    );
+
+    let head = s.get_head_info(&repo_path).unwrap().oid;
+    let diffs = s
+        .get_diffs(
+            DiffTarget::Commit {
+                repo_path: Path::new(&repo_path),
+                commit_sha: &head,
+            },
+            None,
+        )
+        .unwrap();
+    let paths: Vec<_> = diffs.iter().filter_map(|d| d.new_path.as_deref()).collect();
+    assert_eq!(paths, vec!["docs/AGENTS.md"]);
+
+    // The staged edit stays staged and the untracked file untracked
+    let status = GitCli::new()
+        .git(&repo_path, ["status", "--porcelain"])
+        .unwrap();
+    assert!(status.contains("M  src/lib.rs"));
+    assert!(status.contains("?? notes.txt"));
+}
+
+#[test]
+fn checked_out_branch_is_none_on_detached_head() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    assert_eq!(
+        s.get_checked_out_branch(&repo_path).unwrap().as_deref(),
+        Some("main")
+    );
+
+    GitCli::new()
+        .git(&repo_path, ["checkout", "--detach"])
+        .unwrap();
+    assert_eq!(s.get_checked_out_branch(&repo_path).unwrap(), None);
+}
+
 #[test]
 fn commit_in_detached_head_succeeds_via_service() {
     let td = TempDir::new().unwrap();
@@ -520,3 +582,57 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn snapshot_and_restore_worktree_with_untracked_files() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&repo_path, "a.txt", "a1\n");
+    s.commit(&repo_path, "add a").unwrap();
+    let base = s.get_head_info(&repo_path).unwrap().oid;
+
+    // Uncommitted edit plus an untracked file
+    write_file(&repo_path, "a.txt", "a2\n");
+    write_file(&repo_path, "new/b.txt", "b\n");
+    let refname = "refs/vibe-kanban/snapshots/ws/turn-1";
+    let sha = s
+        .snapshot_worktree(&repo_path, refname, "snapshot")
+        .unwrap();
+    // Taking the snapshot leaves HEAD and the worktree alone
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, base);
+    assert_eq!(fs::read_to_string(repo_path.join("a.txt")).unwrap(), "a2\n");
+    let resolved = GitCli::new()
+        .git(&repo_path, ["rev-parse", refname])
+        .unwrap();
+    assert_eq!(resolved.trim(), sha);
+
+    // Later turns commit, add and remove files
+    fs::remove_file(repo_path.join("new/b.txt")).unwrap();
+    write_file(&repo_path, "a.txt", "a3\n");
+    s.commit(&repo_path, "later turn").unwrap();
+    write_file(&repo_path, "c.txt", "c\n");
+
+    s.restore_worktree_snapshot(&repo_path, &sha, true).unwrap();
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, base);
+    assert_eq!(fs::read_to_string(repo_path.join("a.txt")).unwrap(), "a2\n");
+    assert_eq!(
+        fs::read_to_string(repo_path.join("new/b.txt")).unwrap(),
+        "b\n"
+    );
+    assert!(!repo_path.join("c.txt").exists());
+    // Restored changes are uncommitted again, untracked files untracked
+    let status = GitCli::new()
+        .git(&repo_path, ["status", "--porcelain"])
+        .unwrap();
+    assert!(status.lines().any(|l| l == " M a.txt"));
+    assert!(status.lines().any(|l| l == "?? new/"));
+
+    s.delete_snapshot_refs(&repo_path, "refs/vibe-kanban/snapshots/ws")
+        .unwrap();
+    assert!(
+        GitCli::new()
+            .git(&repo_path, ["rev-parse", "--verify", "--quiet", refname])
+            .is_err()
+    );
+}
@@ -3,7 +3,7 @@ use std::{
     io,
     path::{Path, PathBuf},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use anyhow::anyhow;
@@ -13,16 +13,23 @@ use db::{
     DBService,
     models::{
         coding_agent_turn::CodingAgentTurn,
+        coding_agent_turn_usage::CodingAgentTurnUsage,
+        conflict_resolution::{ConflictResolution, ConflictResolutionStatus},
+        dev_server_status::{DevServerState, DevServerStatus},
         execution_process::{
             ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            ExecutionTerminationReason,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
+        project_container_config::ProjectContainerConfig,
         repo::Repo,
         scratch::{DraftFollowUpData, Scratch, ScratchType},
+        search::SearchIndex,
         session::{Session, SessionError},
         task::{Task, TaskStatus},
         workspace::Workspace,
         workspace_repo::WorkspaceRepo,
+        workspace_verification::{VerificationStatus, WorkspaceVerification},
     },
 };
 use deployment::DeploymentError;
@@ -30,29 +37,42 @@ use executors::{
     actions::{
         Executable, ExecutorAction, ExecutorActionType,
         coding_agent_follow_up::CodingAgentFollowUpRequest,
-        coding_agent_initial::CodingAgentInitialRequest,
+        coding_agent_initial::CodingAgentInitialRequest, review::ReviewRequest,
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
+    command::ExecutionLimits,
     env::{ExecutionEnv, RepoContext},
-    executors::{BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal},
+    executors::{
+        AppendPrompt, BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal,
+        build_review_prompt,
+    },
     logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    profile::{ExecutorConfigs, ExecutorProfileId},
+    sandbox::ContainerSandbox,
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
-use git::{Commit, DiffTarget, GitService};
+use git::{Commit, DiffTarget, GitService, GitServiceError, SNAPSHOT_REFS_PREFIX};
 use serde_json::json;
 use services::services::{
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
-    commit_message::{build_branch_commit_prompt, build_diff_context, generate_commit_message, summarize_diffs},
-    config::Config,
-    container::{ContainerError, ContainerRef, ContainerService},
+    commit_message::{
+        build_branch_commit_prompt, build_diff_context, commit_language, generate_commit_message,
+        summarize_diffs,
+    },
+    config::{ConcurrencyConfig, Config},
+    conflict_resolution,
+    container::{ContainerError, ContainerRef, ContainerService, searchable_log_entries},
     diff_stream::{self, DiffStreamHandle},
     image::ImageService,
     notification::NotificationService,
     queued_message::QueuedMessageService,
     workspace_manager::{RepoWorkspaceInput, WorkspaceManager},
 };
-use tokio::{sync::RwLock, task::JoinHandle};
+use tokio::{
+    sync::{RwLock, broadcast::error::RecvError},
+    task::JoinHandle,
+};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
@@ -61,7 +81,12 @@ use utils::{
 };
 use uuid::Uuid;
 
-use crate::{command, copy};
+use crate::{
+    command, copy,
+    dev_server::{self, ServerProcesses},
+    limits::{self, ExecutionCgroup},
+    verify,
+};
 
 #[derive(Clone)]
 pub struct LocalContainerService {
@@ -73,6 +98,10 @@ pub struct LocalContainerService {
     /// When stopping execution, we await these to ensure logs are fully persisted.
     db_stream_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
     exit_monitor_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
+    /// cgroups enforcing memory/CPU limits, removed once the process has exited
+    cgroups: Arc<RwLock<HashMap<Uuid, ExecutionCgroup>>>,
+    /// Containers of executions running in a project's container image
+    sandboxes: Arc<RwLock<HashMap<Uuid, ContainerSandbox>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
     image_service: ImageService,
@@ -98,6 +127,8 @@ impl LocalContainerService {
         let cancellation_tokens = Arc::new(RwLock::new(HashMap::new()));
         let db_stream_handles = Arc::new(RwLock::new(HashMap::new()));
         let exit_monitor_handles = Arc::new(RwLock::new(HashMap::new()));
+        let cgroups = Arc::new(RwLock::new(HashMap::new()));
+        let sandboxes = Arc::new(RwLock::new(HashMap::new()));
         let notification_service = NotificationService::new(config.clone());
 
         let container = LocalContainerService {
@@ -107,6 +138,8 @@ impl LocalContainerService {
             msg_stores,
             db_stream_handles,
             exit_monitor_handles,
+            cgroups,
+            sandboxes,
             config,
             git,
             image_service,
@@ -266,12 +299,12 @@ impl LocalContainerService {
 
     /// Get the commit message based on the execution run reason.
     /// For CodingAgent runs, attempts to generate a standardized Conventional Commits message
-    /// using DeepSeek API. Falls back to the agent's summary or default message on failure.
+    /// with the configured LLM provider. Falls back to the agent's summary or default message on failure.
     async fn get_commit_message(&self, ctx: &ExecutionContext) -> String {
         match ctx.execution_process.run_reason {
             ExecutionProcessRunReason::CodingAgent => {
-                // Try to generate commit message using DeepSeek API
-                if let Some(message) = self.try_generate_commit_message_with_deepseek(ctx).await {
+                // Try to generate commit message using the configured LLM provider
+                if let Some(message) = self.try_generate_commit_message_with_llm(ctx).await {
                     return message;
                 }
 
@@ -316,12 +349,9 @@ impl LocalContainerService {
         }
     }
 
-    /// Attempt to generate a commit message using DeepSeek API.
+    /// Attempt to generate a commit message using the configured LLM provider.
     /// Returns None if generation fails, allowing fallback to other methods.
-    async fn try_generate_commit_message_with_deepseek(
-        &self,
-        ctx: &ExecutionContext,
-    ) -> Option<String> {
+    async fn try_generate_commit_message_with_llm(&self, ctx: &ExecutionContext) -> Option<String> {
         // Get workspace root
         let container_ref = ctx.workspace.container_ref.as_ref()?;
         let workspace_root = PathBuf::from(container_ref);
@@ -432,11 +462,12 @@ impl LocalContainerService {
 
         // If no diffs found, return None to use fallback
         if all_diffs.is_empty() {
-            tracing::debug!("No diffs found, skipping DeepSeek commit message generation");
+            tracing::debug!("No diffs found, skipping LLM commit message generation");
             return None;
         }
 
         // Build prompt and generate message
+        let llm = self.config.read().await.llm.clone();
         let diff_summary = summarize_diffs(&all_diffs);
         let diff_context = build_diff_context(&all_diffs);
         let prompt = build_branch_commit_prompt(
@@ -445,19 +476,20 @@ impl LocalContainerService {
             &target_branch,
             &diff_summary,
             &diff_context,
+            &commit_language(&llm),
         );
 
-        match generate_commit_message(&prompt).await {
+        match generate_commit_message(&llm, &prompt).await {
             Ok(message) => {
                 tracing::info!(
-                    "Generated commit message using DeepSeek API - length: {}, content: '{}'",
+                    "Generated commit message using LLM provider - length: {}, content: '{}'",
                     message.len(),
                     message
                 );
                 Some(message)
             }
             Err(e) => {
-                tracing::warn!("Failed to generate commit message with DeepSeek: {}", e);
+                tracing::warn!("Failed to generate commit message with LLM provider: {}", e);
                 None
             }
         }
@@ -640,6 +672,8 @@ impl LocalContainerService {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
         let msg_stores = self.msg_stores.clone();
+        let cgroups = self.cgroups.clone();
+        let sandboxes = self.sandboxes.clone();
         let db = self.db.clone();
         let config = self.config.clone();
         let container = self.clone();
@@ -694,6 +728,28 @@ impl LocalContainerService {
                 Err(_) => (None, ExecutionProcessStatus::Failed),
             };
 
+            // An OOM kill inside the cgroup only shows up as a failed exit. The group
+            // itself is held until the end of this task, then removed on drop.
+            let cgroup = cgroups.write().await.remove(&exec_id);
+            if cgroup.as_ref().is_some_and(|cgroup| cgroup.oom_killed()) {
+                tracing::info!("Execution {} exceeded its memory limit", exec_id);
+                if let Err(e) = ExecutionProcess::set_termination_reason(
+                    &db.pool,
+                    exec_id,
+                    ExecutionTerminationReason::MemoryLimit,
+                )
+                .await
+                {
+                    tracing::error!("Failed to record termination reason: {}", e);
+                }
+            }
+
+            // The container normally goes away with its runtime client, but not when
+            // the client itself was killed
+            if let Some(sandbox) = sandboxes.write().await.remove(&exec_id) {
+                let _ = sandbox.remove_command().status().await;
+            }
+
             if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
                 && let Err(e) =
                     ExecutionProcess::update_completion(&db.pool, exec_id, status, exit_code).await
@@ -702,11 +758,29 @@ impl LocalContainerService {
             }
 
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+                if ctx.execution_process.run_reason == ExecutionProcessRunReason::DevServer {
+                    container.handle_dev_server_exit(&ctx).await;
+                }
+
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
 
+                if let Err(e) = container.record_token_usage(&ctx).await {
+                    tracing::warn!("Failed to record token usage: {}", e);
+                }
+
+                if let Err(e) = container.index_execution_logs(&ctx).await {
+                    tracing::warn!("Failed to index execution logs for search: {}", e);
+                }
+
+                container.snapshot_worktrees(&ctx).await;
+
+                // Resolved conflicts are committed by continuing the rebase or merge,
+                // not by the usual commit after the agent
+                let conflict_resolution = container.handle_conflict_resolution_exit(&ctx).await;
+
                 let success = matches!(
                     ctx.execution_process.status,
                     ExecutionProcessStatus::Completed
@@ -720,7 +794,7 @@ impl LocalContainerService {
                     ExecutionProcessStatus::Running
                 );
 
-                if success || cleanup_done {
+                if (success || cleanup_done) && conflict_resolution.is_none() {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -761,7 +835,15 @@ impl LocalContainerService {
                     }
                 }
 
-                if container.should_finalize(&ctx) {
+                // A failed verification the agent is asked to fix keeps the task in progress
+                let fixing_verification = ctx.execution_process.run_reason
+                    == ExecutionProcessRunReason::VerifyScript
+                    && container.handle_verify_exit(&ctx).await;
+
+                let resolving_conflicts =
+                    conflict_resolution == Some(ConflictResolutionStatus::Resolving);
+
+                if !fixing_verification && !resolving_conflicts && container.should_finalize(&ctx) {
                     // Only execute queued messages if the execution succeeded
                     // If it failed or was killed, just clear the queue and finalize
                     let should_execute_queued = !matches!(
@@ -769,45 +851,58 @@ impl LocalContainerService {
                         ExecutionProcessStatus::Failed | ExecutionProcessStatus::Killed
                     );
 
-                    if let Some(queued_msg) =
-                        container.queued_message_service.take_queued(ctx.session.id)
-                    {
-                        if should_execute_queued {
-                            tracing::info!(
-                                "Found queued message for session {}, starting follow-up execution",
-                                ctx.session.id
-                            );
-
-                            // Delete the scratch since we're consuming the queued message
-                            if let Err(e) = Scratch::delete(
-                                &db.pool,
-                                ctx.session.id,
-                                &ScratchType::DraftFollowUp,
-                            )
+                    let queued_msg = if should_execute_queued {
+                        container
+                            .queued_message_service
+                            .take_queued(ctx.session.id)
                             .await
-                            {
-                                tracing::warn!(
-                                    "Failed to delete scratch after consuming queued message: {}",
+                            .unwrap_or_else(|e| {
+                                tracing::error!(
+                                    "Failed to take queued message for session {}: {}",
+                                    ctx.session.id,
                                     e
                                 );
-                            }
+                                None
+                            })
+                    } else {
+                        if let Err(e) = container
+                            .queued_message_service
+                            .cancel_queued(ctx.session.id)
+                            .await
+                        {
+                            tracing::error!(
+                                "Failed to discard queued messages for session {}: {}",
+                                ctx.session.id,
+                                e
+                            );
+                        }
+                        None
+                    };
 
-                            // Execute the queued follow-up
-                            if let Err(e) = container
-                                .start_queued_follow_up(&ctx, &queued_msg.data)
+                    if let Some(queued_msg) = queued_msg {
+                        tracing::info!(
+                            "Found queued message for session {}, starting follow-up execution",
+                            ctx.session.id
+                        );
+
+                        // Delete the scratch since we're consuming the queued message
+                        if let Err(e) =
+                            Scratch::delete(&db.pool, ctx.session.id, &ScratchType::DraftFollowUp)
                                 .await
-                            {
-                                tracing::error!("Failed to start queued follow-up: {}", e);
-                                // Fall back to finalization if follow-up fails
-                                container.finalize_task(&ctx).await;
-                            }
-                        } else {
-                            // Execution failed or was killed - discard the queued message and finalize
-                            tracing::info!(
-                                "Discarding queued message for session {} due to execution status {:?}",
-                                ctx.session.id,
-                                ctx.execution_process.status
+                        {
+                            tracing::warn!(
+                                "Failed to delete scratch after consuming queued message: {}",
+                                e
                             );
+                        }
+
+                        // Execute the queued follow-up
+                        if let Err(e) = container
+                            .start_queued_follow_up(&ctx, &queued_msg.data)
+                            .await
+                        {
+                            tracing::error!("Failed to start queued follow-up: {}", e);
+                            // Fall back to finalization if follow-up fails
                             container.finalize_task(&ctx).await;
                         }
                     } else {
@@ -849,9 +944,526 @@ impl LocalContainerService {
 
             // Cleanup child handle
             child_store.write().await.remove(&exec_id);
+
+            // Hand a freed coding agent slot to the next queued run
+            container.dispatch_queued_executions().await;
         })
     }
 
+    /// Stop the process once it runs past its wall-clock or idle timeout
+    fn spawn_limit_watchdog(
+        &self,
+        exec_id: Uuid,
+        msg_store: Arc<MsgStore>,
+        limits: ExecutionLimits,
+    ) {
+        let container = self.clone();
+        let mut messages = msg_store.get_receiver();
+        tokio::spawn(async move {
+            let started_at = Instant::now();
+            let mut last_output_at = started_at;
+            while let Some((deadline, reason)) =
+                limits::next_deadline(&limits, started_at, last_output_at)
+            {
+                tokio::select! {
+                    msg = messages.recv() => match msg {
+                        Ok(LogMsg::Stdout(_) | LogMsg::Stderr(_)) | Err(RecvError::Lagged(_)) => {
+                            last_output_at = Instant::now();
+                        }
+                        Ok(LogMsg::Finished) | Err(RecvError::Closed) => return,
+                        Ok(_) => {}
+                    },
+                    _ = tokio::time::sleep_until(deadline.into()) => {
+                        container.stop_for_limit(exec_id, reason).await;
+                        return;
+                    }
+                }
+            }
+        });
+    }
+
+    async fn stop_for_limit(&self, exec_id: Uuid, reason: ExecutionTerminationReason) {
+        let execution_process = match ExecutionProcess::find_by_id(&self.db.pool, exec_id).await {
+            Ok(Some(process)) if process.status == ExecutionProcessStatus::Running => process,
+            _ => return,
+        };
+        tracing::info!(
+            "Stopping execution {} after exceeding its limits ({:?})",
+            exec_id,
+            reason
+        );
+
+        if let Err(e) =
+            ExecutionProcess::set_termination_reason(&self.db.pool, exec_id, reason).await
+        {
+            tracing::error!("Failed to record termination reason: {}", e);
+        }
+        if let Err(e) = self
+            .stop_execution(&execution_process, ExecutionProcessStatus::Killed)
+            .await
+        {
+            tracing::error!("Failed to stop execution {}: {}", exec_id, e);
+        }
+    }
+
+    /// Track the port and readiness of a dev server until it exits
+    fn spawn_dev_server_monitor(
+        &self,
+        exec_id: Uuid,
+        health_check: Option<String>,
+        processes: Option<ServerProcesses>,
+        msg_store: Arc<MsgStore>,
+    ) {
+        let pool = self.db.pool.clone();
+        let mut messages = msg_store.get_receiver();
+        tokio::spawn(async move {
+            let client = match reqwest::Client::builder()
+                .timeout(dev_server::PROBE_TIMEOUT)
+                .build()
+            {
+                Ok(client) => client,
+                Err(e) => {
+                    tracing::error!("Failed to build dev server probe client: {}", e);
+                    return;
+                }
+            };
+            let mut output_port = None;
+            let mut port = None;
+            let mut was_ready = false;
+            let mut checks = tokio::time::interval(dev_server::CHECK_INTERVAL);
+            loop {
+                tokio::select! {
+                    msg = messages.recv() => match msg {
+                        Ok(LogMsg::Stdout(text) | LogMsg::Stderr(text)) => {
+                            if output_port.is_none() {
+                                output_port = dev_server::port_from_output(&text);
+                            }
+                        }
+                        Ok(LogMsg::Finished) | Err(RecvError::Closed) => return,
+                        _ => {}
+                    },
+                    _ = checks.tick() => {
+                        if port.is_none() {
+                            let listening = match &processes {
+                                Some(processes) => processes.listening_ports().await,
+                                None => Vec::new(),
+                            };
+                            port = dev_server::choose_port(output_port, &listening);
+                            if let Some(port) = port
+                                && let Err(e) =
+                                    DevServerStatus::set_port(&pool, exec_id, port).await
+                            {
+                                tracing::error!("Failed to record dev server port: {}", e);
+                            }
+                        }
+
+                        let probe = match &health_check {
+                            Some(check) => match dev_server::probe_url(check, port) {
+                                Some(url) => dev_server::probe(&client, &url).await,
+                                None => continue,
+                            },
+                            None if port.is_some() => Ok(()),
+                            None => continue,
+                        };
+                        let (state, error) = match probe {
+                            Ok(()) => {
+                                was_ready = true;
+                                (DevServerState::Ready, None)
+                            }
+                            // Probes are expected to fail while the server starts up
+                            Err(_) if !was_ready => continue,
+                            Err(e) => (DevServerState::Unhealthy, Some(e)),
+                        };
+                        if let Err(e) =
+                            DevServerStatus::update_state(&pool, exec_id, state, error.as_deref())
+                                .await
+                        {
+                            tracing::error!("Failed to update dev server status: {}", e);
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Record how a dev server ended, and restart it with backoff when it
+    /// crashed and its repo has auto-restart enabled
+    async fn handle_dev_server_exit(&self, ctx: &ExecutionContext) {
+        let pool = &self.db.pool;
+        let exec_id = ctx.execution_process.id;
+        let status = match DevServerStatus::find_by_execution_process_id(pool, exec_id).await {
+            Ok(Some(status)) => status,
+            Ok(None) => return,
+            Err(e) => {
+                tracing::error!("Failed to load dev server status: {}", e);
+                return;
+            }
+        };
+
+        if ctx.execution_process.status != ExecutionProcessStatus::Failed {
+            if let Err(e) =
+                DevServerStatus::update_state(pool, exec_id, DevServerState::Stopped, None).await
+            {
+                tracing::error!("Failed to update dev server status: {}", e);
+            }
+            return;
+        }
+
+        let error = match ctx.execution_process.exit_code {
+            Some(code) => format!("Exited with code {code}"),
+            None => "Exited unexpectedly".to_string(),
+        };
+        if let Err(e) =
+            DevServerStatus::update_state(pool, exec_id, DevServerState::Crashed, Some(&error))
+                .await
+        {
+            tracing::error!("Failed to update dev server status: {}", e);
+        }
+
+        let auto_restart = ctx
+            .repos
+            .iter()
+            .any(|repo| repo.id == status.repo_id && repo.dev_server_auto_restart);
+        if !auto_restart {
+            return;
+        }
+        if status.restart_count >= dev_server::MAX_RESTARTS {
+            tracing::warn!(
+                "Dev server {} crashed after {} restarts, not restarting it again",
+                exec_id,
+                status.restart_count
+            );
+            return;
+        }
+        let Ok(action) = ctx.execution_process.executor_action().cloned() else {
+            return;
+        };
+
+        let container = self.clone();
+        let workspace = ctx.workspace.clone();
+        let session = ctx.session.clone();
+        let project_id = ctx.project.id;
+        tokio::spawn(async move {
+            tokio::time::sleep(dev_server::restart_backoff(status.restart_count)).await;
+            if container
+                .dev_server_superseded(&status, workspace.id, project_id)
+                .await
+            {
+                return;
+            }
+
+            tracing::info!(
+                "Restarting crashed dev server {} for workspace {}",
+                status.execution_process_id,
+                workspace.id
+            );
+            match container
+                .start_execution(
+                    &workspace,
+                    &session,
+                    &action,
+                    &ExecutionProcessRunReason::DevServer,
+                )
+                .await
+            {
+                Ok(process) => {
+                    if let Err(e) = DevServerStatus::set_restart_count(
+                        &container.db.pool,
+                        process.id,
+                        status.restart_count + 1,
+                    )
+                    .await
+                    {
+                        tracing::error!("Failed to record dev server restart: {}", e);
+                    }
+                }
+                Err(e) => tracing::error!("Failed to restart dev server: {}", e),
+            }
+        });
+    }
+
+    /// Snapshot every repo's worktree at the end of a coding agent turn, so the
+    /// session can later be restored to this turn
+    async fn snapshot_worktrees(&self, ctx: &ExecutionContext) {
+        if ctx.execution_process.run_reason != ExecutionProcessRunReason::CodingAgent {
+            return;
+        }
+        let Some(container_ref) = &ctx.workspace.container_ref else {
+            return;
+        };
+        let workspace_root = PathBuf::from(container_ref);
+        let exec_id = ctx.execution_process.id;
+        let refname = format!("{SNAPSHOT_REFS_PREFIX}/{}/{exec_id}", ctx.workspace.id);
+        let message = format!("Snapshot after execution {exec_id}");
+        for repo in &ctx.repos {
+            let worktree_path = workspace_root.join(&repo.name);
+            let sha = match self
+                .git()
+                .snapshot_worktree(&worktree_path, &refname, &message)
+            {
+                Ok(sha) => sha,
+                Err(e) => {
+                    tracing::warn!("Failed to snapshot worktree for repo {}: {}", repo.name, e);
+                    continue;
+                }
+            };
+            if let Err(e) = ExecutionProcessRepoState::update_after_snapshot_commit(
+                &self.db.pool,
+                exec_id,
+                repo.id,
+                &sha,
+            )
+            .await
+            {
+                tracing::warn!("Failed to record worktree snapshot: {}", e);
+            }
+        }
+    }
+
+    /// Check the worktree a conflict resolution follow-up worked on, and
+    /// continue the rebase or merge once no conflict markers remain. A rebase
+    /// that stops on the next commit's conflicts starts another round. Returns
+    /// None when the execution was not resolving conflicts.
+    async fn handle_conflict_resolution_exit(
+        &self,
+        ctx: &ExecutionContext,
+    ) -> Option<ConflictResolutionStatus> {
+        if ctx.execution_process.run_reason != ExecutionProcessRunReason::CodingAgent {
+            return None;
+        }
+        let pool = &self.db.pool;
+        let resolution = match ConflictResolution::find_for_execution_process(
+            pool,
+            ctx.workspace.id,
+            ctx.execution_process.id,
+        )
+        .await
+        {
+            Ok(resolution) => resolution?,
+            Err(e) => {
+                tracing::error!("Failed to load conflict resolution: {}", e);
+                return None;
+            }
+        };
+        let repo = ctx
+            .repos
+            .iter()
+            .find(|repo| repo.id == resolution.repo_id)?;
+        let worktree_path = PathBuf::from(ctx.workspace.container_ref.as_ref()?).join(&repo.name);
+
+        let (status, error) = if ctx.execution_process.status != ExecutionProcessStatus::Completed {
+            (
+                ConflictResolutionStatus::Failed,
+                Some("The coding agent stopped before resolving the conflicts".to_string()),
+            )
+        } else {
+            let remaining = conflict_resolution::files_with_conflict_markers(
+                &worktree_path,
+                &resolution.conflicted_files,
+            );
+            if !remaining.is_empty() {
+                (
+                    ConflictResolutionStatus::Failed,
+                    Some(format!(
+                        "Conflict markers remain in: {}",
+                        remaining.join(", ")
+                    )),
+                )
+            } else {
+                match self.git().continue_conflicts(&worktree_path) {
+                    Ok(()) => (ConflictResolutionStatus::Resolved, None),
+                    Err(GitServiceError::MergeConflicts { message, .. })
+                        if resolution.rounds < conflict_resolution::MAX_ROUNDS =>
+                    {
+                        match self.start_conflict_resolution(&ctx.workspace, repo).await {
+                            Ok(_) => return Some(ConflictResolutionStatus::Resolving),
+                            Err(e) => (
+                                ConflictResolutionStatus::Failed,
+                                Some(format!("{message} Resolving them failed to start: {e}")),
+                            ),
+                        }
+                    }
+                    Err(e) => (ConflictResolutionStatus::Failed, Some(e.to_string())),
+                }
+            }
+        };
+
+        if let Err(e) =
+            ConflictResolution::finish(pool, ctx.workspace.id, repo.id, status, error.as_deref())
+                .await
+        {
+            tracing::error!("Failed to record conflict resolution: {}", e);
+        }
+        Some(status)
+    }
+
+    /// Record the result of a verify script. While the repo has retries left, a
+    /// failure is sent back to the coding agent as a follow-up; returns whether
+    /// that happened.
+    async fn handle_verify_exit(&self, ctx: &ExecutionContext) -> bool {
+        let pool = &self.db.pool;
+        let workspace_id = ctx.workspace.id;
+        let Ok(action) = ctx.execution_process.executor_action() else {
+            return false;
+        };
+
+        if ctx.execution_process.status != ExecutionProcessStatus::Failed {
+            // Verify scripts of the other repos still run after a passing one
+            let status = match ctx.execution_process.status {
+                ExecutionProcessStatus::Completed if action.next_action.is_some() => return false,
+                ExecutionProcessStatus::Completed => VerificationStatus::Passed,
+                _ => VerificationStatus::Failed,
+            };
+            if let Err(e) = WorkspaceVerification::finish(pool, workspace_id, status, None).await {
+                tracing::error!("Failed to record workspace verification: {}", e);
+            }
+            return false;
+        }
+
+        let output = match self.get_msg_store_by_id(&ctx.execution_process.id).await {
+            Some(msg_store) => verify::output_tail(&msg_store.get_history()),
+            None => String::new(),
+        };
+        if let Err(e) = WorkspaceVerification::finish(
+            pool,
+            workspace_id,
+            VerificationStatus::Failed,
+            Some(&output),
+        )
+        .await
+        {
+            tracing::error!("Failed to record workspace verification: {}", e);
+            return false;
+        }
+
+        let Some(repo) = script_repo(action, &ctx.repos) else {
+            return false;
+        };
+        let retries = match WorkspaceVerification::find_by_workspace_id(pool, workspace_id).await {
+            Ok(Some(verification)) => verification.retries,
+            Ok(None) => return false,
+            Err(e) => {
+                tracing::error!("Failed to load workspace verification: {}", e);
+                return false;
+            }
+        };
+        if !verify::should_retry(retries, repo.verify_max_retries) {
+            tracing::info!(
+                "Verification of workspace {} failed after {} automatic fixes",
+                workspace_id,
+                retries
+            );
+            return false;
+        }
+        let executor_profile_id =
+            match ExecutionProcess::latest_executor_profile_for_session(pool, ctx.session.id).await
+            {
+                Ok(Some(profile)) => profile,
+                Ok(None) => return false,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to find the executor of session {}: {}",
+                        ctx.session.id,
+                        e
+                    );
+                    return false;
+                }
+            };
+
+        let message = verify::fix_prompt(
+            &repo.name,
+            repo.verify_script.as_deref().unwrap_or_default(),
+            ctx.execution_process.exit_code,
+            &output,
+        );
+        if let Err(e) = WorkspaceVerification::start_fix(pool, workspace_id).await {
+            tracing::error!("Failed to record workspace verification: {}", e);
+            return false;
+        }
+        let follow_up = DraftFollowUpData {
+            message,
+            executor_profile_id,
+            review: None,
+        };
+        if let Err(e) = self.start_queued_follow_up(ctx, &follow_up).await {
+            tracing::error!("Failed to ask the agent to fix the verification: {}", e);
+            let _ = WorkspaceVerification::abandon_fix(pool, workspace_id).await;
+            return false;
+        }
+        tracing::info!(
+            "Verification of workspace {} failed, asked the agent for a fix ({}/{})",
+            workspace_id,
+            retries + 1,
+            repo.verify_max_retries
+        );
+        true
+    }
+
+    /// Whether a dev server was started since `status`'s run crashed, for the
+    /// same repo of the workspace or in another workspace of the project
+    async fn dev_server_superseded(
+        &self,
+        status: &DevServerStatus,
+        workspace_id: Uuid,
+        project_id: Uuid,
+    ) -> bool {
+        let pool = &self.db.pool;
+        let (statuses, in_project, in_workspace) = match tokio::try_join!(
+            DevServerStatus::find_by_workspace_id(pool, workspace_id),
+            ExecutionProcess::find_running_dev_servers_by_project(pool, project_id),
+            ExecutionProcess::find_running_dev_servers_by_workspace(pool, workspace_id),
+        ) {
+            Ok(found) => found,
+            Err(e) => {
+                tracing::error!("Failed to check for newer dev servers: {}", e);
+                return true;
+            }
+        };
+        let restarted_here = statuses
+            .iter()
+            .find(|latest| latest.repo_id == status.repo_id)
+            .is_some_and(|latest| latest.execution_process_id != status.execution_process_id);
+        restarted_here || in_project.len() > in_workspace.len()
+    }
+
+    /// Container to run an execution in, when the project runs its executions
+    /// in containers. Besides the workspace, each repo's git directory is
+    /// mounted so the worktrees' links to it resolve inside the container, and
+    /// the agents' configuration so they are signed in as on the host.
+    async fn execution_sandbox(
+        &self,
+        project_id: Uuid,
+        exec_id: Uuid,
+        current_dir: &Path,
+        repos: &[Repo],
+        ports: Vec<u16>,
+    ) -> Result<Option<ContainerSandbox>, ContainerError> {
+        let Some(config) = ProjectContainerConfig::find_by_project_id(&self.db.pool, project_id)
+            .await?
+            .filter(|config| config.enabled)
+        else {
+            return Ok(None);
+        };
+        let mut mounts = vec![current_dir.to_path_buf()];
+        mounts.extend(repos.iter().map(|repo| repo.path.join(".git")));
+        let home = dirs::home_dir();
+        if let Some(home) = &home {
+            mounts.extend(ContainerSandbox::agent_config_mounts(home));
+        }
+        Ok(Some(ContainerSandbox {
+            runtime: config.runtime,
+            image: config.image,
+            name: format!("vk-exec-{exec_id}"),
+            mounts,
+            home,
+            network: config.network_enabled,
+            ports,
+            env_passthrough: config.env_passthrough,
+            user: workspace_owner(current_dir),
+            limits: ExecutionLimits::default(),
+        }))
+    }
+
     pub fn spawn_os_exit_watcher(
         &self,
         exec_id: Uuid,
@@ -960,6 +1572,67 @@ impl LocalContainerService {
         None
     }
 
+    /// Add the final normalized conversation of an execution to the search index
+    async fn index_execution_logs(&self, ctx: &ExecutionContext) -> Result<(), anyhow::Error> {
+        let entries = {
+            let msg_stores = self.msg_stores.read().await;
+            let Some(msg_store) = msg_stores.get(&ctx.execution_process.id) else {
+                return Ok(());
+            };
+            searchable_log_entries(msg_store.get_history())
+        };
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        SearchIndex::replace_log_entries(
+            &self.db.pool,
+            ctx.execution_process.id,
+            ctx.task.id,
+            ctx.execution_process.started_at,
+            &entries,
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Persist the per-model token usage reported at the end of a coding agent turn
+    async fn record_token_usage(&self, ctx: &ExecutionContext) -> Result<(), anyhow::Error> {
+        let model_usage = {
+            let msg_stores = self.msg_stores.read().await;
+            let Some(msg_store) = msg_stores.get(&ctx.execution_process.id) else {
+                return Ok(());
+            };
+            msg_store.get_history().iter().rev().find_map(|msg| {
+                let LogMsg::JsonPatch(patch) = msg else {
+                    return None;
+                };
+                let (_, entry) = extract_normalized_entry_from_patch(patch)?;
+                match entry.entry_type {
+                    NormalizedEntryType::TokenUsageInfo(info) if !info.model_usage.is_empty() => {
+                        Some(info.model_usage)
+                    }
+                    _ => None,
+                }
+            })
+        };
+        let Some(model_usage) = model_usage else {
+            return Ok(());
+        };
+
+        let executor_action = ctx.execution_process.executor_action()?;
+        let (Some(executor_profile_id), Some(turn)) = (
+            executor_action.executor_profile_id(),
+            CodingAgentTurn::find_by_execution_process_id(&self.db.pool, ctx.execution_process.id)
+                .await?,
+        ) else {
+            return Ok(());
+        };
+        CodingAgentTurnUsage::record(&self.db.pool, turn.id, executor_profile_id, &model_usage)
+            .await?;
+        Ok(())
+    }
+
     /// Update the coding agent turn summary with the final assistant message
     async fn update_executor_session_summary(&self, exec_id: &Uuid) -> Result<(), anyhow::Error> {
         // Check if there's a coding agent turn for this execution process
@@ -1076,7 +1749,8 @@ impl LocalContainerService {
         Ok(())
     }
 
-    /// Start a follow-up execution from a queued message
+    /// Start a follow-up execution from a queued message, or from a message
+    /// the server sends on its own such as a verification fix request
     async fn start_queued_follow_up(
         &self,
         ctx: &ExecutionContext,
@@ -1116,7 +1790,7 @@ impl LocalContainerService {
 
         let repos =
             WorkspaceRepo::find_repos_for_workspace(&self.db.pool, ctx.workspace.id).await?;
-        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+        let post_agent_action = self.post_agent_actions_for_repos(&repos);
 
         let working_dir = ctx
             .workspace
@@ -1125,7 +1799,16 @@ impl LocalContainerService {
             .filter(|dir| !dir.is_empty())
             .cloned();
 
-        let action_type = if let Some(agent_session_id) = latest_agent_session_id {
+        let review = queued_data.review == Some(true);
+        let action_type = if review {
+            ExecutorActionType::ReviewRequest(ReviewRequest {
+                executor_profile_id: executor_profile_id.clone(),
+                context: None,
+                prompt: build_review_prompt(None, Some(&queued_data.message)),
+                session_id: latest_agent_session_id,
+                working_dir,
+            })
+        } else if let Some(agent_session_id) = latest_agent_session_id {
             ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
                 prompt: queued_data.message.clone(),
                 session_id: agent_session_id,
@@ -1137,10 +1820,13 @@ impl LocalContainerService {
                 prompt: queued_data.message.clone(),
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir,
+                project_memory: AppendPrompt::default(),
             })
         };
 
-        let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+        // Reviews don't change the worktree, so the post-agent scripts are skipped
+        let next_action = post_agent_action.filter(|_| !review);
+        let action = ExecutorAction::new(action_type, next_action.map(Box::new));
 
         self.start_execution(
             &ctx.workspace,
@@ -1152,6 +1838,28 @@ impl LocalContainerService {
     }
 }
 
+/// Repo whose script an action runs, matched by its working directory
+fn script_repo<'a>(action: &ExecutorAction, repos: &'a [Repo]) -> Option<&'a Repo> {
+    let ExecutorActionType::ScriptRequest(request) = action.typ() else {
+        return None;
+    };
+    let working_dir = request.working_dir.as_deref()?;
+    repos.iter().find(|repo| repo.name == working_dir)
+}
+
+/// `uid:gid` owning the workspace, for containers to write files as that user
+#[cfg(unix)]
+fn workspace_owner(dir: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+    let metadata = std::fs::metadata(dir).ok()?;
+    Some(format!("{}:{}", metadata.uid(), metadata.gid()))
+}
+
+#[cfg(not(unix))]
+fn workspace_owner(_dir: &Path) -> Option<String> {
+    None
+}
+
 fn failure_exit_status() -> std::process::ExitStatus {
     #[cfg(unix)]
     {
@@ -1195,6 +1903,14 @@ impl ContainerService for LocalContainerService {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn default_executor_profile(&self) -> ExecutorProfileId {
+        self.config.read().await.executor_profile.clone()
+    }
+
+    async fn concurrency_limits(&self) -> ConcurrencyConfig {
+        self.config.read().await.concurrency.clone()
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -1351,6 +2067,10 @@ impl ContainerService for LocalContainerService {
             )))?;
         let current_dir = PathBuf::from(container_ref);
 
+        let coding_agent = executor_action
+            .executor_profile_id()
+            .and_then(|profile_id| ExecutorConfigs::get_cached().get_coding_agent(profile_id));
+
         let approvals_service: Arc<dyn ExecutorApprovalService> =
             match executor_action.base_executor() {
                 Some(
@@ -1364,6 +2084,10 @@ impl ContainerService for LocalContainerService {
                     self.db.clone(),
                     self.notification_service.clone(),
                     execution_process.id,
+                    coding_agent
+                        .as_ref()
+                        .and_then(|agent| agent.approval_timeout().cloned())
+                        .unwrap_or_default(),
                 ),
                 _ => Arc::new(NoopExecutorApprovalService {}),
             };
@@ -1392,6 +2116,39 @@ impl ContainerService for LocalContainerService {
         env.insert("VK_TASK_ID", task.id.to_string());
         env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
         env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
+        let dev_server_repo = (execution_process.run_reason
+            == ExecutionProcessRunReason::DevServer)
+            .then(|| script_repo(executor_action, &repos))
+            .flatten();
+        let ports = dev_server_repo
+            .and_then(|repo| repo.dev_server_health_check.as_deref())
+            .and_then(dev_server::health_check_port)
+            .into_iter()
+            .collect();
+        env.sandbox = self
+            .execution_sandbox(
+                project.id,
+                execution_process.id,
+                &current_dir,
+                &repos,
+                ports,
+            )
+            .await?;
+
+        // Resource caps apply from the first instruction: the container runtime
+        // enforces them on a sandbox, otherwise the process joins its cgroup before exec
+        let limits = coding_agent
+            .as_ref()
+            .and_then(|agent| agent.execution_limits().cloned());
+        let mut cgroup = None;
+        if let Some(limits) = &limits {
+            if let Some(sandbox) = env.sandbox.as_mut() {
+                sandbox.limits = limits.clone();
+            } else {
+                cgroup = ExecutionCgroup::create(execution_process.id, limits);
+                env.cgroup_procs = cgroup.as_ref().map(ExecutionCgroup::procs_path);
+            }
+        }
 
         // Create the child and stream, add to execution tracker with timeout
         let mut spawned = tokio::time::timeout(
@@ -1408,6 +2165,46 @@ impl ContainerService for LocalContainerService {
         self.track_child_msgs_in_store(execution_process.id, &mut spawned.child)
             .await;
 
+        let server_processes = match &env.sandbox {
+            Some(sandbox) => Some(ServerProcesses::Container(Box::new(sandbox.clone()))),
+            None => spawned.child.inner().id().map(ServerProcesses::Group),
+        };
+        if let Some(sandbox) = env.sandbox {
+            self.sandboxes
+                .write()
+                .await
+                .insert(execution_process.id, sandbox);
+        }
+
+        if execution_process.run_reason == ExecutionProcessRunReason::VerifyScript
+            && let Err(e) =
+                WorkspaceVerification::start(&self.db.pool, workspace.id, execution_process.id)
+                    .await
+        {
+            tracing::error!("Failed to record workspace verification: {}", e);
+        }
+
+        if let Some(repo) = dev_server_repo
+            && let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            match DevServerStatus::create(&self.db.pool, execution_process.id, repo.id).await {
+                Ok(_) => self.spawn_dev_server_monitor(
+                    execution_process.id,
+                    repo.dev_server_health_check.clone(),
+                    server_processes,
+                    msg_store,
+                ),
+                Err(e) => tracing::error!("Failed to record dev server status: {}", e),
+            }
+        }
+
+        if let Some(cgroup) = cgroup {
+            self.cgroups
+                .write()
+                .await
+                .insert(execution_process.id, cgroup);
+        }
+
         self.add_child_to_store(execution_process.id, spawned.child)
             .await;
 
@@ -1421,6 +2218,12 @@ impl ContainerService for LocalContainerService {
         let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
         self.add_exit_monitor_handle(execution_process.id, hn).await;
 
+        if let Some(limits) = limits
+            && let Some(msg_store) = self.get_msg_store_by_id(&execution_process.id).await
+        {
+            self.spawn_limit_watchdog(execution_process.id, msg_store, limits);
+        }
+
         Ok(())
     }
 
@@ -1429,6 +2232,15 @@ impl ContainerService for LocalContainerService {
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
+        // Nothing to kill while the process is still waiting for a slot
+        if execution_process.status == ExecutionProcessStatus::Queued
+            && ExecutionProcess::cancel_queued(&self.db.pool, execution_process.id).await?
+        {
+            let ctx = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await?;
+            Task::update_status(&self.db.pool, ctx.task.id, TaskStatus::InReview).await?;
+            return Ok(());
+        }
+
         let child = self
             .get_child_from_store(&execution_process.id)
             .await
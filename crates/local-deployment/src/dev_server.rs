@@ -0,0 +1,326 @@
+//! Health of dev server processes started for a workspace.
+//!
+//! The listening port is read from the sockets the server's process group
+//! holds in LISTEN state (Linux `/proc`), or those of every process in its
+//! container when it is sandboxed, with the first address printed in its
+//! output as a fallback. A server counts as ready once it answers the readiness
+//! probe configured on its repo, or once it listens when no probe is set.
+
+use std::{sync::LazyLock, time::Duration};
+
+use executors::sandbox::ContainerSandbox;
+use regex::Regex;
+
+/// How often the port and readiness probe are checked
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// Longest a single readiness probe may take
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Crashes after which a dev server is no longer restarted
+pub const MAX_RESTARTS: u32 = 5;
+
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+static ADDRESS_PORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:localhost|127\.0\.0\.1|0\.0\.0\.0|\[::1?\]):(\d{2,5})\b").unwrap()
+});
+static PORT_PHRASE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(?:port|listening on)\s*[:=]?\s*:?(\d{2,5})\b").unwrap());
+
+/// First port announced in a chunk of dev server output, e.g. from
+/// `Local: http://localhost:5173/` or `Listening on port 3000`
+pub fn port_from_output(text: &str) -> Option<u16> {
+    let text = strip_ansi_escapes::strip_str(text);
+    [&*ADDRESS_PORT, &*PORT_PHRASE].into_iter().find_map(|re| {
+        re.captures_iter(&text)
+            .filter_map(|caps| caps[1].parse::<u16>().ok())
+            .find(|port| *port != 0)
+    })
+}
+
+/// Port to report for a server. Output can mention ports the server does not
+/// own (proxies, HMR sockets), so it only wins when the server listens on it.
+pub fn choose_port(output_port: Option<u16>, listening: &[u16]) -> Option<u16> {
+    match output_port {
+        Some(port) if listening.is_empty() || listening.contains(&port) => Some(port),
+        _ => listening.iter().min().copied(),
+    }
+}
+
+/// URL of the readiness probe: a full URL is used as is, a path is requested
+/// on the detected port
+pub fn probe_url(health_check: &str, port: Option<u16>) -> Option<String> {
+    let health_check = health_check.trim();
+    if health_check.starts_with("http://") || health_check.starts_with("https://") {
+        return Some(health_check.to_string());
+    }
+    let path = health_check.trim_start_matches('/');
+    port.map(|port| format!("http://127.0.0.1:{port}/{path}"))
+}
+
+/// Probe a dev server; any response below 400 means ready
+pub async fn probe(client: &reqwest::Client, url: &str) -> Result<(), String> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Readiness probe {url} failed: {e}"))?;
+    let status = response.status();
+    if status.is_client_error() || status.is_server_error() {
+        return Err(format!("Readiness probe {url} returned {status}"));
+    }
+    Ok(())
+}
+
+/// Port named by a readiness probe URL, which a sandboxed server's container
+/// publishes so the probe and the user can reach it
+pub fn health_check_port(health_check: &str) -> Option<u16> {
+    reqwest::Url::parse(health_check.trim()).ok()?.port()
+}
+
+/// The processes of a dev server, whose sockets give away its port
+pub enum ServerProcesses {
+    /// Process group of a server running on the host
+    Group(u32),
+    /// Container of a sandboxed server; the spawned process is only the
+    /// container runtime's client
+    Container(Box<ContainerSandbox>),
+}
+
+impl ServerProcesses {
+    pub async fn listening_ports(&self) -> Vec<u16> {
+        match self {
+            ServerProcesses::Group(pgid) => {
+                let pgid = *pgid;
+                tokio::task::spawn_blocking(move || listening_ports(pgid))
+                    .await
+                    .unwrap_or_default()
+            }
+            ServerProcesses::Container(sandbox) => {
+                let Some(pid) = container_pid(sandbox).await else {
+                    return Vec::new();
+                };
+                tokio::task::spawn_blocking(move || container_listening_ports(pid))
+                    .await
+                    .unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// Host pid of the container's init process, once it runs
+async fn container_pid(sandbox: &ContainerSandbox) -> Option<u32> {
+    let output = sandbox.pid_command().output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()
+        .filter(|pid| *pid != 0)
+}
+
+/// Delay before restarting a server that already crashed `restart_count` times
+pub fn restart_backoff(restart_count: u32) -> Duration {
+    INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(restart_count))
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::{container_listening_ports, listening_ports};
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{collections::HashSet, fs};
+
+    /// TCP ports the processes of group `pgid` listen on
+    pub fn listening_ports(pgid: u32) -> Vec<u16> {
+        ports_of(&group_pids(pgid), "/proc/net")
+    }
+
+    /// TCP ports the processes of a container listen on, given the host pid
+    /// of its init process. The tables are read in the container's network
+    /// namespace, where a published port is bound at the same number.
+    pub fn container_listening_ports(init_pid: u32) -> Vec<u16> {
+        ports_of(&namespace_pids(init_pid), &format!("/proc/{init_pid}/net"))
+    }
+
+    fn ports_of(pids: &[u32], net_dir: &str) -> Vec<u16> {
+        let inodes = socket_inodes(pids);
+        if inodes.is_empty() {
+            return Vec::new();
+        }
+        let mut ports: Vec<u16> = ["tcp", "tcp6"]
+            .into_iter()
+            .filter_map(|table| fs::read_to_string(format!("{net_dir}/{table}")).ok())
+            .flat_map(|table| listening_ports_in_table(&table, &inodes))
+            .collect();
+        ports.sort_unstable();
+        ports.dedup();
+        ports
+    }
+
+    fn all_pids() -> Vec<u32> {
+        let Ok(entries) = fs::read_dir("/proc") else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+            .collect()
+    }
+
+    /// Processes in the same pid namespace as `pid`
+    fn namespace_pids(pid: u32) -> Vec<u32> {
+        let namespace = |pid: u32| fs::read_link(format!("/proc/{pid}/ns/pid")).ok();
+        let Some(target) = namespace(pid) else {
+            return Vec::new();
+        };
+        all_pids()
+            .into_iter()
+            .filter(|pid| namespace(*pid).as_ref() == Some(&target))
+            .collect()
+    }
+
+    fn group_pids(pgid: u32) -> Vec<u32> {
+        all_pids()
+            .into_iter()
+            .filter(|pid| {
+                // The command name may contain spaces, so the fields are read
+                // after its closing parenthesis: state, ppid, pgrp
+                fs::read_to_string(format!("/proc/{pid}/stat"))
+                    .ok()
+                    .and_then(|stat| {
+                        let fields = stat.rsplit_once(')')?.1;
+                        fields.split_whitespace().nth(2)?.parse::<u32>().ok()
+                    })
+                    == Some(pgid)
+            })
+            .collect()
+    }
+
+    fn socket_inodes(pids: &[u32]) -> HashSet<u64> {
+        pids.iter()
+            .filter_map(|pid| fs::read_dir(format!("/proc/{pid}/fd")).ok())
+            .flatten()
+            .filter_map(|fd| {
+                let target = fs::read_link(fd.ok()?.path()).ok()?;
+                target
+                    .to_str()?
+                    .strip_prefix("socket:[")?
+                    .strip_suffix(']')?
+                    .parse()
+                    .ok()
+            })
+            .collect()
+    }
+
+    /// Ports in LISTEN state in a `/proc/net/tcp` table whose socket inode is one
+    /// of `inodes`
+    pub(super) fn listening_ports_in_table(table: &str, inodes: &HashSet<u64>) -> Vec<u16> {
+        const TCP_LISTEN: &str = "0A";
+        table
+            .lines()
+            .skip(1)
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let (local_address, state, inode) =
+                    (fields.get(1)?, fields.get(3)?, fields.get(9)?);
+                if *state != TCP_LISTEN || !inodes.contains(&inode.parse().ok()?) {
+                    return None;
+                }
+                let (_, port) = local_address.rsplit_once(':')?;
+                u16::from_str_radix(port, 16).ok()
+            })
+            .collect()
+    }
+}
+
+/// Port detection from `/proc` is only supported on Linux
+#[cfg(not(target_os = "linux"))]
+pub fn listening_ports(_pgid: u32) -> Vec<u16> {
+    Vec::new()
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn container_listening_ports(_init_pid: u32) -> Vec<u16> {
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_ports_in_dev_server_output() {
+        assert_eq!(
+            port_from_output(
+                "  \u{1b}[32m➜\u{1b}[39m  Local:   http://localhost:\u{1b}[1m5173\u{1b}[22m/"
+            ),
+            Some(5173)
+        );
+        assert_eq!(
+            port_from_output("Server listening on port 8080"),
+            Some(8080)
+        );
+        assert_eq!(
+            port_from_output("Listening on http://0.0.0.0:3000"),
+            Some(3000)
+        );
+        assert_eq!(port_from_output("compiled 42 modules"), None);
+    }
+
+    #[test]
+    fn prefers_a_port_the_server_listens_on() {
+        assert_eq!(choose_port(Some(3000), &[]), Some(3000));
+        assert_eq!(choose_port(Some(3000), &[24678, 3000]), Some(3000));
+        assert_eq!(choose_port(Some(8080), &[24678, 3000]), Some(3000));
+        assert_eq!(choose_port(None, &[]), None);
+    }
+
+    #[test]
+    fn builds_probe_urls_and_backoff() {
+        assert_eq!(
+            probe_url("/health", Some(3000)).as_deref(),
+            Some("http://127.0.0.1:3000/health")
+        );
+        assert_eq!(probe_url("health", None), None);
+        assert_eq!(
+            probe_url("https://app.localhost/ready", None).as_deref(),
+            Some("https://app.localhost/ready")
+        );
+        assert_eq!(restart_backoff(0), Duration::from_secs(2));
+        assert_eq!(restart_backoff(2), Duration::from_secs(8));
+        assert_eq!(restart_backoff(10), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn reads_the_port_of_a_probe_url() {
+        assert_eq!(
+            health_check_port("http://localhost:3000/health"),
+            Some(3000)
+        );
+        assert_eq!(health_check_port("https://app.localhost/ready"), None);
+        assert_eq!(health_check_port("/health"), None);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn finds_the_ports_of_a_pid_namespace() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(container_listening_ports(std::process::id()).contains(&port));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn reads_listening_sockets_from_proc_table() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode\n   \
+             0: 00000000:0BB8 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 4242 1 0000000000000000 100 0 0 10 0\n   \
+             1: 0100007F:1F90 0100007F:D2F0 01 00000000:00000000 00:00000000 00000000  1000        0 4243 1 0000000000000000 20 4 30 10 -1\n   \
+             2: 00000000:1538 00000000:0000 0A 00000000:00000000 00:00000000 00000000  1000        0 9999 1 0000000000000000 100 0 0 10 0\n";
+        let inodes = [4242, 4243].into_iter().collect();
+        assert_eq!(linux::listening_ports_in_table(table, &inodes), vec![3000]);
+    }
+}
@@ -14,6 +14,7 @@ use services::services::{
     events::EventService,
     file_search::FileSearchCache,
     filesystem::FilesystemService,
+    git_host::GitHostService,
     image::ImageService,
     oauth_credentials::OAuthCredentials,
     project::ProjectService,
@@ -34,7 +35,10 @@ use crate::{container::LocalContainerService, pty::PtyService};
 mod command;
 pub mod container;
 mod copy;
+mod dev_server;
+mod limits;
 pub mod pty;
+mod verify;
 
 #[derive(Clone)]
 pub struct LocalDeployment {
@@ -95,6 +99,7 @@ impl Deployment for LocalDeployment {
             let path = utils::path::expand_tilde(workspace_dir);
             WorktreeManager::set_workspace_dir_override(path);
         }
+        GitHostService::set_self_hosted_instances(raw_config.git_hosts.clone());
 
         let config = Arc::new(RwLock::new(raw_config));
         let user_id = generate_user_id();
@@ -131,7 +136,7 @@ impl Deployment for LocalDeployment {
         }
 
         let approvals = Approvals::new(msg_stores.clone());
-        let queued_message_service = QueuedMessageService::new();
+        let queued_message_service = QueuedMessageService::new(db.clone());
 
         let oauth_credentials = Arc::new(OAuthCredentials::new(credentials_path()));
         if let Err(e) = oauth_credentials.load().await {
@@ -228,7 +233,7 @@ impl Deployment for LocalDeployment {
         &self.analytics
     }
 
-    fn container(&self) -> &impl ContainerService {
+    fn container(&self) -> &(impl ContainerService + Clone + Send + Sync + 'static) {
         &self.container
     }
 
@@ -0,0 +1,241 @@
+//! Enforcement of the [`ExecutionLimits`] configured on executor profiles.
+//!
+//! Timeouts are enforced by a watchdog in the container service. Memory, CPU
+//! and process caps use a cgroup v2 group per execution, created under
+//! `VK_CGROUP_ROOT` or else the server's own cgroup. The executor joins the
+//! group before it execs, so nothing it starts escapes the limits. That root
+//! must be delegated to this user and hold no processes itself, e.g. a systemd
+//! unit with `Delegate=yes` that runs the server in a leaf sub-group.
+
+use std::time::{Duration, Instant};
+
+use db::models::execution_process::ExecutionTerminationReason;
+use executors::command::ExecutionLimits;
+
+/// Next point in time at which a timeout fires, and which one it is
+pub fn next_deadline(
+    limits: &ExecutionLimits,
+    started_at: Instant,
+    last_output_at: Instant,
+) -> Option<(Instant, ExecutionTerminationReason)> {
+    let timeout = limits.timeout_minutes.map(|minutes| {
+        (
+            started_at + minutes_to_duration(minutes),
+            ExecutionTerminationReason::Timeout,
+        )
+    });
+    let idle = limits.idle_timeout_minutes.map(|minutes| {
+        (
+            last_output_at + minutes_to_duration(minutes),
+            ExecutionTerminationReason::IdleTimeout,
+        )
+    });
+    match (timeout, idle) {
+        (Some(timeout), Some(idle)) => Some(if idle.0 < timeout.0 { idle } else { timeout }),
+        (timeout, idle) => timeout.or(idle),
+    }
+}
+
+fn minutes_to_duration(minutes: u64) -> Duration {
+    Duration::from_secs(minutes.saturating_mul(60))
+}
+
+/// Value for `cpu.max`: quota and period in microseconds
+#[cfg(target_os = "linux")]
+fn cpu_max(cpus: f64) -> String {
+    const PERIOD_US: u64 = 100_000;
+    let quota = ((cpus * PERIOD_US as f64).round() as u64).max(1_000);
+    format!("{quota} {PERIOD_US}")
+}
+
+#[cfg(target_os = "linux")]
+pub use linux::ExecutionCgroup;
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        fs, io,
+        path::{Path, PathBuf},
+    };
+
+    use executors::command::ExecutionLimits;
+    use uuid::Uuid;
+
+    const CGROUP_MOUNT: &str = "/sys/fs/cgroup";
+
+    /// cgroup v2 group holding one execution's process tree.
+    /// The group is removed when dropped, which only succeeds once it is empty.
+    #[derive(Debug)]
+    pub struct ExecutionCgroup {
+        path: PathBuf,
+    }
+
+    impl ExecutionCgroup {
+        /// Create a group applying the memory/CPU/process limits, for the
+        /// executor to join through [`Self::procs_path`] before it execs.
+        /// Returns `None` when no such limit is set or cgroups are unavailable.
+        pub fn create(exec_id: Uuid, limits: &ExecutionLimits) -> Option<Self> {
+            if limits.max_memory_mb.is_none()
+                && limits.max_cpus.is_none()
+                && limits.max_processes.is_none()
+            {
+                return None;
+            }
+            match Self::try_create(exec_id, limits) {
+                Ok(cgroup) => Some(cgroup),
+                Err(e) => {
+                    tracing::warn!(
+                        "Could not apply memory/CPU/process limits to execution {}: {}. \
+                         Is cgroup v2 delegated to this user?",
+                        exec_id,
+                        e
+                    );
+                    None
+                }
+            }
+        }
+
+        fn try_create(exec_id: Uuid, limits: &ExecutionLimits) -> io::Result<Self> {
+            let root = cgroup_root()?;
+            // Controllers must be enabled on the parent before children can use them
+            let controllers = [
+                limits.max_memory_mb.map(|_| "+memory"),
+                limits.max_cpus.map(|_| "+cpu"),
+                limits.max_processes.map(|_| "+pids"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" ");
+            fs::write(root.join("cgroup.subtree_control"), controllers)?;
+
+            let cgroup = Self {
+                path: root.join(format!("vk-exec-{exec_id}")),
+            };
+            fs::create_dir(&cgroup.path)?;
+
+            if let Some(mb) = limits.max_memory_mb {
+                cgroup.write("memory.max", &(mb.saturating_mul(1024 * 1024)).to_string())?;
+                // Kill the whole tree on OOM rather than whichever child happens to be picked
+                cgroup.write("memory.oom.group", "1")?;
+                // Keep the executor from pushing itself into swap instead of hitting the limit
+                let _ = cgroup.write("memory.swap.max", "0");
+            }
+            if let Some(cpus) = limits.max_cpus {
+                cgroup.write("cpu.max", &super::cpu_max(cpus))?;
+            }
+            if let Some(processes) = limits.max_processes {
+                cgroup.write("pids.max", &processes.to_string())?;
+            }
+
+            Ok(cgroup)
+        }
+
+        /// `cgroup.procs` file a process writes `0` to in order to join the group
+        pub fn procs_path(&self) -> PathBuf {
+            self.path.join("cgroup.procs")
+        }
+
+        fn write(&self, file: &str, value: &str) -> io::Result<()> {
+            fs::write(self.path.join(file), value)
+        }
+
+        /// Whether the kernel OOM-killed processes in this group
+        pub fn oom_killed(&self) -> bool {
+            fs::read_to_string(self.path.join("memory.events"))
+                .map(|events| {
+                    events.lines().any(|line| {
+                        line.strip_prefix("oom_kill ")
+                            .and_then(|count| count.trim().parse::<u64>().ok())
+                            .is_some_and(|count| count > 0)
+                    })
+                })
+                .unwrap_or(false)
+        }
+    }
+
+    impl Drop for ExecutionCgroup {
+        fn drop(&mut self) {
+            if let Err(e) = fs::remove_dir(&self.path) {
+                tracing::debug!("Failed to remove cgroup {}: {}", self.path.display(), e);
+            }
+        }
+    }
+
+    fn cgroup_root() -> io::Result<PathBuf> {
+        if let Ok(root) = std::env::var("VK_CGROUP_ROOT") {
+            return Ok(PathBuf::from(root));
+        }
+        // cgroup v2 has a single "0::<path>" entry
+        let own = fs::read_to_string("/proc/self/cgroup")?;
+        let relative = own
+            .lines()
+            .find_map(|line| line.strip_prefix("0::"))
+            .ok_or_else(|| io::Error::other("cgroup v2 is not mounted"))?;
+        Ok(Path::new(CGROUP_MOUNT).join(relative.trim_start_matches('/')))
+    }
+}
+
+/// Memory, CPU and process caps are only supported on Linux, so no group is
+/// ever created elsewhere
+#[cfg(not(target_os = "linux"))]
+#[derive(Debug)]
+pub enum ExecutionCgroup {}
+
+#[cfg(not(target_os = "linux"))]
+impl ExecutionCgroup {
+    pub fn create(exec_id: uuid::Uuid, limits: &ExecutionLimits) -> Option<Self> {
+        if limits.max_memory_mb.is_some()
+            || limits.max_cpus.is_some()
+            || limits.max_processes.is_some()
+        {
+            tracing::warn!(
+                "Memory/CPU/process limits are only supported on Linux; ignoring them for execution {}",
+                exec_id
+            );
+        }
+        None
+    }
+
+    pub fn procs_path(&self) -> std::path::PathBuf {
+        match *self {}
+    }
+
+    pub fn oom_killed(&self) -> bool {
+        match *self {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_earliest_deadline() {
+        let start = Instant::now();
+        let limits = ExecutionLimits {
+            timeout_minutes: Some(30),
+            idle_timeout_minutes: Some(10),
+            ..Default::default()
+        };
+
+        let (at, reason) = next_deadline(&limits, start, start).unwrap();
+        assert_eq!(reason, ExecutionTerminationReason::IdleTimeout);
+        assert_eq!(at, start + Duration::from_secs(600));
+
+        // Recent output pushes the idle deadline past the wall-clock one
+        let late_output = start + Duration::from_secs(25 * 60);
+        let (at, reason) = next_deadline(&limits, start, late_output).unwrap();
+        assert_eq!(reason, ExecutionTerminationReason::Timeout);
+        assert_eq!(at, start + Duration::from_secs(1800));
+
+        assert!(next_deadline(&ExecutionLimits::default(), start, start).is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn formats_cpu_quota() {
+        assert_eq!(cpu_max(1.5), "150000 100000");
+        assert_eq!(cpu_max(0.0), "1000 100000");
+    }
+}
@@ -0,0 +1,75 @@
+//! Verify scripts run after the coding agent, and the follow-up that asks the
+//! agent to fix what they reported.
+
+use utils::log_msg::LogMsg;
+
+/// Most output kept from a failed verify script; the end of it usually holds
+/// the errors and the summary
+const OUTPUT_TAIL_BYTES: usize = 8 * 1024;
+
+/// End of a script's combined stdout and stderr, without ANSI escapes
+pub fn output_tail(history: &[LogMsg]) -> String {
+    let output: String = history
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::Stdout(text) | LogMsg::Stderr(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect();
+    let output = strip_ansi_escapes::strip_str(&output);
+    let output = output.trim_end();
+    let start = output
+        .char_indices()
+        .map(|(idx, _)| idx)
+        .find(|&idx| output.len() - idx <= OUTPUT_TAIL_BYTES)
+        .unwrap_or(output.len());
+    output[start..].to_string()
+}
+
+/// Whether a failed verification goes back to the agent for another fix after
+/// `retries` automatic fixes; once they are used up the task is finalized and
+/// stays in progress
+pub fn should_retry(retries: u32, max_retries: u32) -> bool {
+    retries < max_retries
+}
+
+/// Follow-up prompt sent to the agent when a verify script failed
+pub fn fix_prompt(repo_name: &str, script: &str, exit_code: Option<i64>, output: &str) -> String {
+    let exit = exit_code.map_or_else(
+        || "failed".to_string(),
+        |code| format!("exited with {code}"),
+    );
+    format!(
+        "The verification script for `{repo_name}` {exit}. Fix the problems it reports, \
+         then make sure it passes.\n\nScript:\n```\n{script}\n```\n\nOutput:\n```\n{output}\n```"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_end_of_the_output() {
+        let history = vec![
+            LogMsg::Stdout("\u{1b}[31merror\u{1b}[0m: ".to_string()),
+            LogMsg::Stderr("x".repeat(OUTPUT_TAIL_BYTES)),
+            LogMsg::Stdout("done\n".to_string()),
+            LogMsg::Finished,
+        ];
+        let tail = output_tail(&history);
+        assert_eq!(tail.len(), OUTPUT_TAIL_BYTES);
+        assert!(tail.ends_with("xdone"));
+
+        let short = output_tail(&history[..1]);
+        assert_eq!(short, "error:");
+    }
+
+    #[test]
+    fn retries_until_the_limit() {
+        assert!(should_retry(0, 2));
+        assert!(should_retry(1, 2));
+        assert!(!should_retry(2, 2));
+        assert!(!should_retry(0, 0));
+    }
+}
@@ -20,6 +20,13 @@ fn generate_types_content() -> String {
         db::models::repo::UpdateRepo::decl(),
         db::models::project_repo::ProjectRepo::decl(),
         db::models::project_repo::CreateProjectRepo::decl(),
+        db::models::project_container_config::ProjectContainerConfig::decl(),
+        db::models::project_container_config::UpdateProjectContainerConfig::decl(),
+        db::models::project_memory::ProjectMemoryStatus::decl(),
+        db::models::project_memory::ProjectMemoryEntry::decl(),
+        db::models::project_memory::CreateProjectMemoryEntry::decl(),
+        db::models::project_memory::UpdateProjectMemoryEntry::decl(),
+        db::models::project_memory::AcceptProjectMemoryEntry::decl(),
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
@@ -32,6 +39,38 @@ fn generate_types_content() -> String {
         db::models::task::TaskRelationships::decl(),
         db::models::task::CreateTask::decl(),
         db::models::task::UpdateTask::decl(),
+        db::models::task_dependency::TaskDependencyType::decl(),
+        db::models::task_dependency::TaskDependency::decl(),
+        db::models::task_dependency::CreateTaskDependency::decl(),
+        db::models::task_schedule::ScheduleRepo::decl(),
+        db::models::task_schedule::TaskSchedule::decl(),
+        db::models::task_schedule::CreateTaskSchedule::decl(),
+        db::models::task_schedule::UpdateTaskSchedule::decl(),
+        db::models::task_template::TemplateVariableKind::decl(),
+        db::models::task_template::TemplateVariable::decl(),
+        db::models::task_template::TemplateStepKind::decl(),
+        db::models::task_template::TemplateStep::decl(),
+        db::models::task_template::TemplateRepo::decl(),
+        db::models::task_template::TaskTemplate::decl(),
+        db::models::task_template::CreateTaskTemplate::decl(),
+        db::models::task_template::UpdateTaskTemplate::decl(),
+        db::models::task_template::TemplateInstance::decl(),
+        db::models::task_template::TaskTemplateInstance::decl(),
+        db::models::approval_request::ApprovalResolution::decl(),
+        db::models::approval_request::ApprovalRecord::decl(),
+        db::models::approval_rule::ApprovalRuleDecision::decl(),
+        db::models::approval_rule::ApprovalRuleActionType::decl(),
+        db::models::approval_rule::ApprovalRule::decl(),
+        db::models::approval_rule::CreateApprovalRule::decl(),
+        db::models::approval_rule::UpdateApprovalRule::decl(),
+        db::models::dev_server_status::DevServerState::decl(),
+        db::models::dev_server_status::DevServerStatus::decl(),
+        db::models::workspace_verification::VerificationStatus::decl(),
+        db::models::workspace_verification::WorkspaceVerification::decl(),
+        db::models::conflict_resolution::ConflictResolutionStatus::decl(),
+        db::models::conflict_resolution::ConflictResolution::decl(),
+        db::models::search::SearchResultKind::decl(),
+        db::models::search::SearchResult::decl(),
         db::models::scratch::DraftFollowUpData::decl(),
         db::models::scratch::DraftWorkspaceData::decl(),
         db::models::scratch::DraftWorkspaceRepo::decl(),
@@ -46,10 +85,14 @@ fn generate_types_content() -> String {
         db::models::image::CreateImage::decl(),
         db::models::workspace::Workspace::decl(),
         db::models::workspace::WorkspaceWithStatus::decl(),
+        db::models::workspace_drift::WorkspaceDrift::decl(),
+        db::models::workspace_race::WorkspaceRace::decl(),
+        db::models::workspace_stack::WorkspaceStackRepo::decl(),
         db::models::session::Session::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process::ExecutionTerminationReason::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
@@ -59,6 +102,7 @@ fn generate_types_content() -> String {
         utils::approvals::ApprovalStatus::decl(),
         utils::approvals::CreateApprovalRequest::decl(),
         utils::approvals::ApprovalResponse::decl(),
+        services::services::approvals::PendingApprovalInfo::decl(),
         utils::diff::Diff::decl(),
         utils::diff::DiffChangeKind::decl(),
         utils::response::ApiResponse::<()>::decl(),
@@ -95,6 +139,12 @@ fn generate_types_content() -> String {
         server::routes::repo::RegisterRepoRequest::decl(),
         server::routes::repo::InitRepoRequest::decl(),
         server::routes::tags::TagSearchParams::decl(),
+        server::routes::schedules::TaskScheduleQuery::decl(),
+        server::routes::task_templates::TaskTemplateQuery::decl(),
+        server::routes::approval_rules::ApprovalRuleQuery::decl(),
+        server::routes::project_memory::ProjectMemoryQuery::decl(),
+        server::routes::search::SearchQuery::decl(),
+        server::routes::usage::UsageQuery::decl(),
         server::routes::oauth::TokenResponse::decl(),
         server::routes::config::UserSystemInfo::decl(),
         server::routes::config::Environment::decl(),
@@ -106,6 +156,7 @@ fn generate_types_content() -> String {
         server::routes::config::CheckAgentAvailabilityQuery::decl(),
         server::routes::oauth::CurrentUserResponse::decl(),
         server::routes::sessions::CreateFollowUpAttempt::decl(),
+        server::routes::sessions::RestoreSessionRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchRequest::decl(),
         server::routes::task_attempts::ChangeTargetBranchResponse::decl(),
         server::routes::task_attempts::MergeTaskAttemptRequest::decl(),
@@ -127,6 +178,7 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::gh_cli_setup::GhCliSetupError::decl(),
         server::routes::task_attempts::RebaseTaskAttemptRequest::decl(),
         server::routes::task_attempts::AbortConflictsRequest::decl(),
+        server::routes::task_attempts::ResolveConflictsRequest::decl(),
         server::routes::task_attempts::GitOperationError::decl(),
         server::routes::task_attempts::PushError::decl(),
         server::routes::task_attempts::pr::PrError::decl(),
@@ -151,14 +203,31 @@ fn generate_types_content() -> String {
         server::routes::task_attempts::workspace_summary::WorkspaceSummary::decl(),
         server::routes::task_attempts::workspace_summary::WorkspaceSummaryResponse::decl(),
         server::routes::task_attempts::workspace_summary::DiffStats::decl(),
+        server::routes::task_attempts::race::RaceEntry::decl(),
+        server::routes::task_attempts::race::RaceComparison::decl(),
+        server::routes::task_attempts::stack::RestackStatus::decl(),
+        server::routes::task_attempts::stack::RestackResult::decl(),
+        server::routes::task_attempts::stack::RestackLevel::decl(),
+        server::routes::task_attempts::stack::RestackResponse::decl(),
         server::routes::task_attempts::IncrementalDiffQuery::decl(),
         server::routes::task_attempts::IncrementalDiffResponse::decl(),
         server::routes::task_attempts::DiffBaseType::decl(),
         services::services::filesystem::DirectoryEntry::decl(),
         services::services::filesystem::DirectoryListResponse::decl(),
         services::services::file_search::SearchMode::decl(),
+        services::services::usage::UsageGroupBy::decl(),
+        services::services::usage::UsageReportRow::decl(),
         services::services::config::Config::decl(),
         services::services::config::NotificationConfig::decl(),
+        services::services::config::NotificationSink::decl(),
+        services::services::config::NotificationSinkKind::decl(),
+        services::services::config::NotificationEvent::decl(),
+        services::services::config::LlmConfig::decl(),
+        services::services::config::LlmProviderKind::decl(),
+        services::services::config::GitHostInstance::decl(),
+        services::services::config::GitHostKind::decl(),
+        services::services::config::ConcurrencyConfig::decl(),
+        services::services::config::ModelPrice::decl(),
         services::services::config::ThemeMode::decl(),
         services::services::config::EditorConfig::decl(),
         services::services::config::EditorType::decl(),
@@ -183,6 +252,10 @@ fn generate_types_content() -> String {
         executors::executors::SlashCommandDescription::decl(),
         executors::executors::AvailabilityInfo::decl(),
         executors::command::CommandBuilder::decl(),
+        executors::command::ExecutionLimits::decl(),
+        executors::sandbox::ContainerRuntime::decl(),
+        executors::command::ApprovalTimeoutAction::decl(),
+        executors::command::ApprovalTimeout::decl(),
         executors::profile::ExecutorProfileId::decl(),
         executors::profile::ExecutorConfig::decl(),
         executors::profile::ExecutorConfigs::decl(),
@@ -203,6 +276,11 @@ fn generate_types_content() -> String {
         executors::executors::droid::Droid::decl(),
         executors::executors::droid::Autonomy::decl(),
         executors::executors::droid::ReasoningEffortLevel::decl(),
+        executors::executors::custom::Custom::decl(),
+        executors::executors::custom::PromptDelivery::decl(),
+        executors::executors::custom::CustomLogFormat::decl(),
+        executors::executors::custom::JsonLinesMapping::decl(),
+        executors::executors::custom::JsonLineKind::decl(),
         executors::executors::AppendPrompt::decl(),
         executors::actions::coding_agent_initial::CodingAgentInitialRequest::decl(),
         executors::actions::coding_agent_follow_up::CodingAgentFollowUpRequest::decl(),
@@ -213,6 +291,7 @@ fn generate_types_content() -> String {
         executors::logs::NormalizedEntry::decl(),
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::TokenUsageInfo::decl(),
+        executors::logs::ModelTokenUsage::decl(),
         executors::logs::FileChange::decl(),
         executors::logs::ActionType::decl(),
         executors::logs::TodoItem::decl(),
@@ -307,6 +386,10 @@ fn generate_schemas() -> Result<HashMap<&'static str, String>, serde_json::Error
             "droid",
             generate_json_schema::<executors::executors::droid::Droid>()?,
         ),
+        (
+            "custom",
+            generate_json_schema::<executors::executors::custom::Custom>()?,
+        ),
     ]);
     println!(
         "✅ JSON schemas generated. {} schemas created.",
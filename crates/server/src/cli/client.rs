@@ -0,0 +1,91 @@
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, connect_async};
+use utils::port_file::read_port_file;
+
+pub type WebSocket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+#[derive(Debug, Deserialize)]
+struct ApiResponseEnvelope {
+    success: bool,
+    data: Option<serde_json::Value>,
+    message: Option<String>,
+}
+
+/// HTTP and websocket client for the API of a running local server
+pub struct ApiClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl ApiClient {
+    /// Use `base_url` if given, else the server found through the port file
+    pub async fn connect(base_url: Option<String>) -> anyhow::Result<Self> {
+        let base_url = match base_url {
+            Some(url) => url,
+            None => {
+                let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+                let port = read_port_file("vibe-kanban")
+                    .await
+                    .context("No running vibe-kanban server found; start it or pass --url")?;
+                format!("http://{host}:{port}")
+            }
+        };
+        Ok(Self {
+            client: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url, path.trim_start_matches('/'))
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> anyhow::Result<T> {
+        self.send(self.client.get(self.url(path))).await
+    }
+
+    pub async fn post<B: Serialize + ?Sized, T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> anyhow::Result<T> {
+        self.send(self.client.post(self.url(path)).json(body)).await
+    }
+
+    async fn send<T: DeserializeOwned>(&self, rb: reqwest::RequestBuilder) -> anyhow::Result<T> {
+        let resp = rb
+            .send()
+            .await
+            .with_context(|| format!("Failed to connect to {}", self.base_url))?;
+        let status = resp.status();
+        let body = resp.text().await?;
+        let Ok(envelope) = serde_json::from_str::<ApiResponseEnvelope>(&body) else {
+            bail!("Server returned {status}: {body}");
+        };
+        if !envelope.success {
+            bail!(
+                "{}",
+                envelope
+                    .message
+                    .unwrap_or_else(|| format!("Request failed with status {status}"))
+            );
+        }
+        serde_json::from_value(envelope.data.unwrap_or_default())
+            .context("Unexpected response from server")
+    }
+
+    pub async fn websocket(&self, path: &str) -> anyhow::Result<WebSocket> {
+        let url = self.url(path);
+        let url = match url.split_once("://") {
+            Some(("https", rest)) => format!("wss://{rest}"),
+            Some((_, rest)) => format!("ws://{rest}"),
+            None => url,
+        };
+        let (socket, _) = connect_async(url.as_str())
+            .await
+            .with_context(|| format!("Failed to open websocket {url}"))?;
+        Ok(socket)
+    }
+}
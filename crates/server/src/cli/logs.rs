@@ -0,0 +1,265 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io::Write,
+};
+
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessRunReason};
+use executors::logs::{
+    NormalizedEntry, NormalizedEntryType, ToolStatus,
+    utils::patch::extract_normalized_entry_from_patch,
+};
+use futures_util::StreamExt;
+use serde_json::json;
+use tokio_tungstenite::tungstenite::Message;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use super::client::ApiClient;
+
+/// Latest coding agent process of a session, taken from the snapshot the
+/// session's process stream starts with
+pub async fn latest_coding_agent_process(
+    client: &ApiClient,
+    session_id: Uuid,
+) -> anyhow::Result<Option<ExecutionProcess>> {
+    let mut socket = client
+        .websocket(&format!(
+            "/api/execution-processes/stream/session/ws?session_id={session_id}"
+        ))
+        .await?;
+    while let Some(msg) = socket.next().await {
+        let Message::Text(text) = msg? else {
+            continue;
+        };
+        let Ok(LogMsg::JsonPatch(patch)) = serde_json::from_str::<LogMsg>(&text) else {
+            continue;
+        };
+        let snapshot = serde_json::to_value(&patch)?
+            .get(0)
+            .and_then(|op| op.get("value"))
+            .cloned()
+            .unwrap_or_default();
+        let processes: HashMap<Uuid, ExecutionProcess> = serde_json::from_value(snapshot)?;
+        let _ = socket.close(None).await;
+        return Ok(processes
+            .into_values()
+            .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent && !p.dropped)
+            .max_by_key(|p| p.created_at));
+    }
+    Ok(None)
+}
+
+/// Print the normalized log of an execution process until it finishes
+pub async fn tail(
+    client: &ApiClient,
+    execution_process_id: Uuid,
+    json: bool,
+) -> anyhow::Result<()> {
+    let mut socket = client
+        .websocket(&format!(
+            "/api/execution-processes/{execution_process_id}/normalized-logs/ws"
+        ))
+        .await?;
+    let mut printer = LogPrinter::new(std::io::stdout(), json);
+    while let Some(msg) = socket.next().await {
+        let text = match msg? {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+        match serde_json::from_str::<LogMsg>(&text) {
+            Ok(LogMsg::JsonPatch(patch)) => {
+                if let Some((index, entry)) = extract_normalized_entry_from_patch(&patch) {
+                    printer.push(index, entry);
+                }
+            }
+            Ok(LogMsg::Finished) => break,
+            Ok(_) => {}
+            // Sent as `{"finished":true}` rather than the serde form of `LogMsg::Finished`
+            Err(_) => {
+                if serde_json::from_str::<serde_json::Value>(&text)
+                    .is_ok_and(|value| value["finished"] == true)
+                {
+                    break;
+                }
+            }
+        }
+    }
+    printer.finish();
+    Ok(())
+}
+
+/// Prints log entries once a later entry shows they are complete, since the
+/// latest entry is still being streamed and replaced. Each entry is printed
+/// once, in its final state; a tool call waiting for approval is announced on
+/// stderr so the approval id can be acted on before the call resolves.
+struct LogPrinter<W> {
+    out: W,
+    json: bool,
+    unprinted: BTreeMap<usize, NormalizedEntry>,
+    /// Entries before this index have been printed
+    next_index: usize,
+    announced_approvals: HashSet<String>,
+}
+
+impl<W: Write> LogPrinter<W> {
+    fn new(out: W, json: bool) -> Self {
+        Self {
+            out,
+            json,
+            unprinted: BTreeMap::new(),
+            next_index: 0,
+            announced_approvals: HashSet::new(),
+        }
+    }
+
+    fn push(&mut self, index: usize, entry: NormalizedEntry) {
+        if let NormalizedEntryType::ToolUse {
+            tool_name,
+            status: ToolStatus::PendingApproval { approval_id, .. },
+            ..
+        } = &entry.entry_type
+            && self.announced_approvals.insert(approval_id.clone())
+        {
+            eprintln!("[{tool_name}] awaiting approval: vk approvals approve {approval_id}");
+        }
+        if index < self.next_index {
+            return;
+        }
+        self.unprinted.insert(index, entry);
+        while self.unprinted.len() > 1 {
+            self.print_next();
+        }
+    }
+
+    fn finish(&mut self) {
+        while !self.unprinted.is_empty() {
+            self.print_next();
+        }
+    }
+
+    fn print_next(&mut self) {
+        let Some((index, entry)) = self.unprinted.pop_first() else {
+            return;
+        };
+        self.next_index = index + 1;
+        let line = if self.json {
+            Some(json!({ "index": index, "entry": entry }).to_string())
+        } else {
+            render(&entry)
+        };
+        if let Some(line) = line {
+            let _ = writeln!(self.out, "{line}");
+        }
+    }
+}
+
+fn render(entry: &NormalizedEntry) -> Option<String> {
+    let content = entry.content.trim_end();
+    let line = match &entry.entry_type {
+        NormalizedEntryType::UserMessage | NormalizedEntryType::UserFeedback { .. } => {
+            format!("> {content}")
+        }
+        NormalizedEntryType::AssistantMessage => content.to_string(),
+        NormalizedEntryType::Thinking => format!("[thinking] {content}"),
+        NormalizedEntryType::SystemMessage => format!("[system] {content}"),
+        NormalizedEntryType::ErrorMessage { .. } => format!("[error] {content}"),
+        NormalizedEntryType::ToolUse {
+            tool_name, status, ..
+        } => match status {
+            ToolStatus::PendingApproval { approval_id, .. } => format!(
+                "[{tool_name}] {content}\n  awaiting approval: vk approvals approve {approval_id}"
+            ),
+            ToolStatus::Failed => format!("[{tool_name} failed] {content}"),
+            ToolStatus::Denied { .. } => format!("[{tool_name} denied] {content}"),
+            ToolStatus::TimedOut => format!("[{tool_name} timed out] {content}"),
+            ToolStatus::Created | ToolStatus::Success => format!("[{tool_name}] {content}"),
+        },
+        NormalizedEntryType::Loading
+        | NormalizedEntryType::NextAction { .. }
+        | NormalizedEntryType::TokenUsageInfo(_) => return None,
+    };
+    Some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use executors::logs::ActionType;
+
+    use super::*;
+
+    fn message(content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::AssistantMessage,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn command(status: ToolStatus) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type: NormalizedEntryType::ToolUse {
+                tool_name: "bash".to_string(),
+                action_type: ActionType::CommandRun {
+                    command: "rm -rf build".to_string(),
+                    result: None,
+                },
+                status,
+            },
+            content: "rm -rf build".to_string(),
+            metadata: None,
+        }
+    }
+
+    fn printed(printer: LogPrinter<Vec<u8>>) -> Vec<String> {
+        String::from_utf8(printer.out)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn prints_streamed_entries_once_in_their_final_state() {
+        let mut printer = LogPrinter::new(Vec::new(), false);
+        printer.push(0, message("Let me"));
+        printer.push(0, message("Let me look"));
+        printer.push(1, message("Done"));
+        printer.finish();
+
+        assert_eq!(printed(printer), vec!["Let me look", "Done"]);
+    }
+
+    #[test]
+    fn prints_an_approved_tool_call_once() {
+        let pending = ToolStatus::PendingApproval {
+            approval_id: "approval-1".to_string(),
+            requested_at: Utc::now(),
+            timeout_at: Utc::now(),
+        };
+        let mut printer = LogPrinter::new(Vec::new(), false);
+        printer.push(0, command(pending.clone()));
+        printer.push(0, command(pending));
+        printer.push(0, command(ToolStatus::Success));
+        printer.push(1, message("Cleaned up"));
+        printer.finish();
+
+        assert_eq!(printed(printer), vec!["[bash] rm -rf build", "Cleaned up"]);
+    }
+
+    #[test]
+    fn prints_json_with_entry_index() {
+        let mut printer = LogPrinter::new(Vec::new(), true);
+        printer.push(3, command(ToolStatus::Failed));
+        printer.finish();
+
+        let lines = printed(printer);
+        assert_eq!(lines.len(), 1);
+        let value: serde_json::Value = serde_json::from_str(&lines[0]).unwrap();
+        assert_eq!(value["index"], 3);
+        assert_eq!(value["entry"]["content"], "rm -rf build");
+    }
+}
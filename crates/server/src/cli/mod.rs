@@ -0,0 +1,499 @@
+//! `vk`, a headless client for a running local server, for driving tasks and
+//! attempts from scripts and over SSH.
+
+mod client;
+mod logs;
+
+use std::str::FromStr;
+
+use anyhow::{Context, anyhow, bail};
+use clap::{Parser, Subcommand};
+use db::models::{
+    project::Project,
+    repo::Repo,
+    session::Session,
+    task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus},
+    workspace::Workspace,
+    workspace_repo::RepoWithTargetBranch,
+};
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use services::services::approvals::PendingApprovalInfo;
+use utils::approvals::{ApprovalResponse, ApprovalStatus};
+use uuid::Uuid;
+
+use self::client::ApiClient;
+use crate::routes::task_attempts::{
+    CreateTaskAttemptBody, MergeTaskAttemptRequest, PushTaskAttemptRequest, WorkspaceRepoInput,
+};
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "vk",
+    version,
+    about = "Command line client for a running vibe-kanban server"
+)]
+pub struct Cli {
+    /// Server URL; defaults to the local server found through its port file
+    #[arg(long, global = true, env = "VIBE_BACKEND_URL")]
+    url: Option<String>,
+    /// Print machine readable JSON instead of text
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// List or create projects
+    #[command(subcommand)]
+    Projects(ProjectsCommand),
+    /// List or create tasks
+    #[command(subcommand)]
+    Tasks(TasksCommand),
+    /// List or start attempts at a task
+    #[command(subcommand)]
+    Attempts(AttemptsCommand),
+    /// Follow the coding agent log of an attempt
+    Logs {
+        attempt_id: Uuid,
+        /// Execution process to follow instead of the latest coding agent run
+        #[arg(long)]
+        process: Option<Uuid>,
+    },
+    /// List, approve or deny pending tool approvals
+    #[command(subcommand)]
+    Approvals(ApprovalsCommand),
+    /// Merge the branch of an attempt into its target branch
+    Merge {
+        attempt_id: Uuid,
+        /// Repository to merge; required when the attempt spans several
+        #[arg(long)]
+        repo: Option<Uuid>,
+        /// Commit message to use instead of the generated one
+        #[arg(long)]
+        message: Option<String>,
+    },
+    /// Push the branch of an attempt to its remote
+    Push {
+        attempt_id: Uuid,
+        /// Repository to push; required when the attempt spans several
+        #[arg(long)]
+        repo: Option<Uuid>,
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ProjectsCommand {
+    List,
+    Create {
+        #[arg(long)]
+        name: String,
+        /// Path of a git repository to add to the project; may be repeated
+        #[arg(long = "repo", value_name = "PATH", required = true)]
+        repos: Vec<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum TasksCommand {
+    List {
+        #[arg(long)]
+        project: Uuid,
+        #[arg(long)]
+        status: Option<TaskStatus>,
+    },
+    Create {
+        #[arg(long)]
+        project: Uuid,
+        #[arg(long)]
+        title: String,
+        #[arg(long)]
+        description: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum AttemptsCommand {
+    List {
+        #[arg(long)]
+        task: Uuid,
+    },
+    Start {
+        #[arg(long)]
+        task: Uuid,
+        /// Executor profile, e.g. `claude-code` or `claude-code:PLAN`
+        #[arg(long, value_name = "EXECUTOR[:VARIANT]")]
+        #[arg(value_parser = parse_executor_profile_id)]
+        executor: ExecutorProfileId,
+        /// Repository and target branch; defaults to every project repository
+        /// on its default target branch
+        #[arg(long = "repo", value_name = "REPO_ID[:BRANCH]")]
+        repos: Vec<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ApprovalsCommand {
+    List,
+    Approve {
+        approval_id: String,
+    },
+    Deny {
+        approval_id: String,
+        #[arg(long)]
+        reason: Option<String>,
+    },
+}
+
+/// Branch as listed by the repository branches endpoint
+#[derive(Debug, Deserialize)]
+struct Branch {
+    name: String,
+    is_current: bool,
+    is_remote: bool,
+}
+
+fn parse_executor_profile_id(value: &str) -> Result<ExecutorProfileId, String> {
+    let (executor, variant) = match value.split_once(':') {
+        Some((executor, variant)) => (executor, Some(variant.trim().to_string())),
+        None => (value, None),
+    };
+    let executor = BaseCodingAgent::from_str(&executor.trim().replace('-', "_").to_uppercase())
+        .map_err(|_| format!("Unknown executor '{}'", executor.trim()))?;
+    Ok(ExecutorProfileId {
+        executor,
+        variant: variant.filter(|v| !v.is_empty()),
+    })
+}
+
+fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+pub async fn run(cli: Cli) -> anyhow::Result<()> {
+    let client = ApiClient::connect(cli.url).await?;
+    let json = cli.json;
+
+    match cli.command {
+        Command::Projects(ProjectsCommand::List) => {
+            let projects: Vec<Project> = client.get("/api/projects").await?;
+            if json {
+                return print_json(&projects);
+            }
+            for project in projects {
+                println!("{}\t{}", project.id, project.name);
+            }
+        }
+        Command::Projects(ProjectsCommand::Create { name, repos }) => {
+            let mut repositories = Vec::new();
+            for path in repos {
+                let path = std::fs::canonicalize(&path)
+                    .with_context(|| format!("Repository path {path} does not exist"))?;
+                let display_name = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                repositories.push(json!({
+                    "display_name": display_name,
+                    "git_repo_path": path.to_string_lossy(),
+                }));
+            }
+            let project: Project = client
+                .post(
+                    "/api/projects",
+                    &json!({ "name": name, "repositories": repositories }),
+                )
+                .await?;
+            if json {
+                return print_json(&project);
+            }
+            println!("{}", project.id);
+        }
+        Command::Tasks(TasksCommand::List { project, status }) => {
+            let tasks: Vec<TaskWithAttemptStatus> = client
+                .get(&format!("/api/tasks?project_id={project}"))
+                .await?;
+            let tasks: Vec<_> = tasks
+                .into_iter()
+                .filter(|t| status.as_ref().is_none_or(|s| &t.task.status == s))
+                .collect();
+            if json {
+                return print_json(&tasks);
+            }
+            for task in tasks {
+                println!(
+                    "{}\t{}\t{}",
+                    task.task.id, task.task.status, task.task.title
+                );
+            }
+        }
+        Command::Tasks(TasksCommand::Create {
+            project,
+            title,
+            description,
+        }) => {
+            let payload = CreateTask::from_title_description(project, title, description);
+            let task: Task = client.post("/api/tasks", &payload).await?;
+            if json {
+                return print_json(&task);
+            }
+            println!("{}", task.id);
+        }
+        Command::Attempts(AttemptsCommand::List { task }) => {
+            let workspaces: Vec<Workspace> = client
+                .get(&format!("/api/task-attempts?task_id={task}"))
+                .await?;
+            if json {
+                return print_json(&workspaces);
+            }
+            for workspace in workspaces {
+                println!(
+                    "{}\t{}\t{}",
+                    workspace.id,
+                    workspace.branch,
+                    workspace.created_at.to_rfc3339()
+                );
+            }
+        }
+        Command::Attempts(AttemptsCommand::Start {
+            task,
+            executor,
+            repos,
+        }) => {
+            let repos = if repos.is_empty() {
+                default_attempt_repos(&client, task).await?
+            } else {
+                let mut inputs = Vec::new();
+                for repo in repos {
+                    let (repo_id, branch) = match repo.split_once(':') {
+                        Some((id, branch)) => (id, Some(branch.to_string())),
+                        None => (repo.as_str(), None),
+                    };
+                    let repo_id = Uuid::parse_str(repo_id)
+                        .with_context(|| format!("Invalid repository id '{repo_id}'"))?;
+                    let target_branch = match branch {
+                        Some(branch) => branch,
+                        None => {
+                            let repo: Repo = client.get(&format!("/api/repos/{repo_id}")).await?;
+                            target_branch_for(&client, &repo).await?
+                        }
+                    };
+                    inputs.push(WorkspaceRepoInput {
+                        repo_id,
+                        target_branch,
+                    });
+                }
+                inputs
+            };
+            let payload = CreateTaskAttemptBody {
+                task_id: task,
+                executor_profile_id: executor,
+                race_executor_profile_ids: None,
+                base_workspace_id: None,
+                repos,
+            };
+            let workspace: Workspace = client.post("/api/task-attempts", &payload).await?;
+            if json {
+                return print_json(&workspace);
+            }
+            println!("{}\t{}", workspace.id, workspace.branch);
+        }
+        Command::Logs {
+            attempt_id,
+            process,
+        } => {
+            let execution_process_id = match process {
+                Some(id) => id,
+                None => {
+                    let sessions: Vec<Session> = client
+                        .get(&format!("/api/sessions?workspace_id={attempt_id}"))
+                        .await?;
+                    let session = sessions
+                        .into_iter()
+                        .max_by_key(|s| s.created_at)
+                        .ok_or_else(|| anyhow!("Attempt {attempt_id} has no sessions"))?;
+                    logs::latest_coding_agent_process(&client, session.id)
+                        .await?
+                        .ok_or_else(|| anyhow!("Attempt {attempt_id} has no coding agent runs"))?
+                        .id
+                }
+            };
+            logs::tail(&client, execution_process_id, json).await?;
+        }
+        Command::Approvals(ApprovalsCommand::List) => {
+            let approvals: Vec<PendingApprovalInfo> = client.get("/api/approvals").await?;
+            if json {
+                return print_json(&approvals);
+            }
+            for approval in approvals {
+                println!(
+                    "{}\t{}\t{}",
+                    approval.id, approval.execution_process_id, approval.tool_name
+                );
+            }
+        }
+        Command::Approvals(ApprovalsCommand::Approve { approval_id }) => {
+            respond_to_approval(&client, &approval_id, ApprovalStatus::Approved, json).await?;
+        }
+        Command::Approvals(ApprovalsCommand::Deny {
+            approval_id,
+            reason,
+        }) => {
+            respond_to_approval(
+                &client,
+                &approval_id,
+                ApprovalStatus::Denied { reason },
+                json,
+            )
+            .await?;
+        }
+        Command::Merge {
+            attempt_id,
+            repo,
+            message,
+        } => {
+            let repo_id = attempt_repo_id(&client, attempt_id, repo).await?;
+            let payload = MergeTaskAttemptRequest {
+                repo_id,
+                commit_message: message,
+            };
+            client
+                .post::<_, ()>(&format!("/api/task-attempts/{attempt_id}/merge"), &payload)
+                .await?;
+            if json {
+                return print_json(&json!({ "merged": true, "repo_id": repo_id }));
+            }
+            println!("Merged attempt {attempt_id}");
+        }
+        Command::Push {
+            attempt_id,
+            repo,
+            force,
+        } => {
+            let repo_id = attempt_repo_id(&client, attempt_id, repo).await?;
+            let path = if force { "push/force" } else { "push" };
+            client
+                .post::<_, ()>(
+                    &format!("/api/task-attempts/{attempt_id}/{path}"),
+                    &PushTaskAttemptRequest { repo_id },
+                )
+                .await?;
+            if json {
+                return print_json(&json!({ "pushed": true, "repo_id": repo_id }));
+            }
+            println!("Pushed attempt {attempt_id}");
+        }
+    }
+    Ok(())
+}
+
+/// Every repository of the task's project, on its default target branch
+async fn default_attempt_repos(
+    client: &ApiClient,
+    task_id: Uuid,
+) -> anyhow::Result<Vec<WorkspaceRepoInput>> {
+    let task: Task = client.get(&format!("/api/tasks/{task_id}")).await?;
+    let repos: Vec<Repo> = client
+        .get(&format!("/api/projects/{}/repositories", task.project_id))
+        .await?;
+    if repos.is_empty() {
+        bail!("Project {} has no repositories", task.project_id);
+    }
+    let mut inputs = Vec::new();
+    for repo in repos {
+        inputs.push(WorkspaceRepoInput {
+            repo_id: repo.id,
+            target_branch: target_branch_for(client, &repo).await?,
+        });
+    }
+    Ok(inputs)
+}
+
+/// The repository's default target branch, else its checked out branch
+async fn target_branch_for(client: &ApiClient, repo: &Repo) -> anyhow::Result<String> {
+    if let Some(branch) = &repo.default_target_branch {
+        return Ok(branch.clone());
+    }
+    let branches: Vec<Branch> = client
+        .get(&format!("/api/repos/{}/branches", repo.id))
+        .await?;
+    branches
+        .into_iter()
+        .find(|b| b.is_current && !b.is_remote)
+        .map(|b| b.name)
+        .ok_or_else(|| {
+            anyhow!(
+                "Repository {} has no checked out branch; pass --repo {}:BRANCH",
+                repo.display_name,
+                repo.id
+            )
+        })
+}
+
+/// `repo`, or the only repository of the attempt
+async fn attempt_repo_id(
+    client: &ApiClient,
+    attempt_id: Uuid,
+    repo: Option<Uuid>,
+) -> anyhow::Result<Uuid> {
+    if let Some(repo_id) = repo {
+        return Ok(repo_id);
+    }
+    let repos: Vec<RepoWithTargetBranch> = client
+        .get(&format!("/api/task-attempts/{attempt_id}/repos"))
+        .await?;
+    match repos.as_slice() {
+        [repo] => Ok(repo.repo.id),
+        [] => bail!("Attempt {attempt_id} has no repositories"),
+        _ => bail!("Attempt {attempt_id} has several repositories; pass --repo"),
+    }
+}
+
+async fn respond_to_approval(
+    client: &ApiClient,
+    approval_id: &str,
+    status: ApprovalStatus,
+    json: bool,
+) -> anyhow::Result<()> {
+    let approvals: Vec<PendingApprovalInfo> = client.get("/api/approvals").await?;
+    let approval = approvals
+        .into_iter()
+        .find(|a| a.id == approval_id)
+        .ok_or_else(|| anyhow!("No pending approval with id {approval_id}"))?;
+    let payload = ApprovalResponse {
+        execution_process_id: approval.execution_process_id,
+        status,
+    };
+    let status: ApprovalStatus = client
+        .post(&format!("/api/approvals/{approval_id}/respond"), &payload)
+        .await?;
+    if json {
+        return print_json(&json!({ "id": approval_id, "status": status }));
+    }
+    println!("{approval_id}\t{status:?}");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_executor_with_and_without_variant() {
+        let profile = parse_executor_profile_id("claude-code").unwrap();
+        assert_eq!(profile.executor, BaseCodingAgent::ClaudeCode);
+        assert_eq!(profile.variant, None);
+
+        let profile = parse_executor_profile_id("claude-code:PLAN").unwrap();
+        assert_eq!(profile.executor, BaseCodingAgent::ClaudeCode);
+        assert_eq!(profile.variant.as_deref(), Some("PLAN"));
+
+        assert!(parse_executor_profile_id("not-an-agent").is_err());
+    }
+}
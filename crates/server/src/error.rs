@@ -6,7 +6,8 @@ use axum::{
 };
 use db::models::{
     execution_process::ExecutionProcessError, project::ProjectError,
-    project_repo::ProjectRepoError, repo::RepoError, scratch::ScratchError, session::SessionError,
+    project_repo::ProjectRepoError, queued_message::QueuedMessageError, repo::RepoError,
+    scratch::ScratchError, session::SessionError, task_dependency::TaskDependencyError,
     workspace::WorkspaceError,
 };
 use deployment::{DeploymentError, RemoteClientNotConfigured};
@@ -21,8 +22,11 @@ use services::services::{
     git_host::GitHostError,
     image::ImageError,
     project::ProjectServiceError,
+    project_memory::ProjectMemoryError,
     remote_client::RemoteClientError,
     repo::RepoError as RepoServiceError,
+    scheduler::CronParseError,
+    task_archive::TaskArchiveError,
     worktree_manager::WorktreeError,
 };
 use thiserror::Error;
@@ -75,6 +79,8 @@ pub enum ApiError {
     Unauthorized,
     #[error("Bad request: {0}")]
     BadRequest(String),
+    #[error("Not found: {0}")]
+    NotFound(String),
     #[error("Conflict: {0}")]
     Conflict(String),
     #[error("Forbidden: {0}")]
@@ -182,6 +188,7 @@ impl IntoResponse for ApiError {
             ApiError::Http(_) => (StatusCode::BAD_GATEWAY, "HttpError"),
             ApiError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized"),
             ApiError::BadRequest(_) => (StatusCode::BAD_REQUEST, "BadRequest"),
+            ApiError::NotFound(_) => (StatusCode::NOT_FOUND, "NotFound"),
             ApiError::Conflict(_) => (StatusCode::CONFLICT, "ConflictError"),
             ApiError::Forbidden(_) => (StatusCode::FORBIDDEN, "ForbiddenError"),
             ApiError::Pty(err) => match err {
@@ -266,6 +273,7 @@ impl IntoResponse for ApiError {
             ApiError::Http(_) => "HTTP request failed. Please try again.".to_string(),
             ApiError::Unauthorized => "Unauthorized. Please sign in again.".to_string(),
             ApiError::BadRequest(msg) => msg.clone(),
+            ApiError::NotFound(msg) => msg.clone(),
             ApiError::Conflict(msg) => msg.clone(),
             ApiError::Forbidden(msg) => msg.clone(),
             _ => format!("{}: {}", error_type, self),
@@ -350,3 +358,72 @@ impl From<ProjectRepoError> for ApiError {
         }
     }
 }
+
+impl From<TaskDependencyError> for ApiError {
+    fn from(err: TaskDependencyError) -> Self {
+        match err {
+            TaskDependencyError::Database(db_err) => ApiError::Database(db_err),
+            TaskDependencyError::NotFound => {
+                ApiError::BadRequest("Task dependency not found".to_string())
+            }
+            TaskDependencyError::SelfReference => {
+                ApiError::BadRequest("A task cannot depend on itself".to_string())
+            }
+            TaskDependencyError::CrossProject => {
+                ApiError::BadRequest("Dependent tasks must belong to the same project".to_string())
+            }
+            TaskDependencyError::AlreadyExists => {
+                ApiError::Conflict("Task dependency already exists".to_string())
+            }
+            TaskDependencyError::Cycle => ApiError::Conflict(
+                "Adding this dependency would create a dependency cycle".to_string(),
+            ),
+        }
+    }
+}
+
+impl From<QueuedMessageError> for ApiError {
+    fn from(err: QueuedMessageError) -> Self {
+        match err {
+            QueuedMessageError::Database(db_err) => ApiError::Database(db_err),
+            QueuedMessageError::NotFound => {
+                ApiError::BadRequest("Queued message not found".to_string())
+            }
+            QueuedMessageError::InvalidOrder => ApiError::BadRequest(
+                "New order must list every queued message exactly once".to_string(),
+            ),
+        }
+    }
+}
+
+impl From<CronParseError> for ApiError {
+    fn from(err: CronParseError) -> Self {
+        ApiError::BadRequest(err.to_string())
+    }
+}
+
+impl From<TaskArchiveError> for ApiError {
+    fn from(err: TaskArchiveError) -> Self {
+        match err {
+            TaskArchiveError::Database(db_err) => ApiError::Database(db_err),
+            TaskArchiveError::Workspace(workspace_err) => ApiError::Workspace(workspace_err),
+            TaskArchiveError::Io(io_err) => ApiError::Io(io_err),
+            TaskArchiveError::Image(img_err) => ApiError::Image(img_err),
+            TaskArchiveError::TaskNotFound => ApiError::NotFound("Task not found".to_string()),
+            TaskArchiveError::InvalidArchive(_) | TaskArchiveError::UnsupportedVersion(_) => {
+                ApiError::BadRequest(err.to_string())
+            }
+        }
+    }
+}
+
+impl From<ProjectMemoryError> for ApiError {
+    fn from(err: ProjectMemoryError) -> Self {
+        match err {
+            ProjectMemoryError::Database(db_err) => ApiError::Database(db_err),
+            ProjectMemoryError::Io(io_err) => ApiError::Io(io_err),
+            ProjectMemoryError::Git(git_err) => ApiError::GitService(git_err),
+            ProjectMemoryError::InvalidMemoryFile(_) => ApiError::BadRequest(err.to_string()),
+        }
+    }
+}
@@ -68,10 +68,25 @@ async fn main() -> Result<(), VibeKanbanError> {
         .backfill_repo_names()
         .await
         .map_err(DeploymentError::from)?;
+    // Resume coding agents that were waiting in the run queue before the restart
+    deployment.container().dispatch_queued_executions().await;
     deployment.spawn_pr_monitor_service().await;
+    deployment.spawn_drift_monitor_service().await;
+    deployment.spawn_scheduler_service().await;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
+    // Index the logs of executions that finished before search existed
+    let deployment_for_search = deployment.clone();
+    tokio::spawn(async move {
+        if let Err(e) = deployment_for_search
+            .container()
+            .backfill_search_index()
+            .await
+        {
+            tracing::warn!("Failed to backfill search index: {}", e);
+        }
+    });
     // Pre-warm file search cache for most active projects
     let deployment_for_cache = deployment.clone();
     tokio::spawn(async move {
@@ -1,10 +1,18 @@
-use std::{future::Future, str::FromStr};
+use std::{collections::HashMap, future::Future, str::FromStr};
 
+use chrono::{DateTime, Utc};
 use db::models::{
     project::Project,
+    project_memory::{
+        AcceptProjectMemoryEntry, CreateProjectMemoryEntry, ProjectMemoryEntry,
+        ProjectMemoryStatus, UpdateProjectMemoryEntry,
+    },
     repo::Repo,
+    search::SearchResult,
     tag::Tag,
     task::{CreateTask, Task, TaskStatus, TaskWithAttemptStatus, UpdateTask},
+    task_dependency::{CreateTaskDependency, TaskDependency, TaskDependencyType},
+    task_template::{TaskTemplate, TemplateInstance, TemplateVariableKind},
     workspace::{Workspace, WorkspaceContext},
 };
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
@@ -34,6 +42,12 @@ pub struct CreateTaskRequest {
     pub title: String,
     #[schemars(description = "Optional description of the task")]
     pub description: Option<String>,
+    #[schemars(
+        description = "Optional ID of a task template to create the task from. Its rendered title and description are used when `title` is empty and `description` is omitted"
+    )]
+    pub template_id: Option<Uuid>,
+    #[schemars(description = "Values of the template's variables, keyed by variable name")]
+    pub template_variables: Option<HashMap<String, String>>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -147,6 +161,76 @@ pub struct ListProjectsResponse {
     pub count: usize,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListTaskTemplatesRequest {
+    #[schemars(description = "The ID of the project to list task templates for")]
+    pub project_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct TemplateVariableSummary {
+    #[schemars(description = "Name used in `template_variables` when creating a task")]
+    pub name: String,
+    #[schemars(description = "One of text, number, boolean, url or select")]
+    pub kind: String,
+    pub required: bool,
+    pub description: Option<String>,
+    #[schemars(description = "Value used when none is given")]
+    pub default: Option<String>,
+    #[schemars(description = "Allowed values of select variables")]
+    pub options: Vec<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct TaskTemplateSummary {
+    #[schemars(description = "The unique identifier of the template")]
+    pub id: String,
+    #[schemars(description = "The name of the template")]
+    pub name: String,
+    #[schemars(description = "Title of the created tasks, with `{{variable}}` placeholders")]
+    pub title: String,
+    #[schemars(description = "Whether the template is available in every project")]
+    pub global: bool,
+    pub variables: Vec<TemplateVariableSummary>,
+}
+
+impl TaskTemplateSummary {
+    fn from_template(template: TaskTemplate) -> Self {
+        Self {
+            id: template.id.to_string(),
+            name: template.name,
+            title: template.title,
+            global: template.project_id.is_none(),
+            variables: template
+                .variables
+                .into_iter()
+                .map(|variable| TemplateVariableSummary {
+                    name: variable.name,
+                    kind: match variable.kind {
+                        TemplateVariableKind::Text => "text",
+                        TemplateVariableKind::Number => "number",
+                        TemplateVariableKind::Boolean => "boolean",
+                        TemplateVariableKind::Url => "url",
+                        TemplateVariableKind::Select => "select",
+                    }
+                    .to_string(),
+                    required: variable.required,
+                    description: variable.description,
+                    default: variable.default,
+                    options: variable.options,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListTaskTemplatesResponse {
+    pub templates: Vec<TaskTemplateSummary>,
+    pub count: usize,
+    pub project_id: String,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct ListTasksRequest {
     #[schemars(description = "The ID of the project to list tasks from")]
@@ -307,6 +391,207 @@ pub struct GetTaskResponse {
     pub task: TaskDetails,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct SearchRequest {
+    #[schemars(description = "Words to search for. All words must match")]
+    pub query: String,
+    #[schemars(description = "Optional project to restrict the search to")]
+    pub project_id: Option<Uuid>,
+    #[schemars(
+        description = "Optional RFC 3339 timestamp; only include hits created at or after it"
+    )]
+    pub from: Option<String>,
+    #[schemars(
+        description = "Optional RFC 3339 timestamp; only include hits created at or before it"
+    )]
+    pub to: Option<String>,
+    #[schemars(description = "Maximum number of hits to return (default: 20)")]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SearchHit {
+    #[schemars(
+        description = "Where the match was found: 'task' (title/description), 'turn' (agent prompt/summary) or 'log' (agent conversation)"
+    )]
+    pub kind: String,
+    #[schemars(description = "The task the match belongs to")]
+    pub task_id: String,
+    #[schemars(description = "The title of that task")]
+    pub task_title: String,
+    #[schemars(description = "The workspace of the matching turn or log entry")]
+    pub workspace_id: Option<String>,
+    #[schemars(description = "The execution process of the matching turn or log entry")]
+    pub execution_process_id: Option<String>,
+    #[schemars(description = "Matching excerpt with matched words wrapped in <mark> tags")]
+    pub snippet: String,
+    #[schemars(description = "When the matching item was created")]
+    pub created_at: String,
+}
+
+impl SearchHit {
+    fn from_result(result: SearchResult) -> Self {
+        Self {
+            kind: result.kind.to_string(),
+            task_id: result.task_id.to_string(),
+            task_title: result.task_title,
+            workspace_id: result.workspace_id.map(|id| id.to_string()),
+            execution_process_id: result.execution_process_id.map(|id| id.to_string()),
+            snippet: result.snippet,
+            created_at: result.created_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct SearchResponse {
+    pub results: Vec<SearchHit>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListTaskDependenciesRequest {
+    #[schemars(description = "The ID of the task whose dependencies to list")]
+    pub task_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct TaskDependencySummary {
+    #[schemars(description = "The unique identifier of the dependency")]
+    pub id: String,
+    #[schemars(description = "The task on the 'from' side (for 'blocking', the blocker)")]
+    pub task_id: String,
+    #[schemars(description = "The task on the 'to' side (for 'blocking', the blocked task)")]
+    pub related_task_id: String,
+    #[schemars(description = "Relationship type: 'blocking', 'related' or 'has_duplicate'")]
+    pub relationship_type: String,
+}
+
+impl TaskDependencySummary {
+    fn from_dependency(dependency: TaskDependency) -> Self {
+        Self {
+            id: dependency.id.to_string(),
+            task_id: dependency.task_id.to_string(),
+            related_task_id: dependency.related_task_id.to_string(),
+            relationship_type: dependency.relationship_type.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListTaskDependenciesResponse {
+    pub dependencies: Vec<TaskDependencySummary>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AddTaskDependencyRequest {
+    #[schemars(description = "The ID of the blocking (or source) task")]
+    pub task_id: Uuid,
+    #[schemars(description = "The ID of the task that is blocked by (or related to) `task_id`")]
+    pub related_task_id: Uuid,
+    #[schemars(
+        description = "Relationship type: 'blocking' (default), 'related' or 'has_duplicate'"
+    )]
+    pub relationship_type: Option<String>,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct AddTaskDependencyResponse {
+    pub dependency: TaskDependencySummary,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RemoveTaskDependencyRequest {
+    #[schemars(description = "The ID of a task on either side of the dependency")]
+    pub task_id: Uuid,
+    #[schemars(description = "The ID of the dependency to remove")]
+    pub dependency_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct RemoveTaskDependencyResponse {
+    pub removed_dependency_id: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ListProjectMemoryRequest {
+    #[schemars(description = "The ID of the project whose memory to list")]
+    pub project_id: Uuid,
+    #[schemars(
+        description = "Optional status filter: 'candidate', 'accepted' or 'discarded'. All entries are listed when unset"
+    )]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AddProjectMemoryRequest {
+    #[schemars(description = "The ID of the project to remember this for")]
+    pub project_id: Uuid,
+    #[schemars(description = "The knowledge to keep, such as a convention or a pitfall")]
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct AcceptProjectMemoryRequest {
+    #[schemars(description = "The ID of the candidate entry to accept")]
+    pub entry_id: Uuid,
+    #[schemars(description = "Optional edited content to accept instead of the proposed one")]
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct EditProjectMemoryRequest {
+    #[schemars(description = "The ID of the entry to edit")]
+    pub entry_id: Uuid,
+    #[schemars(description = "The new content of the entry")]
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct DiscardProjectMemoryRequest {
+    #[schemars(description = "The ID of the entry to discard")]
+    pub entry_id: Uuid,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ProjectMemorySummary {
+    #[schemars(description = "The unique identifier of the entry")]
+    pub id: String,
+    #[schemars(description = "The remembered knowledge")]
+    pub content: String,
+    #[schemars(description = "Status: 'candidate', 'accepted' or 'discarded'")]
+    pub status: String,
+    #[schemars(description = "The workspace whose task summary the entry was proposed from")]
+    pub workspace_id: Option<String>,
+    #[schemars(description = "When the entry was last updated")]
+    pub updated_at: String,
+}
+
+impl ProjectMemorySummary {
+    fn from_entry(entry: ProjectMemoryEntry) -> Self {
+        Self {
+            id: entry.id.to_string(),
+            content: entry.content,
+            status: entry.status.to_string(),
+            workspace_id: entry.workspace_id.map(|id| id.to_string()),
+            updated_at: entry.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ListProjectMemoryResponse {
+    pub entries: Vec<ProjectMemorySummary>,
+    pub count: usize,
+    pub project_id: String,
+}
+
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct ProjectMemoryResponse {
+    pub entry: ProjectMemorySummary,
+}
+
 #[derive(Debug, Clone)]
 pub struct TaskServer {
     client: reqwest::Client,
@@ -587,6 +872,8 @@ impl TaskServer {
             project_id,
             title,
             description,
+            template_id,
+            template_variables,
         }): Parameters<CreateTaskRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         // Expand @tagname references in description
@@ -596,19 +883,15 @@ impl TaskServer {
         };
 
         let url = self.url("/api/tasks");
+        let payload = CreateTask {
+            template: template_id.map(|template_id| TemplateInstance {
+                template_id,
+                variables: template_variables.unwrap_or_default(),
+            }),
+            ..CreateTask::from_title_description(project_id, title, expanded_description)
+        };
 
-        let task: Task = match self
-            .send_json(
-                self.client
-                    .post(&url)
-                    .json(&CreateTask::from_title_description(
-                        project_id,
-                        title,
-                        expanded_description,
-                    )),
-            )
-            .await
-        {
+        let task: Task = match self.send_json(self.client.post(&url).json(&payload)).await {
             Ok(t) => t,
             Err(e) => return Ok(e),
         };
@@ -639,6 +922,33 @@ impl TaskServer {
         TaskServer::success(&response)
     }
 
+    #[tool(
+        description = "List the task templates available in a project, including global ones. Pass a template's `id` and variable values to `create_task` to create a task from it. `project_id` is required!"
+    )]
+    async fn list_task_templates(
+        &self,
+        Parameters(ListTaskTemplatesRequest { project_id }): Parameters<ListTaskTemplatesRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/task-templates?project_id={}", project_id));
+        let templates: Vec<TaskTemplate> = match self.send_json(self.client.get(&url)).await {
+            Ok(templates) => templates,
+            Err(e) => return Ok(e),
+        };
+
+        let template_summaries: Vec<TaskTemplateSummary> = templates
+            .into_iter()
+            .map(TaskTemplateSummary::from_template)
+            .collect();
+
+        let response = ListTaskTemplatesResponse {
+            count: template_summaries.len(),
+            templates: template_summaries,
+            project_id: project_id.to_string(),
+        };
+
+        TaskServer::success(&response)
+    }
+
     #[tool(description = "List all repositories for a project. `project_id` is required!")]
     async fn list_repos(
         &self,
@@ -896,6 +1206,8 @@ impl TaskServer {
         let payload = CreateTaskAttemptBody {
             task_id,
             executor_profile_id,
+            race_executor_profile_ids: None,
+            base_workspace_id: None,
             repos: workspace_repos,
         };
 
@@ -999,12 +1311,284 @@ impl TaskServer {
 
         TaskServer::success(&response)
     }
+
+    #[tool(
+        description = "Full-text search across task titles and descriptions, coding agent prompts and summaries, and agent conversation logs. Use it to find which task or attempt dealt with a topic. `query` is required; `project_id`, `from` and `to` are optional filters."
+    )]
+    async fn search(
+        &self,
+        Parameters(SearchRequest {
+            query,
+            project_id,
+            from,
+            to,
+            limit,
+        }): Parameters<SearchRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut params = vec![("q", query), ("limit", limit.unwrap_or(20).to_string())];
+        if let Some(project_id) = project_id {
+            params.push(("project_id", project_id.to_string()));
+        }
+        for (name, value) in [("from", from), ("to", to)] {
+            let Some(value) = value else {
+                continue;
+            };
+            match DateTime::parse_from_rfc3339(&value) {
+                Ok(time) => params.push((name, time.with_timezone(&Utc).to_rfc3339())),
+                Err(_) => {
+                    return Self::err(
+                        format!("Invalid `{name}` timestamp, expected RFC 3339"),
+                        Some(value),
+                    );
+                }
+            }
+        }
+
+        let url = self.url("/api/search");
+        let results: Vec<SearchResult> =
+            match self.send_json(self.client.get(&url).query(&params)).await {
+                Ok(r) => r,
+                Err(e) => return Ok(e),
+            };
+
+        let results: Vec<SearchHit> = results.into_iter().map(SearchHit::from_result).collect();
+        let response = SearchResponse {
+            count: results.len(),
+            results,
+        };
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "List the dependencies of a task, in both directions. For 'blocking' dependencies, `task_id` blocks `related_task_id`. `task_id` is required."
+    )]
+    async fn list_task_dependencies(
+        &self,
+        Parameters(ListTaskDependenciesRequest { task_id }): Parameters<
+            ListTaskDependenciesRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/tasks/{}/dependencies", task_id));
+        let dependencies: Vec<TaskDependency> = match self.send_json(self.client.get(&url)).await {
+            Ok(d) => d,
+            Err(e) => return Ok(e),
+        };
+
+        let dependencies: Vec<TaskDependencySummary> = dependencies
+            .into_iter()
+            .map(TaskDependencySummary::from_dependency)
+            .collect();
+        let response = ListTaskDependenciesResponse {
+            count: dependencies.len(),
+            dependencies,
+        };
+
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Add a dependency between two tasks of the same project. By default `task_id` blocks `related_task_id`; once all blockers of a task are done, its first workspace session is started automatically. `task_id` and `related_task_id` are required."
+    )]
+    async fn add_task_dependency(
+        &self,
+        Parameters(AddTaskDependencyRequest {
+            task_id,
+            related_task_id,
+            relationship_type,
+        }): Parameters<AddTaskDependencyRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let relationship_type = match relationship_type {
+            Some(ref type_str) => match TaskDependencyType::from_str(type_str) {
+                Ok(t) => t,
+                Err(_) => {
+                    return Self::err(
+                        "Invalid relationship type. Valid values: 'blocking', 'related', 'has_duplicate'".to_string(),
+                        Some(type_str.to_string()),
+                    );
+                }
+            },
+            None => TaskDependencyType::Blocking,
+        };
+
+        let payload = CreateTaskDependency {
+            related_task_id,
+            relationship_type,
+        };
+        let url = self.url(&format!("/api/tasks/{}/dependencies", task_id));
+        let dependency: TaskDependency =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(d) => d,
+                Err(e) => return Ok(e),
+            };
+
+        let response = AddTaskDependencyResponse {
+            dependency: TaskDependencySummary::from_dependency(dependency),
+        };
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Remove a dependency between two tasks. `task_id` and `dependency_id` are required. Use `list_task_dependencies` to find the `dependency_id`."
+    )]
+    async fn remove_task_dependency(
+        &self,
+        Parameters(RemoveTaskDependencyRequest {
+            task_id,
+            dependency_id,
+        }): Parameters<RemoveTaskDependencyRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/tasks/{}/dependencies/{}",
+            task_id, dependency_id
+        ));
+        if let Err(e) = self.send_empty_json(self.client.delete(&url)).await {
+            return Ok(e);
+        }
+
+        let response = RemoveTaskDependencyResponse {
+            removed_dependency_id: dependency_id.to_string(),
+        };
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "List the memory of a project: knowledge prepended to the prompt of every new coding agent session once accepted. Summaries of completed tasks are proposed as 'candidate' entries to accept, edit or discard. `project_id` is required!"
+    )]
+    async fn list_project_memory(
+        &self,
+        Parameters(ListProjectMemoryRequest { project_id, status }): Parameters<
+            ListProjectMemoryRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let mut url = self.url(&format!("/api/project-memory?project_id={}", project_id));
+        if let Some(ref status_str) = status {
+            match ProjectMemoryStatus::from_str(status_str) {
+                Ok(status) => url = format!("{}&status={}", url, status),
+                Err(_) => {
+                    return Self::err(
+                        "Invalid status filter. Valid values: 'candidate', 'accepted', 'discarded'"
+                            .to_string(),
+                        Some(status_str.to_string()),
+                    );
+                }
+            }
+        }
+        let entries: Vec<ProjectMemoryEntry> = match self.send_json(self.client.get(&url)).await {
+            Ok(entries) => entries,
+            Err(e) => return Ok(e),
+        };
+
+        let entries: Vec<ProjectMemorySummary> = entries
+            .into_iter()
+            .map(ProjectMemorySummary::from_entry)
+            .collect();
+        let response = ListProjectMemoryResponse {
+            count: entries.len(),
+            entries,
+            project_id: project_id.to_string(),
+        };
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Remember something about a project for every future coding agent session, such as a convention or a pitfall. The entry is accepted right away. `project_id` and `content` are required."
+    )]
+    async fn add_project_memory(
+        &self,
+        Parameters(AddProjectMemoryRequest {
+            project_id,
+            content,
+        }): Parameters<AddProjectMemoryRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = CreateProjectMemoryEntry {
+            project_id,
+            content,
+        };
+        let url = self.url("/api/project-memory");
+        let entry: ProjectMemoryEntry =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(entry) => entry,
+                Err(e) => return Ok(e),
+            };
+
+        let response = ProjectMemoryResponse {
+            entry: ProjectMemorySummary::from_entry(entry),
+        };
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Accept a candidate project memory entry, optionally with edited `content`. `entry_id` is required. Use `list_project_memory` to find the candidates."
+    )]
+    async fn accept_project_memory(
+        &self,
+        Parameters(AcceptProjectMemoryRequest { entry_id, content }): Parameters<
+            AcceptProjectMemoryRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = AcceptProjectMemoryEntry { content };
+        let url = self.url(&format!("/api/project-memory/{}/accept", entry_id));
+        let entry: ProjectMemoryEntry =
+            match self.send_json(self.client.post(&url).json(&payload)).await {
+                Ok(entry) => entry,
+                Err(e) => return Ok(e),
+            };
+
+        let response = ProjectMemoryResponse {
+            entry: ProjectMemorySummary::from_entry(entry),
+        };
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Edit the content of a project memory entry, keeping its status. `entry_id` and `content` are required."
+    )]
+    async fn edit_project_memory(
+        &self,
+        Parameters(EditProjectMemoryRequest { entry_id, content }): Parameters<
+            EditProjectMemoryRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let payload = UpdateProjectMemoryEntry { content };
+        let url = self.url(&format!("/api/project-memory/{}", entry_id));
+        let entry: ProjectMemoryEntry =
+            match self.send_json(self.client.put(&url).json(&payload)).await {
+                Ok(entry) => entry,
+                Err(e) => return Ok(e),
+            };
+
+        let response = ProjectMemoryResponse {
+            entry: ProjectMemorySummary::from_entry(entry),
+        };
+        TaskServer::success(&response)
+    }
+
+    #[tool(
+        description = "Discard a candidate or accepted project memory entry, so it is no longer fed to coding agents. `entry_id` is required."
+    )]
+    async fn discard_project_memory(
+        &self,
+        Parameters(DiscardProjectMemoryRequest { entry_id }): Parameters<
+            DiscardProjectMemoryRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!("/api/project-memory/{}/discard", entry_id));
+        let entry: ProjectMemoryEntry = match self.send_json(self.client.post(&url)).await {
+            Ok(entry) => entry,
+            Err(e) => return Ok(e),
+        };
+
+        let response = ProjectMemoryResponse {
+            entry: ProjectMemorySummary::from_entry(entry),
+        };
+        TaskServer::success(&response)
+    }
 }
 
 #[tool_handler]
 impl ServerHandler for TaskServer {
     fn get_info(&self) -> ServerInfo {
-        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_workspace_session', 'get_task', 'update_task', 'delete_task', 'list_repos', 'get_repo', 'update_setup_script', 'update_cleanup_script', 'update_dev_server_script'. Make sure to pass `project_id`, `task_id`, or `repo_id` where required. You can use list tools to get the available ids.".to_string();
+        let mut instruction = "A task and project management server. If you need to create or update tickets or tasks then use these tools. Most of them absolutely require that you pass the `project_id` of the project that you are currently working on. You can get project ids by using `list projects`. Call `list_tasks` to fetch the `task_ids` of all the tasks in a project. TOOLS: 'list_projects', 'list_tasks', 'list_task_templates', 'create_task', 'start_workspace_session', 'get_task', 'search', 'update_task', 'delete_task', 'list_task_dependencies', 'add_task_dependency', 'remove_task_dependency', 'list_project_memory', 'add_project_memory', 'accept_project_memory', 'edit_project_memory', 'discard_project_memory', 'list_repos', 'get_repo', 'update_setup_script', 'update_cleanup_script', 'update_dev_server_script'. Make sure to pass `project_id`, `task_id`, or `repo_id` where required. You can use list tools to get the available ids.".to_string();
         if self.context.is_some() {
             let context_instruction = "Use 'get_context' to fetch project/task/workspace metadata for the active Vibe Kanban workspace session when available.";
             instruction = format!("{} {}", context_instruction, instruction);
@@ -5,8 +5,9 @@ use axum::{
     response::Response,
 };
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, session::Session, tag::Tag, task::Task,
-    workspace::Workspace,
+    approval_rule::ApprovalRule, execution_process::ExecutionProcess, project::Project,
+    project_memory::ProjectMemoryEntry, session::Session, tag::Tag, task::Task,
+    task_schedule::TaskSchedule, task_template::TaskTemplate, workspace::Workspace,
 };
 use deployment::Deployment;
 use uuid::Uuid;
@@ -148,6 +149,102 @@ pub async fn load_tag_middleware(
     Ok(next.run(request).await)
 }
 
+pub async fn load_task_schedule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(schedule_id): Path<Uuid>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let schedule = match TaskSchedule::find_by_id(&deployment.db().pool, schedule_id).await {
+        Ok(Some(schedule)) => schedule,
+        Ok(None) => {
+            tracing::warn!("Task schedule {} not found", schedule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch task schedule {}: {}", schedule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(schedule);
+
+    Ok(next.run(request).await)
+}
+
+pub async fn load_approval_rule_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(rule_id): Path<Uuid>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let rule = match ApprovalRule::find_by_id(&deployment.db().pool, rule_id).await {
+        Ok(Some(rule)) => rule,
+        Ok(None) => {
+            tracing::warn!("Approval rule {} not found", rule_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch approval rule {}: {}", rule_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(rule);
+
+    Ok(next.run(request).await)
+}
+
+pub async fn load_project_memory_entry_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(entry_id): Path<Uuid>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let entry = match ProjectMemoryEntry::find_by_id(&deployment.db().pool, entry_id).await {
+        Ok(Some(entry)) => entry,
+        Ok(None) => {
+            tracing::warn!("Project memory entry {} not found", entry_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch project memory entry {}: {}", entry_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(entry);
+
+    Ok(next.run(request).await)
+}
+
+pub async fn load_task_template_middleware(
+    State(deployment): State<DeploymentImpl>,
+    Path(template_id): Path<Uuid>,
+    request: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let template = match TaskTemplate::find_by_id(&deployment.db().pool, template_id).await {
+        Ok(Some(template)) => template,
+        Ok(None) => {
+            tracing::warn!("Task template {} not found", template_id);
+            return Err(StatusCode::NOT_FOUND);
+        }
+        Err(e) => {
+            tracing::error!("Failed to fetch task template {}: {}", template_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut request = request;
+    request.extensions_mut().insert(template);
+
+    Ok(next.run(request).await)
+}
+
 pub async fn load_session_middleware(
     State(deployment): State<DeploymentImpl>,
     Path(session_id): Path<Uuid>,
@@ -0,0 +1,121 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::{
+    approval_rule::{ApprovalRule, CreateApprovalRule, UpdateApprovalRule},
+    project::Project,
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::approvals::rules::validate_pattern;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_approval_rule_middleware};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ApprovalRuleQuery {
+    /// Only list the rules of this project; all rules are listed when unset
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+}
+
+pub async fn get_approval_rules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ApprovalRuleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRule>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let rules = match query.project_id {
+        Some(project_id) => ApprovalRule::find_by_project_id(pool, project_id).await?,
+        None => ApprovalRule::find_all(pool).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(rules)))
+}
+
+pub async fn get_approval_rule(
+    Extension(rule): Extension<ApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+pub async fn create_approval_rule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if let Some(project_id) = payload.project_id
+        && Project::find_by_id(pool, project_id).await?.is_none()
+    {
+        return Err(ApiError::BadRequest("Project not found".to_string()));
+    }
+    validate_pattern(payload.action_type, payload.pattern.as_deref())
+        .map_err(ApiError::BadRequest)?;
+
+    let rule = ApprovalRule::create(pool, &payload, Uuid::new_v4()).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "approval_rule_created",
+            serde_json::json!({
+                "rule_id": rule.id.to_string(),
+                "global": rule.project_id.is_none(),
+                "decision": rule.decision,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(rule)))
+}
+
+pub async fn update_approval_rule(
+    Extension(rule): Extension<ApprovalRule>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateApprovalRule>,
+) -> Result<ResponseJson<ApiResponse<ApprovalRule>>, ApiError> {
+    validate_pattern(
+        payload.action_type.unwrap_or(rule.action_type),
+        payload.pattern.as_ref().unwrap_or(&rule.pattern).as_deref(),
+    )
+    .map_err(ApiError::BadRequest)?;
+
+    let updated = ApprovalRule::update(&deployment.db().pool, &rule, &payload).await?;
+
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_approval_rule(
+    Extension(rule): Extension<ApprovalRule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ApprovalRule::delete(&deployment.db().pool, rule.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let rule_router = Router::new()
+        .route(
+            "/",
+            get(get_approval_rule)
+                .put(update_approval_rule)
+                .delete(delete_approval_rule),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_approval_rule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_approval_rules).post(create_approval_rule))
+        .nest("/{rule_id}", rule_router);
+
+    Router::new().nest("/approval-rules", inner)
+}
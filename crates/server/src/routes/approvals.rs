@@ -3,9 +3,10 @@ use axum::{
     extract::{Path, State},
     http::StatusCode,
     response::Json as ResponseJson,
-    routing::post,
+    routing::{get, post},
 };
 use deployment::Deployment;
+use services::services::approvals::PendingApprovalInfo;
 use utils::{
     approvals::{ApprovalResponse, ApprovalStatus},
     response::ApiResponse,
@@ -13,6 +14,12 @@ use utils::{
 
 use crate::DeploymentImpl;
 
+pub async fn get_pending_approvals(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Vec<PendingApprovalInfo>>> {
+    ResponseJson(ApiResponse::success(deployment.approvals().list_pending()))
+}
+
 pub async fn respond_to_approval(
     State(deployment): State<DeploymentImpl>,
     Path(id): Path<String>,
@@ -44,5 +51,7 @@ pub async fn respond_to_approval(
 }
 
 pub fn router() -> Router<DeploymentImpl> {
-    Router::new().route("/approvals/{id}/respond", post(respond_to_approval))
+    Router::new()
+        .route("/approvals", get(get_pending_approvals))
+        .route("/approvals/{id}/respond", post(respond_to_approval))
 }
@@ -28,6 +28,7 @@ use services::services::{
         save_config_to_file,
     },
     container::ContainerService,
+    git_host::GitHostService,
 };
 use tokio::fs;
 use ts_rs::TS;
@@ -187,6 +188,15 @@ async fn track_config_events(deployment: &DeploymentImpl, old: &Config, new: &Co
 async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &Config) {
     track_config_events(deployment, old, new).await;
 
+    if old.git_hosts != new.git_hosts {
+        GitHostService::set_self_hosted_instances(new.git_hosts.clone());
+    }
+
+    if old.concurrency != new.concurrency {
+        // Raised limits may free slots for queued coding agents
+        deployment.container().dispatch_queued_executions().await;
+    }
+
     if !old.disclaimer_acknowledged && new.disclaimer_acknowledged {
         // Spawn auto project setup as background task to avoid blocking config response
         let deployment_clone = deployment.clone();
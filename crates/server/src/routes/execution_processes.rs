@@ -10,6 +10,7 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
+    approval_request::ApprovalRecord,
     execution_process::{ExecutionProcess, ExecutionProcessError, ExecutionProcessStatus},
     execution_process_repo_state::ExecutionProcessRepoState,
 };
@@ -243,11 +244,23 @@ pub async fn get_execution_process_repo_states(
     Ok(ResponseJson(ApiResponse::success(repo_states)))
 }
 
+/// Approval requests of the execution process and how they were answered
+pub async fn get_execution_process_approvals(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<ApprovalRecord>>>, ApiError> {
+    let approvals =
+        ApprovalRecord::find_by_execution_process_id(&deployment.db().pool, execution_process.id)
+            .await?;
+    Ok(ResponseJson(ApiResponse::success(approvals)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let workspace_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route("/approvals", get(get_execution_process_approvals))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
@@ -6,6 +6,7 @@ use tower_http::validate_request::ValidateRequestHeaderLayer;
 
 use crate::{DeploymentImpl, middleware};
 
+pub mod approval_rules;
 pub mod approvals;
 pub mod config;
 pub mod containers;
@@ -18,14 +19,19 @@ pub mod health;
 pub mod images;
 pub mod oauth;
 pub mod organizations;
+pub mod project_memory;
 pub mod projects;
 pub mod repo;
+pub mod schedules;
 pub mod scratch;
+pub mod search;
 pub mod sessions;
 pub mod tags;
 pub mod task_attempts;
+pub mod task_templates;
 pub mod tasks;
 pub mod terminal;
+pub mod usage;
 
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     // Create routers with different middleware layers
@@ -34,19 +40,25 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(config::router())
         .merge(containers::router(&deployment))
         .merge(projects::router(&deployment))
+        .merge(project_memory::router(&deployment))
         .merge(tasks::router(&deployment))
         .merge(task_attempts::router(&deployment))
         .merge(execution_processes::router(&deployment))
         .merge(tags::router(&deployment))
+        .merge(schedules::router(&deployment))
+        .merge(task_templates::router(&deployment))
         .merge(oauth::router())
         .merge(organizations::router())
         .merge(filesystem::router())
         .merge(repo::router())
         .merge(events::router(&deployment))
         .merge(approvals::router())
+        .merge(approval_rules::router(&deployment))
         .merge(scratch::router(&deployment))
+        .merge(search::router())
         .merge(sessions::router(&deployment))
         .merge(terminal::router())
+        .merge(usage::router())
         .nest("/images", images::routes())
         .layer(ValidateRequestHeaderLayer::custom(
             middleware::validate_origin,
@@ -0,0 +1,234 @@
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    project::Project,
+    project_memory::{
+        AcceptProjectMemoryEntry, CreateProjectMemoryEntry, ProjectMemoryEntry,
+        ProjectMemoryStatus, UpdateProjectMemoryEntry,
+    },
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::project_memory::mirror_memory_file;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_project_memory_entry_middleware};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct ProjectMemoryQuery {
+    pub project_id: Uuid,
+    /// Only list the entries with this status; all entries are listed when unset
+    #[serde(default)]
+    pub status: Option<ProjectMemoryStatus>,
+}
+
+fn require_content(content: &str) -> Result<String, ApiError> {
+    let content = content.trim();
+    if content.is_empty() {
+        return Err(ApiError::BadRequest(
+            "Project memory entry cannot be empty".to_string(),
+        ));
+    }
+    Ok(content.to_string())
+}
+
+/// Rewrite the project's memory file after its accepted entries changed. The
+/// entries are already saved, so a failure is only logged.
+async fn mirror_project_memory(deployment: &DeploymentImpl, project_id: Uuid) {
+    let pool = &deployment.db().pool;
+    let result = match Project::find_by_id(pool, project_id).await {
+        Ok(Some(project)) => mirror_memory_file(pool, deployment.git(), &project).await,
+        Ok(None) => Ok(()),
+        Err(e) => Err(e.into()),
+    };
+    if let Err(e) = result {
+        tracing::warn!(
+            "Failed to mirror project memory of project {}: {}",
+            project_id,
+            e
+        );
+    }
+}
+
+pub async fn get_project_memory_entries(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ProjectMemoryQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<ProjectMemoryEntry>>>, ApiError> {
+    let entries = ProjectMemoryEntry::find_by_project_id(
+        &deployment.db().pool,
+        query.project_id,
+        query.status,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(entries)))
+}
+
+pub async fn get_project_memory_entry(
+    Extension(entry): Extension<ProjectMemoryEntry>,
+) -> Result<ResponseJson<ApiResponse<ProjectMemoryEntry>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+pub async fn create_project_memory_entry(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateProjectMemoryEntry>,
+) -> Result<ResponseJson<ApiResponse<ProjectMemoryEntry>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if Project::find_by_id(pool, payload.project_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::BadRequest("Project not found".to_string()));
+    }
+    let payload = CreateProjectMemoryEntry {
+        project_id: payload.project_id,
+        content: require_content(&payload.content)?,
+    };
+
+    let entry = ProjectMemoryEntry::create(pool, &payload, Uuid::new_v4()).await?;
+    mirror_project_memory(&deployment, entry.project_id).await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_memory_entry_created",
+            serde_json::json!({
+                "entry_id": entry.id.to_string(),
+                "project_id": entry.project_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(entry)))
+}
+
+/// Edit the content, keeping the status
+pub async fn update_project_memory_entry(
+    Extension(entry): Extension<ProjectMemoryEntry>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectMemoryEntry>,
+) -> Result<ResponseJson<ApiResponse<ProjectMemoryEntry>>, ApiError> {
+    let content = require_content(&payload.content)?;
+    let updated = ProjectMemoryEntry::update(
+        &deployment.db().pool,
+        entry.id,
+        entry.status,
+        Some(&content),
+    )
+    .await?;
+    if updated.status == ProjectMemoryStatus::Accepted {
+        mirror_project_memory(&deployment, updated.project_id).await;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+/// Accept a candidate, optionally with edited content
+pub async fn accept_project_memory_entry(
+    Extension(entry): Extension<ProjectMemoryEntry>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<AcceptProjectMemoryEntry>,
+) -> Result<ResponseJson<ApiResponse<ProjectMemoryEntry>>, ApiError> {
+    let content = payload
+        .content
+        .as_deref()
+        .map(require_content)
+        .transpose()?;
+    let updated = ProjectMemoryEntry::update(
+        &deployment.db().pool,
+        entry.id,
+        ProjectMemoryStatus::Accepted,
+        content.as_deref(),
+    )
+    .await?;
+    mirror_project_memory(&deployment, updated.project_id).await;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_memory_entry_accepted",
+            serde_json::json!({
+                "entry_id": updated.id.to_string(),
+                "project_id": updated.project_id.to_string(),
+                "edited": content.is_some(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+/// Discard an entry. It stays listed as discarded, so the same summary is not
+/// proposed again.
+pub async fn discard_project_memory_entry(
+    Extension(entry): Extension<ProjectMemoryEntry>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ProjectMemoryEntry>>, ApiError> {
+    let updated = ProjectMemoryEntry::update(
+        &deployment.db().pool,
+        entry.id,
+        ProjectMemoryStatus::Discarded,
+        None,
+    )
+    .await?;
+    if entry.status == ProjectMemoryStatus::Accepted {
+        mirror_project_memory(&deployment, updated.project_id).await;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_memory_entry_discarded",
+            serde_json::json!({
+                "entry_id": updated.id.to_string(),
+                "project_id": updated.project_id.to_string(),
+                "was_accepted": entry.status == ProjectMemoryStatus::Accepted,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_project_memory_entry(
+    Extension(entry): Extension<ProjectMemoryEntry>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = ProjectMemoryEntry::delete(&deployment.db().pool, entry.id).await?;
+    if rows_affected == 0 {
+        return Err(ApiError::Database(sqlx::Error::RowNotFound));
+    }
+    if entry.status == ProjectMemoryStatus::Accepted {
+        mirror_project_memory(&deployment, entry.project_id).await;
+    }
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let entry_router = Router::new()
+        .route(
+            "/",
+            get(get_project_memory_entry)
+                .put(update_project_memory_entry)
+                .delete(delete_project_memory_entry),
+        )
+        .route("/accept", post(accept_project_memory_entry))
+        .route("/discard", post(discard_project_memory_entry))
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_project_memory_entry_middleware,
+        ));
+
+    let inner = Router::new()
+        .route(
+            "/",
+            get(get_project_memory_entries).post(create_project_memory_entry),
+        )
+        .nest("/{entry_id}", entry_router);
+
+    Router::new().nest("/project-memory", inner)
+}
@@ -14,6 +14,7 @@ use axum::{
 };
 use db::models::{
     project::{CreateProject, Project, ProjectError, SearchResult, UpdateProject},
+    project_container_config::{ProjectContainerConfig, UpdateProjectContainerConfig},
     project_repo::{CreateProjectRepo, ProjectRepo},
     repo::Repo,
 };
@@ -21,7 +22,9 @@ use deployment::Deployment;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::Deserialize;
 use services::services::{
-    file_search::SearchQuery, project::ProjectServiceError,
+    file_search::SearchQuery,
+    project::ProjectServiceError,
+    project_memory::{mirror_memory_file, validate_memory_file},
     remote_client::CreateRemoteProjectPayload,
 };
 use ts_rs::TS;
@@ -266,12 +269,31 @@ pub async fn update_project(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<UpdateProject>,
 ) -> Result<ResponseJson<ApiResponse<Project>>, StatusCode> {
+    if let Some(Some(memory_file)) = &payload.memory_file
+        && let Err(e) = validate_memory_file(memory_file)
+    {
+        tracing::warn!("Rejected project update: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let pool = &deployment.db().pool;
     match deployment
         .project()
-        .update_project(&deployment.db().pool, &existing_project, payload)
+        .update_project(pool, &existing_project, payload)
         .await
     {
-        Ok(project) => Ok(ResponseJson(ApiResponse::success(project))),
+        Ok(project) => {
+            if project.memory_file != existing_project.memory_file
+                && let Err(e) = mirror_memory_file(pool, deployment.git(), &project).await
+            {
+                tracing::warn!(
+                    "Failed to mirror project memory of project {}: {}",
+                    project.id,
+                    e
+                );
+            }
+            Ok(ResponseJson(ApiResponse::success(project)))
+        }
         Err(e) => {
             tracing::error!("Failed to update project: {}", e);
             Err(StatusCode::INTERNAL_SERVER_ERROR)
@@ -568,6 +590,70 @@ pub async fn get_project_repository(
     }
 }
 
+pub async fn get_project_container_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<ProjectContainerConfig>>>, ApiError> {
+    let config =
+        ProjectContainerConfig::find_by_project_id(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+pub async fn update_project_container_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateProjectContainerConfig>,
+) -> Result<ResponseJson<ApiResponse<ProjectContainerConfig>>, ApiError> {
+    if payload.image.trim().is_empty() {
+        return Err(ApiError::BadRequest(
+            "Container image is required".to_string(),
+        ));
+    }
+    if let Some(name) = payload
+        .env_passthrough
+        .iter()
+        .flatten()
+        .find(|name| !is_env_name(name))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid environment variable name: {name}"
+        )));
+    }
+
+    let config =
+        ProjectContainerConfig::upsert(&deployment.db().pool, project.id, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "project_container_config_updated",
+            serde_json::json!({
+                "project_id": project.id.to_string(),
+                "enabled": config.enabled,
+                "runtime": config.runtime,
+                "network_enabled": config.network_enabled,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(config)))
+}
+
+pub async fn delete_project_container_config(
+    Extension(project): Extension<Project>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ProjectContainerConfig::delete(&deployment.db().pool, project.id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let project_id_router = Router::new()
         .route(
@@ -586,6 +672,12 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/repositories",
             get(get_project_repositories).post(add_project_repository),
         )
+        .route(
+            "/container-config",
+            get(get_project_container_config)
+                .put(update_project_container_config)
+                .delete(delete_project_container_config),
+        )
         .layer(from_fn_with_state(
             deployment.clone(),
             load_project_middleware,
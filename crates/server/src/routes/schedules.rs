@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::{
+    project::Project,
+    project_repo::ProjectRepo,
+    task_schedule::{CreateTaskSchedule, ScheduleRepo, TaskSchedule, UpdateTaskSchedule},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::scheduler::next_run_after;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_task_schedule_middleware};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct TaskScheduleQuery {
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+}
+
+/// Validate the expression and compute the first run, or `None` when disabled
+fn first_run(cron_expression: &str, enabled: bool) -> Result<Option<DateTime<Utc>>, ApiError> {
+    let next = next_run_after(cron_expression, Utc::now())?;
+    if enabled && next.is_none() {
+        return Err(ApiError::BadRequest(format!(
+            "Cron expression '{cron_expression}' never fires"
+        )));
+    }
+    Ok(next.filter(|_| enabled))
+}
+
+/// Scheduled tasks may only run against repositories of their project
+async fn validate_repos(
+    deployment: &DeploymentImpl,
+    project_id: Uuid,
+    repos: &[ScheduleRepo],
+) -> Result<(), ApiError> {
+    if repos.is_empty() {
+        return Err(ApiError::BadRequest(
+            "A schedule needs at least one repository".to_string(),
+        ));
+    }
+    let project_repo_ids: HashSet<Uuid> =
+        ProjectRepo::find_repos_for_project(&deployment.db().pool, project_id)
+            .await?
+            .into_iter()
+            .map(|repo| repo.id)
+            .collect();
+    if let Some(repo) = repos
+        .iter()
+        .find(|repo| !project_repo_ids.contains(&repo.repo_id))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Repository {} does not belong to the project",
+            repo.repo_id
+        )));
+    }
+    Ok(())
+}
+
+pub async fn get_task_schedules(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskScheduleQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskSchedule>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let schedules = match query.project_id {
+        Some(project_id) => TaskSchedule::find_by_project_id(pool, project_id).await?,
+        None => TaskSchedule::find_all(pool).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(schedules)))
+}
+
+pub async fn get_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn create_task_schedule(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if Project::find_by_id(pool, payload.project_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::BadRequest("Project not found".to_string()));
+    }
+    validate_repos(&deployment, payload.project_id, &payload.repos).await?;
+    let next_run_at = first_run(&payload.cron_expression, payload.enabled.unwrap_or(true))?;
+
+    let schedule = TaskSchedule::create(pool, &payload, Uuid::new_v4(), next_run_at).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_schedule_created",
+            serde_json::json!({
+                "schedule_id": schedule.id.to_string(),
+                "project_id": schedule.project_id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(schedule)))
+}
+
+pub async fn update_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskSchedule>,
+) -> Result<ResponseJson<ApiResponse<TaskSchedule>>, ApiError> {
+    if let Some(repos) = &payload.repos {
+        validate_repos(&deployment, schedule.project_id, repos).await?;
+    }
+    let cron_expression = payload
+        .cron_expression
+        .as_deref()
+        .unwrap_or(&schedule.cron_expression);
+    let next_run_at = first_run(cron_expression, payload.enabled.unwrap_or(schedule.enabled))?;
+
+    let updated =
+        TaskSchedule::update(&deployment.db().pool, &schedule, &payload, next_run_at).await?;
+
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_task_schedule(
+    Extension(schedule): Extension<TaskSchedule>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = TaskSchedule::delete(&deployment.db().pool, schedule.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let schedule_router = Router::new()
+        .route(
+            "/",
+            get(get_task_schedule)
+                .put(update_task_schedule)
+                .delete(delete_task_schedule),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_schedule_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_task_schedules).post(create_task_schedule))
+        .nest("/{schedule_id}", schedule_router);
+
+    Router::new().nest("/schedules", inner)
+}
@@ -47,7 +47,7 @@ pub async fn create_scratch(
 ) -> Result<ResponseJson<ApiResponse<Scratch>>, ApiError> {
     // Reject edits to draft_follow_up if a message is queued for this task attempt
     if matches!(scratch_type, ScratchType::DraftFollowUp)
-        && deployment.queued_message_service().has_queued(id)
+        && deployment.queued_message_service().has_queued(id).await?
     {
         return Err(ApiError::BadRequest(
             "Cannot edit scratch while a message is queued".to_string(),
@@ -71,7 +71,7 @@ pub async fn update_scratch(
 ) -> Result<ResponseJson<ApiResponse<Scratch>>, ApiError> {
     // Reject edits to draft_follow_up if a message is queued for this task attempt
     if matches!(scratch_type, ScratchType::DraftFollowUp)
-        && deployment.queued_message_service().has_queued(id)
+        && deployment.queued_message_service().has_queued(id).await?
     {
         return Err(ApiError::BadRequest(
             "Cannot edit scratch while a message is queued".to_string(),
@@ -0,0 +1,52 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::search::{SearchFilters, SearchIndex, SearchResult};
+use deployment::Deployment;
+use serde::Deserialize;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 200;
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SearchQuery {
+    pub q: String,
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    /// Only include hits created at or after this time
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// Only include hits created at or before this time
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+pub async fn search(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<SearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<SearchResult>>>, ApiError> {
+    let filters = SearchFilters {
+        project_id: query.project_id,
+        from: query.from,
+        to: query.to,
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let results =
+        SearchIndex::search(&deployment.db().pool, &query.q, &filters, limit.into()).await?;
+    Ok(ResponseJson(ApiResponse::success(results)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/search", get(search))
+}
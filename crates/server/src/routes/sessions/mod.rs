@@ -21,6 +21,7 @@ use executors::{
     actions::{
         ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
     },
+    executors::AppendPrompt,
     profile::ExecutorProfileId,
 };
 use serde::Deserialize;
@@ -30,8 +31,12 @@ use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_session_middleware,
-    routes::task_attempts::util::restore_worktrees_to_process,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_session_middleware,
+    routes::task_attempts::util::{
+        configured_coding_agent, restore_worktrees_to_process, restore_worktrees_to_snapshot,
+    },
 };
 
 #[derive(Debug, Deserialize)]
@@ -119,6 +124,7 @@ pub async fn follow_up(
         .await?;
 
     let executor_profile_id = payload.executor_profile_id;
+    configured_coding_agent(&executor_profile_id)?;
 
     // Validate executor matches session if session has prior executions
     let expected_executor: Option<String> =
@@ -183,7 +189,7 @@ pub async fn follow_up(
     let prompt = payload.prompt;
 
     let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
-    let cleanup_action = deployment.container().cleanup_actions_for_repos(&repos);
+    let post_agent_action = deployment.container().post_agent_actions_for_repos(&repos);
 
     let working_dir = workspace
         .agent_working_dir
@@ -204,11 +210,12 @@ pub async fn follow_up(
                 prompt,
                 executor_profile_id: executor_profile_id.clone(),
                 working_dir,
+                project_memory: AppendPrompt::default(),
             },
         )
     };
 
-    let action = ExecutorAction::new(action_type, cleanup_action.map(Box::new));
+    let action = ExecutorAction::new(action_type, post_agent_action.map(Box::new));
 
     let execution_process = deployment
         .container()
@@ -236,11 +243,83 @@ pub async fn follow_up(
     Ok(ResponseJson(ApiResponse::success(execution_process)))
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct RestoreSessionRequest {
+    /// Coding agent turn to roll the worktrees and conversation back to
+    pub execution_process_id: Uuid,
+    pub force_when_dirty: Option<bool>,
+}
+
+/// Roll a session back to the end of one of its coding agent turns: every
+/// worktree gets the snapshot taken then, and the later processes are dropped.
+/// The next follow-up resumes the agent from that turn.
+pub async fn restore_session(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<RestoreSessionRequest>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace = Workspace::find_by_id(pool, session.workspace_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Workspace not found".to_string(),
+        )))?;
+
+    let process = ExecutionProcess::find_by_id(pool, payload.execution_process_id)
+        .await?
+        .ok_or(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Process not found".to_string(),
+        )))?;
+    if process.session_id != session.id || process.dropped {
+        return Err(ApiError::Workspace(WorkspaceError::ValidationError(
+            "Process does not belong to this session".to_string(),
+        )));
+    }
+    if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+        return Err(ApiError::BadRequest(
+            "Only coding agent turns can be restored".to_string(),
+        ));
+    }
+
+    if !payload.force_when_dirty.unwrap_or(false)
+        && !deployment
+            .container()
+            .is_container_clean(&workspace)
+            .await?
+    {
+        return Err(ApiError::Conflict(
+            "Workspace has uncommitted changes that restoring would discard".to_string(),
+        ));
+    }
+
+    // Stop any running processes for this workspace (except dev server)
+    deployment.container().try_stop(&workspace, false).await;
+
+    restore_worktrees_to_snapshot(&deployment, pool, &workspace, process.id).await?;
+    let dropped = ExecutionProcess::drop_after(pool, session.id, process.id).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "session_restored",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "session_id": session.id.to_string(),
+                "execution_process_id": process.id.to_string(),
+                "dropped_processes": dropped,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let session_id_router = Router::new()
         .route("/", get(get_session))
         .route("/follow-up", post(follow_up))
         .route("/review", post(review::start_review))
+        .route("/restore", post(restore_session))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
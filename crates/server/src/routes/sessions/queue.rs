@@ -1,38 +1,60 @@
 use axum::{
-    Extension, Json, Router, extract::State, middleware::from_fn_with_state,
-    response::Json as ResponseJson, routing::get,
+    Extension, Json, Router,
+    extract::{Path, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::{get, put},
 };
 use db::models::{scratch::DraftFollowUpData, session::Session};
 use deployment::Deployment;
 use executors::profile::ExecutorProfileId;
 use serde::Deserialize;
-use services::services::queued_message::QueueStatus;
+use services::services::queued_message::{QueueStatus, QueuedMessage};
 use ts_rs::TS;
 use utils::response::ApiResponse;
+use uuid::Uuid;
 
-use crate::{DeploymentImpl, error::ApiError, middleware::load_session_middleware};
+use crate::{
+    DeploymentImpl, error::ApiError, middleware::load_session_middleware,
+    routes::task_attempts::util::configured_coding_agent,
+};
 
 /// Request body for queueing a follow-up message
 #[derive(Debug, Deserialize, TS)]
 pub struct QueueMessageRequest {
     pub message: String,
     pub executor_profile_id: ExecutorProfileId,
+    /// Queue a code review instead of a follow-up
+    #[serde(default)]
+    #[ts(optional)]
+    pub review: Option<bool>,
 }
 
-/// Queue a follow-up message to be executed when the current execution finishes
+/// Request body for reordering a session's queue
+#[derive(Debug, Deserialize, TS)]
+pub struct ReorderQueueRequest {
+    /// Every queued message id, in the order they should be sent
+    pub message_ids: Vec<Uuid>,
+}
+
+/// Queue a follow-up message to be executed when the current execution finishes.
+/// Messages are appended to the session's queue and sent one per completed turn.
 pub async fn queue_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<QueueMessageRequest>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    configured_coding_agent(&payload.executor_profile_id)?;
     let data = DraftFollowUpData {
         message: payload.message,
         executor_profile_id: payload.executor_profile_id,
+        review: payload.review,
     };
 
-    let queued = deployment
+    deployment
         .queued_message_service()
-        .queue_message(session.id, data);
+        .queue_message(session.id, data)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -44,19 +66,23 @@ pub async fn queue_message(
         )
         .await;
 
-    Ok(ResponseJson(ApiResponse::success(QueueStatus::Queued {
-        message: queued,
-    })))
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(status)))
 }
 
-/// Cancel a queued follow-up message
+/// Cancel all queued follow-up messages
 pub async fn cancel_queued_message(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
     deployment
         .queued_message_service()
-        .cancel_queued(session.id);
+        .cancel_queued(session.id)
+        .await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -76,21 +102,89 @@ pub async fn get_queue_status(
     Extension(session): Extension<Session>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
-    let status = deployment.queued_message_service().get_status(session.id);
+    let status = deployment
+        .queued_message_service()
+        .get_status(session.id)
+        .await?;
 
     Ok(ResponseJson(ApiResponse::success(status)))
 }
 
+/// Change the order in which queued messages will be sent
+pub async fn reorder_queue(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ReorderQueueRequest>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let messages = deployment
+        .queued_message_service()
+        .reorder(session.id, &payload.message_ids)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(messages.into())))
+}
+
+/// Edit a queued message in place
+pub async fn update_queued_message(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<QueueMessageRequest>,
+) -> Result<ResponseJson<ApiResponse<QueuedMessage>>, ApiError> {
+    let queue = deployment.queued_message_service();
+    // Edits that don't say otherwise keep a queued review a review
+    let review = match payload.review {
+        Some(review) => Some(review),
+        None => queue
+            .get_queued(session_id)
+            .await?
+            .into_iter()
+            .find(|queued| queued.id == message_id)
+            .and_then(|queued| queued.data.review),
+    };
+    configured_coding_agent(&payload.executor_profile_id)?;
+    let data = DraftFollowUpData {
+        message: payload.message,
+        executor_profile_id: payload.executor_profile_id,
+        review,
+    };
+
+    let message = queue.update_queued(session_id, message_id, data).await?;
+
+    Ok(ResponseJson(ApiResponse::success(message)))
+}
+
+/// Remove a single message from the queue
+pub async fn delete_queued_message(
+    State(deployment): State<DeploymentImpl>,
+    Path((session_id, message_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<QueueStatus>>, ApiError> {
+    let queue = deployment.queued_message_service();
+    queue.remove_queued(session_id, message_id).await?;
+
+    Ok(ResponseJson(ApiResponse::success(
+        queue.get_status(session_id).await?,
+    )))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
-    Router::new()
+    let queue_router = Router::new()
         .route(
             "/",
             get(get_queue_status)
                 .post(queue_message)
                 .delete(cancel_queued_message),
         )
+        .route("/order", put(reorder_queue))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
-        ))
+        ));
+
+    // Item routes carry a second path parameter, so they sit outside the session loader
+    let item_router = Router::new().route(
+        "/{message_id}",
+        put(update_queued_message).delete(delete_queued_message),
+    );
+
+    queue_router.merge(item_router)
 }
@@ -21,7 +21,9 @@ use services::services::container::ContainerService;
 use ts_rs::TS;
 use utils::response::ApiResponse;
 
-use crate::{DeploymentImpl, error::ApiError};
+use crate::{
+    DeploymentImpl, error::ApiError, routes::task_attempts::util::configured_coding_agent,
+};
 
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct StartReviewRequest {
@@ -45,6 +47,7 @@ pub async fn start_review(
     Json(payload): Json<StartReviewRequest>,
 ) -> Result<ResponseJson<ApiResponse<ExecutionProcess, ReviewError>>, ApiError> {
     let pool = &deployment.db().pool;
+    configured_coding_agent(&payload.executor_profile_id)?;
 
     let workspace = Workspace::find_by_id(pool, session.workspace_id)
         .await?
@@ -3,6 +3,8 @@ pub mod cursor_setup;
 pub mod gh_cli_setup;
 pub mod images;
 pub mod pr;
+pub mod race;
+pub mod stack;
 pub mod util;
 pub mod workspace_summary;
 
@@ -24,6 +26,7 @@ use axum::{
 };
 use db::models::{
     coding_agent_turn::CodingAgentTurn,
+    conflict_resolution::ConflictResolution,
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
     project::SearchResult,
@@ -31,7 +34,9 @@ use db::models::{
     session::{CreateSession, Session},
     task::{Task, TaskRelationships, TaskStatus},
     workspace::{CreateWorkspace, Workspace, WorkspaceError},
+    workspace_race::WorkspaceRace,
     workspace_repo::{CreateWorkspaceRepo, RepoWithTargetBranch, WorkspaceRepo},
+    workspace_verification::WorkspaceVerification,
 };
 use deployment::Deployment;
 use executors::{
@@ -40,18 +45,22 @@ use executors::{
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
     executors::{CodingAgent, ExecutorError},
-    profile::{ExecutorConfigs, ExecutorProfileId},
+    profile::ExecutorProfileId,
 };
 use git::{ConflictOp, DiffTarget, GitCliError, GitServiceError};
 use git2::BranchType;
 use serde::{Deserialize, Serialize};
-use services::services::{
-    commit_message::{
-        build_branch_commit_prompt, build_diff_context, generate_commit_message, summarize_diffs,
+use services::{
+    ai::LlmError,
+    services::{
+        commit_message::{
+            CommitMessageError, build_branch_commit_prompt, build_diff_context, commit_language,
+            generate_commit_message, summarize_diffs,
+        },
+        container::ContainerService,
+        file_search::SearchQuery,
+        workspace_manager::WorkspaceManager,
     },
-    container::ContainerService,
-    file_search::SearchQuery,
-    workspace_manager::WorkspaceManager,
 };
 use sqlx::Error as SqlxError;
 use ts_rs::TS;
@@ -75,6 +84,11 @@ pub struct AbortConflictsRequest {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, Serialize, TS)]
+pub struct ResolveConflictsRequest {
+    pub repo_id: Uuid,
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 #[ts(tag = "type", rename_all = "snake_case")]
@@ -159,6 +173,16 @@ pub async fn update_workspace(
 pub struct CreateTaskAttemptBody {
     pub task_id: Uuid,
     pub executor_profile_id: ExecutorProfileId,
+    /// Extra executors to race against `executor_profile_id`.
+    /// Each one gets its own workspace and branch, grouped into a single race.
+    #[serde(default)]
+    #[ts(optional)]
+    pub race_executor_profile_ids: Option<Vec<ExecutorProfileId>>,
+    /// Workspace whose branch the attempt builds on instead of the repos'
+    /// target branches, in the repos both workspaces share
+    #[serde(default)]
+    #[ts(optional)]
+    pub base_workspace_id: Option<Uuid>,
     pub repos: Vec<WorkspaceRepoInput>,
 }
 
@@ -181,8 +205,6 @@ pub async fn create_task_attempt(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTaskAttemptBody>,
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
-    let executor_profile_id = payload.executor_profile_id.clone();
-
     if payload.repos.is_empty() {
         return Err(ApiError::BadRequest(
             "At least one repository is required".to_string(),
@@ -206,56 +228,110 @@ pub async fn create_task_attempt(
         None
     };
 
-    let attempt_id = Uuid::new_v4();
-    let git_branch_name = deployment
-        .container()
-        .git_branch_from_workspace(&attempt_id, &task.title)
-        .await;
-
-    let workspace = Workspace::create(
-        pool,
-        &CreateWorkspace {
-            branch: git_branch_name.clone(),
-            agent_working_dir,
-        },
-        attempt_id,
-        payload.task_id,
-    )
-    .await?;
+    let stack_base = match payload.base_workspace_id {
+        Some(base_workspace_id) => {
+            Some(stack::StackBase::load(&deployment, base_workspace_id, &task).await?)
+        }
+        None => None,
+    };
 
     let workspace_repos: Vec<CreateWorkspaceRepo> = payload
         .repos
         .iter()
         .map(|r| CreateWorkspaceRepo {
             repo_id: r.repo_id,
-            target_branch: r.target_branch.clone(),
+            target_branch: stack_base
+                .as_ref()
+                .and_then(|base| base.branch_for(r.repo_id))
+                .unwrap_or(&r.target_branch)
+                .to_string(),
         })
         .collect();
-
-    WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
-    if let Err(err) = deployment
-        .container()
-        .start_workspace(&workspace, executor_profile_id.clone())
-        .await
-    {
-        tracing::error!("Failed to start task attempt: {}", err);
+    let repo_ids: Vec<Uuid> = workspace_repos.iter().map(|r| r.repo_id).collect();
+
+    let executor_profile_ids: Vec<ExecutorProfileId> =
+        std::iter::once(payload.executor_profile_id.clone())
+            .chain(
+                payload
+                    .race_executor_profile_ids
+                    .clone()
+                    .unwrap_or_default(),
+            )
+            .collect();
+    for executor_profile_id in &executor_profile_ids {
+        util::configured_coding_agent(executor_profile_id)?;
     }
 
-    deployment
-        .track_if_analytics_allowed(
-            "task_attempt_started",
-            serde_json::json!({
-                "task_id": workspace.task_id.to_string(),
-                "variant": &executor_profile_id.variant,
-                "executor": &executor_profile_id.executor,
-                "workspace_id": workspace.id.to_string(),
-                "repository_count": payload.repos.len(),
-            }),
+    // One workspace per executor, each on its own branch
+    let mut workspaces = Vec::with_capacity(executor_profile_ids.len());
+    for _ in &executor_profile_ids {
+        let attempt_id = Uuid::new_v4();
+        let git_branch_name = deployment
+            .container()
+            .git_branch_from_workspace(&attempt_id, &task.title)
+            .await;
+
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch: git_branch_name,
+                agent_working_dir: agent_working_dir.clone(),
+            },
+            attempt_id,
+            payload.task_id,
         )
-        .await;
+        .await?;
+        WorkspaceRepo::create_many(pool, workspace.id, &workspace_repos).await?;
+        if let Some(base) = &stack_base {
+            base.record(&deployment, workspace.id, &repo_ids).await?;
+        }
+        workspaces.push(workspace);
+    }
+
+    if workspaces.len() > 1 {
+        let workspace_ids: Vec<Uuid> = workspaces.iter().map(|w| w.id).collect();
+        let race = WorkspaceRace::create(pool, task.id, &workspace_ids).await?;
+        tracing::info!(
+            "Racing {} executors on task {} (race {})",
+            workspaces.len(),
+            task.id,
+            race.id
+        );
+    }
+
+    for (workspace, executor_profile_id) in workspaces.iter().zip(&executor_profile_ids) {
+        if let Err(err) = deployment
+            .container()
+            .start_workspace(workspace, executor_profile_id.clone())
+            .await
+        {
+            tracing::error!("Failed to start task attempt: {}", err);
+        }
+
+        deployment
+            .track_if_analytics_allowed(
+                "task_attempt_started",
+                serde_json::json!({
+                    "task_id": workspace.task_id.to_string(),
+                    "variant": &executor_profile_id.variant,
+                    "executor": &executor_profile_id.executor,
+                    "workspace_id": workspace.id.to_string(),
+                    "repository_count": payload.repos.len(),
+                    "race_size": workspaces.len(),
+                    "stacked": stack_base.is_some(),
+                }),
+            )
+            .await;
+    }
 
     tracing::info!("Created attempt for task {}", task.id);
 
+    // The first workspace belongs to the primary executor; the rest are
+    // reachable through the race endpoints.
+    let workspace = workspaces
+        .into_iter()
+        .next()
+        .ok_or(SqlxError::RowNotFound)?;
     Ok(ResponseJson(ApiResponse::success(workspace)))
 }
 
@@ -266,8 +342,7 @@ pub async fn run_agent_setup(
     Json(payload): Json<RunAgentSetupRequest>,
 ) -> Result<ResponseJson<ApiResponse<RunAgentSetupResponse>>, ApiError> {
     let executor_profile_id = payload.executor_profile_id;
-    let config = ExecutorConfigs::get_cached();
-    let coding_agent = config.get_coding_agent_or_default(&executor_profile_id);
+    let coding_agent = util::configured_coding_agent(&executor_profile_id)?;
     match coding_agent {
         CodingAgent::CursorAgent(_) => {
             cursor_setup::run_cursor_setup(&deployment, &workspace).await?;
@@ -355,6 +430,60 @@ async fn handle_task_attempt_diff_ws(
     Ok(())
 }
 
+/// Stream the status of the workspace's dev servers: readiness, port, restarts
+pub async fn stream_dev_servers_ws(
+    ws: WebSocketUpgrade,
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| async move {
+        if let Err(e) = handle_dev_servers_ws(socket, deployment, workspace.id).await {
+            tracing::warn!("dev servers WS closed: {}", e);
+        }
+    })
+}
+
+async fn handle_dev_servers_ws(
+    socket: WebSocket,
+    deployment: DeploymentImpl,
+    workspace_id: Uuid,
+) -> anyhow::Result<()> {
+    use futures_util::{SinkExt, StreamExt, TryStreamExt};
+
+    let mut stream = deployment
+        .events()
+        .stream_dev_servers_for_workspace_raw(workspace_id)
+        .await?
+        .map_ok(|msg| msg.to_ws_message_unchecked());
+
+    let (mut sender, mut receiver) = socket.split();
+
+    loop {
+        tokio::select! {
+            item = stream.next() => {
+                match item {
+                    Some(Ok(msg)) => {
+                        if sender.send(msg).await.is_err() {
+                            break;
+                        }
+                    }
+                    Some(Err(e)) => {
+                        tracing::error!("stream error: {}", e);
+                        break;
+                    }
+                    None => break,
+                }
+            }
+            msg = receiver.next() => {
+                if msg.is_none() {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 pub async fn stream_workspaces_ws(
     ws: WebSocketUpgrade,
     Query(query): Query<WorkspaceStreamQuery>,
@@ -577,6 +706,7 @@ pub async fn merge_task_attempt(
         branch_name: &workspace.branch,
         base_branch: &workspace_repo.target_branch,
     }, None)?;
+    let llm = deployment.config().read().await.llm.clone();
     let diff_summary = summarize_diffs(&diffs);
     let diff_context = build_diff_context(&diffs);
     let branch_prompt = build_branch_commit_prompt(
@@ -585,13 +715,18 @@ pub async fn merge_task_attempt(
         &workspace_repo.target_branch,
         &diff_summary,
         &diff_context,
+        &commit_language(&llm),
     );
-    let branch_commit_message = generate_commit_message(&branch_prompt)
-        .await
-        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
-    deployment
-        .git()
-        .amend_commit_message(&worktree_path, &branch_commit_message)?;
+    // Without a configured provider the branch keeps the agent's own commit message
+    match generate_commit_message(&llm, &branch_prompt).await {
+        Ok(branch_commit_message) => {
+            deployment
+                .git()
+                .amend_commit_message(&worktree_path, &branch_commit_message)?;
+        }
+        Err(CommitMessageError::Llm(LlmError::NotConfigured)) => {}
+        Err(e) => return Err(ApiError::BadRequest(e.to_string())),
+    }
 
     let merge_commit_id = deployment.git().merge_changes(
         &repo.path,
@@ -609,7 +744,10 @@ pub async fn merge_task_attempt(
         &merge_commit_id,
     )
     .await?;
-    Task::update_status(pool, task.id, TaskStatus::Done).await?;
+    deployment
+        .container()
+        .update_task_status(task.id, TaskStatus::Done)
+        .await?;
     if !workspace.pinned {
         Workspace::set_archived(pool, workspace.id, true).await?;
     }
@@ -726,7 +864,7 @@ pub async fn revert_merge(
 #[axum::debug_handler]
 pub async fn generate_merge_commit_message(
     Extension(_workspace): Extension<Workspace>,
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
     Json(request): Json<GenerateMergeCommitMessageRequest>,
 ) -> Result<ResponseJson<ApiResponse<GenerateMergeCommitMessageResponse>>, ApiError> {
     if request.prompt.trim().is_empty() {
@@ -746,7 +884,8 @@ pub async fn generate_merge_commit_message(
         prompt.push_str(diff_context.trim());
     }
 
-    let message = generate_commit_message(&prompt)
+    let llm = deployment.config().read().await.llm.clone();
+    let message = generate_commit_message(&llm, &prompt)
         .await
         .map_err(|e| ApiError::BadRequest(e.to_string()))?;
 
@@ -847,7 +986,8 @@ pub async fn commit_worktree_handler(
         )));
     }
 
-    // Generate commit message using DeepSeek
+    // Generate commit message using the configured LLM provider
+    let llm = deployment.config().read().await.llm.clone();
     let diff_summary = summarize_diffs(&diffs);
     let diff_context = build_diff_context(&diffs);
     let prompt = build_branch_commit_prompt(
@@ -856,9 +996,10 @@ pub async fn commit_worktree_handler(
         &workspace_repo.target_branch,
         &diff_summary,
         &diff_context,
+        &commit_language(&llm),
     );
 
-    let commit_message = generate_commit_message(&prompt)
+    let commit_message = generate_commit_message(&llm, &prompt)
         .await
         .map_err(|e| ApiError::BadRequest(format!("Failed to generate commit message: {}", e)))?;
 
@@ -1303,6 +1444,8 @@ pub struct BranchStatus {
     pub conflict_op: Option<ConflictOp>,
     /// List of files currently in conflicted (unmerged) state
     pub conflicted_files: Vec<String>,
+    /// Latest resolution of this worktree's conflicts by the coding agent
+    pub conflict_resolution: Option<ConflictResolution>,
     /// True if the target branch is a remote branch (merging not allowed, must use PR)
     pub is_target_remote: bool,
 }
@@ -1348,6 +1491,13 @@ pub async fn get_task_attempt_branch_status(
                 acc
             });
 
+    let mut conflict_resolutions: HashMap<Uuid, ConflictResolution> =
+        ConflictResolution::find_by_workspace_id(pool, workspace.id)
+            .await?
+            .into_iter()
+            .map(|resolution| (resolution.repo_id, resolution))
+            .collect();
+
     let mut results = Vec::with_capacity(repositories.len());
 
     for repo in repositories {
@@ -1453,6 +1603,7 @@ pub async fn get_task_attempt_branch_status(
                 is_rebase_in_progress,
                 conflict_op,
                 conflicted_files,
+                conflict_resolution: conflict_resolutions.remove(&repo.id),
                 is_target_remote: target_branch_type == BranchType::Remote,
             },
         });
@@ -1813,6 +1964,50 @@ pub async fn abort_conflicts_task_attempt(
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Hand the conflicts of a paused rebase or merge to the coding agent. The
+/// operation is continued once the agent is done and no conflict markers
+/// remain.
+#[axum::debug_handler]
+pub async fn resolve_conflicts_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<ResolveConflictsRequest>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Err(ApiError::Conflict(
+            "A process is already running in this workspace".to_string(),
+        ));
+    }
+
+    let execution_process = deployment
+        .container()
+        .start_conflict_resolution(&workspace, &repo)
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_conflicts_resolution_started",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
 #[axum::debug_handler]
 pub async fn start_dev_server(
     Extension(workspace): Extension<Workspace>,
@@ -2192,6 +2387,16 @@ pub async fn get_task_attempt_repos(
     Ok(ResponseJson(ApiResponse::success(repos)))
 }
 
+/// Result of the latest verify script run, if the workspace's repos have any
+pub async fn get_task_attempt_verification(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<WorkspaceVerification>>>, ApiError> {
+    let verification =
+        WorkspaceVerification::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(verification)))
+}
+
 pub async fn get_worktree_commits_handler(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -2391,6 +2596,8 @@ pub async fn delete_workspace(
         )
         .await;
 
+    util::delete_workspace_snapshots(&deployment, workspace.id, &repositories);
+
     // Spawn background cleanup task for filesystem resources
     if let Some(workspace_dir) = workspace_dir {
         let workspace_id = workspace.id;
@@ -2446,6 +2653,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/run-agent-setup", post(run_agent_setup))
         .route("/gh-cli-setup", post(gh_cli_setup_handler))
         .route("/start-dev-server", post(start_dev_server))
+        .route("/dev-servers/stream/ws", get(stream_dev_servers_ws))
         .route("/run-setup-script", post(run_setup_script))
         .route("/run-cleanup-script", post(run_cleanup_script))
         .route("/branch-status", get(get_task_attempt_branch_status))
@@ -2461,7 +2669,9 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/push", post(push_task_attempt_branch))
         .route("/push/force", post(force_push_task_attempt_branch))
         .route("/rebase", post(rebase_task_attempt))
+        .route("/restack", post(stack::restack_task_attempt))
         .route("/conflicts/abort", post(abort_conflicts_task_attempt))
+        .route("/conflicts/resolve", post(resolve_conflicts_task_attempt))
         .route("/pr", post(pr::create_pr))
         .route("/pr/attach", post(pr::attach_existing_pr))
         .route("/pr/comments", get(pr::get_pr_comments))
@@ -2471,11 +2681,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/change-target-branch", post(change_target_branch))
         .route("/rename-branch", post(rename_branch))
         .route("/repos", get(get_task_attempt_repos))
+        .route("/verification", get(get_task_attempt_verification))
         .route("/worktree-commits", get(get_worktree_commits_handler))
         .route("/commit-diff", get(get_commit_diff_handler))
         .route("/search", get(search_workspace_files))
         .route("/first-message", get(get_first_user_message))
         .route("/mark-seen", put(mark_seen))
+        .route("/race", get(race::get_race_comparison))
+        .route("/race/winner", post(race::select_race_winner))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_workspace_middleware,
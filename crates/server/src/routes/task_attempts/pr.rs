@@ -16,17 +16,27 @@ use db::models::{
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
 use deployment::Deployment;
-use executors::actions::{
-    ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
-    coding_agent_initial::CodingAgentInitialRequest,
+use executors::{
+    actions::{
+        ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
+        coding_agent_initial::CodingAgentInitialRequest,
+    },
+    executors::AppendPrompt,
 };
-use git::{GitCliError, GitRemote, GitServiceError};
+use git::{DiffTarget, GitCliError, GitRemote, GitServiceError};
 use serde::{Deserialize, Serialize};
-use services::services::{
-    container::ContainerService,
-    git_host::{
-        self, CreatePrRequest, GitHostError, GitHostProvider, ProviderKind, UnifiedPrComment,
-        github::GhCli,
+use services::{
+    ai::LlmError,
+    services::{
+        commit_message::{
+            CommitMessageError, build_diff_context, build_pr_description_prompt,
+            generate_pr_description, summarize_diffs,
+        },
+        container::ContainerService,
+        git_host::{
+            self, CreatePrRequest, GitHostError, GitHostProvider, ProviderKind, UnifiedPrComment,
+            github::GhCli,
+        },
     },
 };
 use ts_rs::TS;
@@ -176,6 +186,7 @@ async fn trigger_pr_description_follow_up(
             prompt,
             executor_profile_id: executor_profile_id.clone(),
             working_dir,
+            project_memory: AppendPrompt::default(),
         })
     };
 
@@ -194,6 +205,55 @@ async fn trigger_pr_description_follow_up(
     Ok(())
 }
 
+/// Write the PR body with the configured LLM provider. Returns None when no
+/// provider is configured or generation fails, leaving the description to the
+/// coding agent follow-up instead.
+async fn generate_pr_body_with_llm(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    repo: &Repo,
+    target_branch: &str,
+) -> Option<String> {
+    let llm = deployment.config().read().await.llm.clone();
+    let task = workspace
+        .parent_task(&deployment.db().pool)
+        .await
+        .ok()
+        .flatten()?;
+
+    let diffs = match deployment.git().get_diffs(
+        DiffTarget::Branch {
+            repo_path: &repo.path,
+            branch_name: &workspace.branch,
+            base_branch: target_branch,
+        },
+        None,
+    ) {
+        Ok(diffs) => diffs,
+        Err(e) => {
+            tracing::warn!("Failed to diff branch for PR description: {}", e);
+            return None;
+        }
+    };
+
+    let prompt = build_pr_description_prompt(
+        &task.title,
+        task.description.as_deref(),
+        target_branch,
+        &summarize_diffs(&diffs),
+        &build_diff_context(&diffs),
+    );
+
+    match generate_pr_description(&llm, &prompt).await {
+        Ok(description) => Some(description),
+        Err(CommitMessageError::Llm(LlmError::NotConfigured)) => None,
+        Err(e) => {
+            tracing::warn!("Failed to generate PR description with LLM provider: {}", e);
+            None
+        }
+    }
+}
+
 pub async fn create_pr(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -296,10 +356,21 @@ pub async fn create_pr(
 
     let provider = git_host.provider_kind();
 
+    // Only fill in the description when the user left it empty
+    let body_is_empty = request
+        .body
+        .as_deref()
+        .is_none_or(|body| body.trim().is_empty());
+    let generated_body = if request.auto_generate_description && body_is_empty {
+        generate_pr_body_with_llm(&deployment, &workspace, &repo, &target_branch).await
+    } else {
+        None
+    };
+
     // Create the PR
     let pr_request = CreatePrRequest {
         title: request.title.clone(),
-        body: request.body.clone(),
+        body: generated_body.clone().or_else(|| request.body.clone()),
         head_branch: workspace.branch.clone(),
         base_branch: base_branch.clone(),
         draft: request.draft,
@@ -340,8 +411,9 @@ pub async fn create_pr(
                 )
                 .await;
 
-            // Trigger auto-description follow-up if enabled
+            // Trigger auto-description follow-up if enabled and the LLM provider didn't write it
             if request.auto_generate_description
+                && generated_body.is_none()
                 && let Err(e) = trigger_pr_description_follow_up(
                     &deployment,
                     &workspace,
@@ -478,7 +550,10 @@ pub async fn attach_existing_pr(
 
         // If PR is merged, mark task as done and archive workspace
         if matches!(pr_info.status, MergeStatus::Merged) {
-            Task::update_status(pool, task.id, TaskStatus::Done).await?;
+            deployment
+                .container()
+                .update_task_status(task.id, TaskStatus::Done)
+                .await?;
             if !workspace.pinned {
                 Workspace::set_archived(pool, workspace.id, true).await?;
             }
@@ -651,6 +726,7 @@ pub async fn create_workspace_from_pr(
         status: Some(TaskStatus::InProgress),
         parent_workspace_id: None,
         image_ids: None,
+        template: None,
     };
     let task = Task::create(pool, &create_task, task_id).await?;
 
@@ -0,0 +1,182 @@
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use chrono::Utc;
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    workspace::Workspace,
+    workspace_race::WorkspaceRace,
+};
+use deployment::Deployment;
+use executors::{logs::TokenUsageInfo, profile::ExecutorProfileId};
+use serde::Serialize;
+use services::services::container::ContainerService;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{
+    DeploymentImpl,
+    error::ApiError,
+    routes::task_attempts::workspace_summary::{DiffStats, compute_workspace_diff_stats},
+};
+
+/// How one workspace of a race is doing
+#[derive(Debug, Serialize, TS)]
+pub struct RaceEntry {
+    pub workspace_id: Uuid,
+    pub branch: String,
+    pub archived: bool,
+    pub is_winner: bool,
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// Status of the latest coding agent run
+    pub status: Option<ExecutionProcessStatus>,
+    pub exit_code: Option<i64>,
+    /// Wall-clock duration of the latest coding agent run, up to now if still running
+    pub duration_ms: Option<i64>,
+    /// Changes against the target branch, None once the worktree is cleaned up
+    pub diff_stats: Option<DiffStats>,
+    pub token_usage: Option<TokenUsageInfo>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RaceComparison {
+    pub race: WorkspaceRace,
+    pub entries: Vec<RaceEntry>,
+}
+
+async fn find_race(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+) -> Result<WorkspaceRace, ApiError> {
+    WorkspaceRace::find_by_workspace_id(&deployment.db().pool, workspace.id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Workspace is not part of a race".to_string()))
+}
+
+async fn race_entry(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    race: &WorkspaceRace,
+) -> Result<RaceEntry, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let latest = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+        pool,
+        workspace.id,
+        &ExecutionProcessRunReason::CodingAgent,
+    )
+    .await?;
+
+    let (executor_profile_id, token_usage) = match &latest {
+        Some(process) => (
+            ExecutionProcess::latest_executor_profile_for_session(pool, process.session_id).await?,
+            deployment.container().latest_token_usage(&process.id).await,
+        ),
+        None => (None, None),
+    };
+
+    let diff_stats = if workspace.container_ref.is_some() {
+        compute_workspace_diff_stats(deployment, workspace)
+            .await
+            .ok()
+    } else {
+        None
+    };
+
+    Ok(RaceEntry {
+        workspace_id: workspace.id,
+        branch: workspace.branch.clone(),
+        archived: workspace.archived,
+        is_winner: race.winner_workspace_id == Some(workspace.id),
+        executor_profile_id,
+        status: latest.as_ref().map(|p| p.status.clone()),
+        exit_code: latest.as_ref().and_then(|p| p.exit_code),
+        duration_ms: latest
+            .as_ref()
+            .map(|p| (p.completed_at.unwrap_or_else(Utc::now) - p.started_at).num_milliseconds()),
+        diff_stats,
+        token_usage,
+    })
+}
+
+/// Compare diff stats, exit status, token usage and duration of every
+/// workspace racing alongside this one.
+#[axum::debug_handler]
+pub async fn get_race_comparison(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RaceComparison>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let race = find_race(&deployment, &workspace).await?;
+
+    let mut racers = Vec::new();
+    for workspace_id in WorkspaceRace::find_workspace_ids(pool, race.id).await? {
+        if let Some(racer) = Workspace::find_by_id(pool, workspace_id).await? {
+            racers.push(racer);
+        }
+    }
+
+    let entries = futures_util::future::join_all(
+        racers
+            .iter()
+            .map(|racer| race_entry(&deployment, racer, &race)),
+    )
+    .await
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ResponseJson(ApiResponse::success(RaceComparison {
+        race,
+        entries,
+    })))
+}
+
+/// Pick this workspace as the race winner. The other workspaces are
+/// stopped, archived, and their worktrees cleaned up in the background.
+#[axum::debug_handler]
+pub async fn select_race_winner(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceRace>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let race = find_race(&deployment, &workspace).await?;
+
+    let Some(race) = WorkspaceRace::set_winner(pool, race.id, workspace.id).await? else {
+        return Err(ApiError::Conflict(
+            "A winner has already been picked for this race".to_string(),
+        ));
+    };
+
+    let mut losers = Vec::new();
+    for workspace_id in WorkspaceRace::find_workspace_ids(pool, race.id).await? {
+        if workspace_id == workspace.id {
+            continue;
+        }
+        if let Some(loser) = Workspace::find_by_id(pool, workspace_id).await? {
+            Workspace::set_archived(pool, loser.id, true).await?;
+            losers.push(loser);
+        }
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "race_winner_selected",
+            serde_json::json!({
+                "task_id": race.task_id.to_string(),
+                "workspace_id": workspace.id.to_string(),
+                "race_size": losers.len() + 1,
+            }),
+        )
+        .await;
+
+    // Stops running processes and removes the worktrees via WorkspaceManager::cleanup_workspace
+    let deployment = deployment.clone();
+    tokio::spawn(async move {
+        for loser in &losers {
+            if let Err(e) = deployment.container().delete(loser).await {
+                tracing::error!("Failed to clean up race workspace {}: {}", loser.id, e);
+            }
+        }
+    });
+
+    Ok(ResponseJson(ApiResponse::success(race)))
+}
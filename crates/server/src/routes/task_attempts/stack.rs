@@ -0,0 +1,448 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use db::models::{
+    execution_process::ExecutionProcess,
+    merge::{Merge, MergeStatus},
+    repo::{Repo, RepoError},
+    task::Task,
+    workspace::Workspace,
+    workspace_repo::WorkspaceRepo,
+    workspace_stack::WorkspaceStackRepo,
+};
+use deployment::Deployment;
+use git::GitServiceError;
+use serde::Serialize;
+use services::services::container::ContainerService;
+use sqlx::Error as SqlxError;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Workspace a new attempt is stacked on, with the commit its branch is at in
+/// each of its repos
+pub struct StackBase {
+    pub workspace: Workspace,
+    pub base_commits: HashMap<Uuid, String>,
+}
+
+impl StackBase {
+    pub async fn load(
+        deployment: &DeploymentImpl,
+        base_workspace_id: Uuid,
+        task: &Task,
+    ) -> Result<Self, ApiError> {
+        let pool = &deployment.db().pool;
+        let workspace = Workspace::find_by_id(pool, base_workspace_id)
+            .await?
+            .ok_or_else(|| ApiError::BadRequest("Base workspace not found".to_string()))?;
+        let base_task = Task::find_by_id(pool, workspace.task_id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        if base_task.project_id != task.project_id {
+            return Err(ApiError::BadRequest(
+                "The base workspace belongs to another project".to_string(),
+            ));
+        }
+
+        let mut base_commits = HashMap::new();
+        for repo in WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await? {
+            let commit = deployment
+                .git()
+                .get_branch_oid(&repo.path, &workspace.branch)?;
+            base_commits.insert(repo.id, commit);
+        }
+        Ok(Self {
+            workspace,
+            base_commits,
+        })
+    }
+
+    /// Branch the workspace's repo is created from, if it is stacked
+    pub fn branch_for(&self, repo_id: Uuid) -> Option<&str> {
+        self.base_commits
+            .contains_key(&repo_id)
+            .then_some(self.workspace.branch.as_str())
+    }
+
+    /// Record the stacked repos of a workspace created on this base
+    pub async fn record(
+        &self,
+        deployment: &DeploymentImpl,
+        workspace_id: Uuid,
+        repo_ids: &[Uuid],
+    ) -> Result<(), ApiError> {
+        let pool = &deployment.db().pool;
+        for repo_id in repo_ids {
+            if let Some(base_commit) = self.base_commits.get(repo_id) {
+                WorkspaceStackRepo::create(
+                    pool,
+                    workspace_id,
+                    *repo_id,
+                    self.workspace.id,
+                    base_commit,
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RestackStatus {
+    Rebased,
+    /// The base did not move since the last restack
+    UpToDate,
+    /// The rebase stopped on conflicts, which are left to resolve in the
+    /// workspace
+    Conflicts {
+        message: String,
+        conflicted_files: Vec<String>,
+    },
+    /// Not restacked because of its own state or because its base could not
+    /// be restacked
+    Skipped {
+        reason: String,
+    },
+    Failed {
+        message: String,
+    },
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RestackResult {
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub branch: String,
+    /// Branch the workspace is now based on
+    pub base_branch: String,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub status: RestackStatus,
+}
+
+/// Workspaces at the same distance from the restacked workspace; level 1 is
+/// stacked directly on it
+#[derive(Debug, Serialize, TS)]
+pub struct RestackLevel {
+    pub level: usize,
+    pub results: Vec<RestackResult>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct RestackResponse {
+    pub levels: Vec<RestackLevel>,
+}
+
+fn is_merged(merge: &Merge) -> bool {
+    match merge {
+        Merge::Direct(_) => true,
+        Merge::Pr(pr) => matches!(pr.pr_info.status, MergeStatus::Merged),
+    }
+}
+
+/// Rebase one repo of a stacked workspace onto its base's current branch, or
+/// onto the base's target branch once the base was merged
+async fn restack_repo(
+    deployment: &DeploymentImpl,
+    base: &Workspace,
+    base_merged: bool,
+    workspace: &Workspace,
+    stack_repo: &WorkspaceStackRepo,
+) -> Result<RestackResult, ApiError> {
+    let pool = &deployment.db().pool;
+    let repo = Repo::find_by_id(pool, stack_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    // A merged base hands its stacked workspaces over to its own base, or
+    // unstacks them when it had none
+    let (base_branch, new_base_workspace_id) = if base_merged {
+        let base_repo = WorkspaceRepo::find_by_workspace_and_repo_id(pool, base.id, repo.id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+        let grandparent = WorkspaceStackRepo::find_by_workspace_id(pool, base.id)
+            .await?
+            .into_iter()
+            .find(|row| row.repo_id == repo.id)
+            .map(|row| row.base_workspace_id);
+        (base_repo.target_branch, grandparent)
+    } else {
+        (base.branch.clone(), Some(base.id))
+    };
+    let result = |status| RestackResult {
+        workspace_id: workspace.id,
+        repo_id: repo.id,
+        branch: workspace.branch.clone(),
+        base_branch: base_branch.clone(),
+        status,
+    };
+
+    let new_base_commit = deployment.git().get_branch_oid(&repo.path, &base_branch)?;
+    if !base_merged && new_base_commit == stack_repo.base_commit {
+        return Ok(result(RestackStatus::UpToDate));
+    }
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    let worktree_path = Path::new(&container_ref).join(&repo.name);
+    let rebase = deployment.git().rebase_branch(
+        &repo.path,
+        &worktree_path,
+        &base_branch,
+        &stack_repo.base_commit,
+        &workspace.branch,
+    );
+
+    let status = match rebase {
+        Ok(_) => RestackStatus::Rebased,
+        Err(GitServiceError::MergeConflicts {
+            message,
+            conflicted_files,
+        }) => RestackStatus::Conflicts {
+            message,
+            conflicted_files,
+        },
+        Err(e) => {
+            return Ok(result(RestackStatus::Failed {
+                message: e.to_string(),
+            }));
+        }
+    };
+
+    // Conflicted workspaces are recorded on the new base too: resolving the
+    // rebase is what completes the move
+    if base_merged {
+        WorkspaceRepo::update_target_branch(pool, workspace.id, repo.id, &base_branch).await?;
+    }
+    match new_base_workspace_id {
+        Some(base_workspace_id) => {
+            WorkspaceStackRepo::update_base(
+                pool,
+                workspace.id,
+                repo.id,
+                base_workspace_id,
+                &new_base_commit,
+            )
+            .await?
+        }
+        None => WorkspaceStackRepo::delete(pool, workspace.id, repo.id).await?,
+    }
+
+    Ok(result(status))
+}
+
+/// Why the workspaces stacked on a workspace get skipped: the reason it was
+/// skipped itself, or one of its repos not being restacked
+fn stacked_skip_reason(
+    branch: &str,
+    skip_reason: Option<String>,
+    results: &[RestackResult],
+) -> Option<String> {
+    skip_reason.or_else(|| {
+        results
+            .iter()
+            .any(|result| {
+                !matches!(
+                    result.status,
+                    RestackStatus::Rebased | RestackStatus::UpToDate
+                )
+            })
+            .then(|| format!("Its base '{branch}' could not be restacked"))
+    })
+}
+
+/// Move every workspace stacked on this one onto its new base after it was
+/// rebased or merged, level by level. Workspaces whose base ends up with
+/// conflicts are skipped, as are their own stacked workspaces.
+#[axum::debug_handler]
+pub async fn restack_task_attempt(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<RestackResponse>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let mut levels = Vec::new();
+    // Bases of the current level, with the reason their stacked workspaces
+    // are skipped, if any
+    let mut bases: Vec<(Workspace, Option<String>)> = vec![(workspace.clone(), None)];
+    let mut visited = HashSet::from([workspace.id]);
+    while !bases.is_empty() {
+        let mut results = Vec::new();
+        let mut next_bases = Vec::new();
+
+        for (base, skip_reason) in &bases {
+            let merged_repos: HashSet<Uuid> = Merge::find_by_workspace_id(pool, base.id)
+                .await?
+                .iter()
+                .filter(|merge| is_merged(merge))
+                .map(|merge| match merge {
+                    Merge::Direct(direct) => direct.repo_id,
+                    Merge::Pr(pr) => pr.repo_id,
+                })
+                .collect();
+
+            let mut stacked: Vec<(Uuid, Vec<WorkspaceStackRepo>)> = Vec::new();
+            for row in WorkspaceStackRepo::find_by_base_workspace_id(pool, base.id).await? {
+                match stacked.iter_mut().find(|(id, _)| *id == row.workspace_id) {
+                    Some((_, rows)) => rows.push(row),
+                    None => stacked.push((row.workspace_id, vec![row])),
+                }
+            }
+
+            for (workspace_id, rows) in stacked {
+                if !visited.insert(workspace_id) {
+                    continue;
+                }
+                let Some(stacked_workspace) = Workspace::find_by_id(pool, workspace_id).await?
+                else {
+                    continue;
+                };
+
+                let skip_reason = match skip_reason {
+                    Some(reason) => Some(reason.clone()),
+                    None if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                        pool,
+                        workspace_id,
+                    )
+                    .await? =>
+                    {
+                        Some("A coding agent is running in the workspace".to_string())
+                    }
+                    None => None,
+                };
+
+                let mut workspace_results = Vec::with_capacity(rows.len());
+                for row in &rows {
+                    let result = match &skip_reason {
+                        Some(reason) => RestackResult {
+                            workspace_id,
+                            repo_id: row.repo_id,
+                            branch: stacked_workspace.branch.clone(),
+                            base_branch: base.branch.clone(),
+                            status: RestackStatus::Skipped {
+                                reason: reason.clone(),
+                            },
+                        },
+                        None => {
+                            restack_repo(
+                                &deployment,
+                                base,
+                                merged_repos.contains(&row.repo_id),
+                                &stacked_workspace,
+                                row,
+                            )
+                            .await?
+                        }
+                    };
+                    workspace_results.push(result);
+                }
+                let blocked =
+                    stacked_skip_reason(&stacked_workspace.branch, skip_reason, &workspace_results);
+                results.extend(workspace_results);
+                next_bases.push((stacked_workspace, blocked));
+            }
+        }
+
+        if !results.is_empty() {
+            levels.push(RestackLevel {
+                level: levels.len() + 1,
+                results,
+            });
+        }
+        bases = next_bases;
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_attempt_restacked",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "levels": levels.len(),
+                "workspaces_restacked": levels.iter().map(|level| level.results.len()).sum::<usize>(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(RestackResponse {
+        levels,
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(branch: &str, status: RestackStatus) -> RestackResult {
+        RestackResult {
+            workspace_id: Uuid::new_v4(),
+            repo_id: Uuid::new_v4(),
+            branch: branch.to_string(),
+            base_branch: "base".to_string(),
+            status,
+        }
+    }
+
+    #[test]
+    fn restacked_workspaces_do_not_block_the_next_level() {
+        let results = vec![
+            result("level-1", RestackStatus::Rebased),
+            result("level-1", RestackStatus::UpToDate),
+        ];
+        assert_eq!(stacked_skip_reason("level-1", None, &results), None);
+    }
+
+    #[test]
+    fn skipping_propagates_to_every_later_level() {
+        // One conflicted repo blocks the workspaces stacked on level 1
+        let level_1 = vec![
+            result("level-1", RestackStatus::Rebased),
+            result(
+                "level-1",
+                RestackStatus::Conflicts {
+                    message: "conflict".to_string(),
+                    conflicted_files: vec!["a.txt".to_string()],
+                },
+            ),
+        ];
+        let reason = stacked_skip_reason("level-1", None, &level_1).unwrap();
+        assert_eq!(reason, "Its base 'level-1' could not be restacked");
+
+        // Level 2 is skipped for that reason, and so is level 3
+        let level_2 = vec![result(
+            "level-2",
+            RestackStatus::Skipped {
+                reason: reason.clone(),
+            },
+        )];
+        assert_eq!(
+            stacked_skip_reason("level-2", Some(reason.clone()), &level_2),
+            Some(reason)
+        );
+
+        // A workspace skipped for its own state blocks its stacked ones too
+        let running = vec![result(
+            "level-1",
+            RestackStatus::Skipped {
+                reason: "A coding agent is running in the workspace".to_string(),
+            },
+        )];
+        assert_eq!(
+            stacked_skip_reason(
+                "level-1",
+                Some("A coding agent is running in the workspace".to_string()),
+                &running
+            ),
+            Some("A coding agent is running in the workspace".to_string())
+        );
+    }
+}
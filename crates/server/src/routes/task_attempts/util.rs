@@ -2,16 +2,34 @@ use std::path::PathBuf;
 
 use db::models::{
     execution_process::ExecutionProcess, execution_process_repo_state::ExecutionProcessRepoState,
-    workspace::Workspace, workspace_repo::WorkspaceRepo,
+    repo::Repo, workspace::Workspace, workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
-use git::WorktreeResetOptions;
+use executors::{
+    executors::CodingAgent,
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+use git::{SNAPSHOT_REFS_PREFIX, WorktreeResetOptions};
 use services::services::container::ContainerService;
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
 use crate::{DeploymentImpl, error::ApiError};
 
+/// The coding agent an executor profile runs, rejecting an executor without
+/// any configuration, such as CUSTOM before a profile for it is added
+pub fn configured_coding_agent(
+    executor_profile_id: &ExecutorProfileId,
+) -> Result<CodingAgent, ApiError> {
+    ExecutorConfigs::get_cached()
+        .get_coding_agent_or_default(executor_profile_id)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Executor {executor_profile_id} has no configuration; add a profile for it first"
+            ))
+        })
+}
+
 /// Reset all repository worktrees to the state before the given process.
 /// For each repo, finds the before_head_commit from the target process,
 /// or falls back to the previous process's after_head_commit.
@@ -82,3 +100,185 @@ pub async fn restore_worktrees_to_process(
 
     Ok(())
 }
+
+/// How a repository's worktree is brought back to the end of a coding agent turn
+#[derive(Debug, PartialEq, Eq)]
+enum TurnRestore<'a> {
+    /// Snapshot of the worktree, uncommitted changes included
+    Snapshot(&'a str),
+    /// HEAD at the end of the turn, for a repository no snapshot was taken of
+    Head(&'a str),
+}
+
+/// Restore all repository worktrees to the snapshots taken when the given
+/// coding agent process ended. Repositories without a snapshot are reset to
+/// their HEAD at that time. Fails if the process has no snapshot at all.
+pub async fn restore_worktrees_to_snapshot(
+    deployment: &DeploymentImpl,
+    pool: &SqlitePool,
+    workspace: &Workspace,
+    process_id: Uuid,
+) -> Result<(), ApiError> {
+    let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+    let repo_states =
+        ExecutionProcessRepoState::find_by_execution_process_id(pool, process_id).await?;
+    let restores = turn_restores(&repos, &repo_states)?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(workspace)
+        .await?;
+    let workspace_dir = PathBuf::from(container_ref);
+    for (repo, restore) in restores {
+        let worktree_path = workspace_dir.join(&repo.name);
+        match restore {
+            TurnRestore::Snapshot(snapshot) => {
+                deployment
+                    .git()
+                    .restore_worktree_snapshot(&worktree_path, snapshot, true)?
+            }
+            TurnRestore::Head(oid) => {
+                deployment
+                    .git()
+                    .reset_worktree_to_commit(&worktree_path, oid, true)?
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Pick how each repository is restored, before any worktree is touched
+fn turn_restores<'a>(
+    repos: &'a [Repo],
+    repo_states: &'a [ExecutionProcessRepoState],
+) -> Result<Vec<(&'a Repo, TurnRestore<'a>)>, ApiError> {
+    if !repo_states
+        .iter()
+        .any(|state| state.after_snapshot_commit.is_some())
+    {
+        return Err(ApiError::BadRequest(
+            "No worktree snapshot was taken for this process".to_string(),
+        ));
+    }
+
+    repos
+        .iter()
+        .map(|repo| {
+            let state = repo_states.iter().find(|s| s.repo_id == repo.id);
+            let restore = match state {
+                Some(ExecutionProcessRepoState {
+                    after_snapshot_commit: Some(snapshot),
+                    ..
+                }) => TurnRestore::Snapshot(snapshot),
+                Some(ExecutionProcessRepoState {
+                    after_head_commit: Some(oid),
+                    ..
+                }) => TurnRestore::Head(oid),
+                _ => {
+                    return Err(ApiError::BadRequest(format!(
+                        "The state of repository {} at the end of this process is unknown",
+                        repo.name
+                    )));
+                }
+            };
+            Ok((repo, restore))
+        })
+        .collect()
+}
+
+/// Delete the worktree snapshot refs a workspace left in its repositories
+pub fn delete_workspace_snapshots(deployment: &DeploymentImpl, workspace_id: Uuid, repos: &[Repo]) {
+    let prefix = format!("{SNAPSHOT_REFS_PREFIX}/{workspace_id}");
+    for repo in repos {
+        if let Err(e) = deployment.git().delete_snapshot_refs(&repo.path, &prefix) {
+            tracing::warn!(
+                "Failed to delete snapshots of workspace {} in repo {}: {}",
+                workspace_id,
+                repo.name,
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn repo(name: &str) -> Repo {
+        Repo {
+            id: Uuid::new_v4(),
+            path: PathBuf::from(format!("/repos/{name}")),
+            name: name.to_string(),
+            display_name: name.to_string(),
+            setup_script: None,
+            cleanup_script: None,
+            copy_files: None,
+            parallel_setup_script: false,
+            dev_server_script: None,
+            default_target_branch: None,
+            dev_server_health_check: None,
+            dev_server_auto_restart: false,
+            verify_script: None,
+            verify_max_retries: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn state(repo: &Repo, head: Option<&str>, snapshot: Option<&str>) -> ExecutionProcessRepoState {
+        ExecutionProcessRepoState {
+            id: Uuid::new_v4(),
+            execution_process_id: Uuid::new_v4(),
+            repo_id: repo.id,
+            before_head_commit: None,
+            after_head_commit: head.map(str::to_string),
+            merge_commit: None,
+            after_snapshot_commit: snapshot.map(str::to_string),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn resets_repos_without_a_snapshot_to_their_head() {
+        let repos = vec![repo("app"), repo("docs")];
+        let states = vec![
+            state(&repos[0], Some("a1"), Some("s1")),
+            state(&repos[1], Some("b1"), None),
+        ];
+
+        let restores = turn_restores(&repos, &states).unwrap();
+        let restores: Vec<_> = restores
+            .into_iter()
+            .map(|(repo, restore)| (repo.name.as_str(), restore))
+            .collect();
+        assert_eq!(
+            restores,
+            vec![
+                ("app", TurnRestore::Snapshot("s1")),
+                ("docs", TurnRestore::Head("b1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn fails_without_a_snapshot_or_a_known_state() {
+        let repos = vec![repo("app"), repo("docs")];
+
+        let states = vec![state(&repos[0], Some("a1"), None)];
+        assert!(matches!(
+            turn_restores(&repos, &states),
+            Err(ApiError::BadRequest(_))
+        ));
+
+        let states = vec![state(&repos[0], Some("a1"), Some("s1"))];
+        assert!(matches!(
+            turn_restores(&repos, &states),
+            Err(ApiError::BadRequest(message)) if message.contains("docs")
+        ));
+    }
+}
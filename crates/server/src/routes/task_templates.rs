@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+
+use axum::{
+    Extension, Json, Router,
+    extract::{Query, State},
+    middleware::from_fn_with_state,
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::{
+    project::Project,
+    project_repo::ProjectRepo,
+    repo::Repo,
+    task_template::{CreateTaskTemplate, TaskTemplate, TemplateRepo, UpdateTaskTemplate},
+};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::task_template::validate_template;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError, middleware::load_task_template_middleware};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct TaskTemplateQuery {
+    /// Only list the templates available in this project, global ones
+    /// included; all templates are listed when unset
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+}
+
+/// Repos of project templates must belong to the project; global templates
+/// may use any repository
+async fn validate_repos(
+    deployment: &DeploymentImpl,
+    project_id: Option<Uuid>,
+    repos: &[TemplateRepo],
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let known_repo_ids: HashSet<Uuid> = match project_id {
+        Some(project_id) => ProjectRepo::find_repos_for_project(pool, project_id).await?,
+        None => Repo::list_all(pool).await?,
+    }
+    .into_iter()
+    .map(|repo| repo.id)
+    .collect();
+    if let Some(repo) = repos
+        .iter()
+        .find(|repo| !known_repo_ids.contains(&repo.repo_id))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Repository {} is not available to this template",
+            repo.repo_id
+        )));
+    }
+    Ok(())
+}
+
+pub async fn get_task_templates(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskTemplateQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskTemplate>>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let templates = match query.project_id {
+        Some(project_id) => TaskTemplate::find_for_project(pool, project_id).await?,
+        None => TaskTemplate::find_all(pool).await?,
+    };
+    Ok(ResponseJson(ApiResponse::success(templates)))
+}
+
+pub async fn get_task_template(
+    Extension(template): Extension<TaskTemplate>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplate>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn create_task_template(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskTemplate>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplate>>, ApiError> {
+    let pool = &deployment.db().pool;
+    if let Some(project_id) = payload.project_id
+        && Project::find_by_id(pool, project_id).await?.is_none()
+    {
+        return Err(ApiError::BadRequest("Project not found".to_string()));
+    }
+    validate_template(
+        &payload.variables,
+        &payload.title,
+        payload.description.as_deref(),
+        &payload.steps,
+    )
+    .map_err(ApiError::BadRequest)?;
+    validate_repos(&deployment, payload.project_id, &payload.repos).await?;
+
+    let template = TaskTemplate::create(pool, &payload, Uuid::new_v4()).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_template_created",
+            serde_json::json!({
+                "template_id": template.id.to_string(),
+                "global": template.project_id.is_none(),
+                "variable_count": template.variables.len(),
+                "step_count": template.steps.len(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(template)))
+}
+
+pub async fn update_task_template(
+    Extension(template): Extension<TaskTemplate>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdateTaskTemplate>,
+) -> Result<ResponseJson<ApiResponse<TaskTemplate>>, ApiError> {
+    validate_template(
+        payload.variables.as_deref().unwrap_or(&template.variables),
+        payload.title.as_deref().unwrap_or(&template.title),
+        payload
+            .description
+            .as_deref()
+            .or(template.description.as_deref()),
+        payload.steps.as_deref().unwrap_or(&template.steps),
+    )
+    .map_err(ApiError::BadRequest)?;
+    if let Some(repos) = &payload.repos {
+        validate_repos(&deployment, template.project_id, repos).await?;
+    }
+
+    let updated = TaskTemplate::update(&deployment.db().pool, &template, &payload).await?;
+
+    Ok(ResponseJson(ApiResponse::success(updated)))
+}
+
+pub async fn delete_task_template(
+    Extension(template): Extension<TaskTemplate>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    let rows_affected = TaskTemplate::delete(&deployment.db().pool, template.id).await?;
+    if rows_affected == 0 {
+        Err(ApiError::Database(sqlx::Error::RowNotFound))
+    } else {
+        Ok(ResponseJson(ApiResponse::success(())))
+    }
+}
+
+pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
+    let template_router = Router::new()
+        .route(
+            "/",
+            get(get_task_template)
+                .put(update_task_template)
+                .delete(delete_task_template),
+        )
+        .layer(from_fn_with_state(
+            deployment.clone(),
+            load_task_template_middleware,
+        ));
+
+    let inner = Router::new()
+        .route("/", get(get_task_templates).post(create_task_template))
+        .nest("/{template_id}", template_router);
+
+    Router::new().nest("/task-templates", inner)
+}
@@ -3,11 +3,12 @@ use std::path::PathBuf;
 use anyhow;
 use axum::{
     Extension, Json, Router,
+    body::Bytes,
     extract::{
-        Query, State,
+        DefaultBodyLimit, Path, Query, State,
         ws::{WebSocket, WebSocketUpgrade},
     },
-    http::StatusCode,
+    http::{StatusCode, header},
     middleware::from_fn_with_state,
     response::{IntoResponse, Json as ResponseJson},
     routing::{delete, get, post, put},
@@ -16,6 +17,8 @@ use db::models::{
     image::TaskImage,
     repo::{Repo, RepoError},
     task::{CreateTask, Task, TaskWithAttemptStatus, UpdateTask},
+    task_dependency::{CreateTaskDependency, TaskDependency},
+    task_template::{TaskTemplate, TaskTemplateInstance},
     workspace::{CreateWorkspace, Workspace},
     workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
@@ -23,15 +26,22 @@ use deployment::Deployment;
 use executors::profile::ExecutorProfileId;
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use services::services::{container::ContainerService, workspace_manager::WorkspaceManager};
-use sqlx::Error as SqlxError;
+use services::services::{
+    container::ContainerService, task_archive, task_template, workspace_manager::WorkspaceManager,
+};
+use sqlx::{Error as SqlxError, SqlitePool};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
-    DeploymentImpl, error::ApiError, middleware::load_task_middleware,
-    routes::task_attempts::WorkspaceRepoInput,
+    DeploymentImpl,
+    error::ApiError,
+    middleware::load_task_middleware,
+    routes::task_attempts::{
+        WorkspaceRepoInput,
+        util::{configured_coding_agent, delete_workspace_snapshots},
+    },
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -104,11 +114,47 @@ pub async fn get_task(
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+/// Render the template a task is created from, if any. Its title and
+/// description fill in the ones the request leaves empty.
+async fn apply_template(
+    pool: &SqlitePool,
+    payload: CreateTask,
+    task_id: Uuid,
+) -> Result<(CreateTask, Option<TaskTemplateInstance>), ApiError> {
+    let Some(instance) = &payload.template else {
+        return Ok((payload, None));
+    };
+    let template = TaskTemplate::find_by_id(pool, instance.template_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Task template not found".to_string()))?;
+    if template
+        .project_id
+        .is_some_and(|project_id| project_id != payload.project_id)
+    {
+        return Err(ApiError::BadRequest(
+            "Task template belongs to another project".to_string(),
+        ));
+    }
+    let rendered = task_template::instantiate(&template, &instance.variables, task_id)
+        .map_err(ApiError::BadRequest)?;
+
+    let mut payload = payload;
+    if payload.title.trim().is_empty() {
+        payload.title = rendered.title;
+    }
+    if payload.description.is_none() {
+        payload.description = rendered.description;
+    }
+    Ok((payload, Some(rendered.instance)))
+}
+
 pub async fn create_task(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateTask>,
 ) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let pool = &deployment.db().pool;
     let id = Uuid::new_v4();
+    let (payload, template) = apply_template(pool, payload, id).await?;
 
     tracing::debug!(
         "Creating task '{}' in project {}",
@@ -116,10 +162,13 @@ pub async fn create_task(
         payload.project_id
     );
 
-    let task = Task::create(&deployment.db().pool, &payload, id).await?;
+    let task = Task::create(pool, &payload, id).await?;
 
     if let Some(image_ids) = &payload.image_ids {
-        TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
+        TaskImage::associate_many_dedup(pool, task.id, image_ids).await?;
+    }
+    if let Some(template) = &template {
+        TaskTemplateInstance::create(pool, template).await?;
     }
 
     deployment
@@ -130,6 +179,7 @@ pub async fn create_task(
             "project_id": payload.project_id,
             "has_description": task.description.is_some(),
             "has_images": payload.image_ids.is_some(),
+            "from_template": template.is_some(),
             }),
         )
         .await;
@@ -140,7 +190,12 @@ pub async fn create_task(
 #[derive(Debug, Deserialize, TS)]
 pub struct CreateAndStartTaskRequest {
     pub task: CreateTask,
-    pub executor_profile_id: ExecutorProfileId,
+    /// Defaults to the executor of the task's template
+    #[serde(default)]
+    #[ts(optional)]
+    pub executor_profile_id: Option<ExecutorProfileId>,
+    /// Defaults to the repositories of the task's template
+    #[serde(default)]
     pub repos: Vec<WorkspaceRepoInput>,
 }
 
@@ -148,20 +203,44 @@ pub async fn create_task_and_start(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateAndStartTaskRequest>,
 ) -> Result<ResponseJson<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
-    if payload.repos.is_empty() {
+    let pool = &deployment.db().pool;
+
+    let task_id = Uuid::new_v4();
+    let (create_task, template) = apply_template(pool, payload.task, task_id).await?;
+
+    let repos = if payload.repos.is_empty()
+        && let Some(template) = &template
+    {
+        template
+            .repos
+            .iter()
+            .map(|repo| WorkspaceRepoInput {
+                repo_id: repo.repo_id,
+                target_branch: repo.target_branch.clone(),
+            })
+            .collect()
+    } else {
+        payload.repos
+    };
+    if repos.is_empty() {
         return Err(ApiError::BadRequest(
             "At least one repository is required".to_string(),
         ));
     }
+    let executor_profile_id = payload
+        .executor_profile_id
+        .or_else(|| template.as_ref()?.executor_profile_id.clone())
+        .ok_or_else(|| ApiError::BadRequest("An executor profile is required".to_string()))?;
+    configured_coding_agent(&executor_profile_id)?;
 
-    let pool = &deployment.db().pool;
-
-    let task_id = Uuid::new_v4();
-    let task = Task::create(pool, &payload.task, task_id).await?;
+    let task = Task::create(pool, &create_task, task_id).await?;
 
-    if let Some(image_ids) = &payload.task.image_ids {
+    if let Some(image_ids) = &create_task.image_ids {
         TaskImage::associate_many_dedup(pool, task.id, image_ids).await?;
     }
+    if let Some(template) = &template {
+        TaskTemplateInstance::create(pool, template).await?;
+    }
 
     deployment
         .track_if_analytics_allowed(
@@ -170,7 +249,8 @@ pub async fn create_task_and_start(
                 "task_id": task.id.to_string(),
                 "project_id": task.project_id,
                 "has_description": task.description.is_some(),
-                "has_images": payload.task.image_ids.is_some(),
+                "has_images": create_task.image_ids.is_some(),
+                "from_template": template.is_some(),
             }),
         )
         .await;
@@ -184,8 +264,8 @@ pub async fn create_task_and_start(
     // Compute agent_working_dir based on repo count:
     // - Single repo: use repo name as working dir (agent runs in repo directory)
     // - Multiple repos: use None (agent runs in workspace root)
-    let agent_working_dir = if payload.repos.len() == 1 {
-        let repo = Repo::find_by_id(pool, payload.repos[0].repo_id)
+    let agent_working_dir = if repos.len() == 1 {
+        let repo = Repo::find_by_id(pool, repos[0].repo_id)
             .await?
             .ok_or(RepoError::NotFound)?;
         Some(repo.name)
@@ -204,8 +284,7 @@ pub async fn create_task_and_start(
     )
     .await?;
 
-    let workspace_repos: Vec<CreateWorkspaceRepo> = payload
-        .repos
+    let workspace_repos: Vec<CreateWorkspaceRepo> = repos
         .iter()
         .map(|r| CreateWorkspaceRepo {
             repo_id: r.repo_id,
@@ -216,7 +295,7 @@ pub async fn create_task_and_start(
 
     let is_attempt_running = deployment
         .container()
-        .start_workspace(&workspace, payload.executor_profile_id.clone())
+        .start_workspace(&workspace, executor_profile_id.clone())
         .await
         .inspect_err(|err| tracing::error!("Failed to start task attempt: {}", err))
         .is_ok();
@@ -225,8 +304,8 @@ pub async fn create_task_and_start(
             "task_attempt_started",
             serde_json::json!({
                 "task_id": task.id.to_string(),
-                "executor": &payload.executor_profile_id.executor,
-                "variant": &payload.executor_profile_id.variant,
+                "executor": &executor_profile_id.executor,
+                "variant": &executor_profile_id.variant,
                 "workspace_id": workspace.id.to_string(),
             }),
         )
@@ -241,7 +320,8 @@ pub async fn create_task_and_start(
         task,
         has_in_progress_attempt: is_attempt_running,
         last_attempt_failed: false,
-        executor: payload.executor_profile_id.executor.to_string(),
+        executor: executor_profile_id.executor.to_string(),
+        queue_position: None,
     })))
 }
 
@@ -258,7 +338,7 @@ pub async fn update_task(
         Some(s) => Some(s),                     // Non-empty string = update description
         None => existing_task.description,      // Field omitted = keep existing
     };
-    let status = payload.status.unwrap_or(existing_task.status);
+    let status = payload.status.unwrap_or(existing_task.status.clone());
     let parent_workspace_id = payload
         .parent_workspace_id
         .or(existing_task.parent_workspace_id);
@@ -279,9 +359,51 @@ pub async fn update_task(
         TaskImage::associate_many_dedup(&deployment.db().pool, task.id, image_ids).await?;
     }
 
+    deployment
+        .container()
+        .task_status_changed(task.id, existing_task.status, task.status.clone())
+        .await;
+
     Ok(ResponseJson(ApiResponse::success(task)))
 }
 
+pub async fn get_task_dependencies(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<TaskDependency>>>, ApiError> {
+    let dependencies = TaskDependency::find_by_task_id(&deployment.db().pool, task.id).await?;
+    Ok(ResponseJson(ApiResponse::success(dependencies)))
+}
+
+pub async fn create_task_dependency(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateTaskDependency>,
+) -> Result<ResponseJson<ApiResponse<TaskDependency>>, ApiError> {
+    let dependency = TaskDependency::create(&deployment.db().pool, &task, &payload).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_dependency_created",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "related_task_id": dependency.related_task_id.to_string(),
+                "relationship_type": dependency.relationship_type.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(dependency)))
+}
+
+pub async fn delete_task_dependency(
+    State(deployment): State<DeploymentImpl>,
+    Path((task_id, dependency_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    TaskDependency::delete(&deployment.db().pool, dependency_id, task_id).await?;
+    Ok(ResponseJson(ApiResponse::success(())))
+}
+
 pub async fn delete_task(
     Extension(task): Extension<Task>,
     State(deployment): State<DeploymentImpl>,
@@ -331,6 +453,10 @@ pub async fn delete_task(
     // Commit the transaction - if this fails, all changes are rolled back
     tx.commit().await?;
 
+    for attempt in &attempts {
+        delete_workspace_snapshots(&deployment, attempt.id, &repositories);
+    }
+
     if total_children_affected > 0 {
         tracing::info!(
             "Nullified {} child task references before deleting task {}",
@@ -389,6 +515,56 @@ pub async fn delete_task(
     Ok((StatusCode::ACCEPTED, ResponseJson(ApiResponse::success(()))))
 }
 
+/// Largest task archive accepted for import
+const MAX_TASK_ARCHIVE_BYTES: usize = 512 * 1024 * 1024;
+
+pub async fn export_task(
+    Extension(task): Extension<Task>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<impl IntoResponse, ApiError> {
+    let archive = task_archive::export_task(
+        &deployment.db().pool,
+        deployment.git(),
+        deployment.image(),
+        task.id,
+    )
+    .await?;
+    let disposition = format!("attachment; filename=\"task-{}.tar.gz\"", task.id);
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/gzip".to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        archive,
+    ))
+}
+
+pub async fn import_task(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<TaskQuery>,
+    body: Bytes,
+) -> Result<ResponseJson<ApiResponse<Task>>, ApiError> {
+    let task = task_archive::import_task(
+        &deployment.db().pool,
+        deployment.image(),
+        query.project_id,
+        &body,
+    )
+    .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "task_imported",
+            serde_json::json!({
+                "task_id": task.id.to_string(),
+                "project_id": query.project_id,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(task)))
+}
+
 pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let task_actions_router = Router::new()
         .route("/", put(update_task))
@@ -396,6 +572,11 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
 
     let task_id_router = Router::new()
         .route("/", get(get_task))
+        .route("/export", get(export_task))
+        .route(
+            "/dependencies",
+            get(get_task_dependencies).post(create_task_dependency),
+        )
         .merge(task_actions_router)
         .layer(from_fn_with_state(deployment.clone(), load_task_middleware));
 
@@ -403,6 +584,14 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/", get(get_tasks).post(create_task))
         .route("/stream/ws", get(stream_tasks_ws))
         .route("/create-and-start", post(create_task_and_start))
+        .route(
+            "/import",
+            post(import_task).layer(DefaultBodyLimit::max(MAX_TASK_ARCHIVE_BYTES)),
+        )
+        .route(
+            "/{task_id}/dependencies/{dependency_id}",
+            delete(delete_task_dependency),
+        )
         .nest("/{task_id}", task_id_router);
 
     // mount under /projects/:project_id/tasks
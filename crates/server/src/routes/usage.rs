@@ -0,0 +1,49 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use chrono::{DateTime, Utc};
+use db::models::coding_agent_turn_usage::{CodingAgentTurnUsage, UsageFilters};
+use deployment::Deployment;
+use serde::Deserialize;
+use services::services::usage::{self, UsageGroupBy, UsageReportRow};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct UsageQuery {
+    #[serde(default)]
+    pub group_by: UsageGroupBy,
+    #[serde(default)]
+    pub project_id: Option<Uuid>,
+    /// Only include usage recorded at or after this time
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// Only include usage recorded at or before this time
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+}
+
+pub async fn get_usage_report(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<UsageQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<UsageReportRow>>>, ApiError> {
+    let filters = UsageFilters {
+        project_id: query.project_id,
+        from: query.from,
+        to: query.to,
+    };
+    let records = CodingAgentTurnUsage::find_records(&deployment.db().pool, &filters).await?;
+    let prices = deployment.config().read().await.model_prices.clone();
+    let report = usage::build_report(&records, query.group_by, &prices);
+    Ok(ResponseJson(ApiResponse::success(report)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/usage", get(get_usage_report))
+}
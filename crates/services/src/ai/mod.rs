@@ -1,3 +1,5 @@
 pub mod prompts;
+pub mod provider;
 
 pub use prompts::{CommitFormatConfig, CommitPromptGenerator};
+pub use provider::{CompletionRequest, LlmError, LlmProvider, provider_from_config};
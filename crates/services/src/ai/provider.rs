@@ -0,0 +1,305 @@
+//! LLM backends used for commit messages and PR descriptions.
+//!
+//! Every backend implements [`LlmProvider`]; [`provider_from_config`] picks one
+//! from the user's [`LlmConfig`].
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::services::config::{LlmConfig, LlmProviderKind};
+
+const OPENAI_DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+const OPENAI_DEFAULT_MODEL: &str = "gpt-4o-mini";
+const ANTHROPIC_DEFAULT_BASE_URL: &str = "https://api.anthropic.com/v1";
+const ANTHROPIC_DEFAULT_MODEL: &str = "claude-3-5-haiku-latest";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const LOCAL_DEFAULT_BASE_URL: &str = "http://localhost:11434/v1";
+const LOCAL_DEFAULT_MODEL: &str = "llama3.1";
+const DEEPSEEK_DEFAULT_BASE_URL: &str = "https://api.deepseek.com/v1";
+const DEEPSEEK_DEFAULT_MODEL: &str = "deepseek-chat";
+
+#[derive(Debug, Error)]
+pub enum LlmError {
+    #[error("No LLM provider configured")]
+    NotConfigured,
+    #[error("{provider} API key is not set")]
+    ApiKeyNotSet { provider: &'static str },
+    #[error("LLM API error: {status} {body}")]
+    Api { status: u16, body: String },
+    #[error("Empty response from LLM API")]
+    EmptyResponse,
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+}
+
+#[derive(Debug, Clone)]
+pub struct CompletionRequest<'a> {
+    pub system: &'a str,
+    pub prompt: &'a str,
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Single-turn completion, returning the trimmed response text
+    async fn complete(&self, request: &CompletionRequest<'_>) -> Result<String, LlmError>;
+}
+
+/// Build the provider selected in the config.
+///
+/// When no provider is configured, `DEEPSEEK_API_KEY` (and optionally
+/// `DEEPSEEK_BASE_URL`) still select DeepSeek so existing setups keep working.
+pub fn provider_from_config(config: &LlmConfig) -> Result<Box<dyn LlmProvider>, LlmError> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(120))
+        .build()?;
+    let base_url = |default: &str| {
+        config
+            .base_url
+            .as_deref()
+            .filter(|url| !url.trim().is_empty())
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    };
+    let model = |default: &str| {
+        config
+            .model
+            .as_deref()
+            .filter(|model| !model.trim().is_empty())
+            .unwrap_or(default)
+            .to_string()
+    };
+    let api_key = config
+        .api_key
+        .as_deref()
+        .filter(|key| !key.trim().is_empty())
+        .map(str::to_string);
+
+    match config.provider {
+        Some(LlmProviderKind::OpenaiCompatible) => Ok(Box::new(OpenAiCompatibleProvider {
+            client,
+            base_url: base_url(OPENAI_DEFAULT_BASE_URL),
+            api_key: Some(api_key.ok_or(LlmError::ApiKeyNotSet {
+                provider: "OpenAI-compatible",
+            })?),
+            model: model(OPENAI_DEFAULT_MODEL),
+        })),
+        Some(LlmProviderKind::Anthropic) => Ok(Box::new(AnthropicProvider {
+            client,
+            base_url: base_url(ANTHROPIC_DEFAULT_BASE_URL),
+            api_key: api_key.ok_or(LlmError::ApiKeyNotSet {
+                provider: "Anthropic",
+            })?,
+            model: model(ANTHROPIC_DEFAULT_MODEL),
+        })),
+        Some(LlmProviderKind::Local) => Ok(Box::new(OpenAiCompatibleProvider {
+            client,
+            base_url: base_url(LOCAL_DEFAULT_BASE_URL),
+            api_key,
+            model: model(LOCAL_DEFAULT_MODEL),
+        })),
+        None => match std::env::var("DEEPSEEK_API_KEY") {
+            Ok(key) if !key.trim().is_empty() => Ok(Box::new(OpenAiCompatibleProvider {
+                client,
+                base_url: std::env::var("DEEPSEEK_BASE_URL")
+                    .unwrap_or_else(|_| DEEPSEEK_DEFAULT_BASE_URL.to_string())
+                    .trim_end_matches('/')
+                    .to_string(),
+                api_key: Some(key),
+                model: DEEPSEEK_DEFAULT_MODEL.to_string(),
+            })),
+            _ => Err(LlmError::NotConfigured),
+        },
+    }
+}
+
+fn non_empty(text: Option<String>) -> Result<String, LlmError> {
+    text.map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .ok_or(LlmError::EmptyResponse)
+}
+
+async fn error_for_status(response: reqwest::Response) -> Result<reqwest::Response, LlmError> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    Err(LlmError::Api {
+        status,
+        body: body.trim().to_string(),
+    })
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+/// OpenAI-compatible `/chat/completions` backend. Also serves local
+/// Ollama and llama.cpp servers, which need no API key.
+pub struct OpenAiCompatibleProvider {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiCompatibleProvider {
+    async fn complete(&self, request: &CompletionRequest<'_>) -> Result<String, LlmError> {
+        #[derive(Serialize)]
+        struct ChatRequest<'a> {
+            model: &'a str,
+            messages: Vec<ChatMessage<'a>>,
+            temperature: f32,
+            max_tokens: u32,
+        }
+
+        #[derive(Deserialize)]
+        struct ChatResponse {
+            choices: Vec<ChatChoice>,
+        }
+
+        #[derive(Deserialize)]
+        struct ChatChoice {
+            message: ChatResponseMessage,
+        }
+
+        #[derive(Deserialize)]
+        struct ChatResponseMessage {
+            content: Option<String>,
+        }
+
+        let payload = ChatRequest {
+            model: &self.model,
+            messages: vec![
+                ChatMessage {
+                    role: "system",
+                    content: request.system,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: request.prompt,
+                },
+            ],
+            temperature: request.temperature,
+            max_tokens: request.max_tokens,
+        };
+
+        let mut builder = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url))
+            .json(&payload);
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+
+        let response = error_for_status(builder.send().await?).await?;
+        let data: ChatResponse = response.json().await?;
+        non_empty(
+            data.choices
+                .into_iter()
+                .next()
+                .and_then(|choice| choice.message.content),
+        )
+    }
+}
+
+/// Anthropic Messages API backend
+pub struct AnthropicProvider {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    model: String,
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    async fn complete(&self, request: &CompletionRequest<'_>) -> Result<String, LlmError> {
+        #[derive(Serialize)]
+        struct MessagesRequest<'a> {
+            model: &'a str,
+            system: &'a str,
+            messages: Vec<ChatMessage<'a>>,
+            temperature: f32,
+            max_tokens: u32,
+        }
+
+        #[derive(Deserialize)]
+        struct MessagesResponse {
+            content: Vec<ContentBlock>,
+        }
+
+        #[derive(Deserialize)]
+        struct ContentBlock {
+            #[serde(rename = "type")]
+            kind: String,
+            #[serde(default)]
+            text: Option<String>,
+        }
+
+        let payload = MessagesRequest {
+            model: &self.model,
+            system: request.system,
+            messages: vec![ChatMessage {
+                role: "user",
+                content: request.prompt,
+            }],
+            temperature: request.temperature,
+            max_tokens: request.max_tokens,
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/messages", self.base_url))
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .json(&payload)
+            .send()
+            .await?;
+
+        let data: MessagesResponse = error_for_status(response).await?.json().await?;
+        let text = data
+            .content
+            .into_iter()
+            .filter(|block| block.kind == "text")
+            .filter_map(|block| block.text)
+            .collect::<Vec<_>>()
+            .join("");
+        non_empty(Some(text))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hosted_providers_require_api_key() {
+        let config = LlmConfig {
+            provider: Some(LlmProviderKind::Anthropic),
+            ..Default::default()
+        };
+        assert!(matches!(
+            provider_from_config(&config),
+            Err(LlmError::ApiKeyNotSet { .. })
+        ));
+    }
+
+    #[test]
+    fn local_provider_needs_no_api_key() {
+        let config = LlmConfig {
+            provider: Some(LlmProviderKind::Local),
+            base_url: Some("http://127.0.0.1:8080/v1/".to_string()),
+            ..Default::default()
+        };
+        assert!(provider_from_config(&config).is_ok());
+    }
+}
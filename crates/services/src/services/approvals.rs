@@ -1,4 +1,5 @@
 pub mod executor_approvals;
+pub mod rules;
 
 use std::{
     collections::{HashMap, HashSet},
@@ -8,20 +9,25 @@ use std::{
 
 use dashmap::DashMap;
 use db::models::{
+    approval_request::{ApprovalRecord, ApprovalResolution},
+    approval_rule::{ApprovalRule, ApprovalRuleDecision},
     execution_process::ExecutionProcess,
     task::{Task, TaskStatus},
 };
 use executors::{
     approvals::ToolCallMetadata,
+    command::ApprovalTimeoutAction,
     logs::{
-        NormalizedEntry, NormalizedEntryType, ToolStatus,
+        ActionType, NormalizedEntry, NormalizedEntryType, ToolStatus,
         utils::patch::{ConversationPatch, extract_normalized_entry_from_patch},
     },
 };
 use futures::future::{BoxFuture, FutureExt, Shared};
+use serde::{Deserialize, Serialize};
 use sqlx::{Error as SqlxError, SqlitePool};
 use thiserror::Error;
 use tokio::sync::{RwLock, oneshot};
+use ts_rs::TS;
 use utils::{
     approvals::{ApprovalRequest, ApprovalResponse, ApprovalStatus},
     log_msg::LogMsg,
@@ -40,6 +46,14 @@ struct PendingApproval {
 
 type ApprovalWaiter = Shared<BoxFuture<'static, ApprovalStatus>>;
 
+/// An approval request that is waiting for a response
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PendingApprovalInfo {
+    pub id: String,
+    pub execution_process_id: Uuid,
+    pub tool_name: String,
+}
+
 #[derive(Debug)]
 pub struct ToolContext {
     pub tool_name: String,
@@ -78,10 +92,18 @@ impl Approvals {
         }
     }
 
+    /// Register an approval request and show it on its tool use entry. Requests
+    /// an approval rule decides are answered right away, with the waiter
+    /// already resolved and the rule recorded on the tool use entry.
+    /// Unanswered requests are resolved by `on_timeout` once they time out.
     pub async fn create_with_waiter(
         &self,
+        pool: &SqlitePool,
         request: ApprovalRequest,
+        on_timeout: ApprovalTimeoutAction,
     ) -> Result<(ApprovalRequest, ApprovalWaiter), ApprovalError> {
+        ApprovalRecord::create(pool, &request).await?;
+
         let (tx, rx) = oneshot::channel();
         let waiter: ApprovalWaiter = rx
             .map(|result| result.unwrap_or(ApprovalStatus::TimedOut))
@@ -89,11 +111,54 @@ impl Approvals {
             .shared();
         let req_id = request.id.clone();
 
-        if let Some(store) = self.msg_store_by_id(&request.execution_process_id).await {
-            // Find the matching tool use entry by name and input
-            let matching_tool = find_matching_tool_use(store.clone(), &request.tool_call_id);
+        let store = self.msg_store_by_id(&request.execution_process_id).await;
+        // Find the matching tool use entry by tool call id
+        let matching_tool = store
+            .as_ref()
+            .and_then(|store| find_matching_tool_use(store.clone(), &request.tool_call_id));
+
+        // Rules apply even when the tool use entry has not been logged, using
+        // the action described by the request's input instead
+        let action = match matching_tool.as_ref().map(|(_, entry)| &entry.entry_type) {
+            Some(NormalizedEntryType::ToolUse { action_type, .. }) => action_type.clone(),
+            _ => rules::action_from_tool_input(&request.tool_name, &request.tool_input),
+        };
+        if let Some((rule, status)) = auto_decision(pool, &request, &action).await {
+            if let (Some(store), Some((idx, matching_tool))) = (&store, &matching_tool) {
+                let decided_entry = matching_tool
+                    .with_tool_status(
+                        ToolStatus::from_approval_status(&status)
+                            .ok_or(ApprovalError::NoToolUseEntry)?,
+                    )
+                    .ok_or(ApprovalError::NoToolUseEntry)?;
+                store.push_patch(ConversationPatch::replace(
+                    *idx,
+                    with_rule_metadata(decided_entry, &rule),
+                ));
+            }
+            tracing::info!(
+                "Approval {} for tool '{}' decided by rule '{}' ({}): {:?}",
+                req_id,
+                request.tool_name,
+                rule.name,
+                rule.id,
+                status
+            );
+            ApprovalRecord::resolve(
+                pool,
+                &req_id,
+                &status,
+                ApprovalResolution::Rule,
+                Some(rule.id),
+            )
+            .await?;
+            self.completed.insert(req_id, status.clone());
+            let _ = tx.send(status);
+            return Ok((request, waiter));
+        }
 
-            if let Some((idx, matching_tool)) = matching_tool {
+        match (store, matching_tool) {
+            (Some(store), Some((idx, matching_tool))) => {
                 let approval_entry = matching_tool
                     .with_tool_status(ToolStatus::PendingApproval {
                         approval_id: req_id.clone(),
@@ -119,21 +184,29 @@ impl Approvals {
                     request.tool_name,
                     idx
                 );
-            } else {
+            }
+            (Some(_), None) => {
                 tracing::warn!(
                     "No matching tool use entry found for approval request: tool='{}', execution_process_id={}",
                     request.tool_name,
                     request.execution_process_id
                 );
             }
-        } else {
-            tracing::warn!(
-                "No msg_store found for execution_process_id: {}",
-                request.execution_process_id
-            );
+            (None, _) => {
+                tracing::warn!(
+                    "No msg_store found for execution_process_id: {}",
+                    request.execution_process_id
+                );
+            }
         }
 
-        self.spawn_timeout_watcher(req_id.clone(), request.timeout_at, waiter.clone());
+        self.spawn_timeout_watcher(
+            pool.clone(),
+            req_id.clone(),
+            request.timeout_at,
+            on_timeout,
+            waiter.clone(),
+        );
         Ok((request, waiter))
     }
 
@@ -147,6 +220,11 @@ impl Approvals {
         if let Some((_, p)) = self.pending.remove(id) {
             self.completed.insert(id.to_string(), req.status.clone());
             let _ = p.response_tx.send(req.status.clone());
+            if let Err(e) =
+                ApprovalRecord::resolve(pool, id, &req.status, ApprovalResolution::User, None).await
+            {
+                tracing::error!("Failed to record response to approval '{}': {}", id, e);
+            }
 
             if let Some(store) = self.msg_store_by_id(&p.execution_process_id).await {
                 let status = ToolStatus::from_approval_status(&req.status).ok_or(
@@ -186,18 +264,23 @@ impl Approvals {
             }
 
             Ok((req.status, tool_ctx))
-        } else if self.completed.contains_key(id) {
+        } else if self.completed.contains_key(id)
+            // Requests of an earlier server run were resolved at startup
+            || ApprovalRecord::find_by_id(pool, id).await?.is_some()
+        {
             Err(ApprovalError::AlreadyCompleted)
         } else {
             Err(ApprovalError::NotFound)
         }
     }
 
-    #[tracing::instrument(skip(self, id, timeout_at, waiter))]
+    #[tracing::instrument(skip(self, pool, id, timeout_at, waiter))]
     fn spawn_timeout_watcher(
         &self,
+        pool: SqlitePool,
         id: String,
         timeout_at: chrono::DateTime<chrono::Utc>,
+        on_timeout: ApprovalTimeoutAction,
         waiter: ApprovalWaiter,
     ) {
         let pending = self.pending.clone();
@@ -211,17 +294,28 @@ impl Approvals {
         let deadline = tokio::time::Instant::now() + to_wait;
 
         tokio::spawn(async move {
-            let status = tokio::select! {
+            let (status, is_timeout) = tokio::select! {
                 biased;
 
-                resolved = waiter.clone() => resolved,
-                _ = tokio::time::sleep_until(deadline) => ApprovalStatus::TimedOut,
+                resolved = waiter.clone() => (resolved, false),
+                _ = tokio::time::sleep_until(deadline) => {
+                    let status = match on_timeout {
+                        ApprovalTimeoutAction::Deny => ApprovalStatus::TimedOut,
+                        ApprovalTimeoutAction::Approve => ApprovalStatus::Approved,
+                    };
+                    (status, true)
+                }
             };
 
-            let is_timeout = matches!(&status, ApprovalStatus::TimedOut);
             completed.insert(id.clone(), status.clone());
 
             if is_timeout && let Some((_, pending_approval)) = pending.remove(&id) {
+                if let Err(e) =
+                    ApprovalRecord::resolve(&pool, &id, &status, ApprovalResolution::Timeout, None)
+                        .await
+                {
+                    tracing::error!("Failed to record timeout of approval '{}': {}", id, e);
+                }
                 if pending_approval.response_tx.send(status.clone()).is_err() {
                     tracing::debug!("approval '{}' timeout notification receiver dropped", id);
                 }
@@ -232,9 +326,10 @@ impl Approvals {
                 };
 
                 if let Some(store) = store {
-                    if let Some(updated_entry) = pending_approval
-                        .entry
-                        .with_tool_status(ToolStatus::TimedOut)
+                    if let Some(updated_entry) =
+                        ToolStatus::from_approval_status(&status).and_then(|tool_status| {
+                            pending_approval.entry.with_tool_status(tool_status)
+                        })
                     {
                         store.push_patch(ConversationPatch::replace(
                             pending_approval.entry_index,
@@ -261,14 +356,18 @@ impl Approvals {
         map.get(execution_process_id).cloned()
     }
 
-    pub(crate) async fn cancel(&self, id: &str) {
+    pub(crate) async fn cancel(&self, pool: &SqlitePool, id: &str) {
         if let Some((_, pending_approval)) = self.pending.remove(id) {
-            self.completed.insert(
-                id.to_string(),
-                ApprovalStatus::Denied {
-                    reason: Some("Cancelled".to_string()),
-                },
-            );
+            let status = ApprovalStatus::Denied {
+                reason: Some("Cancelled".to_string()),
+            };
+            self.completed.insert(id.to_string(), status.clone());
+            if let Err(e) =
+                ApprovalRecord::resolve(pool, id, &status, ApprovalResolution::Cancelled, None)
+                    .await
+            {
+                tracing::error!("Failed to record cancellation of approval '{}': {}", id, e);
+            }
 
             if let Some(store) = self
                 .msg_store_by_id(&pending_approval.execution_process_id)
@@ -306,6 +405,72 @@ impl Approvals {
             })
             .collect()
     }
+
+    pub fn list_pending(&self) -> Vec<PendingApprovalInfo> {
+        self.pending
+            .iter()
+            .map(|entry| PendingApprovalInfo {
+                id: entry.key().clone(),
+                execution_process_id: entry.value().execution_process_id,
+                tool_name: entry.value().tool_name.clone(),
+            })
+            .collect()
+    }
+}
+
+/// The decision of the approval rules for a request, unless they leave it to the user
+async fn auto_decision(
+    pool: &SqlitePool,
+    request: &ApprovalRequest,
+    action: &ActionType,
+) -> Option<(ApprovalRule, ApprovalStatus)> {
+    let project_id = match ExecutionProcess::load_context(pool, request.execution_process_id).await
+    {
+        Ok(ctx) => ctx.project.id,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to load context of execution process {} for approval rules: {}",
+                request.execution_process_id,
+                e
+            );
+            return None;
+        }
+    };
+    let rules = match ApprovalRule::find_enabled_for_project(pool, project_id).await {
+        Ok(rules) => rules,
+        Err(e) => {
+            tracing::warn!("Failed to load approval rules: {}", e);
+            return None;
+        }
+    };
+    let rule = rules::find_deciding_rule(&rules, &request.tool_name, Some(action))?;
+    let status = match rule.decision {
+        ApprovalRuleDecision::Allow => ApprovalStatus::Approved,
+        ApprovalRuleDecision::Deny => ApprovalStatus::Denied {
+            reason: Some(format!("Denied by approval rule '{}'", rule.name)),
+        },
+        ApprovalRuleDecision::Ask => return None,
+    };
+    Some((rule.clone(), status))
+}
+
+/// Record the rule that decided a tool call in the entry's metadata, which is
+/// kept with the conversation log
+fn with_rule_metadata(mut entry: NormalizedEntry, rule: &ApprovalRule) -> NormalizedEntry {
+    let decided_by = serde_json::json!({
+        "id": rule.id,
+        "name": rule.name,
+        "decision": rule.decision,
+    });
+    match entry.metadata.as_mut().and_then(|m| m.as_object_mut()) {
+        Some(metadata) => {
+            metadata.insert("approval_rule".to_string(), decided_by);
+        }
+        None => {
+            entry.metadata = Some(serde_json::json!({ "approval_rule": decided_by }));
+        }
+    }
+    entry
 }
 
 pub(crate) async fn ensure_task_in_review(pool: &SqlitePool, execution_process_id: Uuid) {
@@ -1,20 +1,32 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::{Duration, Utc};
 use db::{self, DBService, models::execution_process::ExecutionProcess};
-use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
+use executors::{
+    approvals::{ExecutorApprovalError, ExecutorApprovalService},
+    command::ApprovalTimeout,
+};
+use futures::FutureExt;
 use serde_json::Value;
 use tokio_util::sync::CancellationToken;
-use utils::approvals::{ApprovalRequest, ApprovalStatus, CreateApprovalRequest};
+use utils::approvals::{
+    APPROVAL_TIMEOUT_SECONDS, ApprovalRequest, ApprovalStatus, CreateApprovalRequest,
+};
 use uuid::Uuid;
 
-use crate::services::{approvals::Approvals, notification::NotificationService};
+use crate::services::{
+    approvals::Approvals,
+    config::NotificationEvent,
+    notification::{Notification, NotificationService},
+};
 
 pub struct ExecutorApprovalBridge {
     approvals: Approvals,
     db: DBService,
     notification_service: NotificationService,
     execution_process_id: Uuid,
+    timeout: ApprovalTimeout,
 }
 
 impl ExecutorApprovalBridge {
@@ -23,16 +35,28 @@ impl ExecutorApprovalBridge {
         db: DBService,
         notification_service: NotificationService,
         execution_process_id: Uuid,
+        timeout: ApprovalTimeout,
     ) -> Arc<Self> {
         Arc::new(Self {
             approvals,
             db,
             notification_service,
             execution_process_id,
+            timeout,
         })
     }
 }
 
+/// How long a request waits for a response. Timeouts too large to compute a
+/// deadline from fall back to the default.
+fn request_timeout(timeout_minutes: Option<u64>) -> Duration {
+    timeout_minutes
+        .and_then(|minutes| i64::try_from(minutes).ok())
+        .and_then(Duration::try_minutes)
+        .filter(|timeout| Utc::now().checked_add_signed(*timeout).is_some())
+        .unwrap_or_else(|| Duration::seconds(APPROVAL_TIMEOUT_SECONDS))
+}
+
 #[async_trait]
 impl ExecutorApprovalService for ExecutorApprovalBridge {
     async fn request_tool_approval(
@@ -42,8 +66,6 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         tool_call_id: &str,
         cancel: CancellationToken,
     ) -> Result<ApprovalStatus, ExecutorApprovalError> {
-        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
-
         let request = ApprovalRequest::from_create(
             CreateApprovalRequest {
                 tool_name: tool_name.to_string(),
@@ -51,32 +73,52 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
                 tool_call_id: tool_call_id.to_string(),
             },
             self.execution_process_id,
+            request_timeout(self.timeout.timeout_minutes),
         );
 
         let (request, waiter) = self
             .approvals
-            .create_with_waiter(request)
+            .create_with_waiter(
+                &self.db.pool,
+                request,
+                self.timeout.on_timeout.unwrap_or_default(),
+            )
             .await
             .map_err(ExecutorApprovalError::request_failed)?;
 
+        // Requests decided by an approval rule never reach the user
+        if let Some(status) = waiter.clone().now_or_never() {
+            return Ok(status);
+        }
+
+        super::ensure_task_in_review(&self.db.pool, self.execution_process_id).await;
+
         let approval_id = request.id.clone();
 
-        let task_name = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id)
+        let ctx = ExecutionProcess::load_context(&self.db.pool, self.execution_process_id)
             .await
-            .map(|ctx| ctx.task.title)
-            .unwrap_or_else(|_| "Unknown task".to_string());
+            .ok();
+        let task_name = ctx
+            .as_ref()
+            .map(|ctx| ctx.task.title.clone())
+            .unwrap_or_else(|| "Unknown task".to_string());
 
-        self.notification_service
-            .notify(
-                &format!("Approval Needed: {}", task_name),
-                &format!("Tool '{}' requires approval", tool_name),
-            )
-            .await;
+        let mut notification = Notification::new(
+            NotificationEvent::ApprovalPending,
+            format!("Approval Needed: {}", task_name),
+            format!("Tool '{}' requires approval", tool_name),
+        );
+        if let Some(ctx) = &ctx {
+            notification = notification
+                .with_task(ctx.task.id)
+                .with_workspace(ctx.workspace.id);
+        }
+        self.notification_service.notify(notification).await;
 
         let status = tokio::select! {
             _ = cancel.cancelled() => {
                 tracing::info!("Approval request cancelled for tool_call_id={}", tool_call_id);
-                self.approvals.cancel(&approval_id).await;
+                self.approvals.cancel(&self.db.pool, &approval_id).await;
                 return Err(ExecutorApprovalError::Cancelled);
             }
             status = waiter.clone() => status,
@@ -91,3 +133,18 @@ impl ExecutorApprovalService for ExecutorApprovalBridge {
         Ok(status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_range_timeouts_use_the_default() {
+        let default = Duration::seconds(APPROVAL_TIMEOUT_SECONDS);
+        assert_eq!(request_timeout(None), default);
+        assert_eq!(request_timeout(Some(5)), Duration::minutes(5));
+        assert_eq!(request_timeout(Some(u64::MAX)), default);
+        assert_eq!(request_timeout(Some(i64::MAX as u64)), default);
+        assert_eq!(request_timeout(Some(1 << 40)), default);
+    }
+}
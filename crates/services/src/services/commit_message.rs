@@ -1,17 +1,15 @@
-use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use utils::diff::Diff;
 
+use crate::{
+    ai::{CompletionRequest, LlmError, provider_from_config},
+    services::config::LlmConfig,
+};
+
 #[derive(Debug, Error)]
 pub enum CommitMessageError {
-    #[error("DEEPSEEK_API_KEY environment variable is not set")]
-    ApiKeyNotSet,
-    #[error("DeepSeek API error: {0}")]
-    ApiError(String),
-    #[error("Empty response from DeepSeek API")]
-    EmptyResponse,
-    #[error("Network error: {0}")]
-    NetworkError(#[from] reqwest::Error),
+    #[error(transparent)]
+    Llm(#[from] LlmError),
 }
 
 const MAX_DIFF_CONTEXT_CHARS: usize = 12000;
@@ -105,9 +103,14 @@ pub fn build_diff_context(diffs: &[Diff]) -> String {
     sections.join("\n")
 }
 
-/// Get the commit message language from environment variable
-fn get_commit_language() -> String {
-    std::env::var("DEEPSEEK_COMMIT_LANGUAGE").unwrap_or_else(|_| "English".to_string())
+/// Language for generated commit messages. `DEEPSEEK_COMMIT_LANGUAGE` is
+/// still honoured when the config leaves it unset.
+pub fn commit_language(llm: &LlmConfig) -> String {
+    llm.commit_language
+        .clone()
+        .filter(|language| !language.trim().is_empty())
+        .or_else(|| std::env::var("DEEPSEEK_COMMIT_LANGUAGE").ok())
+        .unwrap_or_else(|| "English".to_string())
 }
 
 /// Build commit message prompt for task branch commits
@@ -117,10 +120,10 @@ pub fn build_branch_commit_prompt(
     target_branch: &str,
     summary: &DiffSummary,
     diff_context: &str,
+    language: &str,
 ) -> String {
     let title = task_title.trim();
     let description = task_description.unwrap_or("").trim();
-    let language = get_commit_language();
 
     let mut prompt = format!(
         "You are a Git commit message generator.\n\
@@ -156,90 +159,70 @@ Rules:\n\
     prompt
 }
 
-/// Generate commit message using DeepSeek API
-pub async fn generate_commit_message(prompt: &str) -> Result<String, CommitMessageError> {
-    #[derive(Serialize)]
-    struct DeepseekMessage {
-        role: String,
-        content: String,
-    }
-
-    #[derive(Serialize)]
-    struct DeepseekRequest {
-        model: String,
-        messages: Vec<DeepseekMessage>,
-        temperature: f32,
-        max_tokens: u32,
-    }
-
-    #[derive(Deserialize)]
-    struct DeepseekResponse {
-        choices: Vec<DeepseekChoice>,
-    }
-
-    #[derive(Deserialize)]
-    struct DeepseekChoice {
-        message: DeepseekResponseMessage,
-    }
-
-    #[derive(Deserialize)]
-    struct DeepseekResponseMessage {
-        content: String,
+/// Build the prompt for a pull request description of a task branch
+pub fn build_pr_description_prompt(
+    task_title: &str,
+    task_description: Option<&str>,
+    target_branch: &str,
+    summary: &DiffSummary,
+    diff_context: &str,
+) -> String {
+    let mut prompt = "Write a pull request description in Markdown for the changes below.\n\
+Rules:\n\
+- Output only the description, without a title line or surrounding code block.\n\
+- Start with a short summary paragraph, then list the notable changes as bullet points.\n\
+- Explain why the changes were made using the task context.\n\n"
+        .to_string();
+
+    prompt.push_str(&format!("Task title: {}\n", task_title.trim()));
+    if let Some(description) = task_description.map(str::trim)
+        && !description.is_empty()
+    {
+        prompt.push_str(&format!("Task description: {}\n", description));
     }
+    prompt.push_str(&format!("Target branch: {}\n", target_branch));
+    prompt.push_str(&format!(
+        "Diff summary: {} files, +{} / -{} lines\n\n",
+        summary.files_changed, summary.lines_added, summary.lines_removed
+    ));
 
-    let api_key =
-        std::env::var("DEEPSEEK_API_KEY").map_err(|_| CommitMessageError::ApiKeyNotSet)?;
-
-    let payload = DeepseekRequest {
-        model: "deepseek-chat".to_string(),
-        messages: vec![
-            DeepseekMessage {
-                role: "system".to_string(),
-                content: "You generate high-quality Git commit messages.".to_string(),
-            },
-            DeepseekMessage {
-                role: "user".to_string(),
-                content: prompt.to_string(),
-            },
-        ],
-        temperature: 0.2,
-        max_tokens: 240,
-    };
-
-    let client = reqwest::Client::new();
-
-    let base_url = std::env::var("DEEPSEEK_BASE_URL")
-        .unwrap_or_else(|_| "https://api.deepseek.com/v1".to_string());
-    
-    let url = format!("{}/chat/completions", base_url.trim_end_matches('/'));
-
-    let response = client
-        .post(&url)
-        .bearer_auth(api_key)
-        .json(&payload)
-        .send()
-        .await?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(CommitMessageError::ApiError(format!(
-            "{} {}",
-            status.as_u16(),
-            body.trim()
-        )));
+    if !diff_context.trim().is_empty() {
+        prompt.push_str("Diff context:\n");
+        prompt.push_str(diff_context.trim());
+        prompt.push('\n');
     }
 
-    let data: DeepseekResponse = response.json().await?;
-    let message = data
-        .choices
-        .first()
-        .map(|choice| choice.message.content.trim().to_string())
-        .unwrap_or_default();
+    prompt
+}
 
-    if message.is_empty() {
-        return Err(CommitMessageError::EmptyResponse);
-    }
+/// Generate a commit message with the configured LLM provider
+pub async fn generate_commit_message(
+    llm: &LlmConfig,
+    prompt: &str,
+) -> Result<String, CommitMessageError> {
+    let provider = provider_from_config(llm)?;
+    Ok(provider
+        .complete(&CompletionRequest {
+            system: "You generate high-quality Git commit messages.",
+            prompt,
+            temperature: 0.2,
+            max_tokens: 240,
+        })
+        .await?)
+}
 
-    Ok(message)
+/// Generate a pull request description with the configured LLM provider
+pub async fn generate_pr_description(
+    llm: &LlmConfig,
+    prompt: &str,
+) -> Result<String, CommitMessageError> {
+    let provider = provider_from_config(llm)?;
+    Ok(provider
+        .complete(&CompletionRequest {
+            system: "You write clear, accurate pull request descriptions.",
+            prompt,
+            temperature: 0.3,
+            max_tokens: 1024,
+        })
+        .await?)
 }
@@ -6,3 +6,4 @@ pub(super) mod v5;
 pub(super) mod v6;
 pub(super) mod v7;
 pub(super) mod v8;
+pub(super) mod v9;
@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+
+use anyhow::Error;
+use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+use ts_rs::TS;
+pub use v8::{
+    EditorConfig, EditorType, GitHubConfig, SendMessageShortcut, ShowcaseState, SoundFile,
+    ThemeMode, UiLanguage,
+};
+
+use crate::services::config::versions::v8;
+
+fn default_git_branch_prefix() -> String {
+    "vk".to_string()
+}
+
+fn default_pr_auto_description_enabled() -> bool {
+    true
+}
+
+fn default_sink_enabled() -> bool {
+    true
+}
+
+fn default_sink_max_retries() -> u32 {
+    3
+}
+
+/// Events that can be delivered to outbound notification sinks
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq, EnumString, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum NotificationEvent {
+    TaskCompleted,
+    TaskFailed,
+    ApprovalPending,
+    PrStatusChanged,
+}
+
+/// Wire format used when posting to a sink
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationSinkKind {
+    /// Generic JSON body with the event, title, message and related ids
+    Webhook,
+    /// Slack-compatible incoming webhook (`{"text": ...}`)
+    Slack,
+    /// ntfy-style plain-text POST with a `Title` header; also works with
+    /// Matrix webhook bridges that accept plain-text bodies
+    Ntfy,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct NotificationSink {
+    pub name: String,
+    #[serde(default = "default_sink_enabled")]
+    pub enabled: bool,
+    pub kind: NotificationSinkKind,
+    pub url: String,
+    /// Sent as `Authorization: Bearer <token>` when set
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Events delivered to this sink; empty means every event
+    #[serde(default)]
+    pub events: Vec<NotificationEvent>,
+    /// Retries after the first failed attempt, with exponential backoff
+    #[serde(default = "default_sink_max_retries")]
+    pub max_retries: u32,
+}
+
+impl NotificationSink {
+    pub fn accepts(&self, event: NotificationEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct NotificationConfig {
+    pub sound_enabled: bool,
+    pub push_enabled: bool,
+    pub sound_file: SoundFile,
+    #[serde(default)]
+    pub sinks: Vec<NotificationSink>,
+}
+
+impl From<v8::NotificationConfig> for NotificationConfig {
+    fn from(old: v8::NotificationConfig) -> Self {
+        Self {
+            sound_enabled: old.sound_enabled,
+            push_enabled: old.push_enabled,
+            sound_file: old.sound_file,
+            sinks: Vec::new(),
+        }
+    }
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self::from(v8::NotificationConfig::default())
+    }
+}
+
+/// Backend used to generate commit messages and PR descriptions
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmProviderKind {
+    /// Any OpenAI-compatible `/chat/completions` API (OpenAI, DeepSeek, vLLM, ...)
+    OpenaiCompatible,
+    /// Anthropic Messages API
+    Anthropic,
+    /// Local server exposing the OpenAI-compatible API without auth (Ollama, llama.cpp)
+    Local,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct LlmConfig {
+    /// When unset, `DEEPSEEK_API_KEY` still selects DeepSeek; generation is
+    /// disabled only if that is missing too
+    #[serde(default)]
+    pub provider: Option<LlmProviderKind>,
+    /// Overrides the provider's default endpoint, e.g. a self-hosted model
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Overrides the provider's default model
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Language generated commit messages are written in, English when unset
+    #[serde(default)]
+    pub commit_language: Option<String>,
+}
+
+/// Kind of forge reachable through a REST API rather than a CLI
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitHostKind {
+    Gitlab,
+    /// Gitea and Forgejo share the same API
+    Gitea,
+}
+
+/// A GitLab or Gitea/Forgejo instance, usually self-hosted
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct GitHostInstance {
+    pub kind: GitHostKind,
+    /// Web root of the instance, e.g. `https://gitlab.example.com`
+    pub base_url: String,
+    /// Personal access token for the REST API
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// Caps on coding agents running at the same time; further runs wait in a queue
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ConcurrencyConfig {
+    /// Across all executors, unlimited when unset
+    #[serde(default)]
+    pub max_concurrent_agents: Option<u32>,
+    /// Per executor, e.g. `{"CLAUDE_CODE": 2}`
+    #[serde(default)]
+    pub per_executor: HashMap<BaseCodingAgent, u32>,
+}
+
+/// Price of a model in USD per million tokens, configured under the model's name
+/// or a prefix of it
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    #[serde(default)]
+    pub cache_read: f64,
+    #[serde(default)]
+    pub cache_write: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct Config {
+    pub config_version: String,
+    pub theme: ThemeMode,
+    pub executor_profile: ExecutorProfileId,
+    pub disclaimer_acknowledged: bool,
+    pub onboarding_acknowledged: bool,
+    pub notifications: NotificationConfig,
+    pub editor: EditorConfig,
+    pub github: GitHubConfig,
+    pub analytics_enabled: bool,
+    pub workspace_dir: Option<String>,
+    pub last_app_version: Option<String>,
+    pub show_release_notes: bool,
+    #[serde(default)]
+    pub language: UiLanguage,
+    #[serde(default = "default_git_branch_prefix")]
+    pub git_branch_prefix: String,
+    #[serde(default)]
+    pub showcases: ShowcaseState,
+    #[serde(default = "default_pr_auto_description_enabled")]
+    pub pr_auto_description_enabled: bool,
+    #[serde(default)]
+    pub pr_auto_description_prompt: Option<String>,
+    #[serde(default)]
+    pub beta_workspaces: bool,
+    #[serde(default)]
+    pub beta_workspaces_invitation_sent: bool,
+    #[serde(default)]
+    pub commit_reminder: bool,
+    #[serde(default)]
+    pub send_message_shortcut: SendMessageShortcut,
+    #[serde(default)]
+    pub llm: LlmConfig,
+    #[serde(default)]
+    pub git_hosts: Vec<GitHostInstance>,
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+    #[serde(default)]
+    pub model_prices: HashMap<String, ModelPrice>,
+}
+
+impl Config {
+    fn from_v8_config(old_config: v8::Config) -> Self {
+        Self {
+            config_version: "v9".to_string(),
+            theme: old_config.theme,
+            executor_profile: old_config.executor_profile,
+            disclaimer_acknowledged: old_config.disclaimer_acknowledged,
+            onboarding_acknowledged: old_config.onboarding_acknowledged,
+            notifications: NotificationConfig::from(old_config.notifications),
+            editor: old_config.editor,
+            github: old_config.github,
+            analytics_enabled: old_config.analytics_enabled,
+            workspace_dir: old_config.workspace_dir,
+            last_app_version: old_config.last_app_version,
+            show_release_notes: old_config.show_release_notes,
+            language: old_config.language,
+            git_branch_prefix: old_config.git_branch_prefix,
+            showcases: old_config.showcases,
+            pr_auto_description_enabled: old_config.pr_auto_description_enabled,
+            pr_auto_description_prompt: old_config.pr_auto_description_prompt,
+            beta_workspaces: old_config.beta_workspaces,
+            beta_workspaces_invitation_sent: old_config.beta_workspaces_invitation_sent,
+            commit_reminder: old_config.commit_reminder,
+            send_message_shortcut: old_config.send_message_shortcut,
+            llm: LlmConfig::default(),
+            git_hosts: Vec::new(),
+            concurrency: ConcurrencyConfig::default(),
+            model_prices: HashMap::new(),
+        }
+    }
+
+    pub fn from_previous_version(raw_config: &str) -> Result<Self, Error> {
+        let old_config = v8::Config::from(raw_config.to_string());
+        Ok(Self::from_v8_config(old_config))
+    }
+}
+
+impl From<String> for Config {
+    fn from(raw_config: String) -> Self {
+        if let Ok(config) = serde_json::from_str::<Config>(&raw_config)
+            && config.config_version == "v9"
+        {
+            return config;
+        }
+
+        match Self::from_previous_version(&raw_config) {
+            Ok(config) => {
+                tracing::info!("Config upgraded to v9");
+                config
+            }
+            Err(e) => {
+                tracing::warn!("Config migration failed: {}, using default", e);
+                Self::default()
+            }
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            config_version: "v9".to_string(),
+            theme: ThemeMode::System,
+            executor_profile: ExecutorProfileId::new(BaseCodingAgent::ClaudeCode),
+            disclaimer_acknowledged: false,
+            onboarding_acknowledged: false,
+            notifications: NotificationConfig::default(),
+            editor: EditorConfig::default(),
+            github: GitHubConfig::default(),
+            analytics_enabled: true,
+            workspace_dir: None,
+            last_app_version: None,
+            show_release_notes: false,
+            language: UiLanguage::default(),
+            git_branch_prefix: default_git_branch_prefix(),
+            showcases: ShowcaseState::default(),
+            pr_auto_description_enabled: true,
+            pr_auto_description_prompt: None,
+            beta_workspaces: false,
+            beta_workspaces_invitation_sent: false,
+            commit_reminder: false,
+            send_message_shortcut: SendMessageShortcut::default(),
+            llm: LlmConfig::default(),
+            git_hosts: Vec::new(),
+            concurrency: ConcurrencyConfig::default(),
+            model_prices: HashMap::new(),
+        }
+    }
+}
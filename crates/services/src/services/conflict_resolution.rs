@@ -0,0 +1,175 @@
+//! Resolving merge conflicts with the coding agent: the follow-up prompt
+//! describing a paused rebase or merge, and the check that the agent left no
+//! conflict markers behind.
+
+use std::path::Path;
+
+use git::{ConflictDetails, ConflictOp, ConflictedFile};
+
+/// Most follow-ups sent for one resolution, each rebased commit that stops on
+/// conflicts taking one
+pub const MAX_ROUNDS: u32 = 20;
+/// Lines kept around each conflicted hunk
+const HUNK_CONTEXT_LINES: usize = 3;
+/// Most of each version of a file included in the prompt
+const MAX_VERSION_BYTES: usize = 16 * 1024;
+
+fn is_marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+/// Whether the content still has `<<<<<<<` or `>>>>>>>` conflict markers.
+/// `=======` alone is not enough, Markdown headings use it too.
+pub fn has_conflict_markers(content: &str) -> bool {
+    content
+        .lines()
+        .any(|line| is_marker(line, "<<<<<<<") || is_marker(line, ">>>>>>>"))
+}
+
+/// Files of the worktree that still have conflict markers. Deleted files are
+/// resolved.
+pub fn files_with_conflict_markers(worktree_path: &Path, files: &[String]) -> Vec<String> {
+    files
+        .iter()
+        .filter(|file| {
+            std::fs::read_to_string(worktree_path.join(file))
+                .is_ok_and(|content| has_conflict_markers(&content))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Each conflicted region of the content, from `<<<<<<<` to `>>>>>>>`, with a
+/// few lines of context
+fn conflict_hunks(content: &str) -> Vec<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut hunks = Vec::new();
+    let mut idx = 0;
+    while idx < lines.len() {
+        if !is_marker(lines[idx], "<<<<<<<") {
+            idx += 1;
+            continue;
+        }
+        let end = lines[idx..]
+            .iter()
+            .position(|line| is_marker(line, ">>>>>>>"))
+            .map_or(lines.len() - 1, |offset| idx + offset);
+        let start = idx.saturating_sub(HUNK_CONTEXT_LINES);
+        let stop = (end + 1 + HUNK_CONTEXT_LINES).min(lines.len());
+        hunks.push(lines[start..stop].join("\n"));
+        idx = end + 1;
+    }
+    hunks
+}
+
+fn version(content: Option<&str>) -> String {
+    let Some(content) = content else {
+        return "(the file does not exist on this side)".to_string();
+    };
+    if content.contains('\0') {
+        return "(binary content omitted)".to_string();
+    }
+    if content.len() <= MAX_VERSION_BYTES {
+        return content.to_string();
+    }
+    let end = (0..=MAX_VERSION_BYTES)
+        .rev()
+        .find(|&idx| content.is_char_boundary(idx))
+        .unwrap_or(0);
+    format!("{}\n... (truncated)", &content[..end])
+}
+
+/// What "ours" and "theirs" are for the operation
+fn sides(op: &ConflictOp) -> (&'static str, &'static str) {
+    match op {
+        ConflictOp::Rebase => (
+            "the base the branch is rebased onto",
+            "the branch's commit being replayed",
+        ),
+        ConflictOp::Merge => ("the workspace branch", "the branch being merged in"),
+        ConflictOp::CherryPick => ("the workspace branch", "the commit being cherry-picked"),
+        ConflictOp::Revert => ("the workspace branch", "the commit being reverted"),
+    }
+}
+
+fn push_file(prompt: &mut String, file: &ConflictedFile) {
+    prompt.push_str(&format!("\n### `{}`\n", file.path));
+    let hunks = file
+        .merged
+        .as_deref()
+        .map(conflict_hunks)
+        .unwrap_or_default();
+    if !hunks.is_empty() {
+        prompt.push_str("\nConflicted hunks:\n");
+        for hunk in hunks {
+            prompt.push_str(&format!("```\n{hunk}\n```\n"));
+        }
+    }
+    for (label, content) in [
+        ("Base (common ancestor)", &file.base),
+        ("Ours", &file.ours),
+        ("Theirs", &file.theirs),
+    ] {
+        prompt.push_str(&format!(
+            "\n{label}:\n```\n{}\n```\n",
+            version(content.as_deref()).trim_end()
+        ));
+    }
+}
+
+/// Follow-up prompt asking the agent to resolve the conflicts of a repo
+pub fn resolution_prompt(repo_name: &str, details: &ConflictDetails) -> String {
+    let command = details.op.command();
+    let (ours, theirs) = sides(&details.op);
+    let mut prompt = format!(
+        "The {command} in `{repo_name}` stopped on merge conflicts. Resolve them by editing \
+         the conflicted files so that they keep the intent of both sides and no conflict \
+         markers remain. Do not stage, commit, or run `git {command} --continue` or \
+         `--abort`: the {command} is continued for you once you are done.\n\n\
+         \"Ours\" is {ours}, \"theirs\" is {theirs}.\n"
+    );
+
+    for (title, commits) in [
+        ("Commits on our side", &details.ours_commits),
+        ("Commits on their side", &details.theirs_commits),
+    ] {
+        if commits.is_empty() {
+            continue;
+        }
+        prompt.push_str(&format!("\n## {title}\n"));
+        for message in commits {
+            prompt.push_str(&format!("\n```\n{message}\n```\n"));
+        }
+    }
+
+    prompt.push_str("\n## Conflicted files\n");
+    for file in &details.files {
+        push_file(&mut prompt, file);
+    }
+    prompt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_conflict_markers() {
+        assert!(has_conflict_markers(
+            "a\n<<<<<<< HEAD\nb\n=======\nc\n>>>>>>> feature\n"
+        ));
+        assert!(!has_conflict_markers("Title\n=======\n\ntext\n"));
+        assert!(!has_conflict_markers("<<<<<<<<<< not a marker\n"));
+    }
+
+    #[test]
+    fn extracts_hunks_with_context() {
+        let content =
+            "1\n2\n3\n4\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\n5\n6\n7\n8\n";
+        let hunks = conflict_hunks(content);
+        assert_eq!(hunks.len(), 1);
+        assert!(hunks[0].starts_with("2\n3\n4\n<<<<<<< HEAD"));
+        assert!(hunks[0].ends_with(">>>>>>> feature\n5\n6\n7"));
+    }
+}
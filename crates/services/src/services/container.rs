@@ -1,7 +1,8 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
@@ -9,7 +10,10 @@ use async_trait::async_trait;
 use db::{
     DBService,
     models::{
+        approval_request::ApprovalRecord,
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
+        conflict_resolution::{ConflictResolution, ConflictResolutionStatus},
+        dev_server_status::DevServerStatus,
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessError,
             ExecutionProcessRunReason, ExecutionProcessStatus,
@@ -18,11 +22,19 @@ use db::{
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        project_memory::ProjectMemoryEntry,
+        project_repo::ProjectRepo,
+        queued_message::QueuedMessage,
         repo::Repo,
+        scratch::DraftFollowUpData,
+        search::SearchIndex,
         session::{CreateSession, Session, SessionError},
         task::{Task, TaskStatus},
-        workspace::{Workspace, WorkspaceError},
-        workspace_repo::WorkspaceRepo,
+        task_dependency::TaskDependency,
+        task_template::{TaskTemplateInstance, TemplateStep, TemplateStepKind},
+        workspace::{CreateWorkspace, Workspace, WorkspaceError},
+        workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
+        workspace_verification::{VerificationStatus, WorkspaceVerification},
     },
 };
 #[cfg(feature = "qa-mode")]
@@ -32,11 +44,15 @@ use executors::profile::ExecutorConfigs;
 use executors::{
     actions::{
         ExecutorAction, ExecutorActionType,
+        coding_agent_follow_up::CodingAgentFollowUpRequest,
         coding_agent_initial::CodingAgentInitialRequest,
         script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
     },
-    executors::{ExecutorError, StandardCodingAgentExecutor},
-    logs::{NormalizedEntry, NormalizedEntryError, NormalizedEntryType, utils::ConversationPatch},
+    executors::{AppendPrompt, BaseCodingAgent, ExecutorError, StandardCodingAgentExecutor},
+    logs::{
+        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TokenUsageInfo,
+        utils::{ConversationPatch, patch::extract_normalized_entry_from_patch},
+    },
     profile::ExecutorProfileId,
 };
 use futures::{StreamExt, future, stream::BoxStream};
@@ -53,7 +69,12 @@ use utils::{
 use uuid::Uuid;
 
 use crate::services::{
-    notification::NotificationService, workspace_manager::WorkspaceError as WorkspaceManagerError,
+    config::{ConcurrencyConfig, NotificationEvent},
+    conflict_resolution,
+    notification::{Notification, NotificationService},
+    project_memory,
+    run_queue::{self, RunningAgents},
+    workspace_manager::WorkspaceError as WorkspaceManagerError,
     worktree_manager::WorktreeError,
 };
 pub type ContainerRef = String;
@@ -141,8 +162,11 @@ pub trait ContainerService {
         }
         #[cfg(not(feature = "qa-mode"))]
         {
-            let executor =
-                ExecutorConfigs::get_cached().get_coding_agent_or_default(&executor_profile_id);
+            let executor = ExecutorConfigs::get_cached()
+                .get_coding_agent_or_default(&executor_profile_id)
+                .ok_or_else(|| {
+                    ExecutorError::UnknownExecutorType(executor_profile_id.to_string())
+                })?;
 
             let stream = executor.available_slash_commands(&agent_workdir).await?;
             Ok(Some(stream))
@@ -159,7 +183,7 @@ pub trait ContainerService {
 
     async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError>;
 
-    /// Check if a task has any running execution processes
+    /// Check if a task has any running or queued execution processes
     async fn has_running_processes(&self, task_id: Uuid) -> Result<bool, ContainerError> {
         let workspaces = Workspace::fetch_all(&self.db().pool, Some(task_id)).await?;
 
@@ -170,7 +194,10 @@ pub trait ContainerService {
                     ExecutionProcess::find_by_session_id(&self.db().pool, session.id, false).await
                 {
                     for process in processes {
-                        if process.status == ExecutionProcessStatus::Running {
+                        if matches!(
+                            process.status,
+                            ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued
+                        ) {
                             return Ok(true);
                         }
                     }
@@ -219,12 +246,27 @@ pub trait ContainerService {
         action.next_action.is_none()
     }
 
-    /// Finalize task execution by updating status to InReview and sending notifications
+    /// Finalize task execution by updating status to InReview and sending notifications.
+    /// A task whose verification failed stays in progress.
     async fn finalize_task(&self, ctx: &ExecutionContext) {
-        if let Err(e) =
-            Task::update_status(&self.db().pool, ctx.task.id, TaskStatus::InReview).await
+        let pool = &self.db().pool;
+        if let Err(e) = WorkspaceVerification::abandon_fix(pool, ctx.workspace.id).await {
+            tracing::error!("Failed to update workspace verification: {e}");
+        }
+        let verification = WorkspaceVerification::find_by_workspace_id(pool, ctx.workspace.id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("Failed to load workspace verification: {e}");
+                None
+            });
+        let task_status = finalized_task_status(verification.as_ref());
+        if let Err(e) = Task::update_status(pool, ctx.task.id, task_status.clone()).await {
+            tracing::error!("Failed to update task status to {task_status}: {e}");
+        }
+        if ctx.execution_process.status == ExecutionProcessStatus::Completed
+            && let Err(e) = self.propose_project_memory(ctx).await
         {
-            tracing::error!("Failed to update task status to InReview: {e}");
+            tracing::error!("Failed to propose project memory: {e}");
         }
 
         // Skip notification if process was intentionally killed by user
@@ -233,14 +275,27 @@ pub trait ContainerService {
         }
 
         let title = format!("Task Complete: {}", ctx.task.title);
-        let message = match ctx.execution_process.status {
-            ExecutionProcessStatus::Completed => format!(
-                "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
-                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+        let (event, message) = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed if task_status != TaskStatus::InReview => (
+                NotificationEvent::TaskFailed,
+                format!(
+                    "❌ '{}' failed verification\nBranch: {:?}\nExecutor: {:?}",
+                    ctx.task.title, ctx.workspace.branch, ctx.session.executor
+                ),
             ),
-            ExecutionProcessStatus::Failed => format!(
-                "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
-                ctx.task.title, ctx.workspace.branch, ctx.session.executor
+            ExecutionProcessStatus::Completed => (
+                NotificationEvent::TaskCompleted,
+                format!(
+                    "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
+                    ctx.task.title, ctx.workspace.branch, ctx.session.executor
+                ),
+            ),
+            ExecutionProcessStatus::Failed => (
+                NotificationEvent::TaskFailed,
+                format!(
+                    "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
+                    ctx.task.title, ctx.workspace.branch, ctx.session.executor
+                ),
             ),
             _ => {
                 tracing::warn!(
@@ -250,11 +305,56 @@ pub trait ContainerService {
                 return;
             }
         };
-        self.notification_service().notify(&title, &message).await;
+        self.notification_service()
+            .notify(
+                Notification::new(event, title, message)
+                    .with_task(ctx.task.id)
+                    .with_workspace(ctx.workspace.id),
+            )
+            .await;
+    }
+
+    /// Offer the summary of the session's last completed coding agent as a
+    /// project memory candidate
+    async fn propose_project_memory(&self, ctx: &ExecutionContext) -> Result<(), SqlxError> {
+        let pool = &self.db().pool;
+        let Some(turn) =
+            CodingAgentTurn::find_latest_with_summary_by_session_id(pool, ctx.session.id).await?
+        else {
+            return Ok(());
+        };
+        let Some(content) = turn
+            .summary
+            .as_deref()
+            .and_then(project_memory::candidate_content)
+        else {
+            return Ok(());
+        };
+        if let Some(entry) =
+            ProjectMemoryEntry::propose(pool, ctx.project.id, ctx.workspace.id, turn.id, content)
+                .await?
+        {
+            tracing::debug!(
+                "Proposed project memory entry {} from workspace {}",
+                entry.id,
+                ctx.workspace.id
+            );
+        }
+        Ok(())
     }
 
     /// Cleanup executions marked as running in the db, call at startup
     async fn cleanup_orphan_executions(&self) -> Result<(), ContainerError> {
+        // Agents waiting for an approval did not survive the restart
+        let cancelled =
+            ApprovalRecord::cancel_all_pending(&self.db().pool, "Server restarted").await?;
+        if cancelled > 0 {
+            tracing::info!(
+                "Cancelled {} approval requests of orphaned executions",
+                cancelled
+            );
+        }
+        DevServerStatus::stop_all_active(&self.db().pool).await?;
         let running_processes = ExecutionProcess::find_running(&self.db().pool).await?;
         for process in running_processes {
             tracing::info!(
@@ -311,6 +411,7 @@ pub trait ContainerService {
                 ExecutionProcessRunReason::CodingAgent
                     | ExecutionProcessRunReason::SetupScript
                     | ExecutionProcessRunReason::CleanupScript
+                    | ExecutionProcessRunReason::VerifyScript
             ) && let Ok(Some(session)) =
                 Session::find_by_id(&self.db().pool, process.session_id).await
                 && let Ok(Some(workspace)) =
@@ -406,6 +507,77 @@ pub trait ContainerService {
         Ok(())
     }
 
+    /// Index the conversation logs of executions that finished before the
+    /// search index existed, or whose indexing was interrupted, from the raw
+    /// logs stored in the database.
+    async fn backfill_search_index(&self) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let executions = SearchIndex::find_unindexed_log_executions(pool).await?;
+        if executions.is_empty() {
+            return Ok(());
+        }
+        tracing::info!(
+            "Indexing logs of {} executions for search",
+            executions.len()
+        );
+
+        for execution in executions {
+            let id = execution.execution_process_id;
+            let records = ExecutionProcessLogs::find_by_execution_id(pool, id).await?;
+            let raw_messages = match ExecutionProcessLogs::parse_logs(&records) {
+                Ok(messages) => messages,
+                Err(e) => {
+                    tracing::warn!("Backfill: Failed to parse logs for execution {}: {}", id, e);
+                    continue;
+                }
+            };
+            let Some(process) = ExecutionProcess::find_by_id(pool, id).await? else {
+                continue;
+            };
+            let Some((workspace, _)) = process.parent_workspace_and_session(pool).await? else {
+                continue;
+            };
+            let current_dir = self.workspace_to_current_dir(&workspace);
+            let Some(store) = spawn_stored_log_normalizer(&process, raw_messages, &current_dir)
+            else {
+                continue;
+            };
+
+            // The stream ends once the normalizer is done and drops the store
+            let stream = store.history_plus_stream();
+            drop(store);
+            let history = match tokio::time::timeout(
+                Duration::from_secs(60),
+                stream
+                    .filter_map(|msg| future::ready(msg.ok()))
+                    .collect::<Vec<_>>(),
+            )
+            .await
+            {
+                Ok(history) => history,
+                Err(_) => {
+                    tracing::warn!("Backfill: Timed out normalizing logs for execution {}", id);
+                    continue;
+                }
+            };
+
+            let entries = searchable_log_entries(history);
+            if entries.is_empty() {
+                continue;
+            }
+            SearchIndex::replace_log_entries(
+                pool,
+                id,
+                execution.task_id,
+                execution.started_at,
+                &entries,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+
     fn cleanup_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
         let repos_with_cleanup: Vec<_> = repos
             .iter()
@@ -443,6 +615,36 @@ pub trait ContainerService {
         Some(root_action)
     }
 
+    fn verify_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
+        repos
+            .iter()
+            .filter_map(|repo| {
+                let script = repo.verify_script.clone()?;
+                Some(ExecutorAction::new(
+                    ExecutorActionType::ScriptRequest(ScriptRequest {
+                        script,
+                        language: ScriptRequestLanguage::Bash,
+                        context: ScriptContext::VerifyScript,
+                        working_dir: Some(repo.name.clone()),
+                    }),
+                    None,
+                ))
+            })
+            .reduce(ExecutorAction::append_action)
+    }
+
+    /// Actions that follow a coding agent turn: the cleanup scripts, then the
+    /// verify scripts, so verification sees the final state of the worktree
+    fn post_agent_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
+        match (
+            self.cleanup_actions_for_repos(repos),
+            self.verify_actions_for_repos(repos),
+        ) {
+            (Some(cleanup), Some(verify)) => Some(cleanup.append_action(verify)),
+            (cleanup, verify) => cleanup.or(verify),
+        }
+    }
+
     fn setup_actions_for_repos(&self, repos: &[Repo]) -> Option<ExecutorAction> {
         let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
 
@@ -530,7 +732,10 @@ pub trait ContainerService {
                     {
                         continue;
                     }
-                    if process.status == ExecutionProcessStatus::Running {
+                    if matches!(
+                        process.status,
+                        ExecutionProcessStatus::Running | ExecutionProcessStatus::Queued
+                    ) {
                         self.stop_execution(&process, ExecutionProcessStatus::Killed)
                             .await
                             .unwrap_or_else(|e| {
@@ -591,6 +796,11 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    /// Executor profile used when no previous attempt provides one
+    async fn default_executor_profile(&self) -> ExecutorProfileId;
+
+    async fn concurrency_limits(&self) -> ConcurrencyConfig;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
         let task_title_id = git_branch_id_with_translation(task_title).await;
         let prefix = self.git_branch_prefix().await;
@@ -688,19 +898,6 @@ pub trait ContainerService {
                 }
             };
 
-            // Create temporary store and populate
-            // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(
-                    msg,
-                    LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
-                ) {
-                    temp_store.push(msg);
-                }
-            }
-            temp_store.push_finished();
-
             let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
                 Ok(Some(process)) => process,
                 Ok(None) => {
@@ -744,75 +941,7 @@ pub trait ContainerService {
 
             let current_dir = self.workspace_to_current_dir(&workspace);
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
-                tracing::error!(
-                    "Failed to parse executor action: {:?}",
-                    process.executor_action()
-                );
-                return None;
-            };
-
-            // Spawn normalizer on populated store
-            match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_profile_id);
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        );
-                    }
-                }
-                #[cfg(feature = "qa-mode")]
-                ExecutorActionType::ReviewRequest(_request) => {
-                    let executor = QaMockExecutor;
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
-                }
-                #[cfg(not(feature = "qa-mode"))]
-                ExecutorActionType::ReviewRequest(request) => {
-                    let executor = ExecutorConfigs::get_cached()
-                        .get_coding_agent_or_default(&request.executor_profile_id);
-                    executor.normalize_logs(temp_store.clone(), &current_dir);
-                }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
-                }
-            }
+            let temp_store = spawn_stored_log_normalizer(&process, raw_messages, &current_dir)?;
             Some(
                 temp_store
                     .history_plus_stream()
@@ -825,6 +954,45 @@ pub trait ContainerService {
         }
     }
 
+    /// Latest token usage reported by a coding agent execution, read from its normalized logs
+    async fn latest_token_usage(&self, execution_id: &Uuid) -> Option<TokenUsageInfo> {
+        let token_usage = |msg: &LogMsg| match msg {
+            LogMsg::JsonPatch(patch) => match extract_normalized_entry_from_patch(patch) {
+                Some((_, entry)) => match entry.entry_type {
+                    NormalizedEntryType::TokenUsageInfo(info) => Some(info),
+                    _ => None,
+                },
+                None => None,
+            },
+            _ => None,
+        };
+
+        // Live executions: the in-memory history is already normalized
+        if let Some(store) = self.get_msg_store_by_id(execution_id).await {
+            return store.get_history().iter().rev().find_map(token_usage);
+        }
+
+        let mut stream = self.stream_normalized_logs(execution_id).await?;
+        let mut latest = None;
+        let collect = async {
+            while let Some(Ok(msg)) = stream.next().await {
+                if matches!(msg, LogMsg::Finished) {
+                    break;
+                }
+                if let Some(info) = token_usage(&msg) {
+                    latest = Some(info);
+                }
+            }
+        };
+        if tokio::time::timeout(Duration::from_secs(10), collect)
+            .await
+            .is_err()
+        {
+            tracing::warn!("Timed out reading token usage for execution {execution_id}");
+        }
+        latest
+    }
+
     fn spawn_stream_raw_logs_to_db(&self, execution_id: &Uuid) -> JoinHandle<()> {
         let execution_id = *execution_id;
         let msg_stores = self.msg_stores().clone();
@@ -930,13 +1098,40 @@ pub trait ContainerService {
         )
         .await?;
 
-        let prompt = task.to_prompt();
+        let mut prompt = task.to_prompt();
+        let mut agent_profile_id = executor_profile_id.clone();
+
+        // Tasks created from a template run its chain of steps: the first one
+        // with the task's prompt, the others queued as follow-ups
+        let template = TaskTemplateInstance::find_by_task_id(&self.db().pool, task.id).await?;
+        let mut steps = template.map(|t| t.steps).unwrap_or_default().into_iter();
+        let step_profile_id = |step: &TemplateStep| ExecutorProfileId {
+            executor: executor_profile_id.executor,
+            variant: step
+                .variant
+                .clone()
+                .or_else(|| executor_profile_id.variant.clone()),
+        };
+        if let Some(first) = steps.next() {
+            if !first.prompt.trim().is_empty() {
+                prompt = format!("{prompt}\n\n{}", first.prompt);
+            }
+            agent_profile_id = step_profile_id(&first);
+        }
+        for step in steps {
+            let data = DraftFollowUpData {
+                executor_profile_id: step_profile_id(&step),
+                review: (step.kind == TemplateStepKind::Review).then_some(true),
+                message: step.prompt,
+            };
+            QueuedMessage::enqueue(&self.db().pool, session.id, &data).await?;
+        }
 
         let repos_with_setup: Vec<_> = repos.iter().filter(|r| r.setup_script.is_some()).collect();
 
         let all_parallel = repos_with_setup.iter().all(|r| r.parallel_setup_script);
 
-        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+        let post_agent_action = self.post_agent_actions_for_repos(&repos);
 
         let working_dir = workspace
             .agent_working_dir
@@ -947,10 +1142,11 @@ pub trait ContainerService {
         let coding_action = ExecutorAction::new(
             ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
                 prompt,
-                executor_profile_id: executor_profile_id.clone(),
+                executor_profile_id: agent_profile_id,
                 working_dir,
+                project_memory: AppendPrompt::default(),
             }),
-            cleanup_action.map(Box::new),
+            post_agent_action.map(Box::new),
         );
 
         let execution_process = if all_parallel {
@@ -991,6 +1187,250 @@ pub trait ContainerService {
         Ok(execution_process)
     }
 
+    /// Set the status of a task and react to the transition, see
+    /// [`ContainerService::task_status_changed`].
+    async fn update_task_status(
+        &self,
+        task_id: Uuid,
+        status: TaskStatus,
+    ) -> Result<(), ContainerError> {
+        let previous = Task::update_status(&self.db().pool, task_id, status.clone()).await?;
+        if let Some(previous) = previous {
+            self.task_status_changed(task_id, previous, status).await;
+        }
+        Ok(())
+    }
+
+    /// Side effects of a task status transition, for paths that write the
+    /// status themselves. Moving a task to Done starts the tasks it was blocking.
+    async fn task_status_changed(&self, task_id: Uuid, previous: TaskStatus, status: TaskStatus) {
+        if previous != TaskStatus::Done && status == TaskStatus::Done {
+            self.start_unblocked_dependents(task_id).await;
+        }
+    }
+
+    /// Start the first attempt of every task that `task_id` was blocking, once
+    /// all of its blockers are Done. Failures are logged and never propagated,
+    /// so callers marking a task Done are not affected.
+    async fn start_unblocked_dependents(&self, task_id: Uuid) {
+        let pool = &self.db().pool;
+        let dependents = match TaskDependency::find_unblocked_dependents(pool, task_id).await {
+            Ok(tasks) => tasks,
+            Err(e) => {
+                tracing::error!("Failed to find unblocked dependents of task {task_id}: {e}");
+                return;
+            }
+        };
+        if dependents.is_empty() {
+            return;
+        }
+
+        // Reuse the repos, target branches and executor of the blocker's latest attempt
+        let blocker_workspace = match Workspace::fetch_all(pool, Some(task_id)).await {
+            Ok(workspaces) => workspaces.into_iter().next(),
+            Err(e) => {
+                tracing::warn!("Failed to load workspaces for task {task_id}: {e}");
+                None
+            }
+        };
+
+        for task in dependents {
+            match self
+                .start_dependent_task(&task, blocker_workspace.as_ref())
+                .await
+            {
+                Ok(_) => tracing::info!(
+                    "Auto-started task {} after blocker {} was completed",
+                    task.id,
+                    task_id
+                ),
+                Err(e) => tracing::error!("Failed to auto-start unblocked task {}: {e}", task.id),
+            }
+        }
+    }
+
+    async fn start_dependent_task(
+        &self,
+        task: &Task,
+        blocker_workspace: Option<&Workspace>,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+
+        let mut workspace_repos = Vec::new();
+        let mut executor_profile_id = None;
+        if let Some(blocker) = blocker_workspace {
+            workspace_repos = WorkspaceRepo::find_by_workspace_id(pool, blocker.id)
+                .await?
+                .into_iter()
+                .map(|wr| CreateWorkspaceRepo {
+                    repo_id: wr.repo_id,
+                    target_branch: wr.target_branch,
+                })
+                .collect();
+            if let Some(session) = Session::find_latest_by_workspace_id(pool, blocker.id).await? {
+                executor_profile_id =
+                    ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await?;
+            }
+        }
+
+        let project_repos = ProjectRepo::find_repos_for_project(pool, task.project_id).await?;
+        if workspace_repos.is_empty() {
+            for repo in &project_repos {
+                let target_branch = match &repo.default_target_branch {
+                    Some(branch) => branch.clone(),
+                    None => self
+                        .git()
+                        .get_current_branch(&repo.path)
+                        .map_err(GitServiceError::from)?,
+                };
+                workspace_repos.push(CreateWorkspaceRepo {
+                    repo_id: repo.id,
+                    target_branch,
+                });
+            }
+        }
+        if workspace_repos.is_empty() {
+            return Err(ContainerError::Other(anyhow!(
+                "Project {} has no repositories",
+                task.project_id
+            )));
+        }
+
+        let executor_profile_id = match executor_profile_id {
+            Some(profile) => profile,
+            None => self.default_executor_profile().await,
+        };
+
+        self.start_task_in_new_workspace(task, &workspace_repos, executor_profile_id)
+            .await
+    }
+
+    /// Create a workspace for `task` over `workspace_repos` and start the coding agent in it
+    async fn start_task_in_new_workspace(
+        &self,
+        task: &Task,
+        workspace_repos: &[CreateWorkspaceRepo],
+        executor_profile_id: ExecutorProfileId,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+
+        // Single repo: agent runs in the repo directory, otherwise in the workspace root
+        let agent_working_dir = match workspace_repos {
+            [single] => Repo::find_by_id(pool, single.repo_id)
+                .await?
+                .map(|repo| repo.name),
+            _ => None,
+        };
+
+        let workspace_id = Uuid::new_v4();
+        let branch = self
+            .git_branch_from_workspace(&workspace_id, &task.title)
+            .await;
+        let workspace = Workspace::create(
+            pool,
+            &CreateWorkspace {
+                branch,
+                agent_working_dir,
+            },
+            workspace_id,
+            task.id,
+        )
+        .await?;
+        WorkspaceRepo::create_many(pool, workspace.id, workspace_repos).await?;
+
+        self.start_workspace(&workspace, executor_profile_id).await
+    }
+
+    /// Ask the coding agent to resolve the conflicts a repo's worktree stopped
+    /// on, in a follow-up on the workspace's latest session. The follow-up has
+    /// no cleanup or verify scripts: they would run in the middle of the
+    /// rebase or merge, which is continued once the agent is done.
+    async fn start_conflict_resolution(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+    ) -> Result<ExecutionProcess, ContainerError> {
+        let pool = &self.db().pool;
+        let container_ref = self.ensure_container_exists(workspace).await?;
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+        let details = self
+            .git()
+            .conflict_details(&worktree_path)?
+            .filter(|details| !details.files.is_empty())
+            .ok_or_else(|| anyhow!("No conflicts to resolve in {}", repo.name))?;
+
+        let session = Session::find_latest_by_workspace_id(pool, workspace.id)
+            .await?
+            .ok_or(SqlxError::RowNotFound)?;
+        let executor_profile_id =
+            match ExecutionProcess::latest_executor_profile_for_session(pool, session.id).await? {
+                Some(profile) => profile,
+                None => self.default_executor_profile().await,
+            };
+        let latest_agent_session_id =
+            ExecutionProcess::find_latest_coding_agent_turn_session_id(pool, session.id).await?;
+
+        let prompt = conflict_resolution::resolution_prompt(&repo.name, &details);
+        let working_dir = workspace
+            .agent_working_dir
+            .as_ref()
+            .filter(|dir| !dir.is_empty())
+            .cloned();
+        let action_type = match latest_agent_session_id {
+            Some(session_id) => {
+                ExecutorActionType::CodingAgentFollowUpRequest(CodingAgentFollowUpRequest {
+                    prompt,
+                    session_id,
+                    executor_profile_id,
+                    working_dir,
+                })
+            }
+            None => ExecutorActionType::CodingAgentInitialRequest(CodingAgentInitialRequest {
+                prompt,
+                executor_profile_id,
+                working_dir,
+                project_memory: AppendPrompt::default(),
+            }),
+        };
+
+        // Recorded before spawning, so the exit of an agent that ends right
+        // away is still handled as a resolution
+        let conflicted_files: Vec<String> =
+            details.files.into_iter().map(|file| file.path).collect();
+        ConflictResolution::start(pool, workspace.id, repo.id, &conflicted_files).await?;
+
+        let execution_process = match self
+            .start_execution(
+                workspace,
+                &session,
+                &ExecutorAction::new(action_type, None),
+                &ExecutionProcessRunReason::CodingAgent,
+            )
+            .await
+        {
+            Ok(execution_process) => execution_process,
+            Err(e) => {
+                ConflictResolution::finish(
+                    pool,
+                    workspace.id,
+                    repo.id,
+                    ConflictResolutionStatus::Failed,
+                    Some(&e.to_string()),
+                )
+                .await?;
+                return Err(e);
+            }
+        };
+        ConflictResolution::set_execution_process(
+            pool,
+            workspace.id,
+            repo.id,
+            execution_process.id,
+        )
+        .await?;
+        Ok(execution_process)
+    }
+
     async fn start_execution(
         &self,
         workspace: &Workspace,
@@ -1008,6 +1448,14 @@ pub trait ContainerService {
         {
             Task::update_status(&self.db().pool, task.id, TaskStatus::InProgress).await?;
         }
+        // New coding agent sessions start from the project's accepted memory
+        let mut executor_action = executor_action.clone();
+        if let ExecutorActionType::CodingAgentInitialRequest(request) = &mut executor_action.typ {
+            request.project_memory = AppendPrompt(
+                project_memory::load_memory_prompt(&self.db().pool, task.project_id).await?,
+            );
+        }
+        let executor_action = &executor_action;
         // Create new execution process record
         // Capture current HEAD per repository as the "before" commit for this execution
         let repositories =
@@ -1041,13 +1489,29 @@ pub trait ContainerService {
             run_reason: run_reason.clone(),
         };
 
+        // Coding agents beyond the concurrency limits wait in the run queue
+        let agent_executor = match run_reason {
+            ExecutionProcessRunReason::CodingAgent => executor_action.base_executor(),
+            _ => None,
+        };
+        let admission = match agent_executor {
+            Some(_) => Some(run_queue::lock_admission().await),
+            None => None,
+        };
+        let status = match agent_executor {
+            Some(executor) if self.must_queue(executor).await? => ExecutionProcessStatus::Queued,
+            _ => ExecutionProcessStatus::Running,
+        };
+
         let execution_process = ExecutionProcess::create(
             &self.db().pool,
             &create_execution_process,
             Uuid::new_v4(),
+            status,
             &repo_states,
         )
         .await?;
+        drop(admission);
 
         Workspace::set_archived(&self.db().pool, workspace.id, false).await?;
 
@@ -1078,61 +1542,129 @@ pub trait ContainerService {
             .await?;
         }
 
-        if let Err(start_error) = self
-            .start_execution_inner(workspace, &execution_process, executor_action)
-            .await
-        {
-            // Mark process as failed
-            if let Err(update_error) = ExecutionProcess::update_completion(
-                &self.db().pool,
-                execution_process.id,
-                ExecutionProcessStatus::Failed,
-                None,
-            )
-            .await
-            {
+        if execution_process.status == ExecutionProcessStatus::Queued {
+            tracing::info!(
+                "Execution process {} queued until a coding agent slot is free",
+                execution_process.id
+            );
+            // Runs of other executors may still fit even though the queue is not empty
+            self.dispatch_queued_executions().await;
+            return Ok(execution_process);
+        }
+
+        self.launch_execution(workspace, &execution_process, executor_action)
+            .await?;
+        Ok(execution_process)
+    }
+
+    /// Whether a new coding agent has to wait: a limit is reached, or earlier
+    /// runs are already waiting and must not be overtaken
+    async fn must_queue(&self, executor: BaseCodingAgent) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        if ExecutionProcess::has_queued(pool).await? {
+            return Ok(true);
+        }
+        let running = RunningAgents::from_processes(&ExecutionProcess::find_running(pool).await?);
+        Ok(!running.has_capacity(executor, &self.concurrency_limits().await))
+    }
+
+    /// Start queued coding agents, oldest first, while the concurrency limits allow
+    async fn dispatch_queued_executions(&self) {
+        loop {
+            match self.try_dispatch_queued_executions().await {
+                // A process that failed to start frees its slot again
+                Ok(true) => continue,
+                Ok(false) => break,
+                Err(e) => {
+                    tracing::error!("Failed to dispatch queued executions: {}", e);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Returns whether any of the admitted processes failed to start
+    async fn try_dispatch_queued_executions(&self) -> Result<bool, ContainerError> {
+        let pool = &self.db().pool;
+        let admitted = {
+            let _admission = run_queue::lock_admission().await;
+            let queued = ExecutionProcess::find_queued(pool).await?;
+            if queued.is_empty() {
+                return Ok(false);
+            }
+            let limits = self.concurrency_limits().await;
+            let mut running =
+                RunningAgents::from_processes(&ExecutionProcess::find_running(pool).await?);
+
+            let mut admitted = Vec::new();
+            for process in queued {
+                let Some(executor) = run_queue::agent_executor(&process) else {
+                    continue;
+                };
+                if running.has_capacity(executor, &limits)
+                    && ExecutionProcess::mark_started(pool, process.id).await?
+                {
+                    running.add(executor);
+                    admitted.push(process.id);
+                }
+            }
+            admitted
+        };
+
+        let mut any_failed = false;
+        for process_id in admitted {
+            if let Err(e) = self.start_queued_execution(process_id).await {
                 tracing::error!(
-                    "Failed to mark execution process {} as failed after start error: {}",
-                    execution_process.id,
-                    update_error
+                    "Failed to start queued execution process {}: {}",
+                    process_id,
+                    e
                 );
+                any_failed = true;
             }
-            Task::update_status(&self.db().pool, task.id, TaskStatus::InReview).await?;
+        }
+        Ok(any_failed)
+    }
 
-            // Emit stderr error message
-            let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
-            if let Ok(json_line) = serde_json::to_string(&log_message) {
-                let _ = ExecutionProcessLogs::append_log_line(
-                    &self.db().pool,
-                    execution_process.id,
-                    &format!("{json_line}\n"),
+    async fn start_queued_execution(&self, process_id: Uuid) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        let ctx = ExecutionProcess::load_context(pool, process_id).await?;
+        let executor_action = ctx.execution_process.executor_action()?.clone();
+
+        if let Err(e) = self.ensure_container_exists(&ctx.workspace).await {
+            self.record_start_failure(&ctx.execution_process, ctx.task.id, &e)
+                .await?;
+            return Err(e);
+        }
+
+        // Earlier runs may have moved HEAD while this one was waiting
+        let workspace_root = self.workspace_to_current_dir(&ctx.workspace);
+        for repo in &ctx.repos {
+            if let Ok(head) = self.git().get_head_info(&workspace_root.join(&repo.name)) {
+                ExecutionProcessRepoState::update_before_head_commit(
+                    pool, process_id, repo.id, &head.oid,
                 )
-                .await;
+                .await?;
             }
+        }
 
-            // Emit NextAction with failure context for coding agent requests
-            if let ContainerError::ExecutorError(ExecutorError::ExecutableNotFound { program }) =
-                &start_error
-            {
-                let help_text = format!("The required executable `{program}` is not installed.");
-                let error_message = NormalizedEntry {
-                    timestamp: None,
-                    entry_type: NormalizedEntryType::ErrorMessage {
-                        error_type: NormalizedEntryError::SetupRequired,
-                    },
-                    content: help_text,
-                    metadata: None,
-                };
-                let patch = ConversationPatch::add_normalized_entry(2, error_message);
-                if let Ok(json_line) = serde_json::to_string::<LogMsg>(&LogMsg::JsonPatch(patch)) {
-                    let _ = ExecutionProcessLogs::append_log_line(
-                        &self.db().pool,
-                        execution_process.id,
-                        &format!("{json_line}\n"),
-                    )
-                    .await;
-                }
-            };
+        tracing::info!("Starting queued execution process {}", process_id);
+        self.launch_execution(&ctx.workspace, &ctx.execution_process, &executor_action)
+            .await
+    }
+
+    /// Spawn the process for an execution record and start streaming its logs
+    async fn launch_execution(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+    ) -> Result<(), ContainerError> {
+        if let Err(start_error) = self
+            .start_execution_inner(workspace, execution_process, executor_action)
+            .await
+        {
+            self.record_start_failure(execution_process, workspace.task_id, &start_error)
+                .await?;
             return Err(start_error);
         }
 
@@ -1179,7 +1711,67 @@ pub trait ContainerService {
         let db_stream_handle = self.spawn_stream_raw_logs_to_db(&execution_process.id);
         self.store_db_stream_handle(execution_process.id, db_stream_handle)
             .await;
-        Ok(execution_process)
+        Ok(())
+    }
+
+    /// Mark an execution that could not be started as failed and log why
+    async fn record_start_failure(
+        &self,
+        execution_process: &ExecutionProcess,
+        task_id: Uuid,
+        start_error: &ContainerError,
+    ) -> Result<(), ContainerError> {
+        if let Err(update_error) = ExecutionProcess::update_completion(
+            &self.db().pool,
+            execution_process.id,
+            ExecutionProcessStatus::Failed,
+            None,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to mark execution process {} as failed after start error: {}",
+                execution_process.id,
+                update_error
+            );
+        }
+        Task::update_status(&self.db().pool, task_id, TaskStatus::InReview).await?;
+
+        // Emit stderr error message
+        let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
+        if let Ok(json_line) = serde_json::to_string(&log_message) {
+            let _ = ExecutionProcessLogs::append_log_line(
+                &self.db().pool,
+                execution_process.id,
+                &format!("{json_line}\n"),
+            )
+            .await;
+        }
+
+        // Emit NextAction with failure context for coding agent requests
+        if let ContainerError::ExecutorError(ExecutorError::ExecutableNotFound { program }) =
+            start_error
+        {
+            let help_text = format!("The required executable `{program}` is not installed.");
+            let error_message = NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::SetupRequired,
+                },
+                content: help_text,
+                metadata: None,
+            };
+            let patch = ConversationPatch::add_normalized_entry(2, error_message);
+            if let Ok(json_line) = serde_json::to_string::<LogMsg>(&LogMsg::JsonPatch(patch)) {
+                let _ = ExecutionProcessLogs::append_log_line(
+                    &self.db().pool,
+                    execution_process.id,
+                    &format!("{json_line}\n"),
+                )
+                .await;
+            }
+        };
+        Ok(())
     }
 
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
@@ -1193,6 +1785,13 @@ pub trait ContainerService {
 
         // Determine the run reason of the next action
         let next_run_reason = match (action.typ(), next_action.typ()) {
+            (
+                _,
+                ExecutorActionType::ScriptRequest(ScriptRequest {
+                    context: ScriptContext::VerifyScript,
+                    ..
+                }),
+            ) => ExecutionProcessRunReason::VerifyScript,
             (ExecutorActionType::ScriptRequest(_), ExecutorActionType::ScriptRequest(_)) => {
                 ExecutionProcessRunReason::SetupScript
             }
@@ -1217,3 +1816,152 @@ pub trait ContainerService {
         Ok(())
     }
 }
+
+/// Text of the entries of a normalized conversation worth searching, in order.
+/// Entries are re-sent while they stream in, so only the last version of each
+/// is kept.
+pub fn searchable_log_entries(history: impl IntoIterator<Item = LogMsg>) -> Vec<String> {
+    let mut entries = BTreeMap::new();
+    for msg in history {
+        if let LogMsg::JsonPatch(patch) = msg
+            && let Some((index, entry)) = extract_normalized_entry_from_patch(&patch)
+        {
+            entries.insert(index, entry);
+        }
+    }
+    entries
+        .into_values()
+        .filter(|entry| {
+            !matches!(
+                entry.entry_type,
+                NormalizedEntryType::Loading
+                    | NormalizedEntryType::NextAction { .. }
+                    | NormalizedEntryType::TokenUsageInfo(_)
+            ) && !entry.content.trim().is_empty()
+        })
+        .map(|entry| entry.content)
+        .collect()
+}
+
+/// Replay the raw logs stored for a finished process into a new store and start
+/// the executor's normalizer on it. Returns `None` when the process's action has
+/// no normalized logs.
+fn spawn_stored_log_normalizer(
+    process: &ExecutionProcess,
+    raw_messages: Vec<LogMsg>,
+    current_dir: &Path,
+) -> Option<Arc<MsgStore>> {
+    // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+    let temp_store = Arc::new(MsgStore::new());
+    for msg in raw_messages {
+        if matches!(
+            msg,
+            LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+        ) {
+            temp_store.push(msg);
+        }
+    }
+    temp_store.push_finished();
+
+    let executor_action = if let Ok(executor_action) = process.executor_action() {
+        executor_action
+    } else {
+        tracing::error!(
+            "Failed to parse executor action: {:?}",
+            process.executor_action()
+        );
+        return None;
+    };
+
+    // Spawn normalizer on populated store
+    match executor_action.typ() {
+        ExecutorActionType::CodingAgentInitialRequest(request) => {
+            #[cfg(feature = "qa-mode")]
+            {
+                let executor = QaMockExecutor;
+                executor.normalize_logs(temp_store.clone(), &request.effective_dir(current_dir));
+            }
+            #[cfg(not(feature = "qa-mode"))]
+            {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id)?;
+                executor.normalize_logs(temp_store.clone(), &request.effective_dir(current_dir));
+            }
+        }
+        ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+            #[cfg(feature = "qa-mode")]
+            {
+                let executor = QaMockExecutor;
+                executor.normalize_logs(temp_store.clone(), &request.effective_dir(current_dir));
+            }
+            #[cfg(not(feature = "qa-mode"))]
+            {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_profile_id)?;
+                executor.normalize_logs(temp_store.clone(), &request.effective_dir(current_dir));
+            }
+        }
+        #[cfg(feature = "qa-mode")]
+        ExecutorActionType::ReviewRequest(_request) => {
+            let executor = QaMockExecutor;
+            executor.normalize_logs(temp_store.clone(), current_dir);
+        }
+        #[cfg(not(feature = "qa-mode"))]
+        ExecutorActionType::ReviewRequest(request) => {
+            let executor = ExecutorConfigs::get_cached()
+                .get_coding_agent_or_default(&request.executor_profile_id)?;
+            executor.normalize_logs(temp_store.clone(), current_dir);
+        }
+        _ => {
+            tracing::debug!(
+                "Executor action doesn't support log normalization: {:?}",
+                process.executor_action()
+            );
+            return None;
+        }
+    }
+    Some(temp_store)
+}
+
+/// Status of a task whose execution chain ended: review, unless the workspace's
+/// latest verification failed and the automatic fixes were used up
+fn finalized_task_status(verification: Option<&WorkspaceVerification>) -> TaskStatus {
+    match verification {
+        Some(verification) if verification.status == VerificationStatus::Failed => {
+            TaskStatus::InProgress
+        }
+        _ => TaskStatus::InReview,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn verification(status: VerificationStatus) -> WorkspaceVerification {
+        WorkspaceVerification {
+            workspace_id: Uuid::new_v4(),
+            execution_process_id: Some(Uuid::new_v4()),
+            status,
+            retries: 2,
+            output: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn failed_verification_keeps_the_task_out_of_review() {
+        assert_eq!(finalized_task_status(None), TaskStatus::InReview);
+        assert_eq!(
+            finalized_task_status(Some(&verification(VerificationStatus::Passed))),
+            TaskStatus::InReview
+        );
+        assert_eq!(
+            finalized_task_status(Some(&verification(VerificationStatus::Failed))),
+            TaskStatus::InProgress
+        );
+    }
+}
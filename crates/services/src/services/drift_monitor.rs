@@ -0,0 +1,363 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    time::Duration,
+};
+
+use chrono::Utc;
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess,
+        project::Project,
+        repo::Repo,
+        task::Task,
+        workspace::{Workspace, WorkspaceError},
+        workspace_drift::WorkspaceDrift,
+        workspace_repo::{RepoWithTargetBranch, WorkspaceRepo},
+        workspace_stack::WorkspaceStackRepo,
+    },
+};
+use git::{GitService, GitServiceError};
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::time::interval;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::services::{container::ContainerService, events::EventService};
+
+#[derive(Debug, Error)]
+enum DriftMonitorError {
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
+    GitService(#[from] GitServiceError),
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+}
+
+/// Whether the drift changed in a way worth showing, ignoring when it was
+/// checked
+fn drift_changed(previous: &WorkspaceDrift, current: &WorkspaceDrift) -> bool {
+    previous.target_branch != current.target_branch
+        || previous.target_commit != current.target_commit
+        || previous.commits_ahead != current.commits_ahead
+        || previous.commits_behind != current.commits_behind
+        || previous.conflicted_files != current.conflicted_files
+}
+
+/// Service that checks how far active workspaces have drifted from their
+/// target branches, and rebases them when their project enables it
+pub struct DriftMonitorService<C> {
+    db: DBService,
+    container: C,
+    events: EventService,
+    poll_interval: Duration,
+}
+
+impl<C: ContainerService + Send + Sync + 'static> DriftMonitorService<C> {
+    pub async fn spawn(
+        db: DBService,
+        container: C,
+        events: EventService,
+    ) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            container,
+            events,
+            poll_interval: Duration::from_secs(300), // Target branches are fetched on every check
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting drift monitoring service with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.check_all_workspaces().await {
+                error!("Error checking workspaces for drift: {}", e);
+            }
+        }
+    }
+
+    async fn check_all_workspaces(&self) -> Result<(), DriftMonitorError> {
+        let workspaces: Vec<Workspace> = Workspace::fetch_all(&self.db.pool, None)
+            .await?
+            .into_iter()
+            .filter(|workspace| !workspace.archived)
+            .collect();
+
+        if workspaces.is_empty() {
+            debug!("No active workspaces to check for drift");
+            return Ok(());
+        }
+
+        debug!("Checking {} workspaces for drift", workspaces.len());
+
+        // Each target branch is fetched once per check, however many
+        // workspaces target it
+        let mut fetched = HashSet::new();
+        let mut auto_rebase_by_project = HashMap::new();
+        for workspace in workspaces {
+            if let Err(e) = self
+                .check_workspace(&workspace, &mut fetched, &mut auto_rebase_by_project)
+                .await
+            {
+                error!("Error checking drift of workspace {}: {}", workspace.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn check_workspace(
+        &self,
+        workspace: &Workspace,
+        fetched: &mut HashSet<(Uuid, String)>,
+        auto_rebase_by_project: &mut HashMap<Uuid, bool>,
+    ) -> Result<(), DriftMonitorError> {
+        let pool = &self.db.pool;
+        let Some(task) = Task::find_by_id(pool, workspace.task_id).await? else {
+            return Ok(());
+        };
+        let auto_rebase = match auto_rebase_by_project.get(&task.project_id) {
+            Some(enabled) => *enabled,
+            None => {
+                let enabled = Project::find_by_id(pool, task.project_id)
+                    .await?
+                    .is_some_and(|project| project.auto_rebase_on_drift);
+                auto_rebase_by_project.insert(task.project_id, enabled);
+                enabled
+            }
+        };
+        // Stacked repos follow their base workspace and are moved by restacking
+        let stacked_repos: HashSet<Uuid> =
+            WorkspaceStackRepo::find_by_workspace_id(pool, workspace.id)
+                .await?
+                .into_iter()
+                .map(|row| row.repo_id)
+                .collect();
+        let previous = WorkspaceDrift::find_by_workspace_id(pool, workspace.id).await?;
+        let repos =
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?;
+
+        let mut changed = false;
+        for RepoWithTargetBranch {
+            repo,
+            target_branch,
+        } in repos
+        {
+            let fetch = fetched.insert((repo.id, target_branch.clone()));
+            let measured = {
+                let git = self.container.git().clone();
+                let (workspace, repo, target_branch) =
+                    (workspace.clone(), repo.clone(), target_branch.clone());
+                tokio::task::spawn_blocking(move || {
+                    if fetch && let Err(e) = fetch_target_branch(&git, &repo.path, &target_branch) {
+                        warn!(
+                            "Failed to fetch target branch '{}' of repo '{}': {}",
+                            target_branch, repo.name, e
+                        );
+                    }
+                    measure_drift(&git, &workspace, &repo, &target_branch)
+                })
+                .await?
+            };
+            let mut drift = match measured {
+                Ok(drift) => drift,
+                Err(e) => {
+                    debug!(
+                        "Could not compare '{}' with '{}' in repo '{}': {}",
+                        workspace.branch, target_branch, repo.name, e
+                    );
+                    continue;
+                }
+            };
+
+            if auto_rebase
+                && drift.commits_behind > 0
+                && drift.conflicted_files.is_empty()
+                && !stacked_repos.contains(&repo.id)
+                && self.auto_rebase(workspace, &repo, &target_branch).await?
+            {
+                let git = self.container.git().clone();
+                let (workspace, repo, target_branch) =
+                    (workspace.clone(), repo.clone(), target_branch.clone());
+                drift = tokio::task::spawn_blocking(move || {
+                    measure_drift(&git, &workspace, &repo, &target_branch)
+                })
+                .await??;
+            }
+
+            if previous
+                .iter()
+                .find(|row| row.repo_id == repo.id)
+                .is_none_or(|row| drift_changed(row, &drift))
+            {
+                changed = true;
+            }
+            WorkspaceDrift::upsert(pool, &drift).await?;
+        }
+
+        if changed {
+            self.events.push_workspace_update(workspace.id).await?;
+        }
+        Ok(())
+    }
+
+    /// Rebase the workspace's worktree of the repo onto its target branch,
+    /// unless an agent is running or the worktree has changes or a paused
+    /// operation. Returns whether it was rebased.
+    async fn auto_rebase(
+        &self,
+        workspace: &Workspace,
+        repo: &Repo,
+        target_branch: &str,
+    ) -> Result<bool, DriftMonitorError> {
+        let Some(container_ref) = &workspace.container_ref else {
+            return Ok(false);
+        };
+        let worktree_path = Path::new(container_ref).join(&repo.name);
+        if !worktree_path.exists()
+            || ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                &self.db.pool,
+                workspace.id,
+            )
+            .await?
+        {
+            return Ok(false);
+        }
+
+        let git = self.container.git().clone();
+        let (workspace_id, branch, repo, target_branch) = (
+            workspace.id,
+            workspace.branch.clone(),
+            repo.clone(),
+            target_branch.to_string(),
+        );
+        let rebased = tokio::task::spawn_blocking(move || {
+            rebase_onto_target(
+                &git,
+                workspace_id,
+                &branch,
+                &repo,
+                &worktree_path,
+                &target_branch,
+            )
+        })
+        .await??;
+        Ok(rebased)
+    }
+}
+
+/// Bring a target branch up to date with its remote. A remote-tracking branch
+/// is fetched directly, and a local one is fast-forwarded from its upstream.
+/// A local branch checked out in the repo is left alone, as moving it would
+/// leave the checkout behind; only its upstream is fetched then.
+fn fetch_target_branch(
+    git: &GitService,
+    repo_path: &Path,
+    target_branch: &str,
+) -> Result<(), GitServiceError> {
+    let Some(upstream) = git.get_branch_upstream(repo_path, target_branch)? else {
+        return git.fetch_remote_branch(repo_path, target_branch);
+    };
+    let checked_out = git
+        .get_head_info(repo_path)
+        .is_ok_and(|head| head.branch == target_branch);
+    if checked_out || upstream.branch != target_branch {
+        return git.fetch_remote_branch(repo_path, &upstream.tracking_branch);
+    }
+    git.fetch_branch(repo_path, &upstream.remote.url, target_branch)
+}
+
+/// Commits ahead of and behind the target branch, and the files a merge
+/// would conflict on
+fn measure_drift(
+    git: &GitService,
+    workspace: &Workspace,
+    repo: &Repo,
+    target_branch: &str,
+) -> Result<WorkspaceDrift, GitServiceError> {
+    let branch_commit = git.get_branch_oid(&repo.path, &workspace.branch)?;
+    let target_commit = git.get_branch_oid(&repo.path, target_branch)?;
+    let (ahead, behind) =
+        git.ahead_behind_commits_by_oid(&repo.path, &branch_commit, &target_commit)?;
+    // Only branches that both moved can conflict
+    let conflicted_files = if ahead > 0 && behind > 0 {
+        git.trial_merge_conflicts(&repo.path, &workspace.branch, target_branch)?
+    } else {
+        Vec::new()
+    };
+
+    Ok(WorkspaceDrift {
+        workspace_id: workspace.id,
+        repo_id: repo.id,
+        target_branch: target_branch.to_string(),
+        target_commit,
+        commits_ahead: ahead as u32,
+        commits_behind: behind as u32,
+        conflicted_files,
+        checked_at: Utc::now(),
+    })
+}
+
+/// Rebase a clean worktree onto its target branch, aborting when replaying
+/// the commits conflicts. Returns whether it was rebased.
+fn rebase_onto_target(
+    git: &GitService,
+    workspace_id: Uuid,
+    branch: &str,
+    repo: &Repo,
+    worktree_path: &Path,
+    target_branch: &str,
+) -> Result<bool, GitServiceError> {
+    if git.detect_conflict_op(worktree_path)?.is_some()
+        || git.get_worktree_change_counts(worktree_path)?.0 > 0
+    {
+        return Ok(false);
+    }
+
+    match git.rebase_branch(
+        &repo.path,
+        worktree_path,
+        target_branch,
+        target_branch,
+        branch,
+    ) {
+        Ok(_) => {
+            info!(
+                "Rebased workspace {} onto '{}' in repo '{}'",
+                workspace_id, target_branch, repo.name
+            );
+            Ok(true)
+        }
+        // The trial merge is clean, but replaying the commits one by one
+        // can still conflict. Those are left for the user to rebase.
+        Err(GitServiceError::MergeConflicts { .. }) => {
+            git.abort_conflicts(worktree_path)?;
+            info!(
+                "Automatic rebase of workspace {} onto '{}' in repo '{}' stopped on conflicts and was aborted",
+                workspace_id, target_branch, repo.name
+            );
+            Ok(false)
+        }
+        Err(e) => {
+            warn!(
+                "Automatic rebase of workspace {} onto '{}' in repo '{}' failed: {}",
+                workspace_id, target_branch, repo.name, e
+            );
+            Ok(false)
+        }
+    }
+}
@@ -1,10 +1,15 @@
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashSet, str::FromStr, sync::Arc};
 
 use db::{
     DBService,
     models::{
-        execution_process::ExecutionProcess, project::Project, scratch::Scratch, session::Session,
-        task::Task, workspace::Workspace,
+        dev_server_status::DevServerStatus,
+        execution_process::{ExecutionProcess, ExecutionProcessStatus},
+        project::Project,
+        scratch::Scratch,
+        session::Session,
+        task::Task,
+        workspace::Workspace,
     },
 };
 use serde_json::json;
@@ -21,7 +26,8 @@ mod streams;
 pub mod types;
 
 pub use patches::{
-    execution_process_patch, project_patch, scratch_patch, task_patch, workspace_patch,
+    dev_server_status_patch, execution_process_patch, project_patch, scratch_patch, task_patch,
+    workspace_patch,
 };
 pub use types::{EventError, EventPatch, EventPatchInner, HookTables, RecordTypes};
 
@@ -76,6 +82,22 @@ impl EventService {
         Ok(())
     }
 
+    /// Queue positions shift whenever a coding agent leaves the run queue
+    async fn push_queued_task_updates(
+        pool: &SqlitePool,
+        msg_store: Arc<MsgStore>,
+    ) -> Result<(), SqlxError> {
+        let session_ids: HashSet<Uuid> = ExecutionProcess::find_queued(pool)
+            .await?
+            .into_iter()
+            .map(|process| process.session_id)
+            .collect();
+        for session_id in session_ids {
+            Self::push_task_update_for_session(pool, msg_store.clone(), session_id).await?;
+        }
+        Ok(())
+    }
+
     async fn push_workspace_update_for_session(
         pool: &SqlitePool,
         msg_store: Arc<MsgStore>,
@@ -90,6 +112,18 @@ impl EventService {
         Ok(())
     }
 
+    /// Push the workspace to the stream after changes the table hooks don't
+    /// see, such as its drift from the target branch
+    pub async fn push_workspace_update(&self, workspace_id: Uuid) -> Result<(), SqlxError> {
+        if let Some(workspace_with_status) =
+            Workspace::find_by_id_with_status(&self.db.pool, workspace_id).await?
+        {
+            self.msg_store
+                .push_patch(workspace_patch::replace(&workspace_with_status));
+        }
+        Ok(())
+    }
+
     /// Creates the hook function that should be used with DBService::new_with_after_connect
     pub fn create_hook(
         msg_store: Arc<MsgStore>,
@@ -150,6 +184,14 @@ impl EventService {
                                     msg_store_for_preupdate.push_patch(patch);
                                 }
                             }
+                            "dev_server_statuses" => {
+                                if let Ok(value) = preupdate.get_old_column_value(0)
+                                    && let Ok(process_id) = <Uuid as Decode<Sqlite>>::decode(value)
+                                {
+                                    let patch = dev_server_status_patch::remove(process_id);
+                                    msg_store_for_preupdate.push_patch(patch);
+                                }
+                            }
                             "scratch" => {
                                 // Composite key: need both id (column 0) and scratch_type (column 1)
                                 if let Ok(id_val) = preupdate.get_old_column_value(0)
@@ -181,7 +223,8 @@ impl EventService {
                                 | (HookTables::Projects, SqliteOperation::Delete)
                                 | (HookTables::Workspaces, SqliteOperation::Delete)
                                 | (HookTables::ExecutionProcesses, SqliteOperation::Delete)
-                                | (HookTables::Scratch, SqliteOperation::Delete) => {
+                                | (HookTables::Scratch, SqliteOperation::Delete)
+                                | (HookTables::DevServerStatuses, SqliteOperation::Delete) => {
                                     // Deletions handled in preupdate hook for reliable data capture
                                     return;
                                 }
@@ -260,6 +303,22 @@ impl EventService {
                                         }
                                     }
                                 }
+                                (HookTables::DevServerStatuses, _) => {
+                                    match DevServerStatus::find_by_rowid(&db.pool, rowid).await {
+                                        Ok(Some(status)) => RecordTypes::DevServerStatus(status),
+                                        Ok(None) => RecordTypes::DeletedDevServerStatus {
+                                            rowid,
+                                            execution_process_id: None,
+                                        },
+                                        Err(e) => {
+                                            tracing::error!(
+                                                "Failed to fetch dev server status: {:?}",
+                                                e
+                                            );
+                                            return;
+                                        }
+                                    }
+                                }
                             };
 
                             let db_op: &str = match hook.operation {
@@ -322,6 +381,16 @@ impl EventService {
                                     msg_store_for_hook.push_patch(patch);
                                     return;
                                 }
+                                RecordTypes::DevServerStatus(status) => {
+                                    let patch = match hook.operation {
+                                        SqliteOperation::Insert => {
+                                            dev_server_status_patch::add(status)
+                                        }
+                                        _ => dev_server_status_patch::replace(status),
+                                    };
+                                    msg_store_for_hook.push_patch(patch);
+                                    return;
+                                }
                                 RecordTypes::DeletedScratch {
                                     scratch_id: Some(scratch_id),
                                     scratch_type: Some(scratch_type_str),
@@ -422,6 +491,20 @@ impl EventService {
                                         );
                                     }
 
+                                    if matches!(hook.operation, SqliteOperation::Update)
+                                        && process.status != ExecutionProcessStatus::Queued
+                                        && let Err(err) = EventService::push_queued_task_updates(
+                                            &db.pool,
+                                            msg_store_for_hook.clone(),
+                                        )
+                                        .await
+                                    {
+                                        tracing::error!(
+                                            "Failed to push queue position updates: {:?}",
+                                            err
+                                        );
+                                    }
+
                                     return;
                                 }
                                 RecordTypes::DeletedExecutionProcess {
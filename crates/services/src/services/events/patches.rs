@@ -1,6 +1,6 @@
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, scratch::Scratch,
-    task::TaskWithAttemptStatus, workspace::WorkspaceWithStatus,
+    dev_server_status::DevServerStatus, execution_process::ExecutionProcess, project::Project,
+    scratch::Scratch, task::TaskWithAttemptStatus, workspace::WorkspaceWithStatus,
 };
 use json_patch::{AddOperation, Patch, PatchOperation, RemoveOperation, ReplaceOperation};
 use uuid::Uuid;
@@ -132,6 +132,50 @@ pub mod execution_process_patch {
     }
 }
 
+/// Helper functions for creating dev server status patches, keyed by the
+/// dev server's execution process
+pub mod dev_server_status_patch {
+    use super::*;
+
+    fn dev_server_path(process_id: Uuid) -> String {
+        format!(
+            "/dev_servers/{}",
+            escape_pointer_segment(&process_id.to_string())
+        )
+    }
+
+    /// Create patch for adding a new dev server status
+    pub fn add(status: &DevServerStatus) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: dev_server_path(status.execution_process_id)
+                .try_into()
+                .expect("Dev server path should be valid"),
+            value: serde_json::to_value(status)
+                .expect("Dev server status serialization should not fail"),
+        })])
+    }
+
+    /// Create patch for updating an existing dev server status
+    pub fn replace(status: &DevServerStatus) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: dev_server_path(status.execution_process_id)
+                .try_into()
+                .expect("Dev server path should be valid"),
+            value: serde_json::to_value(status)
+                .expect("Dev server status serialization should not fail"),
+        })])
+    }
+
+    /// Create patch for removing a dev server status
+    pub fn remove(process_id: Uuid) -> Patch {
+        Patch(vec![PatchOperation::Remove(RemoveOperation {
+            path: dev_server_path(process_id)
+                .try_into()
+                .expect("Dev server path should be valid"),
+        })])
+    }
+}
+
 /// Helper functions for creating workspace-specific patches
 pub mod workspace_patch {
     use super::*;
@@ -1,4 +1,5 @@
 use db::models::{
+    dev_server_status::DevServerStatus,
     execution_process::ExecutionProcess,
     project::Project,
     scratch::Scratch,
@@ -522,4 +523,74 @@ impl EventService {
         let initial_stream = futures::stream::iter(vec![Ok(initial_msg), Ok(LogMsg::Ready)]);
         Ok(initial_stream.chain(filtered_stream).boxed())
     }
+
+    /// Stream the statuses of a workspace's dev servers with initial snapshot,
+    /// keyed by the dev server's execution process
+    pub async fn stream_dev_servers_for_workspace_raw(
+        &self,
+        workspace_id: Uuid,
+    ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, EventError>
+    {
+        let statuses = DevServerStatus::find_by_workspace_id(&self.db.pool, workspace_id).await?;
+        let statuses_map: serde_json::Map<String, serde_json::Value> = statuses
+            .into_iter()
+            .map(|status| {
+                (
+                    status.execution_process_id.to_string(),
+                    serde_json::to_value(status).unwrap(),
+                )
+            })
+            .collect();
+
+        let initial_patch = json!([{
+            "op": "replace",
+            "path": "/dev_servers",
+            "value": statuses_map
+        }]);
+        let initial_msg = LogMsg::JsonPatch(serde_json::from_value(initial_patch).unwrap());
+
+        let db_pool = self.db.pool.clone();
+        let filtered_stream =
+            BroadcastStream::new(self.msg_store.get_receiver()).filter_map(move |msg_result| {
+                let db_pool = db_pool.clone();
+                async move {
+                    match msg_result {
+                        Ok(LogMsg::JsonPatch(patch)) => {
+                            let patch_op = patch.0.first()?;
+                            if !patch_op.path().starts_with("/dev_servers/") {
+                                return None;
+                            }
+                            let value = match patch_op {
+                                json_patch::PatchOperation::Add(op) => &op.value,
+                                json_patch::PatchOperation::Replace(op) => &op.value,
+                                json_patch::PatchOperation::Remove(_) => {
+                                    // The status is gone, so let the client ignore
+                                    // removals of servers it does not know
+                                    return Some(Ok(LogMsg::JsonPatch(patch)));
+                                }
+                                _ => return None,
+                            };
+                            let status =
+                                serde_json::from_value::<DevServerStatus>(value.clone()).ok()?;
+                            match ExecutionProcess::load_context(
+                                &db_pool,
+                                status.execution_process_id,
+                            )
+                            .await
+                            {
+                                Ok(ctx) if ctx.workspace.id == workspace_id => {
+                                    Some(Ok(LogMsg::JsonPatch(patch)))
+                                }
+                                _ => None,
+                            }
+                        }
+                        Ok(other) => Some(Ok(other)),
+                        Err(_) => None,
+                    }
+                }
+            });
+
+        let initial_stream = futures::stream::iter(vec![Ok(initial_msg), Ok(LogMsg::Ready)]);
+        Ok(initial_stream.chain(filtered_stream).boxed())
+    }
 }
@@ -1,7 +1,7 @@
 use anyhow::Error as AnyhowError;
 use db::models::{
-    execution_process::ExecutionProcess, project::Project, scratch::Scratch, task::Task,
-    workspace::Workspace,
+    dev_server_status::DevServerStatus, execution_process::ExecutionProcess, project::Project,
+    scratch::Scratch, task::Task, workspace::Workspace,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::Error as SqlxError;
@@ -32,6 +32,8 @@ pub enum HookTables {
     Scratch,
     #[strum(to_string = "projects")]
     Projects,
+    #[strum(to_string = "dev_server_statuses")]
+    DevServerStatuses,
 }
 
 #[derive(Serialize, Deserialize, TS)]
@@ -42,6 +44,7 @@ pub enum RecordTypes {
     ExecutionProcess(ExecutionProcess),
     Scratch(Scratch),
     Project(Project),
+    DevServerStatus(DevServerStatus),
     DeletedTask {
         rowid: i64,
         project_id: Option<Uuid>,
@@ -65,6 +68,10 @@ pub enum RecordTypes {
         rowid: i64,
         project_id: Option<Uuid>,
     },
+    DeletedDevServerStatus {
+        rowid: i64,
+        execution_process_id: Option<Uuid>,
+    },
 }
 
 #[derive(Serialize, Deserialize, TS)]
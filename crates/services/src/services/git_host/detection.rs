@@ -1,6 +1,6 @@
 //! Git hosting provider detection from repository URLs.
 
-use super::types::ProviderKind;
+use super::{rest::RemoteUrl, types::ProviderKind};
 
 /// Detect the git hosting provider from a remote URL.
 ///
@@ -8,6 +8,10 @@ use super::types::ProviderKind;
 /// - GitHub.com: `https://github.com/owner/repo` or `git@github.com:owner/repo.git`
 /// - GitHub Enterprise: URLs containing `github.` (e.g., `https://github.company.com/owner/repo`)
 /// - Azure DevOps: `https://dev.azure.com/org/project/_git/repo` or legacy `https://org.visualstudio.com/...`
+/// - GitLab: gitlab.com and hosts starting with `gitlab.`
+/// - Gitea/Forgejo: gitea.com, codeberg.org and hosts starting with `gitea.` or `forgejo.`
+///
+/// Self-hosted instances on other hostnames are configured explicitly in `git_hosts`.
 pub fn detect_provider_from_url(url: &str) -> ProviderKind {
     let url_lower = url.to_lowercase();
 
@@ -33,6 +37,27 @@ pub fn detect_provider_from_url(url: &str) -> ProviderKind {
         return ProviderKind::GitHub;
     }
 
+    // /-/ separates project paths from GitLab UI routes (merge requests, blobs, ...)
+    if url_lower.contains("/-/merge_requests/") {
+        return ProviderKind::GitLab;
+    }
+
+    let Some(remote) = RemoteUrl::parse(&url_lower) else {
+        return ProviderKind::Unknown;
+    };
+    let hostname = remote.hostname();
+
+    if hostname == "gitlab.com" || hostname.starts_with("gitlab.") {
+        return ProviderKind::GitLab;
+    }
+
+    if matches!(hostname, "gitea.com" | "codeberg.org")
+        || hostname.starts_with("gitea.")
+        || hostname.starts_with("forgejo.")
+    {
+        return ProviderKind::Gitea;
+    }
+
     ProviderKind::Unknown
 }
 
@@ -137,17 +162,61 @@ mod tests {
     }
 
     #[test]
-    fn test_unknown_provider() {
+    fn test_gitlab() {
         assert_eq!(
             detect_provider_from_url("https://gitlab.com/owner/repo"),
-            ProviderKind::Unknown
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_url("git@gitlab.company.com:group/sub/repo.git"),
+            ProviderKind::GitLab
+        );
+        assert_eq!(
+            detect_provider_from_pr_url("https://code.company.com/group/repo/-/merge_requests/7"),
+            ProviderKind::GitLab
+        );
+    }
+
+    #[test]
+    fn test_gitea() {
+        assert_eq!(
+            detect_provider_from_url("https://codeberg.org/owner/repo.git"),
+            ProviderKind::Gitea
         );
+        assert_eq!(
+            detect_provider_from_url("git@gitea.company.com:owner/repo.git"),
+            ProviderKind::Gitea
+        );
+        assert_eq!(
+            detect_provider_from_url("https://forgejo.example.org/owner/repo"),
+            ProviderKind::Gitea
+        );
+    }
+
+    #[test]
+    fn test_unknown_provider() {
         assert_eq!(
             detect_provider_from_url("https://bitbucket.org/owner/repo"),
             ProviderKind::Unknown
         );
     }
 
+    #[test]
+    fn test_gitlab_gitea_match_hostname_only() {
+        assert_eq!(
+            detect_provider_from_url("https://git.example.com/tools/gitlab.migrate.git"),
+            ProviderKind::Unknown
+        );
+        assert_eq!(
+            detect_provider_from_url("git@git.example.com:mirrors/gitea.docs.git"),
+            ProviderKind::Unknown
+        );
+        assert_eq!(
+            detect_provider_from_url("https://mygitlab.example.com/group/repo"),
+            ProviderKind::Unknown
+        );
+    }
+
     #[test]
     fn test_pr_url_github() {
         assert_eq!(
@@ -0,0 +1,358 @@
+//! Gitea and Forgejo hosting service implementation (including codeberg.org),
+//! using the REST API v1.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{
+    GitHostProvider,
+    rest::{ForgeBase, RestClient, encode_segment},
+    types::{CreatePrRequest, GitHostError, OpenPrInfo, ProviderKind, UnifiedPrComment},
+};
+
+/// Gitea's default `MAX_RESPONSE_ITEMS`
+const PAGE_SIZE: u32 = 50;
+
+#[derive(Debug, Clone)]
+pub struct GiteaProvider {
+    base: ForgeBase,
+    api: RestClient,
+}
+
+#[derive(Debug, Deserialize)]
+struct Branch {
+    #[serde(rename = "ref")]
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequest {
+    number: i64,
+    html_url: String,
+    title: String,
+    state: String,
+    #[serde(default)]
+    merged: bool,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    head: Branch,
+    base: Branch,
+}
+
+impl From<PullRequest> for PullRequestInfo {
+    fn from(pr: PullRequest) -> Self {
+        // Merged PRs also report state "closed"
+        let status = match (pr.merged, pr.state.as_str()) {
+            (true, _) => MergeStatus::Merged,
+            (false, "open") => MergeStatus::Open,
+            (false, "closed") => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        Self {
+            number: pr.number,
+            url: pr.html_url,
+            status,
+            merged_at: pr.merged_at,
+            merge_commit_sha: pr.merge_commit_sha,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueComment {
+    id: i64,
+    body: String,
+    user: User,
+    created_at: DateTime<Utc>,
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Review {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewComment {
+    id: i64,
+    body: String,
+    user: User,
+    created_at: DateTime<Utc>,
+    html_url: String,
+    path: String,
+    #[serde(default)]
+    position: Option<i64>,
+    #[serde(default)]
+    original_position: Option<i64>,
+    #[serde(default)]
+    diff_hunk: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreatePullRequest<'a> {
+    head: &'a str,
+    base: &'a str,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body: Option<&'a str>,
+}
+
+/// `owner/repo` split into its two parts
+struct RepoRef {
+    owner: String,
+    name: String,
+}
+
+impl GiteaProvider {
+    pub fn new(base_url: &str, token: Option<String>) -> Result<Self, GitHostError> {
+        let base = ForgeBase::new(base_url)?;
+        let api = RestClient::new(
+            base.api_url("api/v1"),
+            token.map(|token| ("Authorization", format!("token {token}"))),
+            ProviderKind::Gitea,
+        )?;
+        Ok(Self { base, api })
+    }
+
+    fn repo_ref(&self, url: &str) -> Result<RepoRef, GitHostError> {
+        let path = self.base.repo_path(url)?;
+        match path.split_once('/') {
+            Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+                Ok(RepoRef {
+                    owner: owner.to_string(),
+                    name: name.to_string(),
+                })
+            }
+            _ => Err(GitHostError::Repository(format!(
+                "Expected an owner/repo URL, got '{url}'"
+            ))),
+        }
+    }
+
+    fn repo_endpoint(&self, repo: &RepoRef, suffix: &str) -> String {
+        format!(
+            "repos/{}/{}{suffix}",
+            encode_segment(&repo.owner),
+            encode_segment(&repo.name)
+        )
+    }
+
+    /// Split `https://host/owner/repo/pulls/12` into repo and number
+    fn parse_pr_url(&self, pr_url: &str) -> Result<(RepoRef, i64), GitHostError> {
+        let invalid =
+            || GitHostError::PullRequest(format!("Invalid Gitea pull request URL: {pr_url}"));
+        let (repo_url, rest) = pr_url.split_once("/pulls/").ok_or_else(invalid)?;
+        let number = rest
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(invalid)?;
+        Ok((self.repo_ref(repo_url)?, number))
+    }
+
+    async fn list_prs(
+        &self,
+        repo: &RepoRef,
+        state: &str,
+    ) -> Result<Vec<PullRequest>, GitHostError> {
+        self.api
+            .get_all(
+                &self.repo_endpoint(repo, "/pulls"),
+                &[("state", state.to_string())],
+                "limit",
+                PAGE_SIZE,
+            )
+            .await
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GiteaProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let target = self.repo_ref(remote_url)?;
+
+        // For cross-fork PRs, format head_branch as "owner:branch"
+        let head_branch = match &request.head_repo_url {
+            Some(head_url) => {
+                let head = self.repo_ref(head_url)?;
+                if head.owner != target.owner {
+                    format!("{}:{}", head.owner, request.head_branch)
+                } else {
+                    request.head_branch.clone()
+                }
+            }
+            None => request.head_branch.clone(),
+        };
+
+        // Gitea has no draft flag on the API; a WIP prefix marks the PR as work in progress
+        let title = if request.draft.unwrap_or(false) {
+            format!("WIP: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let body = CreatePullRequest {
+            head: &head_branch,
+            base: &request.base_branch,
+            title,
+            body: request.body.as_deref(),
+        };
+
+        let pr: PullRequest = self
+            .api
+            .post(&self.repo_endpoint(&target, "/pulls"), &body)
+            .await?;
+        info!("Created Gitea PR #{} for branch {}", pr.number, head_branch);
+
+        Ok(pr.into())
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        let (repo, number) = self.parse_pr_url(pr_url)?;
+        let pr: PullRequest = self
+            .api
+            .get(&self.repo_endpoint(&repo, &format!("/pulls/{number}")), &[])
+            .await?;
+        Ok(pr.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let repo = self.repo_ref(remote_url)?;
+        // The list endpoint cannot filter by head branch
+        Ok(self
+            .list_prs(&repo, "all")
+            .await?
+            .into_iter()
+            .filter(|pr| pr.head.name == branch_name)
+            .map(Into::into)
+            .collect())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let repo = self.repo_ref(remote_url)?;
+
+        let comments_endpoint = self.repo_endpoint(&repo, &format!("/issues/{pr_number}/comments"));
+        let reviews_endpoint = self.repo_endpoint(&repo, &format!("/pulls/{pr_number}/reviews"));
+        let (general_result, reviews_result) = tokio::join!(
+            self.api
+                .get_all::<IssueComment>(&comments_endpoint, &[], "limit", PAGE_SIZE),
+            self.api
+                .get_all::<Review>(&reviews_endpoint, &[], "limit", PAGE_SIZE)
+        );
+
+        let mut unified: Vec<UnifiedPrComment> = general_result?
+            .into_iter()
+            .map(|c| UnifiedPrComment::General {
+                id: c.id.to_string(),
+                author: c.user.login,
+                author_association: None,
+                body: c.body,
+                created_at: c.created_at,
+                url: Some(c.html_url),
+            })
+            .collect();
+
+        for review in reviews_result? {
+            let comments: Vec<ReviewComment> = self
+                .api
+                .get(
+                    &self.repo_endpoint(
+                        &repo,
+                        &format!("/pulls/{pr_number}/reviews/{}/comments", review.id),
+                    ),
+                    &[],
+                )
+                .await?;
+
+            unified.extend(comments.into_iter().map(|c| UnifiedPrComment::Review {
+                id: c.id,
+                author: c.user.login,
+                author_association: None,
+                body: c.body,
+                created_at: c.created_at,
+                url: Some(c.html_url),
+                path: c.path,
+                line: c.position.filter(|&line| line > 0).or(c.original_position),
+                side: None,
+                diff_hunk: c.diff_hunk,
+            }));
+        }
+
+        unified.sort_by_key(|c| c.created_at());
+
+        Ok(unified)
+    }
+
+    async fn list_open_prs(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+    ) -> Result<Vec<OpenPrInfo>, GitHostError> {
+        let repo = self.repo_ref(remote_url)?;
+
+        Ok(self
+            .list_prs(&repo, "open")
+            .await?
+            .into_iter()
+            .map(|pr| OpenPrInfo {
+                number: pr.number,
+                url: pr.html_url,
+                title: pr.title,
+                head_branch: pr.head.name,
+                base_branch: pr.base.name,
+            })
+            .collect())
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::Gitea
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pull_request_urls() {
+        // reqwest is built without a default crypto provider
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let provider = GiteaProvider::new("https://codeberg.org", None).unwrap();
+        let (repo, number) = provider
+            .parse_pr_url("https://codeberg.org/owner/repo/pulls/17/files")
+            .unwrap();
+        assert_eq!(
+            (repo.owner.as_str(), repo.name.as_str(), number),
+            ("owner", "repo", 17)
+        );
+        assert!(
+            provider
+                .parse_pr_url("https://codeberg.org/owner/repo/issues/3")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! GitLab hosting service implementation (gitlab.com and self-managed),
+//! using the REST API v4.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use db::models::merge::{MergeStatus, PullRequestInfo};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use super::{
+    GitHostProvider,
+    rest::{ForgeBase, RestClient, encode_segment},
+    types::{CreatePrRequest, GitHostError, OpenPrInfo, ProviderKind, UnifiedPrComment},
+};
+
+const PAGE_SIZE: u32 = 100;
+
+#[derive(Debug, Clone)]
+pub struct GitLabProvider {
+    base: ForgeBase,
+    api: RestClient,
+}
+
+#[derive(Debug, Deserialize)]
+struct Project {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct MergeRequest {
+    iid: i64,
+    web_url: String,
+    title: String,
+    state: String,
+    source_branch: String,
+    target_branch: String,
+    merged_at: Option<DateTime<Utc>>,
+    merge_commit_sha: Option<String>,
+    squash_commit_sha: Option<String>,
+}
+
+impl From<MergeRequest> for PullRequestInfo {
+    fn from(mr: MergeRequest) -> Self {
+        let status = match mr.state.as_str() {
+            "opened" | "locked" => MergeStatus::Open,
+            "merged" => MergeStatus::Merged,
+            "closed" => MergeStatus::Closed,
+            _ => MergeStatus::Unknown,
+        };
+        Self {
+            number: mr.iid,
+            url: mr.web_url,
+            status,
+            merged_at: mr.merged_at,
+            // Squash merges leave merge_commit_sha empty when fast-forwarding
+            merge_commit_sha: mr.merge_commit_sha.or(mr.squash_commit_sha),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct NoteAuthor {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotePosition {
+    new_path: Option<String>,
+    old_path: Option<String>,
+    new_line: Option<i64>,
+    old_line: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Note {
+    id: i64,
+    body: String,
+    author: NoteAuthor,
+    created_at: DateTime<Utc>,
+    system: bool,
+    #[serde(default)]
+    position: Option<NotePosition>,
+}
+
+#[derive(Debug, Serialize)]
+struct CreateMergeRequest<'a> {
+    source_branch: &'a str,
+    target_branch: &'a str,
+    title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    target_project_id: Option<i64>,
+}
+
+impl GitLabProvider {
+    pub fn new(base_url: &str, token: Option<String>) -> Result<Self, GitHostError> {
+        let base = ForgeBase::new(base_url)?;
+        let api = RestClient::new(
+            base.api_url("api/v4"),
+            token.map(|token| ("PRIVATE-TOKEN", token)),
+            ProviderKind::GitLab,
+        )?;
+        Ok(Self { base, api })
+    }
+
+    fn project_endpoint(&self, project_path: &str, suffix: &str) -> String {
+        format!("projects/{}{suffix}", encode_segment(project_path))
+    }
+
+    /// Split `https://host/group/repo/-/merge_requests/12` into project path and iid
+    fn parse_mr_url(&self, pr_url: &str) -> Result<(String, i64), GitHostError> {
+        let invalid =
+            || GitHostError::PullRequest(format!("Invalid GitLab merge request URL: {pr_url}"));
+        let (project_url, rest) = pr_url
+            .split_once("/-/merge_requests/")
+            .ok_or_else(invalid)?;
+        let iid = rest
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(invalid)?;
+        Ok((self.base.repo_path(project_url)?, iid))
+    }
+
+    fn note_url(&self, project_path: &str, iid: i64, note_id: i64) -> String {
+        format!(
+            "{}/{project_path}/-/merge_requests/{iid}#note_{note_id}",
+            self.base.web_url()
+        )
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GitLabProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        request: &CreatePrRequest,
+    ) -> Result<PullRequestInfo, GitHostError> {
+        let target_path = self.base.repo_path(remote_url)?;
+
+        // Merge requests from forks are created on the source project and point at the target
+        let (source_path, target_project_id) = match &request.head_repo_url {
+            Some(head_url) => {
+                let head_path = self.base.repo_path(head_url)?;
+                if head_path != target_path {
+                    let target: Project = self
+                        .api
+                        .get(&self.project_endpoint(&target_path, ""), &[])
+                        .await?;
+                    (head_path, Some(target.id))
+                } else {
+                    (target_path, None)
+                }
+            }
+            None => (target_path, None),
+        };
+
+        let title = if request.draft.unwrap_or(false) {
+            format!("Draft: {}", request.title)
+        } else {
+            request.title.clone()
+        };
+        let body = CreateMergeRequest {
+            source_branch: &request.head_branch,
+            target_branch: &request.base_branch,
+            title,
+            description: request.body.as_deref(),
+            target_project_id,
+        };
+
+        let mr: MergeRequest = self
+            .api
+            .post(
+                &self.project_endpoint(&source_path, "/merge_requests"),
+                &body,
+            )
+            .await?;
+        info!(
+            "Created GitLab MR !{} for branch {}",
+            mr.iid, request.head_branch
+        );
+
+        Ok(mr.into())
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestInfo, GitHostError> {
+        let (project_path, iid) = self.parse_mr_url(pr_url)?;
+        let mr: MergeRequest = self
+            .api
+            .get(
+                &self.project_endpoint(&project_path, &format!("/merge_requests/{iid}")),
+                &[],
+            )
+            .await?;
+        Ok(mr.into())
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        branch_name: &str,
+    ) -> Result<Vec<PullRequestInfo>, GitHostError> {
+        let project_path = self.base.repo_path(remote_url)?;
+        let mrs: Vec<MergeRequest> = self
+            .api
+            .get_all(
+                &self.project_endpoint(&project_path, "/merge_requests"),
+                &[
+                    ("source_branch", branch_name.to_string()),
+                    ("state", "all".to_string()),
+                ],
+                "per_page",
+                PAGE_SIZE,
+            )
+            .await?;
+        Ok(mrs.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+        pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        let project_path = self.base.repo_path(remote_url)?;
+        let notes: Vec<Note> = self
+            .api
+            .get_all(
+                &self
+                    .project_endpoint(&project_path, &format!("/merge_requests/{pr_number}/notes")),
+                &[
+                    ("sort", "asc".to_string()),
+                    ("order_by", "created_at".to_string()),
+                ],
+                "per_page",
+                PAGE_SIZE,
+            )
+            .await?;
+
+        let mut unified: Vec<UnifiedPrComment> = notes
+            .into_iter()
+            // System notes are activity events ("added 1 commit", "approved", ...)
+            .filter(|note| !note.system)
+            .map(|note| {
+                let url = Some(self.note_url(&project_path, pr_number, note.id));
+                match note.position {
+                    Some(position) => UnifiedPrComment::Review {
+                        id: note.id,
+                        author: note.author.username,
+                        author_association: None,
+                        body: note.body,
+                        created_at: note.created_at,
+                        url,
+                        path: position.new_path.or(position.old_path).unwrap_or_default(),
+                        line: position.new_line.or(position.old_line),
+                        side: Some(
+                            if position.new_line.is_some() {
+                                "RIGHT"
+                            } else {
+                                "LEFT"
+                            }
+                            .to_string(),
+                        ),
+                        diff_hunk: None,
+                    },
+                    None => UnifiedPrComment::General {
+                        id: note.id.to_string(),
+                        author: note.author.username,
+                        author_association: None,
+                        body: note.body,
+                        created_at: note.created_at,
+                        url,
+                    },
+                }
+            })
+            .collect();
+
+        unified.sort_by_key(|c| c.created_at());
+
+        Ok(unified)
+    }
+
+    async fn list_open_prs(
+        &self,
+        _repo_path: &Path,
+        remote_url: &str,
+    ) -> Result<Vec<OpenPrInfo>, GitHostError> {
+        let project_path = self.base.repo_path(remote_url)?;
+        let mrs: Vec<MergeRequest> = self
+            .api
+            .get_all(
+                &self.project_endpoint(&project_path, "/merge_requests"),
+                &[("state", "opened".to_string())],
+                "per_page",
+                PAGE_SIZE,
+            )
+            .await?;
+
+        Ok(mrs
+            .into_iter()
+            .map(|mr| OpenPrInfo {
+                number: mr.iid,
+                url: mr.web_url,
+                title: mr.title,
+                head_branch: mr.source_branch,
+                base_branch: mr.target_branch,
+            })
+            .collect())
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::GitLab
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_merge_request_urls() {
+        // reqwest is built without a default crypto provider
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let provider = GitLabProvider::new("https://gitlab.example.com", None).unwrap();
+        assert_eq!(
+            provider
+                .parse_mr_url("https://gitlab.example.com/group/sub/repo/-/merge_requests/42/diffs")
+                .unwrap(),
+            ("group/sub/repo".to_string(), 42)
+        );
+        assert!(
+            provider
+                .parse_mr_url("https://gitlab.example.com/group/repo/-/issues/1")
+                .is_err()
+        );
+    }
+}
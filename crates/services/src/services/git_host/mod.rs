@@ -1,10 +1,16 @@
 mod detection;
+mod rest;
 mod types;
 
 pub mod azure;
+pub mod gitea;
 pub mod github;
+pub mod gitlab;
 
-use std::path::Path;
+use std::{
+    path::Path,
+    sync::{LazyLock, RwLock},
+};
 
 use async_trait::async_trait;
 use db::models::merge::PullRequestInfo;
@@ -15,7 +21,15 @@ pub use types::{
     ProviderKind, ReviewCommentUser, UnifiedPrComment,
 };
 
-use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
+use self::{
+    azure::AzureDevOpsProvider, gitea::GiteaProvider, github::GitHubProvider,
+    gitlab::GitLabProvider, rest::ForgeBase,
+};
+use crate::services::config::{GitHostInstance, GitHostKind};
+
+/// Self-hosted GitLab/Gitea instances from the user's config
+static SELF_HOSTED_INSTANCES: LazyLock<RwLock<Vec<GitHostInstance>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
 
 #[async_trait]
 #[enum_dispatch(GitHostService)]
@@ -56,14 +70,67 @@ pub trait GitHostProvider: Send + Sync {
 pub enum GitHostService {
     GitHub(GitHubProvider),
     AzureDevOps(AzureDevOpsProvider),
+    GitLab(GitLabProvider),
+    Gitea(GiteaProvider),
 }
 
 impl GitHostService {
+    /// Replace the configured self-hosted instances consulted by [`Self::from_url`]
+    pub fn set_self_hosted_instances(instances: Vec<GitHostInstance>) {
+        *SELF_HOSTED_INSTANCES
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = instances;
+    }
+
     pub fn from_url(url: &str) -> Result<Self, GitHostError> {
+        if let Some(instance) = Self::find_self_hosted_instance(url) {
+            let token = instance
+                .token
+                .filter(|token| !token.trim().is_empty())
+                .or_else(|| token_from_env(instance.kind));
+            return match instance.kind {
+                GitHostKind::Gitlab => Ok(Self::GitLab(GitLabProvider::new(
+                    &instance.base_url,
+                    token,
+                )?)),
+                GitHostKind::Gitea => {
+                    Ok(Self::Gitea(GiteaProvider::new(&instance.base_url, token)?))
+                }
+            };
+        }
+
         match detect_provider_from_url(url) {
             ProviderKind::GitHub => Ok(Self::GitHub(GitHubProvider::new()?)),
             ProviderKind::AzureDevOps => Ok(Self::AzureDevOps(AzureDevOpsProvider::new()?)),
+            ProviderKind::GitLab => Ok(Self::GitLab(GitLabProvider::new(
+                ForgeBase::from_remote_url(url)?.web_url(),
+                token_from_env(GitHostKind::Gitlab),
+            )?)),
+            ProviderKind::Gitea => Ok(Self::Gitea(GiteaProvider::new(
+                ForgeBase::from_remote_url(url)?.web_url(),
+                token_from_env(GitHostKind::Gitea),
+            )?)),
             ProviderKind::Unknown => Err(GitHostError::UnsupportedProvider),
         }
     }
+
+    fn find_self_hosted_instance(url: &str) -> Option<GitHostInstance> {
+        SELF_HOSTED_INSTANCES
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find(|instance| ForgeBase::new(&instance.base_url).is_ok_and(|base| base.matches(url)))
+            .cloned()
+    }
+}
+
+/// Fallback token for instances without one in the config
+fn token_from_env(kind: GitHostKind) -> Option<String> {
+    let var = match kind {
+        GitHostKind::Gitlab => "GITLAB_TOKEN",
+        GitHostKind::Gitea => "GITEA_TOKEN",
+    };
+    std::env::var(var)
+        .ok()
+        .filter(|token| !token.trim().is_empty())
 }
@@ -0,0 +1,304 @@
+//! Shared plumbing for providers that talk to a forge's REST API directly
+//! (GitLab, Gitea/Forgejo) instead of going through a CLI.
+
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use reqwest::{Method, StatusCode};
+use serde::{Serialize, de::DeserializeOwned};
+use url::Url;
+
+use super::types::{GitHostError, ProviderKind};
+
+/// Upper bound on pages fetched by [`RestClient::get_all`]
+const MAX_PAGES: u32 = 20;
+
+/// Web root of a forge instance, used to turn remote and PR URLs into
+/// repository paths. Instances may be served under a sub-path
+/// (e.g. `https://example.com/gitlab`), which is stripped from repo paths.
+#[derive(Debug, Clone)]
+pub(super) struct ForgeBase {
+    web_url: String,
+    host: String,
+    path_prefix: String,
+}
+
+impl ForgeBase {
+    pub fn new(base_url: &str) -> Result<Self, GitHostError> {
+        let parsed = Url::parse(base_url.trim()).map_err(|e| {
+            GitHostError::Repository(format!("Invalid git host base URL '{base_url}': {e}"))
+        })?;
+        let host = parsed
+            .host_str()
+            .ok_or_else(|| GitHostError::Repository(format!("No host in URL '{base_url}'")))?
+            .to_ascii_lowercase();
+        Ok(Self {
+            web_url: base_url.trim().trim_end_matches('/').to_string(),
+            host,
+            path_prefix: parsed.path().trim_matches('/').to_string(),
+        })
+    }
+
+    /// Derive the web root from a remote on a public instance (gitlab.com, codeberg.org, ...)
+    pub fn from_remote_url(url: &str) -> Result<Self, GitHostError> {
+        let remote = RemoteUrl::parse(url)
+            .ok_or_else(|| GitHostError::Repository(format!("Unrecognized remote URL '{url}'")))?;
+        Self::new(&format!("https://{}", remote.host))
+    }
+
+    pub fn web_url(&self) -> &str {
+        &self.web_url
+    }
+
+    pub fn api_url(&self, suffix: &str) -> String {
+        format!("{}/{}", self.web_url, suffix.trim_start_matches('/'))
+    }
+
+    /// Whether a remote or PR URL points at this instance
+    pub fn matches(&self, url: &str) -> bool {
+        RemoteUrl::parse(url).is_some_and(|remote| remote.hostname() == self.host)
+    }
+
+    /// Repository path (`owner/repo`, or `group/subgroup/repo` on GitLab) of a remote URL
+    pub fn repo_path(&self, url: &str) -> Result<String, GitHostError> {
+        let remote = RemoteUrl::parse(url)
+            .ok_or_else(|| GitHostError::Repository(format!("Unrecognized remote URL '{url}'")))?;
+        let path = if self.path_prefix.is_empty() {
+            remote.path.as_str()
+        } else {
+            remote
+                .path
+                .strip_prefix(&format!("{}/", self.path_prefix))
+                .unwrap_or(&remote.path)
+        };
+        Ok(path.to_string())
+    }
+}
+
+/// Host and repository path of a git remote
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct RemoteUrl {
+    /// `host` or `host:port` for http(s) remotes
+    pub host: String,
+    /// Path without leading slash or `.git` suffix
+    pub path: String,
+}
+
+impl RemoteUrl {
+    /// Parse `https://host/owner/repo.git`, `ssh://git@host:22/owner/repo.git`
+    /// and scp-like `git@host:owner/repo.git` remotes.
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        if let Ok(parsed) = Url::parse(url)
+            && let Some(host) = parsed.host_str()
+        {
+            // The ssh port says nothing about where the web UI lives
+            let host = match (parsed.scheme(), parsed.port()) {
+                ("http" | "https", Some(port)) => format!("{host}:{port}"),
+                _ => host.to_string(),
+            };
+            return Some(Self {
+                host: host.to_ascii_lowercase(),
+                path: clean_path(parsed.path())?,
+            });
+        }
+
+        let (user_host, path) = url.split_once(':')?;
+        let host = user_host.rsplit('@').next()?;
+        if host.is_empty() || host.contains('/') {
+            return None;
+        }
+        Some(Self {
+            host: host.to_ascii_lowercase(),
+            path: clean_path(path)?,
+        })
+    }
+
+    /// Host without port
+    pub fn hostname(&self) -> &str {
+        self.host.split(':').next().unwrap_or(&self.host)
+    }
+}
+
+fn clean_path(path: &str) -> Option<String> {
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    (!path.is_empty()).then(|| path.to_string())
+}
+
+/// Percent-encode a value for use as a single URL path segment
+pub(super) fn encode_segment(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
+/// JSON client with authentication, error mapping and retries
+#[derive(Debug, Clone)]
+pub(super) struct RestClient {
+    client: reqwest::Client,
+    api_base: String,
+    auth_header: Option<(&'static str, String)>,
+    provider: ProviderKind,
+}
+
+impl RestClient {
+    pub fn new(
+        api_base: String,
+        auth_header: Option<(&'static str, String)>,
+        provider: ProviderKind,
+    ) -> Result<Self, GitHostError> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| GitHostError::Repository(format!("Failed to build HTTP client: {e}")))?;
+        Ok(Self {
+            client,
+            api_base,
+            auth_header,
+            provider,
+        })
+    }
+
+    pub async fn get<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, GitHostError> {
+        self.send::<T, ()>(Method::GET, path, query, None).await
+    }
+
+    pub async fn post<T: DeserializeOwned, B: Serialize + Sync>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, GitHostError> {
+        self.send(Method::POST, path, &[], Some(body)).await
+    }
+
+    /// Fetch every page of a list endpoint. `page_size_param` is `per_page`
+    /// on GitLab and `limit` on Gitea.
+    pub async fn get_all<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+        page_size_param: &str,
+        page_size: u32,
+    ) -> Result<Vec<T>, GitHostError> {
+        let mut items = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let mut page_query = query.to_vec();
+            page_query.push(("page", page.to_string()));
+            page_query.push((page_size_param, page_size.to_string()));
+
+            let batch: Vec<T> = self.get(path, &page_query).await?;
+            let done = batch.len() < page_size as usize;
+            items.extend(batch);
+            if done {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    async fn send<T: DeserializeOwned, B: Serialize + Sync>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+        body: Option<&B>,
+    ) -> Result<T, GitHostError> {
+        let url = format!("{}/{}", self.api_base, path.trim_start_matches('/'));
+
+        (|| async {
+            let mut request = self.client.request(method.clone(), &url).query(query);
+            if let Some((name, value)) = &self.auth_header {
+                request = request.header(*name, value);
+            }
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+
+            let response = request.send().await.map_err(|e| {
+                GitHostError::PullRequest(format!("{} API request failed: {e}", self.provider))
+            })?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(self.status_error(status, body.trim()));
+            }
+
+            response.json::<T>().await.map_err(|e| {
+                GitHostError::UnexpectedOutput(format!(
+                    "Failed to parse {} API response: {e}",
+                    self.provider
+                ))
+            })
+        })
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(3)
+                .with_jitter(),
+        )
+        .when(|e: &GitHostError| e.should_retry())
+        .notify(|err: &GitHostError, dur: Duration| {
+            tracing::warn!(
+                "{} API call failed, retrying after {:.2}s: {}",
+                self.provider,
+                dur.as_secs_f64(),
+                err
+            );
+        })
+        .await
+    }
+
+    fn status_error(&self, status: StatusCode, body: &str) -> GitHostError {
+        let msg = format!("{} API returned {status}: {body}", self.provider);
+        match status {
+            StatusCode::UNAUTHORIZED => GitHostError::AuthFailed(msg),
+            StatusCode::FORBIDDEN => GitHostError::InsufficientPermissions(msg),
+            StatusCode::NOT_FOUND => GitHostError::RepoNotFoundOrNoAccess(msg),
+            _ => GitHostError::PullRequest(msg),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_remote_url_forms() {
+        assert_eq!(
+            RemoteUrl::parse("https://gitlab.example.com/group/sub/repo.git"),
+            Some(RemoteUrl {
+                host: "gitlab.example.com".to_string(),
+                path: "group/sub/repo".to_string(),
+            })
+        );
+        assert_eq!(
+            RemoteUrl::parse("git@codeberg.org:owner/repo.git"),
+            Some(RemoteUrl {
+                host: "codeberg.org".to_string(),
+                path: "owner/repo".to_string(),
+            })
+        );
+        let ssh = RemoteUrl::parse("ssh://git@gitea.local:2222/owner/repo.git").unwrap();
+        assert_eq!(ssh.host, "gitea.local");
+        let http = RemoteUrl::parse("http://gitea.local:3000/owner/repo").unwrap();
+        assert_eq!(http.hostname(), "gitea.local");
+    }
+
+    #[test]
+    fn strips_instance_sub_path() {
+        let base = ForgeBase::new("https://example.com/gitlab/").unwrap();
+        assert!(base.matches("git@example.com:gitlab/team/repo.git"));
+        assert_eq!(
+            base.repo_path("https://example.com/gitlab/team/repo.git")
+                .unwrap(),
+            "team/repo"
+        );
+        assert!(!base.matches("https://github.com/team/repo"));
+    }
+}
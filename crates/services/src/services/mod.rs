@@ -3,8 +3,10 @@ pub mod approvals;
 pub mod auth;
 pub mod commit_message;
 pub mod config;
+pub mod conflict_resolution;
 pub mod container;
 pub mod diff_stream;
+pub mod drift_monitor;
 pub mod events;
 pub mod file_ranker;
 pub mod file_search;
@@ -16,10 +18,16 @@ pub mod notification;
 pub mod oauth_credentials;
 pub mod pr_monitor;
 pub mod project;
+pub mod project_memory;
 #[cfg(feature = "qa-mode")]
 pub mod qa_repos;
 pub mod queued_message;
 pub mod remote_client;
 pub mod repo;
+pub mod run_queue;
+pub mod scheduler;
+pub mod task_archive;
+pub mod task_template;
+pub mod usage;
 pub mod workspace_manager;
 pub mod worktree_manager;
@@ -5,10 +5,16 @@ use utils;
 
 use crate::services::config::{Config, NotificationConfig, SoundFile};
 
-/// Service for handling cross-platform notifications including sound alerts and push notifications
+mod sinks;
+
+pub use sinks::{Notification, SinkDispatcher, SinkError};
+
+/// Service for handling cross-platform notifications including sound alerts and push notifications,
+/// plus delivery to the outbound sinks configured in `NotificationConfig`
 #[derive(Debug, Clone)]
 pub struct NotificationService {
     config: Arc<RwLock<Config>>,
+    sinks: SinkDispatcher,
 }
 
 /// Cache for WSL root path from PowerShell
@@ -16,13 +22,38 @@ static WSL_ROOT_PATH_CACHE: OnceLock<Option<String>> = OnceLock::new();
 
 impl NotificationService {
     pub fn new(config: Arc<RwLock<Config>>) -> Self {
-        Self { config }
+        Self {
+            config,
+            sinks: SinkDispatcher::default(),
+        }
+    }
+
+    /// Send both sound and push notifications if enabled, and deliver to matching sinks
+    /// in the background so slow or retrying sinks never hold up the caller
+    pub async fn notify(&self, notification: Notification) {
+        let config = self.config.read().await.notifications.clone();
+        Self::send_notification(&config, &notification.title, &notification.message).await;
+        self.dispatch_to_sinks(config, notification);
     }
 
-    /// Send both sound and push notifications if enabled
-    pub async fn notify(&self, title: &str, message: &str) {
+    /// Deliver only to the matching sinks, without sound or push notification.
+    /// Used for background events the user did not trigger, such as PR polling.
+    pub async fn notify_sinks(&self, notification: Notification) {
         let config = self.config.read().await.notifications.clone();
-        Self::send_notification(&config, title, message).await;
+        self.dispatch_to_sinks(config, notification);
+    }
+
+    fn dispatch_to_sinks(&self, config: NotificationConfig, notification: Notification) {
+        if config
+            .sinks
+            .iter()
+            .any(|sink| sink.accepts(notification.event))
+        {
+            let sinks = self.sinks.clone();
+            tokio::spawn(async move {
+                sinks.dispatch(&config.sinks, &notification).await;
+            });
+        }
     }
 
     /// Internal method to send notifications with a given config
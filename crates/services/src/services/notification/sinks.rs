@@ -0,0 +1,332 @@
+//! Outbound notification sinks: generic JSON webhooks, Slack incoming webhooks
+//! and ntfy-style plain-text endpoints.
+
+use std::time::Duration;
+
+use backon::{ExponentialBuilder, Retryable};
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::Serialize;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::services::config::{NotificationEvent, NotificationSink, NotificationSinkKind};
+
+/// A notification as delivered to sinks. The generic webhook sink posts it as-is.
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub event: NotificationEvent,
+    pub title: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl Notification {
+    pub fn new(
+        event: NotificationEvent,
+        title: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            event,
+            title: title.into(),
+            message: message.into(),
+            task_id: None,
+            workspace_id: None,
+            url: None,
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn with_task(mut self, task_id: Uuid) -> Self {
+        self.task_id = Some(task_id);
+        self
+    }
+
+    pub fn with_workspace(mut self, workspace_id: Uuid) -> Self {
+        self.workspace_id = Some(workspace_id);
+        self
+    }
+
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SinkError {
+    #[error(transparent)]
+    Transport(#[from] reqwest::Error),
+    #[error("Sink responded with status {0}")]
+    Status(StatusCode),
+}
+
+impl SinkError {
+    /// Network failures, rate limiting and server errors are worth retrying;
+    /// other client errors mean the sink is misconfigured.
+    fn should_retry(&self) -> bool {
+        match self {
+            SinkError::Transport(_) => true,
+            SinkError::Status(status) => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SinkDispatcher {
+    client: reqwest::Client,
+    min_delay: Duration,
+}
+
+impl Default for SinkDispatcher {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1))
+    }
+}
+
+impl SinkDispatcher {
+    pub fn new(min_delay: Duration) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+        Self { client, min_delay }
+    }
+
+    /// Deliver to every sink that accepts the event, concurrently.
+    /// Failures are logged; one failing sink never blocks the others.
+    pub async fn dispatch(&self, sinks: &[NotificationSink], notification: &Notification) {
+        let deliveries = sinks
+            .iter()
+            .filter(|sink| sink.accepts(notification.event))
+            .map(|sink| async move {
+                if let Err(e) = self.deliver(sink, notification).await {
+                    tracing::warn!(
+                        "Failed to deliver '{}' notification to sink '{}': {}",
+                        notification.event,
+                        sink.name,
+                        e
+                    );
+                }
+            });
+        futures::future::join_all(deliveries).await;
+    }
+
+    /// Deliver to a single sink, retrying with exponential backoff
+    pub async fn deliver(
+        &self,
+        sink: &NotificationSink,
+        notification: &Notification,
+    ) -> Result<(), SinkError> {
+        (|| async { self.send_once(sink, notification).await })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(self.min_delay)
+                    .with_max_delay(self.min_delay * 30)
+                    .with_max_times(sink.max_retries as usize)
+                    .with_jitter(),
+            )
+            .when(|e: &SinkError| e.should_retry())
+            .notify(|err: &SinkError, dur: Duration| {
+                tracing::debug!(
+                    "Notification sink '{}' failed, retrying after {:.2}s: {}",
+                    sink.name,
+                    dur.as_secs_f64(),
+                    err
+                );
+            })
+            .await
+    }
+
+    async fn send_once(
+        &self,
+        sink: &NotificationSink,
+        notification: &Notification,
+    ) -> Result<(), SinkError> {
+        let mut request = self.client.post(&sink.url);
+        if let Some(token) = &sink.token {
+            request = request.bearer_auth(token);
+        }
+
+        request = match sink.kind {
+            NotificationSinkKind::Webhook => request.json(notification),
+            NotificationSinkKind::Slack => {
+                let mut text = format!("*{}*\n{}", notification.title, notification.message);
+                if let Some(url) = &notification.url {
+                    text.push_str(&format!("\n<{url}>"));
+                }
+                request.json(&serde_json::json!({ "text": text }))
+            }
+            NotificationSinkKind::Ntfy => {
+                let mut request = request
+                    .header("Title", &notification.title)
+                    .header("Tags", notification.event.to_string())
+                    .body(notification.message.clone());
+                if let Some(url) = &notification.url {
+                    request = request.header("Click", url);
+                }
+                request
+            }
+        };
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(SinkError::Status(response.status()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+        sync::Mutex,
+    };
+
+    use super::*;
+
+    /// Minimal HTTP stub: answers requests with the given statuses in order
+    /// (repeating the last one) and records each raw request.
+    async fn spawn_stub(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<String>>>, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let hits = Arc::new(AtomicUsize::new(0));
+
+        let (requests_clone, hits_clone) = (requests.clone(), hits.clone());
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut buf = vec![0u8; 16 * 1024];
+                let n = socket.read(&mut buf).await.unwrap_or(0);
+                requests_clone
+                    .lock()
+                    .await
+                    .push(String::from_utf8_lossy(&buf[..n]).to_string());
+                let hit = hits_clone.fetch_add(1, Ordering::SeqCst);
+                let status = statuses[hit.min(statuses.len() - 1)];
+                let response = format!(
+                    "HTTP/1.1 {status} Stub\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (url, requests, hits)
+    }
+
+    fn sink(kind: NotificationSinkKind, url: String) -> NotificationSink {
+        NotificationSink {
+            name: "stub".to_string(),
+            enabled: true,
+            kind,
+            url,
+            token: Some("secret".to_string()),
+            events: vec![],
+            max_retries: 3,
+        }
+    }
+
+    fn notification() -> Notification {
+        Notification::new(
+            NotificationEvent::TaskCompleted,
+            "Task Complete",
+            "All done",
+        )
+    }
+
+    #[tokio::test]
+    async fn webhook_posts_json_with_bearer_token() {
+        let (url, requests, _) = spawn_stub(vec![200]).await;
+        let dispatcher = SinkDispatcher::new(Duration::from_millis(5));
+
+        dispatcher
+            .deliver(&sink(NotificationSinkKind::Webhook, url), &notification())
+            .await
+            .unwrap();
+
+        let request = requests.lock().await[0].to_lowercase();
+        assert!(request.starts_with("post /hook"));
+        assert!(request.contains("authorization: bearer secret"));
+        assert!(request.contains("\"event\":\"task_completed\""));
+    }
+
+    #[tokio::test]
+    async fn slack_and_ntfy_formats() {
+        let (url, requests, _) = spawn_stub(vec![200]).await;
+        let dispatcher = SinkDispatcher::new(Duration::from_millis(5));
+
+        dispatcher
+            .deliver(
+                &sink(NotificationSinkKind::Slack, url.clone()),
+                &notification(),
+            )
+            .await
+            .unwrap();
+        dispatcher
+            .deliver(&sink(NotificationSinkKind::Ntfy, url), &notification())
+            .await
+            .unwrap();
+
+        let requests = requests.lock().await;
+        assert!(requests[0].contains(r#"{"text":"*Task Complete*\nAll done"}"#));
+        assert!(requests[1].to_lowercase().contains("title: task complete"));
+        assert!(requests[1].ends_with("All done"));
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_until_success() {
+        let (url, _, hits) = spawn_stub(vec![500, 503, 200]).await;
+        let dispatcher = SinkDispatcher::new(Duration::from_millis(5));
+
+        dispatcher
+            .deliver(&sink(NotificationSinkKind::Webhook, url), &notification())
+            .await
+            .unwrap();
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_errors() {
+        let (url, _, hits) = spawn_stub(vec![404]).await;
+        let dispatcher = SinkDispatcher::new(Duration::from_millis(5));
+
+        let result = dispatcher
+            .deliver(&sink(NotificationSinkKind::Webhook, url), &notification())
+            .await;
+        assert!(matches!(
+            result,
+            Err(SinkError::Status(StatusCode::NOT_FOUND))
+        ));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn dispatch_respects_event_filters() {
+        let (url, _, hits) = spawn_stub(vec![200]).await;
+        let dispatcher = SinkDispatcher::new(Duration::from_millis(5));
+        let mut filtered = sink(NotificationSinkKind::Webhook, url);
+        filtered.events = vec![NotificationEvent::PrStatusChanged];
+
+        dispatcher.dispatch(&[filtered], &notification()).await;
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+    }
+}
@@ -16,7 +16,10 @@ use tracing::{debug, error, info};
 
 use crate::services::{
     analytics::AnalyticsContext,
+    config::NotificationEvent,
+    container::{ContainerError, ContainerService},
     git_host::{self, GitHostError, GitHostProvider},
+    notification::Notification,
 };
 
 #[derive(Debug, Error)]
@@ -27,22 +30,27 @@ enum PrMonitorError {
     WorkspaceError(#[from] WorkspaceError),
     #[error(transparent)]
     Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
 }
 
 /// Service to monitor PRs and update task status when they are merged
-pub struct PrMonitorService {
+pub struct PrMonitorService<C> {
     db: DBService,
+    container: C,
     poll_interval: Duration,
     analytics: Option<AnalyticsContext>,
 }
 
-impl PrMonitorService {
+impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
     pub async fn spawn(
         db: DBService,
+        container: C,
         analytics: Option<AnalyticsContext>,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
+            container,
             poll_interval: Duration::from_secs(60), // Check every minute
             analytics,
         };
@@ -110,16 +118,22 @@ impl PrMonitorService {
             )
             .await?;
 
+            let workspace = Workspace::find_by_id(&self.db.pool, pr_merge.workspace_id).await?;
+            self.notify_status_change(pr_merge, &pr_status.status, workspace.as_ref())
+                .await;
+
             // If the PR was merged, update the task status to done
             if matches!(&pr_status.status, MergeStatus::Merged)
-                && let Some(workspace) =
-                    Workspace::find_by_id(&self.db.pool, pr_merge.workspace_id).await?
+                && let Some(workspace) = workspace
             {
                 info!(
                     "PR #{} was merged, updating task {} to done and archiving workspace",
                     pr_merge.pr_info.number, workspace.task_id
                 );
-                Task::update_status(&self.db.pool, workspace.task_id, TaskStatus::Done).await?;
+                // Also starts the tasks this one was blocking
+                self.container
+                    .update_task_status(workspace.task_id, TaskStatus::Done)
+                    .await?;
 
                 // Archive workspace unless pinned
                 if !workspace.pinned {
@@ -145,4 +159,35 @@ impl PrMonitorService {
 
         Ok(())
     }
+
+    /// Let the configured notification sinks know the PR left the open state
+    async fn notify_status_change(
+        &self,
+        pr_merge: &PrMerge,
+        status: &MergeStatus,
+        workspace: Option<&Workspace>,
+    ) {
+        let status = match status {
+            MergeStatus::Open => "open",
+            MergeStatus::Merged => "merged",
+            MergeStatus::Closed => "closed",
+            MergeStatus::Unknown => "unknown",
+        };
+
+        let mut notification = Notification::new(
+            NotificationEvent::PrStatusChanged,
+            format!("PR #{} {}", pr_merge.pr_info.number, status),
+            format!("Pull request {} is now {}", pr_merge.pr_info.url, status),
+        )
+        .with_workspace(pr_merge.workspace_id)
+        .with_url(pr_merge.pr_info.url.clone());
+        if let Some(workspace) = workspace {
+            notification = notification.with_task(workspace.task_id);
+        }
+
+        self.container
+            .notification_service()
+            .notify_sinks(notification)
+            .await;
+    }
 }
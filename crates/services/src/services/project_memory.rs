@@ -0,0 +1,304 @@
+//! Project memory: accepted entries prepended to the prompt of every new
+//! coding agent session, optionally mirrored into a file of each project repo.
+
+use std::{
+    io,
+    path::{Component, Path},
+};
+
+use db::models::{
+    project::Project,
+    project_memory::{ProjectMemoryEntry, ProjectMemoryStatus},
+    project_repo::ProjectRepo,
+};
+use git::{GitService, GitServiceError};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+const SECTION_START: &str = "<!-- project-memory:start -->";
+const SECTION_END: &str = "<!-- project-memory:end -->";
+
+#[derive(Debug, Error)]
+pub enum ProjectMemoryError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Git(#[from] GitServiceError),
+    #[error("Invalid memory file: {0}")]
+    InvalidMemoryFile(String),
+}
+
+/// The memory file must stay inside the repo
+pub fn validate_memory_file(memory_file: &str) -> Result<(), ProjectMemoryError> {
+    let path = Path::new(memory_file);
+    if memory_file.trim().is_empty()
+        || !path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Err(ProjectMemoryError::InvalidMemoryFile(format!(
+            "'{memory_file}' must be a path relative to the repository root"
+        )));
+    }
+    Ok(())
+}
+
+/// One bullet per entry, continuation lines indented under it
+fn memory_list(entries: &[ProjectMemoryEntry]) -> String {
+    entries
+        .iter()
+        .map(|entry| {
+            let mut lines = entry.content.trim().lines();
+            let first = lines.next().unwrap_or_default();
+            lines.fold(format!("- {first}"), |item, line| {
+                if line.trim().is_empty() {
+                    format!("{item}\n")
+                } else {
+                    format!("{item}\n  {line}")
+                }
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Text prepended to the prompt of new coding agent sessions, `None` without
+/// accepted entries
+pub fn memory_prompt(entries: &[ProjectMemoryEntry]) -> Option<String> {
+    let accepted: Vec<ProjectMemoryEntry> = entries
+        .iter()
+        .filter(|entry| entry.status == ProjectMemoryStatus::Accepted)
+        .cloned()
+        .collect();
+    if accepted.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "Project memory, notes kept from earlier work on this project:\n{}\n\n---\n\n",
+        memory_list(&accepted)
+    ))
+}
+
+/// Memory prompt of the project's accepted entries
+pub async fn load_memory_prompt(
+    pool: &SqlitePool,
+    project_id: Uuid,
+) -> Result<Option<String>, sqlx::Error> {
+    let entries = ProjectMemoryEntry::find_by_project_id(
+        pool,
+        project_id,
+        Some(ProjectMemoryStatus::Accepted),
+    )
+    .await?;
+    Ok(memory_prompt(&entries))
+}
+
+/// Replace the managed section of the file content, appending it when the file
+/// has none. Without a section, the managed one is removed and the rest of the
+/// file left alone.
+pub fn merge_memory_section(existing: &str, section: Option<&str>) -> String {
+    let block = section.map(|section| format!("{SECTION_START}\n{section}\n{SECTION_END}"));
+    let bounds = existing.find(SECTION_START).and_then(|start| {
+        existing[start..]
+            .find(SECTION_END)
+            .map(|end| (start, start + end + SECTION_END.len()))
+    });
+
+    match (bounds, block) {
+        (Some((start, end)), Some(block)) => {
+            format!("{}{}{}", &existing[..start], block, &existing[end..])
+        }
+        (Some((start, end)), None) => {
+            let before = existing[..start].trim_end();
+            let after = existing[end..].trim_start();
+            match (before.is_empty(), after.is_empty()) {
+                (true, _) => after.to_string(),
+                (false, true) => format!("{before}\n"),
+                (false, false) => format!("{before}\n\n{after}"),
+            }
+        }
+        (None, Some(block)) if existing.trim().is_empty() => format!("{block}\n"),
+        (None, Some(block)) => format!("{}\n\n{}\n", existing.trim_end(), block),
+        (None, None) => existing.to_string(),
+    }
+}
+
+/// Why the memory file is not committed on the checked out branch, if it
+/// isn't: only the repo's default target branch gets the commit, or any named
+/// branch when the repo has none, so it never lands on a detached HEAD or on
+/// the user's feature branch.
+fn memory_branch_skip_reason(
+    checked_out: Option<&str>,
+    default_target_branch: Option<&str>,
+) -> Option<String> {
+    match (checked_out, default_target_branch) {
+        (None, _) => Some("HEAD is detached".to_string()),
+        (Some(branch), Some(target)) if branch != target => Some(format!(
+            "{branch} is checked out instead of the target branch {target}"
+        )),
+        _ => None,
+    }
+}
+
+/// Write the accepted entries into the project's memory file in every repo of
+/// the project and commit it there. Only the managed section is touched, and
+/// the file is not created when there is nothing to write. Repos where the
+/// file has uncommitted edits, a merge or rebase is in progress or the target
+/// branch is not checked out are skipped, so the user's work is never swept
+/// into the commit.
+pub async fn mirror_memory_file(
+    pool: &SqlitePool,
+    git: &GitService,
+    project: &Project,
+) -> Result<(), ProjectMemoryError> {
+    let Some(memory_file) = &project.memory_file else {
+        return Ok(());
+    };
+    validate_memory_file(memory_file)?;
+
+    let entries = ProjectMemoryEntry::find_by_project_id(
+        pool,
+        project.id,
+        Some(ProjectMemoryStatus::Accepted),
+    )
+    .await?;
+    let section = (!entries.is_empty()).then(|| {
+        format!(
+            "## Project memory\n\nNotes kept from earlier work on this project.\n\n{}",
+            memory_list(&entries)
+        )
+    });
+
+    for repo in ProjectRepo::find_repos_for_project(pool, project.id).await? {
+        let path = repo.path.join(memory_file);
+        let existing = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                if section.is_none() {
+                    continue;
+                }
+                String::new()
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let updated = merge_memory_section(&existing, section.as_deref());
+        if updated == existing {
+            continue;
+        }
+        let checked_out = git.get_checked_out_branch(&repo.path)?;
+        if let Some(reason) = memory_branch_skip_reason(
+            checked_out.as_deref(),
+            repo.default_target_branch.as_deref(),
+        ) {
+            tracing::warn!(
+                "Not mirroring project memory into {}: {}",
+                repo.path.display(),
+                reason
+            );
+            continue;
+        }
+        if git.file_has_changes(&repo.path, memory_file)? {
+            tracing::warn!(
+                "Not mirroring project memory into {}: {} has uncommitted changes",
+                repo.path.display(),
+                memory_file
+            );
+            continue;
+        }
+        if let Some(op) = git.detect_conflict_op(&repo.path)? {
+            tracing::warn!(
+                "Not mirroring project memory into {}: {:?} in progress",
+                repo.path.display(),
+                op
+            );
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, updated)?;
+        git.commit_file(&repo.path, memory_file, "Update project memory")?;
+    }
+    Ok(())
+}
+
+/// Candidate content from a completion summary, `None` when it is empty
+pub fn candidate_content(summary: &str) -> Option<&str> {
+    let summary = summary.trim();
+    (!summary.is_empty()).then_some(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn entry(content: &str, status: ProjectMemoryStatus) -> ProjectMemoryEntry {
+        ProjectMemoryEntry {
+            id: Uuid::new_v4(),
+            project_id: Uuid::nil(),
+            content: content.to_string(),
+            status,
+            workspace_id: None,
+            coding_agent_turn_id: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn memory_is_committed_only_on_the_target_branch() {
+        assert!(memory_branch_skip_reason(None, None).is_some());
+        assert!(memory_branch_skip_reason(None, Some("main")).is_some());
+        assert!(memory_branch_skip_reason(Some("feature"), Some("main")).is_some());
+        assert_eq!(memory_branch_skip_reason(Some("main"), Some("main")), None);
+        assert_eq!(memory_branch_skip_reason(Some("main"), None), None);
+    }
+
+    #[test]
+    fn prompt_lists_only_accepted_entries() {
+        let entries = vec![
+            entry(
+                "Run `pnpm lint` before committing",
+                ProjectMemoryStatus::Accepted,
+            ),
+            entry("Proposed", ProjectMemoryStatus::Candidate),
+            entry("Migrations\nare append-only", ProjectMemoryStatus::Accepted),
+        ];
+        let prompt = memory_prompt(&entries).unwrap();
+        assert!(
+            prompt.contains("- Run `pnpm lint` before committing\n- Migrations\n  are append-only")
+        );
+        assert!(!prompt.contains("Proposed"));
+        assert!(memory_prompt(&entries[1..2]).is_none());
+    }
+
+    #[test]
+    fn merges_managed_section() {
+        let appended = merge_memory_section("# Agents\n", Some("notes"));
+        assert_eq!(
+            appended,
+            format!("# Agents\n\n{SECTION_START}\nnotes\n{SECTION_END}\n")
+        );
+        let replaced = merge_memory_section(&appended, Some("other"));
+        assert_eq!(
+            replaced,
+            format!("# Agents\n\n{SECTION_START}\nother\n{SECTION_END}\n")
+        );
+        assert_eq!(merge_memory_section(&replaced, None), "# Agents\n");
+        assert_eq!(merge_memory_section("# Agents\n", None), "# Agents\n");
+    }
+
+    #[test]
+    fn memory_file_stays_inside_repo() {
+        assert!(validate_memory_file("AGENTS.md").is_ok());
+        assert!(validate_memory_file("docs/AGENTS.md").is_ok());
+        assert!(validate_memory_file("../AGENTS.md").is_err());
+        assert!(validate_memory_file("/etc/AGENTS.md").is_err());
+        assert!(validate_memory_file(" ").is_err());
+    }
+}
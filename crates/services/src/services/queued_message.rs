@@ -1,24 +1,12 @@
-use std::sync::Arc;
-
-use chrono::{DateTime, Utc};
-use dashmap::DashMap;
-use db::models::scratch::DraftFollowUpData;
+pub use db::models::queued_message::QueuedMessage;
+use db::{
+    DBService,
+    models::{queued_message::QueuedMessageError, scratch::DraftFollowUpData},
+};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
 
-/// Represents a queued follow-up message for a session
-#[derive(Debug, Clone, Serialize, Deserialize, TS)]
-#[ts(export)]
-pub struct QueuedMessage {
-    /// The session this message is queued for
-    pub session_id: Uuid,
-    /// The follow-up data (message + variant)
-    pub data: DraftFollowUpData,
-    /// Timestamp when the message was queued
-    pub queued_at: DateTime<Utc>,
-}
-
 /// Status of the queue for a session (for frontend display)
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "status", rename_all = "snake_case")]
@@ -26,67 +14,111 @@ pub struct QueuedMessage {
 pub enum QueueStatus {
     /// No message queued
     Empty,
-    /// Message is queued and waiting for execution to complete
-    Queued { message: QueuedMessage },
+    /// Messages are queued and waiting for execution to complete
+    Queued {
+        /// The next message to be sent
+        message: QueuedMessage,
+        /// Every queued message in the order they will be sent, starting with `message`
+        messages: Vec<QueuedMessage>,
+    },
 }
 
-/// In-memory service for managing queued follow-up messages.
-/// One queued message per session.
+impl From<Vec<QueuedMessage>> for QueueStatus {
+    fn from(messages: Vec<QueuedMessage>) -> Self {
+        match messages.first() {
+            Some(next) => QueueStatus::Queued {
+                message: next.clone(),
+                messages,
+            },
+            None => QueueStatus::Empty,
+        }
+    }
+}
+
+/// Service for managing queued follow-up messages.
+/// Messages are persisted per session and drain in FIFO order, one per
+/// completed coding agent turn.
 #[derive(Clone)]
 pub struct QueuedMessageService {
-    queue: Arc<DashMap<Uuid, QueuedMessage>>,
+    db: DBService,
 }
 
 impl QueuedMessageService {
-    pub fn new() -> Self {
-        Self {
-            queue: Arc::new(DashMap::new()),
-        }
+    pub fn new(db: DBService) -> Self {
+        Self { db }
     }
 
-    /// Queue a message for a session. Replaces any existing queued message.
-    pub fn queue_message(&self, session_id: Uuid, data: DraftFollowUpData) -> QueuedMessage {
-        let queued = QueuedMessage {
-            session_id,
-            data,
-            queued_at: Utc::now(),
-        };
-        self.queue.insert(session_id, queued.clone());
-        queued
+    /// Append a message to the end of a session's queue
+    pub async fn queue_message(
+        &self,
+        session_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueuedMessage, QueuedMessageError> {
+        Ok(QueuedMessage::enqueue(&self.db.pool, session_id, &data).await?)
     }
 
-    /// Cancel/remove a queued message for a session
-    pub fn cancel_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Edit the contents of a queued message
+    pub async fn update_queued(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+        data: DraftFollowUpData,
+    ) -> Result<QueuedMessage, QueuedMessageError> {
+        QueuedMessage::update_data(&self.db.pool, message_id, session_id, &data).await
     }
 
-    /// Get the queued message for a session (if any)
-    pub fn get_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.get(&session_id).map(|r| r.clone())
+    /// Reorder a session's queue; `message_ids` must list every queued message
+    pub async fn reorder(
+        &self,
+        session_id: Uuid,
+        message_ids: &[Uuid],
+    ) -> Result<Vec<QueuedMessage>, QueuedMessageError> {
+        QueuedMessage::reorder(&self.db.pool, session_id, message_ids).await
     }
 
-    /// Take (remove and return) the queued message for a session.
-    /// Used by finalization flow to consume the queued message.
-    pub fn take_queued(&self, session_id: Uuid) -> Option<QueuedMessage> {
-        self.queue.remove(&session_id).map(|(_, v)| v)
+    /// Remove a single queued message
+    pub async fn remove_queued(
+        &self,
+        session_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<(), QueuedMessageError> {
+        let removed = QueuedMessage::delete(&self.db.pool, message_id, session_id).await?;
+        if removed == 0 {
+            return Err(QueuedMessageError::NotFound);
+        }
+        Ok(())
     }
 
-    /// Check if a session has a queued message
-    pub fn has_queued(&self, session_id: Uuid) -> bool {
-        self.queue.contains_key(&session_id)
+    /// Cancel/remove every queued message for a session
+    pub async fn cancel_queued(&self, session_id: Uuid) -> Result<(), QueuedMessageError> {
+        QueuedMessage::delete_by_session_id(&self.db.pool, session_id).await?;
+        Ok(())
     }
 
-    /// Get queue status for frontend display
-    pub fn get_status(&self, session_id: Uuid) -> QueueStatus {
-        match self.get_queued(session_id) {
-            Some(msg) => QueueStatus::Queued { message: msg },
-            None => QueueStatus::Empty,
-        }
+    /// Get the queued messages for a session in send order
+    pub async fn get_queued(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Vec<QueuedMessage>, QueuedMessageError> {
+        Ok(QueuedMessage::find_by_session_id(&self.db.pool, session_id).await?)
     }
-}
 
-impl Default for QueuedMessageService {
-    fn default() -> Self {
-        Self::new()
+    /// Take (remove and return) the next queued message for a session.
+    /// Used by finalization flow to consume one message per turn.
+    pub async fn take_queued(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<QueuedMessage>, QueuedMessageError> {
+        Ok(QueuedMessage::take_next(&self.db.pool, session_id).await?)
+    }
+
+    /// Check if a session has any queued messages
+    pub async fn has_queued(&self, session_id: Uuid) -> Result<bool, QueuedMessageError> {
+        Ok(QueuedMessage::has_queued(&self.db.pool, session_id).await?)
+    }
+
+    /// Get queue status for frontend display
+    pub async fn get_status(&self, session_id: Uuid) -> Result<QueueStatus, QueuedMessageError> {
+        Ok(self.get_queued(session_id).await?.into())
     }
 }
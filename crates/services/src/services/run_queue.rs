@@ -0,0 +1,92 @@
+//! Admission control for coding agent processes.
+//!
+//! Coding agents that would exceed the configured [`ConcurrencyConfig`] are
+//! created with [`ExecutionProcessStatus::Queued`] and started later, oldest
+//! first, by [`ContainerService::dispatch_queued_executions`].
+//!
+//! [`ExecutionProcessStatus::Queued`]: db::models::execution_process::ExecutionProcessStatus::Queued
+//! [`ContainerService::dispatch_queued_executions`]: crate::services::container::ContainerService::dispatch_queued_executions
+
+use std::{collections::HashMap, sync::LazyLock};
+
+use db::models::execution_process::{ExecutionProcess, ExecutionProcessRunReason};
+use executors::executors::BaseCodingAgent;
+use tokio::sync::{Mutex, MutexGuard};
+
+use crate::services::config::ConcurrencyConfig;
+
+/// Serializes admission decisions so two starts cannot both take the last slot
+static ADMISSION: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+pub async fn lock_admission() -> MutexGuard<'static, ()> {
+    ADMISSION.lock().await
+}
+
+/// The executor a process counts against, if it is a coding agent
+pub fn agent_executor(process: &ExecutionProcess) -> Option<BaseCodingAgent> {
+    if process.run_reason != ExecutionProcessRunReason::CodingAgent {
+        return None;
+    }
+    process.executor_action().ok()?.base_executor()
+}
+
+/// Coding agents currently holding a slot
+#[derive(Debug, Default)]
+pub struct RunningAgents {
+    total: u32,
+    per_executor: HashMap<BaseCodingAgent, u32>,
+}
+
+impl RunningAgents {
+    pub fn from_processes<'a>(processes: impl IntoIterator<Item = &'a ExecutionProcess>) -> Self {
+        let mut running = Self::default();
+        for executor in processes.into_iter().filter_map(agent_executor) {
+            running.add(executor);
+        }
+        running
+    }
+
+    pub fn add(&mut self, executor: BaseCodingAgent) {
+        self.total += 1;
+        *self.per_executor.entry(executor).or_default() += 1;
+    }
+
+    pub fn has_capacity(&self, executor: BaseCodingAgent, limits: &ConcurrencyConfig) -> bool {
+        let within_global = limits
+            .max_concurrent_agents
+            .is_none_or(|max| self.total < max);
+        let within_executor = limits.per_executor.get(&executor).is_none_or(|&max| {
+            self.per_executor
+                .get(&executor)
+                .copied()
+                .unwrap_or_default()
+                < max
+        });
+        within_global && within_executor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enforces_global_and_per_executor_limits() {
+        let limits = ConcurrencyConfig {
+            max_concurrent_agents: Some(3),
+            per_executor: HashMap::from([(BaseCodingAgent::ClaudeCode, 1)]),
+        };
+        let mut running = RunningAgents::default();
+        assert!(running.has_capacity(BaseCodingAgent::ClaudeCode, &limits));
+
+        running.add(BaseCodingAgent::ClaudeCode);
+        assert!(!running.has_capacity(BaseCodingAgent::ClaudeCode, &limits));
+        assert!(running.has_capacity(BaseCodingAgent::Codex, &limits));
+
+        running.add(BaseCodingAgent::Codex);
+        running.add(BaseCodingAgent::Gemini);
+        assert!(!running.has_capacity(BaseCodingAgent::Codex, &limits));
+
+        assert!(running.has_capacity(BaseCodingAgent::Codex, &ConcurrencyConfig::default()));
+    }
+}
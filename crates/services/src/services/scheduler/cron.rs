@@ -0,0 +1,288 @@
+//! Standard five-field cron expressions (`minute hour day-of-month month day-of-week`).
+//!
+//! Supports `*`, lists, ranges, steps (`*/15`, `1-5/2`), month and weekday
+//! names, and the `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` macros.
+//! As in Vixie cron, when both day fields are restricted a time matches if
+//! either of them does.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use thiserror::Error;
+
+/// How far ahead [`CronSchedule::next_after`] searches before giving up
+/// (covers Feb 29 on leap years)
+const MAX_SEARCH_YEARS: i32 = 5;
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("Invalid cron expression '{expression}': {reason}")]
+pub struct CronParseError {
+    expression: String,
+    reason: String,
+}
+
+/// Set of allowed values for one field, as a bitmask
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Field {
+    bits: u64,
+    /// The field was `*` (or `*/1`), which matters for the day-of-month/day-of-week rule
+    any: bool,
+}
+
+impl Field {
+    fn contains(&self, value: u32) -> bool {
+        self.bits & (1 << value) != 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: Field,
+    hours: Field,
+    days_of_month: Field,
+    months: Field,
+    days_of_week: Field,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, CronParseError> {
+        let error = |reason: String| CronParseError {
+            expression: expression.to_string(),
+            reason,
+        };
+
+        let trimmed = expression.trim();
+        let expanded = match trimmed.to_ascii_lowercase().as_str() {
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            "@monthly" => "0 0 1 * *",
+            "@weekly" => "0 0 * * 0",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@hourly" => "0 * * * *",
+            other if other.starts_with('@') => {
+                return Err(error(format!("unknown macro '{trimmed}'")));
+            }
+            _ => trimmed,
+        };
+
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let &[minute, hour, dom, month, dow] = fields.as_slice() else {
+            return Err(error(format!("expected 5 fields, found {}", fields.len())));
+        };
+
+        let mut days_of_week = parse_field(dow, 0, 7, &WEEKDAY_NAMES).map_err(error)?;
+        // 7 is an alias for Sunday
+        if days_of_week.contains(7) {
+            days_of_week.bits |= 1;
+        }
+
+        Ok(Self {
+            minutes: parse_field(minute, 0, 59, &[]).map_err(error)?,
+            hours: parse_field(hour, 0, 23, &[]).map_err(error)?,
+            days_of_month: parse_field(dom, 1, 31, &[]).map_err(error)?,
+            months: parse_field(month, 1, 12, &MONTH_NAMES).map_err(error)?,
+            days_of_week,
+        })
+    }
+
+    /// First matching minute strictly after `after`, evaluated in `after`'s timezone.
+    /// Returns `None` for expressions that never fire (e.g. `0 0 30 2 *`).
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = start + Duration::days(366 * MAX_SEARCH_YEARS as i64);
+
+        let mut t = start;
+        while t < limit {
+            if !self.months.contains(t.month()) {
+                t = first_of_next_month(t.date())?;
+                continue;
+            }
+            if !self.day_matches(t.date()) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+                continue;
+            }
+            if !self.hours.contains(t.hour()) {
+                t = t.with_minute(0)? + Duration::hours(1);
+                continue;
+            }
+            if !self.minutes.contains(t.minute()) {
+                t += Duration::minutes(1);
+                continue;
+            }
+            // Local times skipped by a DST transition do not exist; move on
+            match tz.from_local_datetime(&t).earliest() {
+                Some(next) if next > *after => return Some(next),
+                _ => t += Duration::minutes(1),
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, date: NaiveDate) -> bool {
+        let dom = self.days_of_month.contains(date.day());
+        let dow = self
+            .days_of_week
+            .contains(date.weekday().num_days_from_sunday());
+        match (self.days_of_month.any, self.days_of_week.any) {
+            (false, false) => dom || dow,
+            _ => dom && dow,
+        }
+    }
+}
+
+impl FromStr for CronSchedule {
+    type Err = CronParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+fn first_of_next_month(date: NaiveDate) -> Option<NaiveDateTime> {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)
+}
+
+fn parse_field(field: &str, min: u32, max: u32, names: &[&str]) -> Result<Field, String> {
+    let mut bits = 0u64;
+    let mut any = false;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| format!("invalid step '{step}' in '{field}'"))?;
+                if step == 0 {
+                    return Err(format!("step must be positive in '{field}'"));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            if step == 1 {
+                any = true;
+            }
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                parse_value(start, min, max, names)?,
+                parse_value(end, min, max, names)?,
+            )
+        } else {
+            let start = parse_value(range, min, max, names)?;
+            // `5/10` means "from 5 to the end of the range, every 10"
+            (start, if step > 1 { max } else { start })
+        };
+        if start > end {
+            return Err(format!("range '{range}' is reversed"));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+
+    Ok(Field { bits, any })
+}
+
+fn parse_value(value: &str, min: u32, max: u32, names: &[&str]) -> Result<u32, String> {
+    let lower = value.to_ascii_lowercase();
+    let parsed = match names.iter().position(|name| *name == lower) {
+        // Month names start at 1, weekday names at 0
+        Some(index) => index as u32 + min,
+        None => value
+            .parse()
+            .map_err(|_| format!("invalid value '{value}'"))?,
+    };
+    if !(min..=max).contains(&parsed) {
+        return Err(format!("value {parsed} is outside {min}-{max}"));
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn at(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn next(expression: &str, after: &str) -> Option<String> {
+        CronSchedule::parse(expression)
+            .unwrap()
+            .next_after(&at(after))
+            .map(|t| t.to_rfc3339())
+    }
+
+    #[test]
+    fn computes_next_occurrence() {
+        // Every Monday at 09:00, from a Wednesday
+        assert_eq!(
+            next("0 9 * * mon", "2026-01-07T12:00:00Z").as_deref(),
+            Some("2026-01-12T09:00:00+00:00")
+        );
+        assert_eq!(
+            next("*/15 * * * *", "2026-01-07T12:07:30Z").as_deref(),
+            Some("2026-01-07T12:15:00+00:00")
+        );
+        // Never returns the current minute
+        assert_eq!(
+            next("@hourly", "2026-01-07T12:00:00Z").as_deref(),
+            Some("2026-01-07T13:00:00+00:00")
+        );
+        assert_eq!(
+            next("0 0 29 feb *", "2026-03-01T00:00:00Z").as_deref(),
+            Some("2028-02-29T00:00:00+00:00")
+        );
+        assert_eq!(next("0 0 30 2 *", "2026-01-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn either_day_field_matches_when_both_restricted() {
+        // The 15th, or any Friday
+        assert_eq!(
+            next("0 0 15 * 5", "2026-01-10T00:00:00Z").as_deref(),
+            Some("2026-01-15T00:00:00+00:00")
+        );
+        assert_eq!(
+            next("0 0 15 * 5", "2026-01-15T00:00:00Z").as_deref(),
+            Some("2026-01-16T00:00:00+00:00")
+        );
+        assert_eq!(
+            next("0 0 * * 7", "2026-01-10T00:00:00Z").as_deref(),
+            Some("2026-01-11T00:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* * * * 8",
+            "5-1 * * * *",
+            "@often",
+        ] {
+            assert!(
+                CronSchedule::parse(expression).is_err(),
+                "{expression} should not parse"
+            );
+        }
+    }
+}
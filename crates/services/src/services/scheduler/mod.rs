@@ -0,0 +1,164 @@
+mod cron;
+
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+pub use cron::{CronParseError, CronSchedule};
+use db::{
+    DBService,
+    models::{
+        execution_process::ExecutionProcess,
+        task::{CreateTask, Task},
+        task_schedule::TaskSchedule,
+        workspace_repo::CreateWorkspaceRepo,
+    },
+};
+use sqlx::error::Error as SqlxError;
+use thiserror::Error;
+use tokio::time::interval;
+use tracing::{debug, error, info};
+use uuid::Uuid;
+
+use crate::services::container::{ContainerError, ContainerService};
+
+#[derive(Debug, Error)]
+enum SchedulerError {
+    #[error(transparent)]
+    Sqlx(#[from] SqlxError),
+    #[error(transparent)]
+    Container(#[from] ContainerError),
+}
+
+/// Next tick of `cron_expression` after `after`, in the server's local timezone
+pub fn next_run_after(
+    cron_expression: &str,
+    after: DateTime<Utc>,
+) -> Result<Option<DateTime<Utc>>, CronParseError> {
+    let schedule = CronSchedule::parse(cron_expression)?;
+    Ok(schedule
+        .next_after(&after.with_timezone(&Local))
+        .map(|next| next.with_timezone(&Utc)))
+}
+
+/// Service that creates and starts tasks for due [`TaskSchedule`]s
+pub struct SchedulerService<C> {
+    db: DBService,
+    container: C,
+    poll_interval: Duration,
+}
+
+impl<C: ContainerService + Send + Sync + 'static> SchedulerService<C> {
+    pub async fn spawn(db: DBService, container: C) -> tokio::task::JoinHandle<()> {
+        let service = Self {
+            db,
+            container,
+            poll_interval: Duration::from_secs(30),
+        };
+        tokio::spawn(async move {
+            service.start().await;
+        })
+    }
+
+    async fn start(&self) {
+        info!(
+            "Starting task scheduler with interval {:?}",
+            self.poll_interval
+        );
+
+        let mut interval = interval(self.poll_interval);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.run_due_schedules().await {
+                error!("Error running scheduled tasks: {}", e);
+            }
+        }
+    }
+
+    async fn run_due_schedules(&self) -> Result<(), SchedulerError> {
+        let now = Utc::now();
+        let due = TaskSchedule::find_due(&self.db.pool, now).await?;
+
+        if due.is_empty() {
+            return Ok(());
+        }
+        debug!("{} task schedules are due", due.len());
+
+        for schedule in due {
+            if let Err(e) = self.run_schedule(&schedule, now).await {
+                error!(
+                    "Failed to run schedule '{}' ({}): {}",
+                    schedule.name, schedule.id, e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Run one tick of a schedule. Ticks missed while the server was down
+    /// collapse into a single run, as the next run is computed from `now`.
+    async fn run_schedule(
+        &self,
+        schedule: &TaskSchedule,
+        now: DateTime<Utc>,
+    ) -> Result<(), SchedulerError> {
+        let pool = &self.db.pool;
+
+        let next_run_at = match next_run_after(&schedule.cron_expression, now) {
+            Ok(next) => next,
+            Err(e) => {
+                // Expressions are validated on write; stop ticking rather than retrying every poll
+                error!("Schedule {} has an invalid expression: {}", schedule.id, e);
+                TaskSchedule::set_next_run_at(pool, schedule.id, None).await?;
+                return Ok(());
+            }
+        };
+
+        if let Some(last_task_id) = schedule.last_task_id
+            && ExecutionProcess::has_running_non_dev_server_processes_for_task(pool, last_task_id)
+                .await?
+        {
+            info!(
+                "Skipping tick of schedule '{}': task {} from the previous run is still running",
+                schedule.name, last_task_id
+            );
+            TaskSchedule::set_next_run_at(pool, schedule.id, next_run_at).await?;
+            return Ok(());
+        }
+
+        let task = Task::create(
+            pool,
+            &CreateTask::from_title_description(
+                schedule.project_id,
+                schedule.task_title.clone(),
+                schedule.task_description.clone(),
+            ),
+            Uuid::new_v4(),
+        )
+        .await?;
+        // Record the run before starting so a failing start does not re-fire every poll
+        TaskSchedule::record_run(pool, schedule.id, task.id, now, next_run_at).await?;
+
+        let workspace_repos: Vec<CreateWorkspaceRepo> = schedule
+            .repos
+            .iter()
+            .map(|repo| CreateWorkspaceRepo {
+                repo_id: repo.repo_id,
+                target_branch: repo.target_branch.clone(),
+            })
+            .collect();
+        self.container
+            .start_task_in_new_workspace(
+                &task,
+                &workspace_repos,
+                schedule.executor_profile_id.clone(),
+            )
+            .await?;
+
+        info!(
+            "Schedule '{}' started task {} (next run: {:?})",
+            schedule.name, task.id, next_run_at
+        );
+        Ok(())
+    }
+}
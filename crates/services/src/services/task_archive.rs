@@ -0,0 +1,292 @@
+//! Portable archives of a task and its full history, for moving finished tasks
+//! between machines or attaching agent transcripts to post-mortems.
+//!
+//! An archive is a gzipped tarball containing:
+//! - `task.json`: format version and the database records of the task
+//! - `logs/<execution_process_id>.jsonl`: raw execution process logs
+//! - `images/<image_id>`: images attached to the task
+//! - `diffs/<workspace_id>/<repo_name>.patch`: changes committed on each
+//!   workspace branch; kept for reference only and not applied on import
+
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+};
+
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process_logs::ExecutionProcessLogs, project_repo::ProjectRepo, task::Task,
+    task_archive::TaskRecords, workspace::WorkspaceError, workspace_repo::WorkspaceRepo,
+};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use git::GitService;
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::services::image::{ImageError, ImageService};
+
+/// Bumped whenever the archive layout or `task.json` change incompatibly
+pub const TASK_ARCHIVE_VERSION: u32 = 1;
+
+const MANIFEST_PATH: &str = "task.json";
+
+/// Cap on the decompressed size of an archive's files, so a small upload
+/// cannot expand into more than the server can hold in memory
+const MAX_ARCHIVE_CONTENT_BYTES: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum TaskArchiveError {
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Image(#[from] ImageError),
+    #[error("Task not found")]
+    TaskNotFound,
+    #[error("Invalid task archive: {0}")]
+    InvalidArchive(String),
+    #[error("Unsupported task archive version {0}")]
+    UnsupportedVersion(u32),
+}
+
+impl From<serde_json::Error> for TaskArchiveError {
+    fn from(err: serde_json::Error) -> Self {
+        TaskArchiveError::InvalidArchive(err.to_string())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskArchiveManifest {
+    version: u32,
+    exported_at: DateTime<Utc>,
+    #[serde(flatten)]
+    records: TaskRecords,
+}
+
+fn log_path(execution_process_id: Uuid) -> String {
+    format!("logs/{execution_process_id}.jsonl")
+}
+
+fn image_path(image_id: Uuid) -> String {
+    format!("images/{image_id}")
+}
+
+/// Bundle a task, its attempts, logs, images and final diffs into an archive
+pub async fn export_task(
+    pool: &SqlitePool,
+    git: &GitService,
+    images: &ImageService,
+    task_id: Uuid,
+) -> Result<Vec<u8>, TaskArchiveError> {
+    let records = TaskRecords::load(pool, task_id)
+        .await?
+        .ok_or(TaskArchiveError::TaskNotFound)?;
+    let mut files = Vec::new();
+
+    for process in &records.execution_processes {
+        let rows = ExecutionProcessLogs::find_by_execution_id(pool, process.id).await?;
+        if rows.is_empty() {
+            continue;
+        }
+        let mut jsonl = String::new();
+        for row in &rows {
+            jsonl.push_str(&row.logs);
+            if !row.logs.ends_with('\n') {
+                jsonl.push('\n');
+            }
+        }
+        files.push((log_path(process.id), jsonl.into_bytes()));
+    }
+
+    for image in &records.images {
+        match tokio::fs::read(images.get_absolute_path(image)).await {
+            Ok(data) => files.push((image_path(image.id), data)),
+            Err(e) => tracing::warn!("Skipping image {} in task export: {}", image.id, e),
+        }
+    }
+
+    for workspace in &records.workspaces {
+        for repo in
+            WorkspaceRepo::find_repos_with_target_branch_for_workspace(pool, workspace.id).await?
+        {
+            match git.get_branch_patch(&repo.repo.path, &workspace.branch, &repo.target_branch) {
+                Ok(patch) if !patch.is_empty() => files.push((
+                    format!("diffs/{}/{}.patch", workspace.id, repo.repo.name),
+                    patch,
+                )),
+                Ok(_) => {}
+                Err(e) => tracing::debug!(
+                    "No diff exported for workspace {} repo {}: {}",
+                    workspace.id,
+                    repo.repo.name,
+                    e
+                ),
+            }
+        }
+    }
+
+    let manifest = TaskArchiveManifest {
+        version: TASK_ARCHIVE_VERSION,
+        exported_at: Utc::now(),
+        records,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    files.push((MANIFEST_PATH.to_string(), manifest_json));
+    Ok(write_archive(&files, manifest.exported_at)?)
+}
+
+/// Recreate the task of an archive under `project_id`, with new ids.
+/// Workspace repositories are matched to the project's repositories by name.
+pub async fn import_task(
+    pool: &SqlitePool,
+    images: &ImageService,
+    project_id: Uuid,
+    archive: &[u8],
+) -> Result<Task, TaskArchiveError> {
+    let files = read_archive(archive)?;
+    let manifest = files
+        .get(MANIFEST_PATH)
+        .ok_or_else(|| TaskArchiveError::InvalidArchive(format!("missing {MANIFEST_PATH}")))?;
+    let manifest: TaskArchiveManifest = serde_json::from_slice(manifest)?;
+    if manifest.version > TASK_ARCHIVE_VERSION {
+        return Err(TaskArchiveError::UnsupportedVersion(manifest.version));
+    }
+    let mut records = manifest.records;
+
+    let repo_ids: HashMap<String, Uuid> = ProjectRepo::find_repos_for_project(pool, project_id)
+        .await?
+        .into_iter()
+        .map(|repo| (repo.name, repo.id))
+        .collect();
+
+    // Images are deduplicated by content, so they may come back under another
+    // file name that the task description has to point to
+    let mut image_ids = Vec::new();
+    for image in &records.images {
+        let Some(data) = files.get(&image_path(image.id)) else {
+            continue;
+        };
+        let stored = images.store_image(data, &image.original_name).await?;
+        if let Some(description) = records.task.description.as_mut() {
+            *description = description.replace(&image.file_path, &stored.file_path);
+        }
+        if !image_ids.contains(&stored.id) {
+            image_ids.push(stored.id);
+        }
+    }
+
+    let mut logs = HashMap::new();
+    for process in &records.execution_processes {
+        if let Some(data) = files.get(&log_path(process.id)) {
+            let jsonl = String::from_utf8(data.clone()).map_err(|_| {
+                TaskArchiveError::InvalidArchive(format!(
+                    "logs of execution process {} are not valid UTF-8",
+                    process.id
+                ))
+            })?;
+            logs.insert(process.id, jsonl);
+        }
+    }
+
+    Ok(records
+        .insert_copy(pool, project_id, &repo_ids, &image_ids, &logs)
+        .await?)
+}
+
+fn write_archive(files: &[(String, Vec<u8>)], mtime: DateTime<Utc>) -> io::Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (path, data) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(mtime.timestamp().max(0) as u64);
+        header.set_cksum();
+        builder.append_data(&mut header, path, data.as_slice())?;
+    }
+    builder.into_inner()?.finish()
+}
+
+fn read_archive(archive: &[u8]) -> Result<HashMap<String, Vec<u8>>, TaskArchiveError> {
+    read_archive_with_limit(archive, MAX_ARCHIVE_CONTENT_BYTES)
+}
+
+fn read_archive_with_limit(
+    archive: &[u8],
+    max_content_bytes: u64,
+) -> Result<HashMap<String, Vec<u8>>, TaskArchiveError> {
+    let invalid = |e: io::Error| TaskArchiveError::InvalidArchive(e.to_string());
+    let mut remaining = max_content_bytes;
+    let mut files = HashMap::new();
+    let mut archive = tar::Archive::new(GzDecoder::new(archive));
+    for entry in archive.entries().map_err(invalid)? {
+        let mut entry = entry.map_err(invalid)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = entry
+            .path()
+            .map_err(invalid)?
+            .to_string_lossy()
+            .into_owned();
+        let mut data = Vec::new();
+        // One byte past the limit tells a file that fills it from one that exceeds it
+        (&mut entry)
+            .take(remaining + 1)
+            .read_to_end(&mut data)
+            .map_err(invalid)?;
+        remaining = remaining.checked_sub(data.len() as u64).ok_or_else(|| {
+            TaskArchiveError::InvalidArchive(format!(
+                "content is larger than {max_content_bytes} bytes"
+            ))
+        })?;
+        files.insert(path, data);
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_round_trips_files() {
+        let files = vec![
+            (MANIFEST_PATH.to_string(), b"{}".to_vec()),
+            (log_path(Uuid::nil()), b"{\"Stdout\":\"hi\"}\n".to_vec()),
+        ];
+        let archive = write_archive(&files, Utc::now()).unwrap();
+
+        let read = read_archive(&archive).unwrap();
+        assert_eq!(read.len(), 2);
+        assert_eq!(read[MANIFEST_PATH], b"{}");
+        assert_eq!(read[&log_path(Uuid::nil())], b"{\"Stdout\":\"hi\"}\n");
+    }
+
+    #[test]
+    fn rejects_archives_expanding_past_the_limit() {
+        let files = vec![
+            (MANIFEST_PATH.to_string(), vec![b' '; 600]),
+            (log_path(Uuid::nil()), vec![b' '; 400]),
+        ];
+        let archive = write_archive(&files, Utc::now()).unwrap();
+
+        assert_eq!(read_archive_with_limit(&archive, 1000).unwrap().len(), 2);
+        assert!(matches!(
+            read_archive_with_limit(&archive, 999),
+            Err(TaskArchiveError::InvalidArchive(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_data_that_is_not_an_archive() {
+        assert!(matches!(
+            read_archive(b"not a tarball"),
+            Err(TaskArchiveError::InvalidArchive(_))
+        ));
+    }
+}
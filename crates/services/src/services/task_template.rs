@@ -0,0 +1,252 @@
+//! Task templates: checking template definitions, validating the values given
+//! for their variables and rendering `{{variable}}` placeholders.
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+};
+
+use db::models::task_template::{
+    TaskTemplate, TaskTemplateInstance, TemplateStep, TemplateStepKind, TemplateVariable,
+    TemplateVariableKind,
+};
+use regex::Regex;
+use uuid::Uuid;
+
+static PLACEHOLDER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*([A-Za-z_][A-Za-z0-9_]*)\s*\}\}").unwrap());
+static VARIABLE_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[A-Za-z_][A-Za-z0-9_]*$").unwrap());
+
+/// Title, description and steps of a template rendered for a new task
+#[derive(Debug, Clone)]
+pub struct RenderedTemplate {
+    pub title: String,
+    pub description: Option<String>,
+    pub instance: TaskTemplateInstance,
+}
+
+/// Replace the placeholders of known variables; unknown ones are left as is
+pub fn render(text: &str, values: &HashMap<String, String>) -> String {
+    PLACEHOLDER
+        .replace_all(text, |caps: &regex::Captures| match values.get(&caps[1]) {
+            Some(value) => value.clone(),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+fn check_value(variable: &TemplateVariable, value: &str) -> Result<(), String> {
+    let valid = match variable.kind {
+        TemplateVariableKind::Text => true,
+        TemplateVariableKind::Number => value.trim().parse::<f64>().is_ok_and(f64::is_finite),
+        TemplateVariableKind::Boolean => matches!(value, "true" | "false"),
+        TemplateVariableKind::Url => {
+            url::Url::parse(value).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+        }
+        TemplateVariableKind::Select => variable.options.iter().any(|option| option == value),
+    };
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "'{value}' is not a valid value for variable '{}'",
+            variable.name
+        ))
+    }
+}
+
+/// Check a template definition: variables are unique identifiers with valid
+/// defaults, placeholders only reference them and the chain starts with a
+/// prompt
+pub fn validate_template(
+    variables: &[TemplateVariable],
+    title: &str,
+    description: Option<&str>,
+    steps: &[TemplateStep],
+) -> Result<(), String> {
+    let mut names = HashSet::new();
+    for variable in variables {
+        if !VARIABLE_NAME.is_match(&variable.name) {
+            return Err(format!("Invalid variable name '{}'", variable.name));
+        }
+        if !names.insert(variable.name.as_str()) {
+            return Err(format!("Variable '{}' is declared twice", variable.name));
+        }
+        if variable.kind == TemplateVariableKind::Select && variable.options.is_empty() {
+            return Err(format!("Variable '{}' has no options", variable.name));
+        }
+        if let Some(default) = &variable.default {
+            check_value(variable, default)?;
+        }
+    }
+
+    let texts = std::iter::once(title)
+        .chain(description)
+        .chain(steps.iter().map(|step| step.prompt.as_str()));
+    for text in texts {
+        if let Some(caps) = PLACEHOLDER
+            .captures_iter(text)
+            .find(|caps| !names.contains(&caps[1]))
+        {
+            return Err(format!("Unknown variable '{}'", &caps[1]));
+        }
+    }
+
+    if steps
+        .first()
+        .is_some_and(|step| step.kind != TemplateStepKind::Prompt)
+    {
+        return Err("The first step of a template must be a prompt".to_string());
+    }
+    Ok(())
+}
+
+/// Values of all the template's variables, with defaults applied. Optional
+/// variables without a value render as an empty string.
+pub fn resolve_variables(
+    variables: &[TemplateVariable],
+    values: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, String> {
+    if let Some(name) = values
+        .keys()
+        .find(|name| !variables.iter().any(|variable| &variable.name == *name))
+    {
+        return Err(format!("Unknown variable '{name}'"));
+    }
+
+    let mut resolved = HashMap::new();
+    for variable in variables {
+        let value = values
+            .get(&variable.name)
+            .filter(|value| !value.is_empty())
+            .or(variable.default.as_ref());
+        let value = match value {
+            Some(value) => {
+                check_value(variable, value)?;
+                value.clone()
+            }
+            None if variable.required => {
+                return Err(format!("Missing value for variable '{}'", variable.name));
+            }
+            None => String::new(),
+        };
+        resolved.insert(variable.name.clone(), value);
+    }
+    Ok(resolved)
+}
+
+/// Render a template for the task `task_id`
+pub fn instantiate(
+    template: &TaskTemplate,
+    values: &HashMap<String, String>,
+    task_id: Uuid,
+) -> Result<RenderedTemplate, String> {
+    let values = resolve_variables(&template.variables, values)?;
+    let steps = template
+        .steps
+        .iter()
+        .map(|step| TemplateStep {
+            prompt: render(&step.prompt, &values),
+            ..step.clone()
+        })
+        .collect();
+    Ok(RenderedTemplate {
+        title: render(&template.title, &values),
+        description: template
+            .description
+            .as_deref()
+            .map(|description| render(description, &values)),
+        instance: TaskTemplateInstance {
+            task_id,
+            template_id: Some(template.id),
+            executor_profile_id: template.executor_profile_id.clone(),
+            repos: template.repos.clone(),
+            steps,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variable(name: &str, kind: TemplateVariableKind, required: bool) -> TemplateVariable {
+        TemplateVariable {
+            name: name.to_string(),
+            kind,
+            description: None,
+            required,
+            default: None,
+            options: Vec::new(),
+        }
+    }
+
+    fn step(kind: TemplateStepKind, prompt: &str) -> TemplateStep {
+        TemplateStep {
+            kind,
+            prompt: prompt.to_string(),
+            variant: None,
+        }
+    }
+
+    #[test]
+    fn renders_known_placeholders() {
+        let values = HashMap::from([("module".to_string(), "billing".to_string())]);
+        assert_eq!(
+            render("Refactor {{module}} ({{ module }}), see {{other}}", &values),
+            "Refactor billing (billing), see {{other}}"
+        );
+    }
+
+    #[test]
+    fn resolves_and_checks_variable_values() {
+        let mut level = variable("level", TemplateVariableKind::Select, false);
+        level.options = vec!["low".to_string(), "high".to_string()];
+        level.default = Some("low".to_string());
+        let variables = vec![
+            variable("ticket_url", TemplateVariableKind::Url, true),
+            variable("count", TemplateVariableKind::Number, false),
+            level,
+        ];
+
+        let values = HashMap::from([(
+            "ticket_url".to_string(),
+            "https://example.com/T-1".to_string(),
+        )]);
+        let resolved = resolve_variables(&variables, &values).unwrap();
+        assert_eq!(resolved["level"], "low");
+        assert_eq!(resolved["count"], "");
+
+        assert!(resolve_variables(&variables, &HashMap::new()).is_err());
+        let bad_url = HashMap::from([("ticket_url".to_string(), "not a url".to_string())]);
+        assert!(resolve_variables(&variables, &bad_url).is_err());
+        let unknown = HashMap::from([
+            ("ticket_url".to_string(), "https://example.com".to_string()),
+            ("nope".to_string(), "1".to_string()),
+        ]);
+        assert!(resolve_variables(&variables, &unknown).is_err());
+    }
+
+    #[test]
+    fn validates_template_definitions() {
+        let variables = vec![variable("module", TemplateVariableKind::Text, true)];
+        let steps = vec![
+            step(TemplateStepKind::Prompt, "Plan {{module}}"),
+            step(TemplateStepKind::Review, ""),
+        ];
+        assert!(validate_template(&variables, "Refactor {{module}}", None, &steps).is_ok());
+        assert!(validate_template(&variables, "Refactor {{missing}}", None, &steps).is_err());
+        assert!(
+            validate_template(
+                &variables,
+                "Refactor",
+                None,
+                &[step(TemplateStepKind::Review, "")]
+            )
+            .is_err()
+        );
+        let duplicated = vec![variables[0].clone(), variables[0].clone()];
+        assert!(validate_template(&duplicated, "Refactor", None, &[]).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Spend reports built from the token usage recorded per coding agent turn.
+
+use std::collections::{BTreeSet, HashMap};
+
+use db::models::coding_agent_turn_usage::UsageRecord;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::services::config::ModelPrice;
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageGroupBy {
+    #[default]
+    Task,
+    Project,
+    ExecutorProfile,
+    Day,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, TS)]
+pub struct UsageReportRow {
+    /// Task or project id, `EXECUTOR` / `EXECUTOR:VARIANT`, or a `YYYY-MM-DD` date
+    pub key: String,
+    pub label: String,
+    #[ts(type = "number")]
+    pub input_tokens: i64,
+    #[ts(type = "number")]
+    pub output_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_read_tokens: i64,
+    #[ts(type = "number")]
+    pub cache_write_tokens: i64,
+    /// Cost of the usage whose model has a configured price
+    pub cost_usd: f64,
+    /// Models without a configured price; their usage is not in `cost_usd`
+    pub unpriced_models: Vec<String>,
+}
+
+/// Price for a model: an exact match, else the longest configured prefix of its name
+pub fn price_for<'a>(
+    prices: &'a HashMap<String, ModelPrice>,
+    model: &str,
+) -> Option<&'a ModelPrice> {
+    prices.get(model).or_else(|| {
+        prices
+            .iter()
+            .filter(|(name, _)| model.starts_with(name.as_str()))
+            .max_by_key(|(name, _)| name.len())
+            .map(|(_, price)| price)
+    })
+}
+
+fn cost_usd(record: &UsageRecord, price: &ModelPrice) -> f64 {
+    const PER_TOKENS: f64 = 1_000_000.0;
+    (record.input_tokens as f64 * price.input
+        + record.output_tokens as f64 * price.output
+        + record.cache_read_tokens as f64 * price.cache_read
+        + record.cache_write_tokens as f64 * price.cache_write)
+        / PER_TOKENS
+}
+
+/// Sum usage records into one row per group, most expensive first
+pub fn build_report(
+    records: &[UsageRecord],
+    group_by: UsageGroupBy,
+    prices: &HashMap<String, ModelPrice>,
+) -> Vec<UsageReportRow> {
+    let mut rows: HashMap<String, (UsageReportRow, BTreeSet<String>)> = HashMap::new();
+    for record in records {
+        let (key, label) = match group_by {
+            UsageGroupBy::Task => (record.task_id.to_string(), record.task_title.clone()),
+            UsageGroupBy::Project => (record.project_id.to_string(), record.project_name.clone()),
+            UsageGroupBy::ExecutorProfile => {
+                let profile = match &record.variant {
+                    Some(variant) => format!("{}:{variant}", record.executor),
+                    None => record.executor.to_string(),
+                };
+                (profile.clone(), profile)
+            }
+            UsageGroupBy::Day => (record.day.clone(), record.day.clone()),
+        };
+        let (row, unpriced) = rows.entry(key.clone()).or_insert_with(|| {
+            (
+                UsageReportRow {
+                    key,
+                    label,
+                    ..Default::default()
+                },
+                BTreeSet::new(),
+            )
+        });
+        row.input_tokens += record.input_tokens;
+        row.output_tokens += record.output_tokens;
+        row.cache_read_tokens += record.cache_read_tokens;
+        row.cache_write_tokens += record.cache_write_tokens;
+        match price_for(prices, &record.model) {
+            Some(price) => row.cost_usd += cost_usd(record, price),
+            None => {
+                unpriced.insert(record.model.clone());
+            }
+        }
+    }
+
+    let mut report: Vec<UsageReportRow> = rows
+        .into_values()
+        .map(|(mut row, unpriced)| {
+            row.unpriced_models = unpriced.into_iter().collect();
+            row
+        })
+        .collect();
+    match group_by {
+        UsageGroupBy::Day => report.sort_by(|a, b| a.key.cmp(&b.key)),
+        _ => report.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd)),
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::executors::BaseCodingAgent;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn record(model: &str, variant: Option<&str>, input: i64, output: i64) -> UsageRecord {
+        UsageRecord {
+            task_id: Uuid::nil(),
+            task_title: "Task".to_string(),
+            project_id: Uuid::nil(),
+            project_name: "Project".to_string(),
+            executor: BaseCodingAgent::ClaudeCode,
+            variant: variant.map(str::to_string),
+            day: "2026-01-30".to_string(),
+            model: model.to_string(),
+            input_tokens: input,
+            output_tokens: output,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+        }
+    }
+
+    #[test]
+    fn prices_usage_by_longest_model_prefix() {
+        let prices = HashMap::from([
+            (
+                "claude-".to_string(),
+                ModelPrice {
+                    input: 1.0,
+                    output: 1.0,
+                    ..Default::default()
+                },
+            ),
+            (
+                "claude-sonnet-4".to_string(),
+                ModelPrice {
+                    input: 3.0,
+                    output: 15.0,
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let records = [
+            record("claude-sonnet-4-5-20250929", None, 1_000_000, 100_000),
+            record("gpt-5", Some("PLAN"), 10, 10),
+        ];
+
+        let report = build_report(&records, UsageGroupBy::ExecutorProfile, &prices);
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].key, "CLAUDE_CODE");
+        assert!((report[0].cost_usd - 4.5).abs() < 1e-9);
+        assert_eq!(report[1].key, "CLAUDE_CODE:PLAN");
+        assert_eq!(report[1].unpriced_models, vec!["gpt-5".to_string()]);
+
+        let report = build_report(&records, UsageGroupBy::Task, &prices);
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].input_tokens, 1_000_010);
+    }
+}
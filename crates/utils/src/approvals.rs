@@ -3,6 +3,7 @@ use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
 
+/// Used unless the executor profile configures another timeout
 pub const APPROVAL_TIMEOUT_SECONDS: i64 = 3600; // 1 hour
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -17,7 +18,11 @@ pub struct ApprovalRequest {
 }
 
 impl ApprovalRequest {
-    pub fn from_create(request: CreateApprovalRequest, execution_process_id: Uuid) -> Self {
+    pub fn from_create(
+        request: CreateApprovalRequest,
+        execution_process_id: Uuid,
+        timeout: Duration,
+    ) -> Self {
         let now = Utc::now();
         Self {
             id: Uuid::new_v4().to_string(),
@@ -26,7 +31,7 @@ impl ApprovalRequest {
             tool_call_id: request.tool_call_id,
             execution_process_id,
             created_at: now,
-            timeout_at: now + Duration::seconds(APPROVAL_TIMEOUT_SECONDS),
+            timeout_at: now + timeout,
         }
     }
 }
@@ -38,6 +38,8 @@ export function getAgentName(
       return 'Copilot';
     case BaseCodingAgent.DROID:
       return 'Droid';
+    case BaseCodingAgent.CUSTOM:
+      return 'Custom';
   }
 }
 
@@ -90,8 +90,14 @@ export function TaskCard({
           title={task.title}
           right={
             <>
-              {task.has_in_progress_attempt && (
-                <Loader2 className="h-4 w-4 animate-spin text-blue-500" />
+              {task.queue_position != null ? (
+                <span className="text-xs text-muted-foreground">
+                  {t('queuePosition', { position: task.queue_position })}
+                </span>
+              ) : (
+                task.has_in_progress_attempt && (
+                  <Loader2 className="h-4 w-4 animate-spin text-blue-500" />
+                )
               )}
               {task.last_attempt_failed && (
                 <XCircle className="h-4 w-4 text-destructive" />
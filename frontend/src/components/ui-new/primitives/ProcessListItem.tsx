@@ -3,6 +3,7 @@ import {
   GearIcon,
   CodeIcon,
   GlobeIcon,
+  CheckCircleIcon,
 } from '@phosphor-icons/react';
 import { cn } from '@/lib/utils';
 import { formatRelativeTime } from '@/utils/date';
@@ -26,6 +27,7 @@ const RUN_REASON_LABELS: Record<ExecutionProcessRunReason, string> = {
   setupscript: 'Setup Script',
   cleanupscript: 'Cleanup Script',
   devserver: 'Dev Server',
+  verifyscript: 'Verify Script',
 };
 
 const RUN_REASON_ICONS: Record<ExecutionProcessRunReason, typeof TerminalIcon> =
@@ -34,9 +36,11 @@ const RUN_REASON_ICONS: Record<ExecutionProcessRunReason, typeof TerminalIcon> =
     setupscript: GearIcon,
     cleanupscript: GearIcon,
     devserver: GlobeIcon,
+    verifyscript: CheckCircleIcon,
   };
 
 const STATUS_COLORS: Record<ExecutionProcessStatus, string> = {
+  queued: 'bg-low',
   running: 'bg-info',
   completed: 'bg-success',
   failed: 'bg-destructive',
@@ -4,11 +4,21 @@
 
 // If you are an AI, and you absolutely have to edit this file, please confirm with the user first.
 
-export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
+export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, 
+/**
+ * Rebase workspaces onto their target branch when it moves and the rebase
+ * would apply cleanly
+ */
+auto_rebase_on_drift: boolean, 
+/**
+ * Repo file, such as `AGENTS.md`, the accepted project memory is mirrored
+ * into, relative to the root of each repo
+ */
+memory_file: string | null, created_at: Date, updated_at: Date, };
 
 export type CreateProject = { name: string, repositories: Array<CreateProjectRepo>, };
 
-export type UpdateProject = { name: string | null, };
+export type UpdateProject = { name: string | null, auto_rebase_on_drift?: boolean, memory_file?: string | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
 /**
@@ -18,19 +28,97 @@ score: bigint, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
-export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, created_at: Date, updated_at: Date, };
+export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, 
+/**
+ * Path (e.g. `/health`) or full URL probed over HTTP to tell when the dev
+ * server is ready. Without it the server counts as ready once it listens.
+ */
+dev_server_health_check: string | null, 
+/**
+ * Restart the dev server with backoff when it crashes
+ */
+dev_server_auto_restart: boolean, 
+/**
+ * Script (tests, lint, typecheck) run after the coding agent; a task only
+ * counts as done once it passes
+ */
+verify_script: string | null, 
+/**
+ * Automatic follow-ups asking the agent to fix a failed verification
+ */
+verify_max_retries: number, created_at: Date, updated_at: Date, };
 
-export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, };
+export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, dev_server_health_check?: string | null, dev_server_auto_restart?: boolean, verify_script?: string | null, verify_max_retries?: number | null, };
 
 export type ProjectRepo = { id: string, project_id: string, repo_id: string, };
 
 export type CreateProjectRepo = { display_name: string, git_repo_path: string, };
 
+/**
+ * Runs a project's executions in a Docker/Podman container instead of on the
+ * host. Projects without one, or with it disabled, run on the host.
+ */
+export type ProjectContainerConfig = { project_id: string, enabled: boolean, runtime: ContainerRuntime, 
+/**
+ * Image every execution's container is started from
+ */
+image: string, 
+/**
+ * Share the host network; when off the container has no network at all
+ */
+network_enabled: boolean, 
+/**
+ * Host environment variables to forward into the container, e.g. API keys
+ */
+env_passthrough: Array<string>, created_at: Date, updated_at: Date, };
+
+export type UpdateProjectContainerConfig = { enabled: boolean | null, runtime: ContainerRuntime | null, image: string, network_enabled: boolean | null, env_passthrough: Array<string> | null, };
+
+export type ProjectMemoryStatus = "candidate" | "accepted" | "discarded";
+
+/**
+ * Knowledge about a project, such as a convention or a pitfall, prepended to
+ * the prompt of every new coding agent session once accepted
+ */
+export type ProjectMemoryEntry = { id: string, project_id: string, content: string, status: ProjectMemoryStatus, 
+/**
+ * Workspace whose task summary the entry was proposed from
+ */
+workspace_id: string | null, coding_agent_turn_id: string | null, created_at: Date, updated_at: Date, };
+
+export type CreateProjectMemoryEntry = { project_id: string, content: string, };
+
+export type UpdateProjectMemoryEntry = { content: string, };
+
+export type AcceptProjectMemoryEntry = { 
+/**
+ * Edited content to accept instead of the proposed one
+ */
+content?: string, };
+
 export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
 
 export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
 
-export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, created_at: Date, updated_at: Date, };
+export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, 
+/**
+ * Path (e.g. `/health`) or full URL probed over HTTP to tell when the dev
+ * server is ready. Without it the server counts as ready once it listens.
+ */
+dev_server_health_check: string | null, 
+/**
+ * Restart the dev server with backoff when it crashes
+ */
+dev_server_auto_restart: boolean, 
+/**
+ * Script (tests, lint, typecheck) run after the coding agent; a task only
+ * counts as done once it passes
+ */
+verify_script: string | null, 
+/**
+ * Automatic follow-ups asking the agent to fix a failed verification
+ */
+verify_max_retries: number, created_at: Date, updated_at: Date, };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -42,15 +130,251 @@ export type TaskStatus = "todo" | "inprogress" | "inreview" | "done" | "cancelle
 
 export type Task = { id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
 
-export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
+export type TaskWithAttemptStatus = { has_in_progress_attempt: boolean, last_attempt_failed: boolean, executor: string, 
+/**
+ * 1-based position in the coding agent run queue, if an attempt is waiting for a slot
+ */
+queue_position?: number, id: string, project_id: string, title: string, description: string | null, status: TaskStatus, parent_workspace_id: string | null, created_at: string, updated_at: string, };
 
 export type TaskRelationships = { parent_task: Task | null, current_workspace: Workspace, children: Array<Task>, };
 
-export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
+export type CreateTask = { project_id: string, title: string, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, 
+/**
+ * Template to create the task from. Its rendered title and description are
+ * used when `title` is empty and `description` is unset.
+ */
+template?: TemplateInstance, };
 
 export type UpdateTask = { title: string | null, description: string | null, status: TaskStatus | null, parent_workspace_id: string | null, image_ids: Array<string> | null, };
 
-export type DraftFollowUpData = { message: string, executor_profile_id: ExecutorProfileId, };
+export type TaskDependencyType = "blocking" | "related" | "has_duplicate";
+
+export type TaskDependency = { id: string, task_id: string, related_task_id: string, relationship_type: TaskDependencyType, created_at: string, };
+
+export type CreateTaskDependency = { related_task_id: string, relationship_type: TaskDependencyType, };
+
+export type ScheduleRepo = { repo_id: string, target_branch: string, };
+
+export type TaskSchedule = { id: string, project_id: string, name: string, 
+/**
+ * Five-field cron expression, evaluated in the server's local timezone
+ */
+cron_expression: string, executor_profile_id: ExecutorProfileId, repos: Array<ScheduleRepo>, task_title: string, task_description: string | null, enabled: boolean, 
+/**
+ * Unset while the schedule is disabled
+ */
+next_run_at: string | null, last_run_at: string | null, 
+/**
+ * Task created by the most recent run
+ */
+last_task_id: string | null, created_at: string, updated_at: string, };
+
+export type CreateTaskSchedule = { project_id: string, name: string, cron_expression: string, executor_profile_id: ExecutorProfileId, repos: Array<ScheduleRepo>, task_title: string, task_description: string | null, enabled: boolean | null, };
+
+export type UpdateTaskSchedule = { name: string | null, cron_expression: string | null, executor_profile_id: ExecutorProfileId | null, repos: Array<ScheduleRepo> | null, task_title: string | null, task_description: string | null, enabled: boolean | null, };
+
+export type TemplateVariableKind = "text" | "number" | "boolean" | "url" | "select";
+
+/**
+ * Value a template asks for when it is instantiated, referenced as
+ * `{{name}}` in the title, description and step prompts
+ */
+export type TemplateVariable = { name: string, kind: TemplateVariableKind, description: string | null, required: boolean, 
+/**
+ * Used when no value is given
+ */
+default: string | null, 
+/**
+ * Allowed values of `select` variables
+ */
+options: Array<string>, };
+
+export type TemplateStepKind = "prompt" | "review";
+
+/**
+ * One coding agent turn of a template's chain. The first step runs with the
+ * task's prompt, the others are queued as follow-ups of the same session.
+ */
+export type TemplateStep = { kind: TemplateStepKind, prompt: string, 
+/**
+ * Executor variant for this step, e.g. `PLAN`; the attempt's when unset
+ */
+variant: string | null, };
+
+/**
+ * Repository a template's attempts run against, with the branch to base them on
+ */
+export type TemplateRepo = { repo_id: string, target_branch: string, };
+
+export type TaskTemplate = { id: string, 
+/**
+ * Unset for global templates, which are available in every project
+ */
+project_id: string | null, name: string, 
+/**
+ * Title of the created tasks, may contain `{{variable}}` placeholders
+ */
+title: string, description: string | null, variables: Array<TemplateVariable>, 
+/**
+ * Executor preselected for the task's attempts
+ */
+executor_profile_id: ExecutorProfileId | null, repos: Array<TemplateRepo>, steps: Array<TemplateStep>, created_at: Date, updated_at: Date, };
+
+export type CreateTaskTemplate = { project_id: string | null, name: string, title: string, description: string | null, variables: Array<TemplateVariable>, executor_profile_id: ExecutorProfileId | null, repos: Array<TemplateRepo>, steps: Array<TemplateStep>, };
+
+export type UpdateTaskTemplate = { name: string | null, title: string | null, description: string | null, variables: Array<TemplateVariable> | null, executor_profile_id: ExecutorProfileId | null, repos: Array<TemplateRepo> | null, steps: Array<TemplateStep> | null, };
+
+/**
+ * Template to create a task from, with the values of its variables
+ */
+export type TemplateInstance = { template_id: string, variables: { [key in string]?: string }, };
+
+/**
+ * What a task took from the template it was created from. Step prompts are
+ * already rendered with the task's variables.
+ */
+export type TaskTemplateInstance = { task_id: string, 
+/**
+ * Unset once the template is deleted
+ */
+template_id: string | null, executor_profile_id: ExecutorProfileId | null, repos: Array<TemplateRepo>, steps: Array<TemplateStep>, };
+
+/**
+ * How a tool approval request was answered. `cancelled` requests were given
+ * up by the agent or left pending when the server restarted.
+ */
+export type ApprovalResolution = "user" | "rule" | "timeout" | "cancelled";
+
+/**
+ * A tool approval request as stored in the database, with its outcome
+ */
+export type ApprovalRecord = { id: string, execution_process_id: string, tool_name: string, tool_input: JsonValue, tool_call_id: string, status: ApprovalStatus, 
+/**
+ * Unset while the request is pending
+ */
+resolved_by: ApprovalResolution | null, 
+/**
+ * Rule that answered the request, when it was answered by a rule
+ */
+approval_rule_id: string | null, created_at: string, timeout_at: string, responded_at: string | null, };
+
+/**
+ * What an approval rule does with the tool calls it matches. `ask` shows the
+ * request to the user even when an allow rule matches too.
+ */
+export type ApprovalRuleDecision = "allow" | "deny" | "ask";
+
+/**
+ * Kind of tool action an approval rule applies to
+ */
+export type ApprovalRuleActionType = "file_read" | "file_edit" | "command_run" | "search" | "web_fetch" | "tool";
+
+/**
+ * Rule that answers tool approval requests without asking the user
+ */
+export type ApprovalRule = { id: string, 
+/**
+ * Unset for global rules, which apply to every project
+ */
+project_id: string | null, name: string, 
+/**
+ * Tool name to match exactly, e.g. `Bash`
+ */
+tool_name: string | null, action_type: ApprovalRuleActionType | null, 
+/**
+ * Glob on the path for file actions, otherwise a regex on the command,
+ * search query, URL or tool arguments
+ */
+pattern: string | null, decision: ApprovalRuleDecision, enabled: boolean, created_at: Date, updated_at: Date, };
+
+export type CreateApprovalRule = { project_id: string | null, name: string, tool_name: string | null, action_type: ApprovalRuleActionType | null, pattern: string | null, decision: ApprovalRuleDecision, enabled: boolean | null, };
+
+export type UpdateApprovalRule = { name: string | null, tool_name?: string | null, action_type?: ApprovalRuleActionType | null, pattern?: string | null, decision: ApprovalRuleDecision | null, enabled: boolean | null, };
+
+/**
+ * Health of a dev server process. `unhealthy` servers are running but fail
+ * their readiness probe; `crashed` ones exited on their own.
+ */
+export type DevServerState = "starting" | "ready" | "unhealthy" | "crashed" | "stopped";
+
+export type DevServerStatus = { execution_process_id: string, repo_id: string, state: DevServerState, 
+/**
+ * Port the server listens on, once detected
+ */
+port: number | null, 
+/**
+ * How many times the server was restarted after crashing before this run
+ */
+restart_count: number, last_error: string | null, created_at: Date, updated_at: Date, };
+
+/**
+ * Outcome of a workspace's verify scripts. `fixing` means the failure was
+ * sent back to the coding agent, which is working on a fix.
+ */
+export type VerificationStatus = "running" | "passed" | "failed" | "fixing";
+
+export type WorkspaceVerification = { workspace_id: string, 
+/**
+ * Latest verify script run
+ */
+execution_process_id: string | null, status: VerificationStatus, 
+/**
+ * Automatic fix follow-ups sent to the agent since verification last
+ * passed or was given up on
+ */
+retries: number, 
+/**
+ * Tail of the failed script's output
+ */
+output: string | null, created_at: Date, updated_at: Date, };
+
+export type ConflictResolutionStatus = "resolving" | "resolved" | "failed";
+
+/**
+ * Conflicts of a worktree handed to the coding agent. Once the agent is done
+ * the files are checked for conflict markers and the rebase or merge is
+ * continued.
+ */
+export type ConflictResolution = { workspace_id: string, repo_id: string, 
+/**
+ * Latest follow-up asking the agent to resolve the conflicts
+ */
+execution_process_id: string | null, status: ConflictResolutionStatus, 
+/**
+ * Files that were conflicted when the latest follow-up started
+ */
+conflicted_files: Array<string>, 
+/**
+ * Follow-ups sent so far; a rebase can stop on conflicts again on a
+ * later commit
+ */
+rounds: number, error: string | null, created_at: Date, updated_at: Date, };
+
+/**
+ * What a search hit was found in: a task's title or description, the prompt or
+ * summary of a coding agent turn, or an entry of an execution's conversation log
+ */
+export type SearchResultKind = "task" | "turn" | "log";
+
+export type SearchResult = { kind: SearchResultKind, task_id: string, task_title: string, project_id: string, 
+/**
+ * Set for turn and log hits
+ */
+workspace_id: string | null, 
+/**
+ * Set for turn and log hits
+ */
+execution_process_id: string | null, 
+/**
+ * Matching excerpt, with matched terms wrapped in `<mark>` tags
+ */
+snippet: string, created_at: string, };
+
+export type DraftFollowUpData = { message: string, executor_profile_id: ExecutorProfileId, 
+/**
+ * Start a code review with `message` as extra instructions instead of sending it as a follow-up
+ */
+review?: boolean, };
 
 export type DraftWorkspaceData = { message: string, project_id: string | null, repos: Array<DraftWorkspaceRepo>, selected_profile: ExecutorProfileId | null, };
 
@@ -76,11 +400,47 @@ export type CreateImage = { file_path: string, original_name: string, mime_type:
 
 export type Workspace = { id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
 
-export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
+export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, 
+/**
+ * How far each repo is from its target branch, once checked by the drift
+ * monitor
+ */
+drift: Array<WorkspaceDrift>, id: string, task_id: string, container_ref: string | null, branch: string, agent_working_dir: string | null, setup_completed_at: string | null, created_at: string, updated_at: string, archived: boolean, pinned: boolean, name: string | null, };
+
+/**
+ * How far a repo of a workspace is from its target branch, as of the latest
+ * check of the drift monitor
+ */
+export type WorkspaceDrift = { workspace_id: string, repo_id: string, target_branch: string, 
+/**
+ * Commit the target branch was at when checked
+ */
+target_commit: string, commits_ahead: number, commits_behind: number, 
+/**
+ * Files that would conflict when merging the workspace branch with the
+ * target branch
+ */
+conflicted_files: Array<string>, checked_at: Date, };
+
+export type WorkspaceRace = { id: string, task_id: string, winner_workspace_id: string | null, created_at: Date, updated_at: Date, };
+
+/**
+ * A repo of a stacked workspace: its branch was created from the branch of
+ * `base_workspace_id` rather than from the repo's target branch.
+ */
+export type WorkspaceStackRepo = { workspace_id: string, repo_id: string, base_workspace_id: string, 
+/**
+ * Commit of the base branch the workspace was last based on
+ */
+base_commit: string, created_at: Date, updated_at: Date, };
 
 export type Session = { id: string, workspace_id: string, executor: string | null, created_at: string, updated_at: string, };
 
 export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
+/**
+ * Set when the process was stopped for exceeding a timeout or resource limit
+ */
+termination_reason: ExecutionTerminationReason | null, 
 /**
  * dropped: true if this process is excluded from the current
  * history view (due to restore/trimming). Hidden from logs/timeline;
@@ -88,11 +448,22 @@ export type ExecutionProcess = { id: string, session_id: string, run_reason: Exe
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { 
+/**
+ * Waiting for a free coding agent slot
+ */
+queued = "queued", running = "running", completed = "completed", failed = "failed", killed = "killed" }
+
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver" | "verifyscript";
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "codingagent" | "devserver";
+export type ExecutionTerminationReason = "timeout" | "idle_timeout" | "memory_limit";
 
-export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
+export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, 
+/**
+ * Snapshot of the worktree, untracked files included, taken when a coding
+ * agent turn ended. Its parent is the HEAD at that time.
+ */
+after_snapshot_commit: string | null, created_at: Date, updated_at: Date, };
 
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
@@ -110,6 +481,11 @@ export type CreateApprovalRequest = { tool_name: string, tool_input: JsonValue,
 
 export type ApprovalResponse = { execution_process_id: string, status: ApprovalStatus, };
 
+/**
+ * An approval request that is waiting for a response
+ */
+export type PendingApprovalInfo = { id: string, execution_process_id: string, tool_name: string, };
+
 export type Diff = { change: DiffChangeKind, oldPath: string | null, newPath: string | null, oldContent: string | null, newContent: string | null, 
 /**
  * True when file contents are intentionally omitted (e.g., too large)
@@ -190,6 +566,47 @@ export type InitRepoRequest = { parent_path: string, folder_name: string, };
 
 export type TagSearchParams = { search: string | null, };
 
+export type TaskScheduleQuery = { project_id: string | null, };
+
+export type TaskTemplateQuery = { 
+/**
+ * Only list the templates available in this project, global ones
+ * included; all templates are listed when unset
+ */
+project_id: string | null, };
+
+export type ApprovalRuleQuery = { 
+/**
+ * Only list the rules of this project; all rules are listed when unset
+ */
+project_id: string | null, };
+
+export type ProjectMemoryQuery = { project_id: string, 
+/**
+ * Only list the entries with this status; all entries are listed when unset
+ */
+status: ProjectMemoryStatus | null, };
+
+export type SearchQuery = { q: string, project_id: string | null, 
+/**
+ * Only include hits created at or after this time
+ */
+from: string | null, 
+/**
+ * Only include hits created at or before this time
+ */
+to: string | null, limit: number | null, };
+
+export type UsageQuery = { group_by: UsageGroupBy, project_id: string | null, 
+/**
+ * Only include usage recorded at or after this time
+ */
+from: string | null, 
+/**
+ * Only include usage recorded at or before this time
+ */
+to: string | null, };
+
 export type TokenResponse = { access_token: string, expires_at: string | null, };
 
 export type UserSystemInfo = { config: Config, analytics_user_id: string, login_status: LoginStatus, environment: Environment, 
@@ -216,6 +633,12 @@ export type CurrentUserResponse = { user_id: string, };
 
 export type CreateFollowUpAttempt = { prompt: string, executor_profile_id: ExecutorProfileId, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
+export type RestoreSessionRequest = { 
+/**
+ * Coding agent turn to roll the worktrees and conversation back to
+ */
+execution_process_id: string, force_when_dirty: boolean | null, };
+
 export type ChangeTargetBranchRequest = { repo_id: string, new_target_branch: string, };
 
 export type ChangeTargetBranchResponse = { repo_id: string, new_target_branch: string, status: [number, number], };
@@ -240,7 +663,15 @@ export type OpenEditorRequest = { editor_type: string | null, file_path: string
 
 export type OpenEditorResponse = { url: string | null, };
 
-export type CreateAndStartTaskRequest = { task: CreateTask, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateAndStartTaskRequest = { task: CreateTask, 
+/**
+ * Defaults to the executor of the task's template
+ */
+executor_profile_id?: ExecutorProfileId, 
+/**
+ * Defaults to the repositories of the task's template
+ */
+repos: Array<WorkspaceRepoInput>, };
 
 export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
 
@@ -248,7 +679,17 @@ export type ImageResponse = { id: string, file_path: string, original_name: stri
 
 export type ImageMetadata = { exists: boolean, file_name: string | null, path: string | null, size_bytes: bigint | null, format: string | null, proxy_url: string | null, };
 
-export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, repos: Array<WorkspaceRepoInput>, };
+export type CreateTaskAttemptBody = { task_id: string, executor_profile_id: ExecutorProfileId, 
+/**
+ * Extra executors to race against `executor_profile_id`.
+ * Each one gets its own workspace and branch, grouped into a single race.
+ */
+race_executor_profile_ids?: Array<ExecutorProfileId>, 
+/**
+ * Workspace whose branch the attempt builds on instead of the repos'
+ * target branches, in the repos both workspaces share
+ */
+base_workspace_id?: string, repos: Array<WorkspaceRepoInput>, };
 
 export type WorkspaceRepoInput = { repo_id: string, target_branch: string, };
 
@@ -262,6 +703,8 @@ export type RebaseTaskAttemptRequest = { repo_id: string, old_base_branch: strin
 
 export type AbortConflictsRequest = { repo_id: string, };
 
+export type ResolveConflictsRequest = { repo_id: string, };
+
 export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
 
 export type PushError = { "type": "force_push_required" };
@@ -282,7 +725,7 @@ export type GetPrCommentsQuery = { repo_id: string, };
 
 export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type ProviderKind = "git_hub" | "azure_dev_ops" | "git_lab" | "gitea" | "unknown";
 
 export type OpenPrInfo = { number: bigint, url: string, title: string, head_branch: string, base_branch: string, };
 
@@ -311,6 +754,10 @@ conflict_op: ConflictOp | null,
  * List of files currently in conflicted (unmerged) state
  */
 conflicted_files: Array<string>, 
+/**
+ * Latest resolution of this worktree's conflicts by the coding agent
+ */
+conflict_resolution: ConflictResolution | null, 
 /**
  * True if the target branch is a remote branch (merging not allowed, must use PR)
  */
@@ -366,6 +813,38 @@ export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
 
 export type DiffStats = { files_changed: number, lines_added: number, lines_removed: number, };
 
+export type RaceEntry = { workspace_id: string, branch: string, archived: boolean, is_winner: boolean, executor_profile_id: ExecutorProfileId | null, 
+/**
+ * Status of the latest coding agent run
+ */
+status: ExecutionProcessStatus | null, exit_code: bigint | null, 
+/**
+ * Wall-clock duration of the latest coding agent run, up to now if still running
+ */
+duration_ms: bigint | null, 
+/**
+ * Changes against the target branch, None once the worktree is cleaned up
+ */
+diff_stats: DiffStats | null, token_usage: TokenUsageInfo | null, };
+
+export type RaceComparison = { race: WorkspaceRace, entries: Array<RaceEntry>, };
+
+export type RestackStatus = { "status": "rebased" } | { "status": "up_to_date" } | { "status": "conflicts", message: string, conflicted_files: Array<string>, } | { "status": "skipped", reason: string, } | { "status": "failed", message: string, };
+
+export type RestackResult = { workspace_id: string, repo_id: string, branch: string, 
+/**
+ * Branch the workspace is now based on
+ */
+base_branch: string, } & RestackStatus;
+
+/**
+ * Workspaces at the same distance from the restacked workspace; level 1 is
+ * stacked directly on it
+ */
+export type RestackLevel = { level: number, results: Array<RestackResult>, };
+
+export type RestackResponse = { levels: Array<RestackLevel>, };
+
 export type IncrementalDiffQuery = { repo_id: string, };
 
 export type IncrementalDiffResponse = { diffs: Array<Diff>, base_commit: string | null, base_type: DiffBaseType, };
@@ -378,9 +857,95 @@ export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_pa
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, send_message_shortcut: SendMessageShortcut, };
+export type UsageGroupBy = "task" | "project" | "executor_profile" | "day";
+
+export type UsageReportRow = { 
+/**
+ * Task or project id, `EXECUTOR` / `EXECUTOR:VARIANT`, or a `YYYY-MM-DD` date
+ */
+key: string, label: string, input_tokens: number, output_tokens: number, cache_read_tokens: number, cache_write_tokens: number, 
+/**
+ * Cost of the usage whose model has a configured price
+ */
+cost_usd: number, 
+/**
+ * Models without a configured price; their usage is not in `cost_usd`
+ */
+unpriced_models: Array<string>, };
+
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, beta_workspaces: boolean, beta_workspaces_invitation_sent: boolean, commit_reminder: boolean, send_message_shortcut: SendMessageShortcut, llm: LlmConfig, git_hosts: Array<GitHostInstance>, concurrency: ConcurrencyConfig, model_prices: { [key in string]?: ModelPrice }, };
+
+export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, sinks: Array<NotificationSink>, };
+
+export type NotificationSink = { name: string, enabled: boolean, kind: NotificationSinkKind, url: string, 
+/**
+ * Sent as `Authorization: Bearer <token>` when set
+ */
+token: string | null, 
+/**
+ * Events delivered to this sink; empty means every event
+ */
+events: Array<NotificationEvent>, 
+/**
+ * Retries after the first failed attempt, with exponential backoff
+ */
+max_retries: number, };
+
+export type NotificationSinkKind = "webhook" | "slack" | "ntfy";
+
+export type NotificationEvent = "task_completed" | "task_failed" | "approval_pending" | "pr_status_changed";
+
+export type LlmConfig = { 
+/**
+ * When unset, `DEEPSEEK_API_KEY` still selects DeepSeek; generation is
+ * disabled only if that is missing too
+ */
+provider: LlmProviderKind | null, 
+/**
+ * Overrides the provider's default endpoint, e.g. a self-hosted model
+ */
+base_url: string | null, api_key: string | null, 
+/**
+ * Overrides the provider's default model
+ */
+model: string | null, 
+/**
+ * Language generated commit messages are written in, English when unset
+ */
+commit_language: string | null, };
+
+export type LlmProviderKind = "openai_compatible" | "anthropic" | "local";
+
+export type GitHostInstance = { kind: GitHostKind, 
+/**
+ * Web root of the instance, e.g. `https://gitlab.example.com`
+ */
+base_url: string, 
+/**
+ * Personal access token for the REST API
+ */
+token: string | null, };
+
+export type GitHostKind = "gitlab" | "gitea";
+
+/**
+ * Caps on coding agents running at the same time; further runs wait in a queue
+ */
+export type ConcurrencyConfig = { 
+/**
+ * Across all executors, unlimited when unset
+ */
+max_concurrent_agents: number | null, 
+/**
+ * Per executor, e.g. `{"CLAUDE_CODE": 2}`
+ */
+per_executor: { [key in BaseCodingAgent]?: number }, };
 
-export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
+/**
+ * Price of a model in USD per million tokens, configured under the model's name
+ * or a prefix of it
+ */
+export type ModelPrice = { input: number, output: number, cache_read: number, cache_write: number, };
 
 export enum ThemeMode { LIGHT = "LIGHT", DARK = "DARK", SYSTEM = "SYSTEM" }
 
@@ -402,7 +967,7 @@ export type SendMessageShortcut = "ModifierEnter" | "Enter";
 
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
-export type QueuedMessage = { 
+export type QueuedMessage = { id: string, 
 /**
  * The session this message is queued for
  */
@@ -416,7 +981,15 @@ data: DraftFollowUpData,
  */
 queued_at: string, };
 
-export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
+export type QueueStatus = { "status": "empty" } | { "status": "queued", 
+/**
+ * The next message to be sent
+ */
+message: QueuedMessage, 
+/**
+ * Every queued message in the order they will be sent, starting with `message`
+ */
+messages: Array<QueuedMessage>, };
 
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
@@ -426,7 +999,7 @@ export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path
 
 export type ExecutorActionType = { "type": "CodingAgentInitialRequest" } & CodingAgentInitialRequest | { "type": "CodingAgentFollowUpRequest" } & CodingAgentFollowUpRequest | { "type": "ScriptRequest" } & ScriptRequest | { "type": "ReviewRequest" } & ReviewRequest;
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "DevServer" | "ToolInstallScript" | "VerifyScript";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
@@ -437,9 +1010,9 @@ working_dir: string | null, };
 
 export type ScriptRequestLanguage = "Bash";
 
-export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID" }
+export enum BaseCodingAgent { CLAUDE_CODE = "CLAUDE_CODE", AMP = "AMP", GEMINI = "GEMINI", CODEX = "CODEX", OPENCODE = "OPENCODE", CURSOR_AGENT = "CURSOR_AGENT", QWEN_CODE = "QWEN_CODE", COPILOT = "COPILOT", DROID = "DROID", CUSTOM = "CUSTOM" }
 
-export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid };
+export type CodingAgent = { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM": Custom };
 
 export type SlashCommandDescription = { 
 /**
@@ -459,6 +1032,17 @@ base: string,
  */
 params: Array<string> | null, };
 
+export type ExecutionLimits = { timeout_minutes?: bigint | null, idle_timeout_minutes?: bigint | null, max_memory_mb?: bigint | null, max_cpus?: number | null, max_processes?: bigint | null, };
+
+export type ContainerRuntime = "docker" | "podman";
+
+/**
+ * What happens to a tool approval request nobody answered in time
+ */
+export type ApprovalTimeoutAction = "deny" | "approve";
+
+export type ApprovalTimeout = { timeout_minutes?: bigint | null, on_timeout?: ApprovalTimeoutAction | null, };
+
 export type ExecutorProfileId = { 
 /**
  * The executor type (e.g., "CLAUDE_CODE", "AMP")
@@ -469,19 +1053,19 @@ executor: BaseCodingAgent,
  */
 variant: string | null, };
 
-export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } };
+export type ExecutorConfig = { [key in string]?: { "CLAUDE_CODE": ClaudeCode } | { "AMP": Amp } | { "GEMINI": Gemini } | { "CODEX": Codex } | { "OPENCODE": Opencode } | { "CURSOR_AGENT": CursorAgent } | { "QWEN_CODE": QwenCode } | { "COPILOT": Copilot } | { "DROID": Droid } | { "CUSTOM": Custom } };
 
 export type ExecutorConfigs = { executors: { [key in BaseCodingAgent]?: ExecutorConfig }, };
 
 export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER = "SETUP_HELPER", CONTEXT_USAGE = "CONTEXT_USAGE" }
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, limits?: ExecutionLimits | null, approval_timeout?: ApprovalTimeout | null, };
 
-export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, limits?: ExecutionLimits | null, approval_timeout?: ApprovalTimeout | null, };
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, limits?: ExecutionLimits | null, approval_timeout?: ApprovalTimeout | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, limits?: ExecutionLimits | null, approval_timeout?: ApprovalTimeout | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -493,9 +1077,9 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, limits?: ExecutionLimits | null, approval_timeout?: ApprovalTimeout | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, limits?: ExecutionLimits | null, approval_timeout?: ApprovalTimeout | null, };
 
 export type Opencode = { append_prompt: AppendPrompt, model?: string | null, variant?: string | null, mode?: string | null, 
 /**
@@ -505,16 +1089,46 @@ auto_approve: boolean,
 /**
  * Enable auto-compaction when the context length approaches the model's context window limit
  */
-auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, limits?: ExecutionLimits | null, approval_timeout?: ApprovalTimeout | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type QwenCode = { append_prompt: AppendPrompt, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, limits?: ExecutionLimits | null, approval_timeout?: ApprovalTimeout | null, };
 
-export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, limits?: ExecutionLimits | null, approval_timeout?: ApprovalTimeout | null, };
 
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
 export type DroidReasoningEffort = "none" | "dynamic" | "off" | "low" | "medium" | "high";
 
+/**
+ * Any CLI agent, configured through its profile instead of code
+ */
+export type Custom = { append_prompt: AppendPrompt, command: string, follow_up_command?: string | null, prompt_delivery: PromptDelivery, session_id_regex?: string | null, log_format: CustomLogFormat, json_lines?: JsonLinesMapping | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, limits?: ExecutionLimits | null, approval_timeout?: ApprovalTimeout | null, };
+
+/**
+ * How the prompt is handed to the agent: written to its stdin, passed as the
+ * `{prompt}` argument, or written to a file passed as the `{prompt_file}`
+ * argument. Missing placeholders are appended as the last argument.
+ */
+export type PromptDelivery = "stdin" | "arg" | "file";
+
+/**
+ * How the agent's output is turned into conversation entries: each line as
+ * agent text, JSON objects read with `json_lines`, or the Agent Client
+ * Protocol over stdio
+ */
+export type CustomLogFormat = "plain_text" | "json_lines" | "acp";
+
+/**
+ * Where the fields of JSON-lines output are found. Fields are dot-separated
+ * paths, e.g. `message.content`.
+ */
+export type JsonLinesMapping = { type_field?: string | null, content_field?: string | null, tool_name_field?: string | null, session_id_field?: string | null, event_types?: { [key in string]?: JsonLineKind } | null, };
+
+/**
+ * Entry a JSON line is shown as
+ */
+export type JsonLineKind = "assistant" | "thinking" | "tool" | "system" | "error";
+
 export type AppendPrompt = string | null;
 
 export type CodingAgentInitialRequest = { prompt: string, 
@@ -526,7 +1140,11 @@ executor_profile_id: ExecutorProfileId,
  * Optional relative path to execute the agent in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Accepted project memory, prepended to the prompt when the agent is spawned
+ */
+project_memory: AppendPrompt, };
 
 export type CodingAgentFollowUpRequest = { prompt: string, session_id: string, 
 /**
@@ -559,7 +1177,17 @@ export type NormalizedEntry = { timestamp: string | null, entry_type: Normalized
 
 export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, } | { "type": "token_usage_info" } & TokenUsageInfo;
 
-export type TokenUsageInfo = { total_tokens: number, model_context_window: number, };
+export type TokenUsageInfo = { total_tokens: number, model_context_window: number, 
+/**
+ * Billable usage of the whole turn per model, reported once the turn has finished
+ */
+model_usage?: Array<ModelTokenUsage>, };
+
+export type ModelTokenUsage = { model: string, 
+/**
+ * Input tokens that were neither read from nor written to the prompt cache
+ */
+input_tokens: number, output_tokens: number, cache_read_tokens: number, cache_write_tokens: number, };
 
 export type FileChange = { "action": "write", content: string, } | { "action": "delete" } | { "action": "rename", new_path: string, } | { "action": "edit", 
 /**